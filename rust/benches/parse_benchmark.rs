@@ -0,0 +1,48 @@
+//! Parsing and lookup benchmarks
+//!
+//! Run with `cargo bench`. The lookup group parses documents of increasing
+//! size and measures `SemanticDOM::get`, which should stay flat as the
+//! document grows because nodes are stored in a hash index.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use semantic_dom_ssg::{Config, SemanticDOM};
+
+/// Build a page with `n` sections, each holding a heading, a link and a button
+fn page(n: usize) -> String {
+    let mut html = String::from("<html><body><nav><a href=\"/\">Home</a></nav><main>");
+    for i in 0..n {
+        html.push_str(&format!(
+            "<section id=\"s{i}\"><h2>Section {i}</h2><a id=\"l{i}\" href=\"/p/{i}\">Page {i}</a>\
+             <button id=\"b{i}\">Action {i}</button></section>"
+        ));
+    }
+    html.push_str("</main></body></html>");
+    html
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for n in [10, 100, 1000] {
+        let html = page(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &html, |b, html| {
+            b.iter(|| SemanticDOM::parse(black_box(html), Config::default()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    for n in [10, 100, 1000, 10_000] {
+        let sdom = SemanticDOM::parse(&page(n), Config::default()).unwrap();
+        let id = format!("sdom_b{}", n / 2);
+        assert!(sdom.get(&id).is_some());
+        group.bench_with_input(BenchmarkId::from_parameter(n), &id, |b, id| {
+            b.iter(|| sdom.get(black_box(id)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_lookup);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semantic_dom_ssg::{AgentCertification, Config, SemanticDOM};
+
+// Parsing and every output format must never panic, whatever the markup
+fuzz_target!(|html: &str| {
+    for sdom in [
+        SemanticDOM::parse(html, Config::default()),
+        SemanticDOM::parse_fragment(html, Config::default()),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let _ = sdom.to_agent_summary();
+        let _ = sdom.to_one_liner();
+        let _ = sdom.to_json();
+        let _ = AgentCertification::certify(&sdom);
+    }
+});
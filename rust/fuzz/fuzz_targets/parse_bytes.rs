@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semantic_dom_ssg::{detect_encoding, Config, SemanticDOM};
+
+// Encoding sniffing runs on raw fetched bytes, which may be anything
+fuzz_target!(|bytes: &[u8]| {
+    let _ = detect_encoding(bytes);
+    if let Ok(sdom) = SemanticDOM::parse_bytes(bytes, Config::default()) {
+        let _ = sdom.to_agent_summary();
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semantic_dom_ssg::{validate_url, validate_url_with, SecurityConfig};
+
+fuzz_target!(|url: &str| {
+    let _ = validate_url(url);
+
+    let config = SecurityConfig::new()
+        .with_allowed_host("*.example.com")
+        .with_blocked_host("*admin*");
+    let _ = validate_url_with(url, &config);
+});
@@ -0,0 +1,102 @@
+//! Labeled boxes for screenshot annotation
+//!
+//! Set-of-marks prompting draws a numbered box over each element of a
+//! screenshot and lets the model answer with a number.
+//! [`SemanticDOM::to_annotation_json`] lists those boxes for the nodes that
+//! have rendered geometry, so a pipeline can draw the marks and map the
+//! number the model picks back to a node ID.
+
+use crate::parser::SemanticDOM;
+use crate::types::Bounds;
+use crate::{Error, Result};
+use serde::Serialize;
+
+/// One box to draw
+#[derive(Debug, Serialize)]
+struct Annotation<'a> {
+    /// Number drawn on the box, from 1 in reading order
+    mark: usize,
+    id: &'a str,
+    label: &'a str,
+    role: &'a str,
+    rect: Bounds,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    interactive: bool,
+}
+
+impl SemanticDOM {
+    /// Export labeled bounding boxes for set-of-marks prompting
+    ///
+    /// Emits a JSON array with one entry per node that has non-empty
+    /// [`bounds`](crate::SemanticNode::bounds), in reading order: `mark`
+    /// (the number to draw, from 1), `id`, `label`, `role`, `rect` (`x`,
+    /// `y`, `width`, `height`) and `interactive` for nodes an agent can act
+    /// on. Geometry comes from a browser capture via [`Self::set_bounds`];
+    /// a document parsed from HTML alone yields an empty array.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Bounds, Config, SemanticDOM};
+    ///
+    /// let html = r#"<main id="main"><button id="buy">Buy</button></main>"#;
+    /// let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let rect = Bounds { x: 10.0, y: 20.0, width: 80.0, height: 32.0 };
+    /// sdom.set_bounds("sdom_buy", Some(rect)).unwrap();
+    ///
+    /// let json = sdom.to_annotation_json().unwrap();
+    /// let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(json[0]["mark"], 1);
+    /// assert_eq!(json[0]["id"], "sdom_buy");
+    /// assert_eq!(json[0]["rect"]["width"], 80.0);
+    /// assert_eq!(json[0]["interactive"], true);
+    /// assert_eq!(json.as_array().unwrap().len(), 1);
+    /// ```
+    pub fn to_annotation_json(&self) -> Result<String> {
+        let annotations: Vec<Annotation> = self
+            .reading_order()
+            .into_iter()
+            .filter_map(|n| n.bounds.filter(|b| !b.is_empty()).map(|b| (n, b)))
+            .enumerate()
+            .map(|(i, (node, rect))| Annotation {
+                mark: i + 1,
+                id: &node.id,
+                label: &node.label,
+                role: node.role.as_str(),
+                rect,
+                interactive: node.role.is_interactable(),
+            })
+            .collect();
+        serde_json::to_string_pretty(&annotations)
+            .map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_marks_skip_nodes_without_area() {
+        let html = r#"<nav id="n"><a id="a" href="/">Home</a><a id="b" href="/x">X</a></nav>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert_eq!(sdom.to_annotation_json().unwrap(), "[]");
+
+        let rect = |width| Some(Bounds { x: 0.0, y: 0.0, width, height: 20.0 });
+        sdom.set_bounds("sdom_b", rect(40.0)).unwrap();
+        sdom.set_bounds("sdom_a", rect(0.0)).unwrap();
+        sdom.set_bounds("sdom_n", rect(300.0)).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&sdom.to_annotation_json().unwrap()).unwrap();
+        let marks: Vec<(u64, &str)> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| (a["mark"].as_u64().unwrap(), a["id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(marks, [(1, "sdom_n"), (2, "sdom_b")]);
+        assert!(json[0].get("interactive").is_none());
+        assert!(sdom.set_bounds("sdom_missing", None).is_err());
+    }
+}
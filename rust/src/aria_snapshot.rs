@@ -0,0 +1,135 @@
+//! Playwright ARIA snapshot export
+//!
+//! Playwright's `toMatchAriaSnapshot` compares a page against a YAML tree
+//! of roles and accessible names. [`SemanticDOM::to_aria_snapshot`] writes
+//! that tree from a parsed page, so baselines can be created from crawled
+//! pages instead of by hand.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+
+/// Whether a role takes its accessible name from the element's content;
+/// other roles are only named by `aria-label` and the like, which the
+/// model doesn't tell apart from text content, so their names are left
+/// out (a template without a name still matches)
+fn named_from_content(role: &SemanticRole) -> bool {
+    matches!(
+        role,
+        SemanticRole::Button
+            | SemanticRole::Link
+            | SemanticRole::Heading
+            | SemanticRole::TextInput
+            | SemanticRole::Checkbox
+            | SemanticRole::Radio
+            | SemanticRole::Select
+            | SemanticRole::Image
+            | SemanticRole::Tab
+            | SemanticRole::TreeItem
+            | SemanticRole::Slider
+            | SemanticRole::SpinButton
+    )
+}
+
+/// Quote a name the way Playwright does
+fn quote(name: &str) -> String {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// One `- role "name" [attrs]` line without the indent
+fn entry(node: &SemanticNode, role: &str) -> String {
+    let mut line = format!("- {}", role);
+    let name = node.accessible_name.as_deref().unwrap_or(&node.label);
+    if named_from_content(&node.role) && !name.trim().is_empty() {
+        line.push(' ');
+        line.push_str(&quote(name));
+    }
+    if let Some(level) = node.level {
+        line.push_str(&format!(" [level={}]", level));
+    }
+    if node.expanded == Some(true) {
+        line.push_str(" [expanded]");
+    }
+    if node.selected {
+        line.push_str(" [selected]");
+    }
+    line
+}
+
+impl SemanticDOM {
+    /// Write the page as a Playwright ARIA snapshot
+    ///
+    /// Nodes without an ARIA role, and `<section>`s, which only become
+    /// regions when named, are left out and their children take their
+    /// place. Names are written for roles named from content; links carry
+    /// their target as a `/url` child. The result matches the page
+    /// partially, as Playwright templates do: text runs are not included.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<header><a href="/">Home</a></header>
+    ///     <main><div><h1>Sign in</h1><button aria-expanded="true">Options</button></div></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert_eq!(sdom.to_aria_snapshot(), "\
+    /// - banner:
+    ///   - link \"Home\":
+    ///     - /url: /
+    /// - main:
+    ///   - heading \"Sign in\" [level=1]
+    ///   - button \"Options\" [expanded]
+    /// ");
+    /// ```
+    pub fn to_aria_snapshot(&self) -> String {
+        let mut out = String::new();
+        let roots = self.reading_order().into_iter().filter(|n| n.parent.is_none());
+        for root in roots {
+            self.write_aria_node(root, 0, &mut out);
+        }
+        out
+    }
+
+    fn write_aria_node(&self, node: &SemanticNode, indent: usize, out: &mut String) {
+        let Some(role) = node.role.aria_role() else {
+            for child in node.children.iter().filter_map(|c| self.index.get(c)) {
+                self.write_aria_node(child, indent, out);
+            }
+            return;
+        };
+
+        let mut children = String::new();
+        if let Some(href) = &node.href {
+            children.push_str(&format!("{:width$}- /url: {}\n", "", href, width = indent + 2));
+        }
+        for child in node.children.iter().filter_map(|c| self.index.get(c)) {
+            self.write_aria_node(child, indent + 2, &mut children);
+        }
+        let colon = if children.is_empty() { "" } else { ":" };
+        out.push_str(&format!("{:width$}{}{}\n", "", entry(node, role), colon, width = indent));
+        out.push_str(&children);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_names_are_quoted_and_generic_nodes_flattened() {
+        let html = r#"<nav aria-label="Primary"><div><a href="/say">Say
+            "hi"</a></div></nav>
+            <section><img src="a.png" alt="Logo"><input placeholder="Email"></section>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let expected = r#"- navigation:
+  - link "Say \"hi\"":
+    - /url: /say
+- img "Logo"
+- textbox "Email"
+"#;
+        assert_eq!(sdom.to_aria_snapshot(), expected);
+    }
+}
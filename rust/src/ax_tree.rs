@@ -0,0 +1,159 @@
+//! Chrome DevTools accessibility tree export
+//!
+//! Tools built on the DevTools protocol read pages through
+//! `Accessibility.getFullAXTree`. [`SemanticDOM::to_ax_tree`] produces the
+//! same response shape from the semantic model, so they can take parsed
+//! documents without a browser.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use crate::{Error, Result};
+use serde_json::{json, Value};
+
+/// ID of the synthetic document node at the top of the tree
+const ROOT_ID: &str = "root";
+
+/// Typed value as used by the protocol (`AXValue`)
+fn ax_value(kind: &str, value: impl Into<Value>) -> Value {
+    json!({ "type": kind, "value": value.into() })
+}
+
+/// Role as Chrome reports it: the ARIA role, `image` for images and
+/// `generic` for elements without one
+fn ax_role(role: &SemanticRole) -> &'static str {
+    match role.aria_role() {
+        Some("img") => "image",
+        Some(role) => role,
+        None => "generic",
+    }
+}
+
+/// Named properties (`AXProperty`) of a node
+fn properties(node: &SemanticNode) -> Vec<Value> {
+    let mut props = Vec::new();
+    let mut push = |name: &str, value: Value| props.push(json!({ "name": name, "value": value }));
+    if node.role.is_interactable() {
+        push("focusable", ax_value("booleanOrUndefined", true));
+    }
+    if let Some(level) = node.level {
+        push("level", ax_value("integer", level));
+    }
+    if let Some(expanded) = node.expanded {
+        push("expanded", ax_value("booleanOrUndefined", expanded));
+    }
+    if node.selected {
+        push("selected", ax_value("booleanOrUndefined", true));
+    }
+    if node.invalid {
+        push("invalid", ax_value("token", "true"));
+    }
+    if !node.key_shortcuts.is_empty() {
+        push("keyshortcuts", ax_value("string", node.key_shortcuts.join(" ")));
+    }
+    if let Some(href) = &node.href {
+        push("url", ax_value("string", href.as_str()));
+    }
+    if let Some(range) = &node.range {
+        if let Some(min) = range.min {
+            push("valuemin", ax_value("number", min));
+        }
+        if let Some(max) = range.max {
+            push("valuemax", ax_value("number", max));
+        }
+        if let Some(text) = &range.text {
+            push("valuetext", ax_value("string", text.as_str()));
+        }
+    }
+    props
+}
+
+impl SemanticDOM {
+    /// Export the accessibility tree in the shape of the DevTools
+    /// `Accessibility.getFullAXTree` response
+    ///
+    /// Emits `{"nodes": [...]}` with a `RootWebArea` node named after the
+    /// page title, followed by every semantic node in reading order. Each
+    /// node has `nodeId` (the semantic node ID), `ignored`, `role`, `name`,
+    /// `properties`, `childIds` and `parentId`; a range's current value is
+    /// `value`.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<title>Shop</title><header id="top"><a id="home" href="/">Home</a></header>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let tree: serde_json::Value = serde_json::from_str(&sdom.to_ax_tree().unwrap()).unwrap();
+    ///
+    /// let nodes = tree["nodes"].as_array().unwrap();
+    /// assert_eq!(nodes[0]["role"]["value"], "RootWebArea");
+    /// assert_eq!(nodes[0]["name"]["value"], "Shop");
+    /// assert_eq!(nodes[1]["role"]["value"], "banner");
+    /// assert_eq!(nodes[2]["name"]["value"], "Home");
+    /// assert_eq!(nodes[2]["parentId"], "sdom_top");
+    /// ```
+    pub fn to_ax_tree(&self) -> Result<String> {
+        let order = self.reading_order();
+        let roots: Vec<&str> =
+            order.iter().filter(|n| n.parent.is_none()).map(|n| n.id.as_str()).collect();
+
+        let mut nodes = vec![json!({
+            "nodeId": ROOT_ID,
+            "ignored": false,
+            "role": ax_value("internalRole", "RootWebArea"),
+            "name": ax_value("computedString", self.title.as_deref().unwrap_or_default()),
+            "properties": [],
+            "childIds": roots,
+        })];
+        for node in order {
+            let name = node.accessible_name.as_deref().unwrap_or(&node.label);
+            let mut entry = json!({
+                "nodeId": node.id,
+                "ignored": false,
+                "role": ax_value("role", ax_role(&node.role)),
+                "name": ax_value("computedString", name),
+                "properties": properties(node),
+                "childIds": node.children,
+                "parentId": node.parent.as_deref().unwrap_or(ROOT_ID),
+            });
+            if let Some(now) = node.range.as_ref().and_then(|r| r.now) {
+                entry["value"] = ax_value("number", now);
+            }
+            nodes.push(entry);
+        }
+        serde_json::to_string_pretty(&json!({ "nodes": nodes }))
+            .map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_ax_properties() {
+        let html = r#"<main><h2>Volume</h2>
+            <div role="slider" aria-label="Volume" aria-valuenow="3" aria-valuemin="0"
+                aria-valuemax="10"></div>
+            <button aria-expanded="false" aria-keyshortcuts="Alt+M">Menu</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let tree: Value = serde_json::from_str(&sdom.to_ax_tree().unwrap()).unwrap();
+        let nodes = tree["nodes"].as_array().unwrap();
+        let by_role = |role: &str| nodes.iter().find(|n| n["role"]["value"] == role).unwrap();
+        let props = |node: &Value| -> Vec<(String, Value)> {
+            let props = node["properties"].as_array().unwrap().iter();
+            props.map(|p| (p["name"].as_str().unwrap().to_string(), p["value"]["value"].clone()))
+                .collect()
+        };
+
+        assert_eq!(props(by_role("heading")), [("level".to_string(), json!(2))]);
+        let slider = by_role("slider");
+        assert_eq!(slider["value"], json!({"type": "number", "value": 3.0}));
+        assert!(props(slider).contains(&("valuemax".to_string(), json!(10.0))));
+        let button = props(by_role("button"));
+        assert!(button.contains(&("expanded".to_string(), json!(false))));
+        assert!(button.contains(&("keyshortcuts".to_string(), json!("Alt+M"))));
+        assert_eq!(nodes[0]["childIds"], json!([by_role("main")["nodeId"]]));
+    }
+}
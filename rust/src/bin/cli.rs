@@ -4,7 +4,7 @@
 //! and token-efficient output formats.
 
 use clap::{Parser, Subcommand, ValueEnum};
-use semantic_dom_ssg::{AgentCertification, Config, SemanticDOM};
+use semantic_dom_ssg::{Config, SemanticDOM, SerializationProfile};
 use std::fs;
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -34,6 +34,14 @@ enum Commands {
         /// Pretty print JSON output
         #[arg(short, long)]
         pretty: bool,
+
+        /// Node fields emitted in JSON output
+        #[arg(long, default_value = "full")]
+        profile: Profile,
+
+        /// Emit only these node fields in JSON output, e.g. id,role,label,intent
+        #[arg(long, value_delimiter = ',', conflicts_with = "profile")]
+        fields: Option<Vec<String>>,
     },
 
     /// Validate an HTML file for agent compatibility
@@ -51,24 +59,96 @@ enum Commands {
         ci: bool,
     },
 
+    /// Output the state graph of an HTML file
+    Graph {
+        /// Input file (use '-' for stdin)
+        #[arg(value_name = "FILE")]
+        input: String,
+
+        /// Print complexity metrics instead of the graph
+        #[arg(long)]
+        stats: bool,
+    },
+
     /// Show token usage comparison between formats
     Tokens {
         /// Input file (use '-' for stdin)
         #[arg(value_name = "FILE")]
         input: String,
     },
+
+    /// Generate an llms.txt-style agent manifest from HTML pages
+    Manifest {
+        /// Input files (use '-' for stdin)
+        #[arg(value_name = "FILE", required = true)]
+        inputs: Vec<String>,
+
+        /// Site name for the title (default: first page's title)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Summary of what the site is for (default: derived from the pages)
+        #[arg(long)]
+        purpose: Option<String>,
+
+        /// Requests per minute agents should stay under
+        #[arg(long, value_name = "N")]
+        requests_per_minute: Option<u32>,
+    },
+
+    /// Check a published SemanticDOM JSON document against the page's HTML
+    Conform {
+        /// Declared SemanticDOM JSON
+        #[arg(value_name = "JSON")]
+        declared: String,
+
+        /// HTML of the live page (use '-' for stdin)
+        #[arg(value_name = "FILE")]
+        input: String,
+    },
+
+    /// List the certification checks
+    Rules {
+        /// Print the catalog as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Serve a GraphQL API over a parsed HTML file
+    #[cfg(feature = "graphql")]
+    Serve {
+        /// Input file (use '-' for stdin)
+        #[arg(value_name = "FILE")]
+        input: String,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
 }
 
 #[derive(Clone, ValueEnum)]
 enum OutputFormat {
     /// Full JSON output
     Json,
-    /// Token-efficient summary (~100 tokens)
+    /// Token-efficient summary (~100-200 tokens)
     Summary,
     /// One-line summary (~20 tokens)
     Oneline,
     /// Navigation-focused summary
     Nav,
+    /// Lists and their items, for result pages
+    Lists,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Profile {
+    /// IDs, roles, labels, intents, links and children
+    Minimal,
+    /// All but confidences, source positions, attributes and metadata
+    Standard,
+    /// Every node field
+    Full,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -89,6 +169,8 @@ fn main() {
             input,
             format,
             pretty: _,
+            profile,
+            fields,
         } => {
             let html = read_input(&input).expect("Failed to read input");
             let config = Config::default();
@@ -96,13 +178,28 @@ fn main() {
             match SemanticDOM::parse(&html, config) {
                 Ok(sdom) => {
                     let output = match format {
-                        OutputFormat::Json => sdom.to_json().unwrap_or_else(|e| {
-                            eprintln!("JSON serialization error: {}", e);
-                            std::process::exit(1);
-                        }),
+                        OutputFormat::Json => {
+                            let json = match fields {
+                                Some(fields) => {
+                                    let fields: Vec<&str> =
+                                        fields.iter().map(String::as_str).collect();
+                                    sdom.to_json_fields(&fields)
+                                }
+                                None => sdom.to_json_with(match profile {
+                                    Profile::Minimal => SerializationProfile::Minimal,
+                                    Profile::Standard => SerializationProfile::Standard,
+                                    Profile::Full => SerializationProfile::Full,
+                                }),
+                            };
+                            json.unwrap_or_else(|e| {
+                                eprintln!("JSON serialization error: {}", e);
+                                std::process::exit(1);
+                            })
+                        }
                         OutputFormat::Summary => sdom.to_agent_summary(),
                         OutputFormat::Oneline => sdom.to_one_liner(),
                         OutputFormat::Nav => semantic_dom_ssg::to_nav_summary(&sdom),
+                        OutputFormat::Lists => semantic_dom_ssg::to_list_summary(&sdom),
                     };
                     println!("{}", output);
                 }
@@ -119,7 +216,7 @@ fn main() {
 
             match SemanticDOM::parse(&html, config) {
                 Ok(sdom) => {
-                    let cert = AgentCertification::certify(&sdom);
+                    let cert = sdom.certification.clone().unwrap_or_else(|| sdom.certify());
 
                     // Print certification results
                     println!("{} SemanticDOM Certification", cert.level.badge());
@@ -141,7 +238,7 @@ fn main() {
                     println!();
 
                     // Print failed checks
-                    let failed: Vec<_> = cert.checks.iter().filter(|c| !c.passed).collect();
+                    let failed: Vec<_> = cert.checks.iter().filter(|c| c.is_failure()).collect();
                     if !failed.is_empty() {
                         println!("Failed checks:");
                         for check in failed {
@@ -154,6 +251,43 @@ fn main() {
                         println!();
                     }
 
+                    // Print waived checks
+                    let waived: Vec<_> = cert
+                        .checks
+                        .iter()
+                        .filter_map(|c| c.waiver.as_ref().map(|w| (c, w)))
+                        .collect();
+                    if !waived.is_empty() {
+                        println!("Waived checks:");
+                        for (check, waiver) in waived {
+                            let reason = waiver.reason.as_deref().unwrap_or(&check.name);
+                            println!("  ~ {} - {}", check.id, reason);
+                        }
+                        println!();
+                    }
+
+                    // Print security findings
+                    let report = sdom.security_report();
+                    if !report.is_clean() {
+                        println!("Security findings:");
+                        for finding in &report.findings {
+                            println!(
+                                "  ⚠ {:?} ({:?}) <{}> {}",
+                                finding.kind, finding.severity, finding.element, finding.url
+                            );
+                        }
+                        println!();
+                    }
+
+                    // Print parse warnings
+                    if !sdom.warnings().is_empty() {
+                        println!("Warnings:");
+                        for warning in sdom.warnings() {
+                            println!("  ⚠ {}", warning);
+                        }
+                        println!();
+                    }
+
                     // Check against required level
                     let required = match level {
                         CertLevel::A => semantic_dom_ssg::CertificationLevel::A,
@@ -177,6 +311,43 @@ fn main() {
             }
         }
 
+        Commands::Graph { input, stats } => {
+            let html = read_input(&input).expect("Failed to read input");
+            let config = Config::default();
+
+            match SemanticDOM::parse(&html, config) {
+                Ok(sdom) if stats => {
+                    let metrics = sdom.state_graph.metrics();
+
+                    println!("State Graph Metrics");
+                    println!("===================");
+                    println!();
+                    println!("States:            {:>6}", metrics.state_count);
+                    println!("Transitions:       {:>6}", metrics.transition_count);
+                    println!("Branching factor:  {:>6.2}", metrics.branching_factor);
+                    println!("Diameter:          {:>6}", metrics.diameter);
+                    println!(
+                        "Components:        {:>6}",
+                        metrics.strongly_connected_components.len()
+                    );
+                    for cycle in metrics.cycles() {
+                        println!("  cycle: {}", cycle.join(", "));
+                    }
+                }
+                Ok(sdom) => {
+                    let json = sdom.state_graph.to_json().unwrap_or_else(|e| {
+                        eprintln!("JSON serialization error: {}", e);
+                        std::process::exit(1);
+                    });
+                    println!("{}", json);
+                }
+                Err(e) => {
+                    eprintln!("Parse error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
         Commands::Tokens { input } => {
             let html = read_input(&input).expect("Failed to read input");
             let config = Config::default();
@@ -209,6 +380,152 @@ fn main() {
                 }
             }
         }
+
+        Commands::Manifest {
+            inputs,
+            name,
+            purpose,
+            requests_per_minute,
+        } => {
+            let mut pages = Vec::with_capacity(inputs.len());
+            for input in &inputs {
+                let html = read_input(input).expect("Failed to read input");
+                match SemanticDOM::parse(&html, Config::default()) {
+                    Ok(sdom) => pages.push(sdom),
+                    Err(e) => {
+                        eprintln!("Parse error in {}: {}", input, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            let options = semantic_dom_ssg::ManifestOptions {
+                name,
+                purpose,
+                requests_per_minute,
+            };
+            print!("{}", semantic_dom_ssg::to_agent_manifest(&pages, &options));
+        }
+
+        Commands::Conform { declared, input } => {
+            let json = read_input(&declared).expect("Failed to read declared document");
+            let html = read_input(&input).expect("Failed to read input");
+            let declared = SemanticDOM::from_json(&json, Config::default()).unwrap_or_else(|e| {
+                eprintln!("Invalid declared document: {}", e);
+                std::process::exit(1);
+            });
+            let live = SemanticDOM::parse(&html, Config::default()).unwrap_or_else(|e| {
+                eprintln!("Parse error: {}", e);
+                std::process::exit(1);
+            });
+
+            let report = live.conformance(&declared);
+            for id in &report.missing {
+                println!("missing    {}", id);
+            }
+            for renamed in &report.renamed {
+                println!("renamed    {} -> {}", renamed.declared, renamed.parsed);
+            }
+            let show = |value: &Option<String>| value.as_deref().unwrap_or("(none)").to_string();
+            for m in &report.mismatches {
+                println!(
+                    "mismatch   {} {}: {:?} -> {:?}",
+                    m.id,
+                    m.field,
+                    show(&m.declared),
+                    show(&m.parsed)
+                );
+            }
+            println!("{} undeclared nodes", report.undeclared.len());
+            if !report.is_conformant() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Rules { json } => {
+            let checks = semantic_dom_ssg::all_checks();
+            if json {
+                match serde_json::to_string_pretty(checks) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        eprintln!("JSON serialization error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                for check in checks {
+                    println!(
+                        "{:<12} {:<17} {:>4.2}  {}",
+                        check.id,
+                        format!("{:?}", check.category),
+                        check.weight,
+                        check.name
+                    );
+                    println!("{:<37}{}", "", check.description);
+                    if !check.wcag.is_empty() {
+                        println!("{:<37}WCAG {}", "", check.wcag.join(", "));
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "graphql")]
+        Commands::Serve { input, addr } => {
+            let html = read_input(&input).expect("Failed to read input");
+            let config = Config::default();
+
+            match SemanticDOM::parse(&html, config) {
+                Ok(sdom) => serve(semantic_dom_ssg::graphql::schema(sdom), &addr),
+                Err(e) => {
+                    eprintln!("Parse error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Answer GraphQL requests POSTed to `/graphql` until killed
+#[cfg(feature = "graphql")]
+fn serve(schema: semantic_dom_ssg::graphql::DocumentSchema, addr: &str) {
+    use tiny_http::{Header, Method, Response, Server};
+
+    let server = Server::http(addr).unwrap_or_else(|e| {
+        eprintln!("Cannot listen on {}: {}", addr, e);
+        std::process::exit(1);
+    });
+    println!("GraphQL endpoint: http://{}/graphql", addr);
+
+    let header = |name: &str, value: &str| {
+        Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+    };
+    for mut request in server.incoming_requests() {
+        let path = request.url().split('?').next().unwrap_or_default();
+        let response = match (request.method(), path) {
+            (_, p) if p != "/graphql" => Response::from_string("Not found").with_status_code(404),
+            // CORS preflight from dashboards on another origin
+            (Method::Options, _) => Response::from_string("")
+                .with_status_code(204)
+                .with_header(header("Access-Control-Allow-Methods", "POST, OPTIONS"))
+                .with_header(header("Access-Control-Allow-Headers", "Content-Type")),
+            (Method::Post, _) => {
+                let mut body = String::new();
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => match semantic_dom_ssg::graphql::execute(&schema, &body) {
+                        Ok(json) => Response::from_string(json)
+                            .with_header(header("Content-Type", "application/json")),
+                        Err(e) => Response::from_string(e.to_string()).with_status_code(400),
+                    },
+                    Err(e) => Response::from_string(e.to_string()).with_status_code(400),
+                }
+            }
+            _ => Response::from_string("Method not allowed")
+                .with_status_code(405)
+                .with_header(header("Allow", "POST, OPTIONS")),
+        };
+        let response = response.with_header(header("Access-Control-Allow-Origin", "*"));
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
     }
 }
 
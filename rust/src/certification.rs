@@ -4,9 +4,38 @@
 //! ISO/IEC-SDOM-SSG-DRAFT-2024 specification.
 
 use crate::parser::SemanticDOM;
-use crate::types::SemanticRole;
+use crate::types::{Bounds, SemanticNode, SemanticRole};
+use crate::waivers::Waiver;
 use serde::{Deserialize, Serialize};
 
+/// Confidence below which a role or intent is reported as a guess
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Alt text longer than this is a description, not an alternative
+const MAX_ALT_CHARS: usize = 150;
+
+/// Alt texts that only say there is an image
+const PLACEHOLDER_ALTS: &[&str] = &[
+    "image", "img", "photo", "picture", "pic", "graphic", "icon", "logo", "banner", "spacer",
+    "placeholder", "untitled", "alt",
+];
+
+/// Link texts that do not say where the link goes
+const GENERIC_LINK_TEXTS: &[&str] = &[
+    "click here", "here", "click", "read more", "more", "learn more", "more info",
+    "more information", "details", "continue", "continue reading", "link", "this link", "go",
+];
+
+/// File extensions that mark alt text copied from the file name
+const IMAGE_EXTENSIONS: &[&str] =
+    &[".jpg", ".jpeg", ".png", ".gif", ".webp", ".svg", ".avif", ".bmp", ".tif", ".tiff"];
+
+/// Smallest comfortable target in CSS pixels, from WCAG 2.5.8
+const MIN_TARGET_SIZE: f64 = 24.0;
+
+/// Controls in one container beyond which, without geometry, it is crowded
+const MAX_CONTROLS_PER_CONTAINER: usize = 25;
+
 /// Certification levels based on compliance
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum CertificationLevel {
@@ -21,6 +50,16 @@ pub enum CertificationLevel {
 }
 
 impl CertificationLevel {
+    /// Level reached with a score (0-100)
+    pub fn for_score(score: u32) -> Self {
+        match score {
+            90.. => CertificationLevel::AAA,
+            70..=89 => CertificationLevel::AA,
+            50..=69 => CertificationLevel::A,
+            _ => CertificationLevel::None,
+        }
+    }
+
     /// Get the badge emoji for this level
     pub fn badge(&self) -> &'static str {
         match self {
@@ -57,6 +96,16 @@ pub struct ValidationCheck {
     pub details: Option<String>,
     /// Weight for scoring (0.0 - 1.0)
     pub weight: f32,
+    /// Waiver accepting the failure of this check on this page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub waiver: Option<Waiver>,
+}
+
+impl ValidationCheck {
+    /// Whether the check failed without a waiver
+    pub fn is_failure(&self) -> bool {
+        !self.passed && self.waiver.is_none()
+    }
 }
 
 /// Categories of validation checks
@@ -84,6 +133,226 @@ impl CheckCategory {
     }
 }
 
+/// Description of a built-in check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckInfo {
+    /// Check identifier, as in [`ValidationCheck::id`]
+    pub id: &'static str,
+    /// Human-readable name
+    pub name: &'static str,
+    /// Category of the check
+    pub category: CheckCategory,
+    /// Weight for scoring within the category
+    pub weight: f32,
+    /// What the check looks for
+    pub description: &'static str,
+    /// Related WCAG 2.2 success criteria; empty for agent-specific checks
+    pub wcag: &'static [&'static str],
+}
+
+/// Every built-in check, in the order reports list them
+const CHECKS: [CheckInfo; 23] = [
+    CheckInfo {
+        id: "STRUCT-001",
+        name: "Has landmark regions",
+        category: CheckCategory::Structure,
+        weight: 1.0,
+        description: "The page has at least one landmark region",
+        wcag: &["1.3.1", "2.4.1"],
+    },
+    CheckInfo {
+        id: "STRUCT-002",
+        name: "Has main content region",
+        category: CheckCategory::Structure,
+        weight: 1.0,
+        description: "The page has a main landmark",
+        wcag: &["1.3.1", "2.4.1"],
+    },
+    CheckInfo {
+        id: "STRUCT-003",
+        name: "Has heading structure",
+        category: CheckCategory::Structure,
+        weight: 0.5,
+        description: "The page has at least one heading",
+        wcag: &["1.3.1", "2.4.6"],
+    },
+    CheckInfo {
+        id: "STRUCT-004",
+        name: "Unique element IDs",
+        category: CheckCategory::Structure,
+        weight: 0.5,
+        description: "No HTML id is used by more than one element",
+        wcag: &["4.1.1"],
+    },
+    CheckInfo {
+        id: "STRUCT-005",
+        name: "Exactly one main landmark",
+        category: CheckCategory::Structure,
+        weight: 0.5,
+        description: "The page has one main landmark, not several",
+        wcag: &["1.3.1"],
+    },
+    CheckInfo {
+        id: "STRUCT-006",
+        name: "Repeated landmarks have distinct labels",
+        category: CheckCategory::Structure,
+        weight: 0.5,
+        description: "Landmarks sharing a role are told apart by their labels",
+        wcag: &["1.3.1", "2.4.1"],
+    },
+    CheckInfo {
+        id: "STRUCT-007",
+        name: "Banner and contentinfo are top-level",
+        category: CheckCategory::Structure,
+        weight: 0.5,
+        description: "Explicit banner and contentinfo landmarks are not nested in other landmarks",
+        wcag: &["1.3.1"],
+    },
+    CheckInfo {
+        id: "A11Y-001",
+        name: "Interactables have accessible names",
+        category: CheckCategory::Accessibility,
+        weight: 1.0,
+        description: "At least 80% of interactive elements have an accessible name",
+        wcag: &["4.1.2"],
+    },
+    CheckInfo {
+        id: "A11Y-002",
+        name: "Links have descriptive text",
+        category: CheckCategory::Accessibility,
+        weight: 0.75,
+        description: "At least 80% of links have text",
+        wcag: &["2.4.4"],
+    },
+    CheckInfo {
+        id: "A11Y-003",
+        name: "Buttons have descriptive text",
+        category: CheckCategory::Accessibility,
+        weight: 0.75,
+        description: "At least 80% of buttons have text",
+        wcag: &["4.1.2"],
+    },
+    CheckInfo {
+        id: "A11Y-004",
+        name: "Form inputs have labels",
+        category: CheckCategory::Accessibility,
+        weight: 0.5,
+        description: "At least 80% of text inputs, checkboxes, radios and selects are labeled",
+        wcag: &["1.3.1", "3.3.2"],
+    },
+    CheckInfo {
+        id: "A11Y-005",
+        name: "Language declarations match content",
+        category: CheckCategory::Accessibility,
+        weight: 0.5,
+        description: "Declared languages and text directions match the text they cover",
+        wcag: &["3.1.1", "3.1.2"],
+    },
+    CheckInfo {
+        id: "A11Y-006",
+        name: "Click targets are semantic elements",
+        category: CheckCategory::Accessibility,
+        weight: 0.5,
+        description: "Clickable elements are native controls, not generic elements with handlers",
+        wcag: &["2.1.1", "4.1.2"],
+    },
+    CheckInfo {
+        id: "A11Y-007",
+        name: "Text alternatives are meaningful",
+        category: CheckCategory::Accessibility,
+        weight: 0.5,
+        description: "Alt text is not a placeholder, file name or essay, and link text is not \
+            generic like \"click here\"",
+        wcag: &["1.1.1", "2.4.4"],
+    },
+    CheckInfo {
+        id: "NAV-001",
+        name: "Has navigation landmark",
+        category: CheckCategory::Navigation,
+        weight: 1.0,
+        description: "The page has a navigation landmark",
+        wcag: &["2.4.1"],
+    },
+    CheckInfo {
+        id: "NAV-002",
+        name: "State graph is deterministic",
+        category: CheckCategory::Navigation,
+        weight: 1.0,
+        description: "No state has two transitions with the same trigger",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "NAV-003",
+        name: "All states reachable",
+        category: CheckCategory::Navigation,
+        weight: 0.75,
+        description: "Every state can be reached from the initial state",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-001",
+        name: "Elements have CSS selectors",
+        category: CheckCategory::Interoperability,
+        weight: 1.0,
+        description: "At least 80% of nodes have a selector to find them again",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-002",
+        name: "Interactables have intents",
+        category: CheckCategory::Interoperability,
+        weight: 0.75,
+        description: "At least 80% of interactive elements have an intent",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-003",
+        name: "Classifications are confident",
+        category: CheckCategory::Interoperability,
+        weight: 0.5,
+        description: "At most 20% of roles and intents are low-confidence guesses",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-004",
+        name: "Page can be embedded by agent hosts",
+        category: CheckCategory::Interoperability,
+        weight: 0.25,
+        description: "Response headers do not forbid framing the page",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-005",
+        name: "Referrer policy keeps URLs private",
+        category: CheckCategory::Interoperability,
+        weight: 0.25,
+        description: "The referrer policy does not send full URLs to other origins",
+        wcag: &[],
+    },
+    CheckInfo {
+        id: "INTEROP-006",
+        name: "Controls are not crowded",
+        category: CheckCategory::Interoperability,
+        weight: 0.25,
+        description: "Small targets are spaced apart, or without geometry, no container holds \
+            more than 25 controls",
+        wcag: &["2.5.8"],
+    },
+];
+
+/// Metadata of every built-in check, in the order reports list them
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::all_checks;
+///
+/// let links = all_checks().iter().find(|c| c.id == "A11Y-002").unwrap();
+/// assert_eq!(links.wcag, ["2.4.4"]);
+/// ```
+pub fn all_checks() -> &'static [CheckInfo] {
+    &CHECKS
+}
+
 /// Agent certification result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentCertification {
@@ -104,6 +373,9 @@ pub struct CertificationStats {
     pub total_checks: usize,
     /// Checks that passed
     pub passed_checks: usize,
+    /// Failed checks that were waived
+    #[serde(default)]
+    pub waived_checks: usize,
     /// Number of landmarks found
     pub landmark_count: usize,
     /// Number of interactables found
@@ -117,28 +389,43 @@ pub struct CertificationStats {
 impl AgentCertification {
     /// Certify a SemanticDOM document
     pub fn certify(sdom: &SemanticDOM) -> Self {
-        let mut checks = Vec::new();
-
-        // Structure checks (30%)
-        checks.push(Self::check_has_landmarks(sdom));
-        checks.push(Self::check_has_main(sdom));
-        checks.push(Self::check_heading_hierarchy(sdom));
-        checks.push(Self::check_unique_ids(sdom));
-
-        // Accessibility checks (30%)
-        checks.push(Self::check_accessible_names(sdom));
-        checks.push(Self::check_link_text(sdom));
-        checks.push(Self::check_button_text(sdom));
-        checks.push(Self::check_form_labels(sdom));
-
-        // Navigation checks (25%)
-        checks.push(Self::check_navigation_exists(sdom));
-        checks.push(Self::check_deterministic_fsm(sdom));
-        checks.push(Self::check_reachable_states(sdom));
+        crate::metrics::instrument("certify", || Self::evaluate(sdom), |c| c.checks.len())
+    }
 
-        // Interoperability checks (15%)
-        checks.push(Self::check_selectors(sdom));
-        checks.push(Self::check_intents(sdom));
+    fn evaluate(sdom: &SemanticDOM) -> Self {
+        let mut checks = vec![
+            // Structure checks (30%)
+            Self::check_has_landmarks(sdom),
+            Self::check_has_main(sdom),
+            Self::check_heading_hierarchy(sdom),
+            Self::check_unique_ids(sdom),
+            Self::check_single_main(sdom),
+            Self::check_landmark_labels(sdom),
+            Self::check_top_level_landmarks(sdom),
+            // Accessibility checks (30%)
+            Self::check_accessible_names(sdom),
+            Self::check_link_text(sdom),
+            Self::check_button_text(sdom),
+            Self::check_form_labels(sdom),
+            Self::check_language(sdom),
+            Self::check_click_targets(sdom),
+            Self::check_text_alternatives(sdom),
+            // Navigation checks (25%)
+            Self::check_navigation_exists(sdom),
+            Self::check_deterministic_fsm(sdom),
+            Self::check_reachable_states(sdom),
+            // Interoperability checks (15%)
+            Self::check_selectors(sdom),
+            Self::check_intents(sdom),
+            Self::check_classification_confidence(sdom),
+            Self::check_framing(sdom),
+            Self::check_referrer_policy(sdom),
+            Self::check_control_density(sdom),
+        ];
+        let waivers = sdom.waivers();
+        for check in checks.iter_mut().filter(|c| !c.passed) {
+            check.waiver = waivers.iter().find(|w| w.check == check.id).cloned();
+        }
 
         // Calculate scores by category
         let mut category_scores: std::collections::HashMap<CheckCategory, (f32, f32)> =
@@ -147,7 +434,7 @@ impl AgentCertification {
         for check in &checks {
             let entry = category_scores.entry(check.category).or_insert((0.0, 0.0));
             entry.1 += check.weight;
-            if check.passed {
+            if !check.is_failure() {
                 entry.0 += check.weight;
             }
         }
@@ -166,24 +453,21 @@ impl AgentCertification {
         total_score += completeness * 0.1; // 10% bonus for completeness
 
         // Normalize to 0-100
-        let score = ((total_score * 100.0).min(100.0).max(0.0)) as u32;
+        let score = (total_score * 100.0).clamp(0.0, 100.0) as u32;
 
-        // Determine level
-        let level = match score {
-            90..=100 => CertificationLevel::AAA,
-            70..=89 => CertificationLevel::AA,
-            50..=69 => CertificationLevel::A,
-            _ => CertificationLevel::None,
-        };
+        let level = CertificationLevel::for_score(score);
 
         let passed_checks = checks.iter().filter(|c| c.passed).count();
+        let waived_checks = checks.iter().filter(|c| c.waiver.is_some()).count();
 
+        let counts = sdom.stats();
         let stats = CertificationStats {
             total_checks: checks.len(),
             passed_checks,
-            landmark_count: sdom.landmarks.len(),
-            interactable_count: sdom.interactables.len(),
-            heading_count: sdom.headings.len(),
+            waived_checks,
+            landmark_count: counts.landmark_count,
+            interactable_count: counts.interactable_count,
+            heading_count: counts.heading_count,
             completeness,
         };
 
@@ -205,7 +489,7 @@ impl AgentCertification {
         let labeled = sdom
             .index
             .values()
-            .filter(|n| !n.label.is_empty() && n.label != n.role.to_string().to_lowercase())
+            .filter(|n| !n.label.is_empty() && n.label != n.role.as_str())
             .count();
         let total = sdom.index.len().max(1);
         completeness += 0.25 * (labeled as f32 / total as f32).min(1.0);
@@ -244,29 +528,36 @@ impl AgentCertification {
     // Structure checks
 
     fn check_has_landmarks(sdom: &SemanticDOM) -> ValidationCheck {
-        let has_landmarks = !sdom.landmarks.is_empty();
+        // Inferred landmarks orient agents but are not in the markup
+        let inferred = sdom.inferred_landmarks().len();
+        let declared = sdom.landmarks.len() - inferred;
+        let mut details = format!("Found {} landmarks", declared);
+        if inferred > 0 {
+            details.push_str(&format!(" ({} inferred)", inferred));
+        }
         ValidationCheck {
             id: "STRUCT-001".to_string(),
             name: "Has landmark regions".to_string(),
             category: CheckCategory::Structure,
-            passed: has_landmarks,
-            details: Some(format!("Found {} landmarks", sdom.landmarks.len())),
+            passed: declared > 0,
+            details: Some(details),
             weight: 1.0,
+            waiver: None,
         }
     }
 
     fn check_has_main(sdom: &SemanticDOM) -> ValidationCheck {
-        let has_main = sdom
-            .index
-            .values()
-            .any(|n| matches!(n.role, SemanticRole::Main));
+        let (inferred, declared): (Vec<&SemanticNode>, _) =
+            sdom.query_by_role(&SemanticRole::Main).into_iter().partition(|n| n.inferred);
         ValidationCheck {
             id: "STRUCT-002".to_string(),
             name: "Has main content region".to_string(),
             category: CheckCategory::Structure,
-            passed: has_main,
-            details: None,
+            passed: !declared.is_empty(),
+            details: (declared.is_empty() && !inferred.is_empty())
+                .then(|| "Main content region inferred, not declared".to_string()),
             weight: 1.0,
+            waiver: None,
         }
     }
 
@@ -279,19 +570,128 @@ impl AgentCertification {
             passed: has_headings,
             details: Some(format!("Found {} headings", sdom.headings.len())),
             weight: 0.5,
+            waiver: None,
         }
     }
 
     fn check_unique_ids(sdom: &SemanticDOM) -> ValidationCheck {
-        // All IDs in the index should be unique by definition (HashMap)
-        let unique_count = sdom.index.len();
+        // Semantic IDs are unique by construction; duplicate HTML ids are
+        // disambiguated with a suffix but make selectors ambiguous
+        let duplicates = &sdom.duplicate_ids;
+        let details = if duplicates.is_empty() {
+            format!("{} unique nodes", sdom.index.len())
+        } else {
+            let shown: Vec<&str> = duplicates.iter().take(5).map(String::as_str).collect();
+            format!("{} duplicate HTML ids: {}", duplicates.len(), shown.join(", "))
+        };
         ValidationCheck {
             id: "STRUCT-004".to_string(),
             name: "Unique element IDs".to_string(),
             category: CheckCategory::Structure,
-            passed: true,
-            details: Some(format!("{} unique nodes", unique_count)),
+            passed: duplicates.is_empty(),
+            details: Some(details),
             weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_single_main(sdom: &SemanticDOM) -> ValidationCheck {
+        let mains = sdom
+            .query_by_role(&SemanticRole::Main)
+            .iter()
+            .filter(|n| !n.inferred)
+            .count();
+        ValidationCheck {
+            id: "STRUCT-005".to_string(),
+            name: "Exactly one main landmark".to_string(),
+            category: CheckCategory::Structure,
+            passed: mains == 1,
+            details: Some(format!("Found {} main landmarks", mains)),
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_landmark_labels(sdom: &SemanticDOM) -> ValidationCheck {
+        // Landmarks of a role, each with its label; an unlabeled landmark
+        // only carries the role name
+        let mut by_role: Vec<(&SemanticRole, Vec<(&str, String)>)> = Vec::new();
+        for node in sdom.landmarks.iter().filter_map(|id| sdom.index.get(id)) {
+            let name = node.accessible_name.as_deref().unwrap_or(&node.label);
+            let label = name.trim().to_lowercase();
+            let label = if label == node.role.as_str() { String::new() } else { label };
+            match by_role.iter_mut().find(|(role, _)| **role == node.role) {
+                Some((_, nodes)) => nodes.push((&node.id, label)),
+                None => by_role.push((&node.role, vec![(&node.id, label)])),
+            }
+        }
+
+        let mut clashing: Vec<&str> = Vec::new();
+        for (_, nodes) in by_role.iter().filter(|(_, nodes)| nodes.len() > 1) {
+            for (id, label) in nodes {
+                let shared = nodes.iter().filter(|(_, other)| other == label).count() > 1;
+                if label.is_empty() || shared {
+                    clashing.push(id);
+                }
+            }
+        }
+
+        let details = if clashing.is_empty() {
+            None
+        } else {
+            let mut shown = clashing.iter().take(5).copied().collect::<Vec<_>>().join(", ");
+            if clashing.len() > 5 {
+                shown.push_str(&format!(" (+{} more)", clashing.len() - 5));
+            }
+            Some(format!("{} unlabeled or same-labeled: {}", clashing.len(), shown))
+        };
+        ValidationCheck {
+            id: "STRUCT-006".to_string(),
+            name: "Repeated landmarks have distinct labels".to_string(),
+            category: CheckCategory::Structure,
+            passed: clashing.is_empty(),
+            details,
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_top_level_landmarks(sdom: &SemanticDOM) -> ValidationCheck {
+        // A native <header> or <footer> inside another landmark is scoped to
+        // it rather than a banner or contentinfo, so only explicit roles count
+        let nested: Vec<&str> = sdom
+            .landmarks
+            .iter()
+            .filter_map(|id| sdom.index.get(id))
+            .filter(|n| matches!(n.role, SemanticRole::Header | SemanticRole::Footer))
+            .filter(|n| n.explicit_role)
+            .filter(|n| {
+                let mut parent = n.parent.as_deref().and_then(|id| sdom.index.get(id));
+                while let Some(node) = parent {
+                    if node.role.is_landmark() {
+                        return true;
+                    }
+                    parent = node.parent.as_deref().and_then(|id| sdom.index.get(id));
+                }
+                false
+            })
+            .map(|n| n.id.as_str())
+            .collect();
+
+        let details = if nested.is_empty() {
+            None
+        } else {
+            let shown: Vec<&str> = nested.iter().take(5).copied().collect();
+            Some(format!("{} nested: {}", nested.len(), shown.join(", ")))
+        };
+        ValidationCheck {
+            id: "STRUCT-007".to_string(),
+            name: "Banner and contentinfo are top-level".to_string(),
+            category: CheckCategory::Structure,
+            passed: nested.is_empty(),
+            details,
+            weight: 0.5,
+            waiver: None,
         }
     }
 
@@ -318,15 +718,12 @@ impl AgentCertification {
                 interactables_with_names, total_interactables, pct
             )),
             weight: 1.0,
+            waiver: None,
         }
     }
 
     fn check_link_text(sdom: &SemanticDOM) -> ValidationCheck {
-        let links: Vec<_> = sdom
-            .index
-            .values()
-            .filter(|n| matches!(n.role, SemanticRole::Link))
-            .collect();
+        let links = sdom.query_by_role(&SemanticRole::Link);
 
         let with_text = links
             .iter()
@@ -343,15 +740,12 @@ impl AgentCertification {
             passed,
             details: Some(format!("{}/{} links have text", with_text, total)),
             weight: 0.75,
+            waiver: None,
         }
     }
 
     fn check_button_text(sdom: &SemanticDOM) -> ValidationCheck {
-        let buttons: Vec<_> = sdom
-            .index
-            .values()
-            .filter(|n| matches!(n.role, SemanticRole::Button))
-            .collect();
+        let buttons = sdom.query_by_role(&SemanticRole::Button);
 
         let with_text = buttons
             .iter()
@@ -368,23 +762,20 @@ impl AgentCertification {
             passed,
             details: Some(format!("{}/{} buttons have text", with_text, total)),
             weight: 0.75,
+            waiver: None,
         }
     }
 
     fn check_form_labels(sdom: &SemanticDOM) -> ValidationCheck {
-        let inputs: Vec<_> = sdom
-            .index
-            .values()
-            .filter(|n| {
-                matches!(
-                    n.role,
-                    SemanticRole::TextInput
-                        | SemanticRole::Checkbox
-                        | SemanticRole::Radio
-                        | SemanticRole::Select
-                )
-            })
-            .collect();
+        let inputs: Vec<_> = [
+            SemanticRole::TextInput,
+            SemanticRole::Checkbox,
+            SemanticRole::Radio,
+            SemanticRole::Select,
+        ]
+        .iter()
+        .flat_map(|role| sdom.query_by_role(role))
+        .collect();
 
         let with_labels = inputs
             .iter()
@@ -401,29 +792,112 @@ impl AgentCertification {
             passed,
             details: Some(format!("{}/{} inputs have labels", with_labels, total)),
             weight: 0.5,
+            waiver: None,
         }
     }
 
     // Navigation checks
 
+    fn check_language(sdom: &SemanticDOM) -> ValidationCheck {
+        let mismatches = sdom.lang_mismatches();
+        let details = match (mismatches.first(), &sdom.lang) {
+            (Some(first), _) => format!(
+                "{} nodes contradict their lang/dir, e.g. '{}' ({})",
+                mismatches.len(),
+                first.label,
+                sdom.lang_of(first).unwrap_or("")
+            ),
+            (None, Some(lang)) => format!("Document language: {}", lang),
+            (None, None) => "No document language declared".to_string(),
+        };
+        ValidationCheck {
+            id: "A11Y-005".to_string(),
+            name: "Language declarations match content".to_string(),
+            category: CheckCategory::Accessibility,
+            passed: mismatches.is_empty(),
+            details: Some(details),
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_click_targets(sdom: &SemanticDOM) -> ValidationCheck {
+        let targets = sdom.nonstandard_controls();
+        let details = match targets.first() {
+            Some(first) => format!(
+                "{} controls built from generic elements, e.g. '{}'",
+                targets.len(),
+                first.label
+            ),
+            None => "All controls are native elements".to_string(),
+        };
+        ValidationCheck {
+            id: "A11Y-006".to_string(),
+            name: "Click targets are semantic elements".to_string(),
+            category: CheckCategory::Accessibility,
+            passed: targets.is_empty(),
+            details: Some(details),
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_text_alternatives(sdom: &SemanticDOM) -> ValidationCheck {
+        let problems: Vec<String> = sdom
+            .reading_order()
+            .into_iter()
+            .filter_map(|n| {
+                let problem = match n.role {
+                    SemanticRole::Image => alt_text_problem(n.accessible_name.as_deref()?),
+                    SemanticRole::Link => is_generic_link_text(&n.label).then_some("generic"),
+                    _ => None,
+                }?;
+                Some(format!("{} ({})", n.id, problem))
+            })
+            .collect();
+
+        let details = if problems.is_empty() {
+            "All alt and link texts are specific".to_string()
+        } else {
+            let shown: Vec<&str> = problems.iter().map(String::as_str).take(5).collect();
+            let more = if problems.len() > shown.len() {
+                format!(" (+{} more)", problems.len() - shown.len())
+            } else {
+                String::new()
+            };
+            format!("{} unhelpful: {}{}", problems.len(), shown.join(", "), more)
+        };
+
+        ValidationCheck {
+            id: "A11Y-007".to_string(),
+            name: "Text alternatives are meaningful".to_string(),
+            category: CheckCategory::Accessibility,
+            passed: problems.is_empty(),
+            details: Some(details),
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
     fn check_navigation_exists(sdom: &SemanticDOM) -> ValidationCheck {
-        let has_nav = sdom
-            .index
-            .values()
-            .any(|n| matches!(n.role, SemanticRole::Navigation));
+        let (inferred, declared): (Vec<&SemanticNode>, _) =
+            sdom.query_by_role(&SemanticRole::Navigation).into_iter().partition(|n| n.inferred);
 
         ValidationCheck {
             id: "NAV-001".to_string(),
             name: "Has navigation landmark".to_string(),
             category: CheckCategory::Navigation,
-            passed: has_nav,
-            details: None,
+            passed: !declared.is_empty(),
+            details: (declared.is_empty() && !inferred.is_empty())
+                .then(|| "Navigation inferred, not declared".to_string()),
             weight: 1.0,
+            waiver: None,
         }
     }
 
     fn check_deterministic_fsm(sdom: &SemanticDOM) -> ValidationCheck {
         let is_deterministic = sdom.state_graph.is_deterministic();
+        let metrics = sdom.state_graph.metrics();
 
         ValidationCheck {
             id: "NAV-002".to_string(),
@@ -431,11 +905,15 @@ impl AgentCertification {
             category: CheckCategory::Navigation,
             passed: is_deterministic,
             details: Some(format!(
-                "{} states, {} transitions",
-                sdom.state_graph.states.len(),
-                sdom.state_graph.transitions.len()
+                "{} states, {} transitions, branching {:.1}, diameter {}, {} cycles",
+                metrics.state_count,
+                metrics.transition_count,
+                metrics.branching_factor,
+                metrics.diameter,
+                metrics.cycles().count()
             )),
             weight: 1.0,
+            waiver: None,
         }
     }
 
@@ -452,6 +930,7 @@ impl AgentCertification {
             passed,
             details: Some(format!("{}/{} states reachable", reachable, total_states)),
             weight: 0.75,
+            waiver: None,
         }
     }
 
@@ -474,6 +953,7 @@ impl AgentCertification {
             passed: pct >= 80.0,
             details: Some(format!("{:.0}% coverage", pct)),
             weight: 1.0,
+            waiver: None,
         }
     }
 
@@ -495,13 +975,214 @@ impl AgentCertification {
             passed: pct >= 80.0,
             details: Some(format!("{:.0}% coverage", pct)),
             weight: 0.75,
+            waiver: None,
+        }
+    }
+
+    fn check_classification_confidence(sdom: &SemanticDOM) -> ValidationCheck {
+        let low: Vec<&str> = sdom
+            .low_confidence_nodes(LOW_CONFIDENCE_THRESHOLD)
+            .into_iter()
+            .map(|n| n.id.as_str())
+            .collect();
+
+        let total = sdom.index.len().max(1);
+        let pct = (low.len() as f32 / total as f32) * 100.0;
+
+        let details = if low.is_empty() {
+            "All classifications confident".to_string()
+        } else {
+            let shown: Vec<&str> = low.iter().copied().take(5).collect();
+            let more = if low.len() > shown.len() {
+                format!(" (+{} more)", low.len() - shown.len())
+            } else {
+                String::new()
+            };
+            format!("{} low-confidence: {}{}", low.len(), shown.join(", "), more)
+        };
+
+        ValidationCheck {
+            id: "INTEROP-003".to_string(),
+            name: "Classifications are confident".to_string(),
+            category: CheckCategory::Interoperability,
+            passed: pct <= 20.0,
+            details: Some(details),
+            weight: 0.5,
+            waiver: None,
+        }
+    }
+
+    fn check_framing(sdom: &SemanticDOM) -> ValidationCheck {
+        let headers = &sdom.security_report().headers;
+        let details = match (headers.frame_ancestors(), headers.x_frame_options.as_deref()) {
+            (Some(sources), _) => format!("frame-ancestors {}", sources.join(" ")),
+            (None, Some(xfo)) => format!("X-Frame-Options: {}", xfo),
+            (None, None) => "No framing restrictions".to_string(),
+        };
+
+        ValidationCheck {
+            id: "INTEROP-004".to_string(),
+            name: "Page can be embedded by agent hosts".to_string(),
+            category: CheckCategory::Interoperability,
+            passed: !headers.blocks_framing(),
+            details: Some(details),
+            weight: 0.25,
+            waiver: None,
+        }
+    }
+
+    fn check_referrer_policy(sdom: &SemanticDOM) -> ValidationCheck {
+        let headers = &sdom.security_report().headers;
+
+        ValidationCheck {
+            id: "INTEROP-005".to_string(),
+            name: "Referrer policy keeps URLs private".to_string(),
+            category: CheckCategory::Interoperability,
+            passed: !headers.leaks_referrer(),
+            details: headers.referrer_policy.clone(),
+            weight: 0.25,
+            waiver: None,
+        }
+    }
+
+    fn check_control_density(sdom: &SemanticDOM) -> ValidationCheck {
+        let controls: Vec<_> =
+            sdom.interactables.iter().filter_map(|id| sdom.index.get(id)).collect();
+        let boxes: Vec<_> = controls
+            .iter()
+            .filter_map(|n| n.bounds.filter(|b| !b.is_empty()).map(|b| (*n, b)))
+            .collect();
+
+        let container = |n: &SemanticNode| n.parent.clone().unwrap_or_else(|| "document".into());
+        // Container of each crowded control
+        let mut flagged: Vec<String> = Vec::new();
+        let method = if boxes.is_empty() {
+            flagged.extend(controls.iter().map(|n| container(n)));
+            "controls"
+        } else {
+            // WCAG 2.5.8: a target under 24x24 is fine if a 24px circle on its
+            // center does not reach another target's circle
+            let center = |b: &Bounds| (b.x + b.width / 2.0, b.y + b.height / 2.0);
+            for (i, (node, bounds)) in boxes.iter().enumerate() {
+                if bounds.width >= MIN_TARGET_SIZE && bounds.height >= MIN_TARGET_SIZE {
+                    continue;
+                }
+                let (x, y) = center(bounds);
+                let too_close = boxes.iter().enumerate().any(|(j, (_, other))| {
+                    let (ox, oy) = center(other);
+                    i != j && (x - ox).hypot(y - oy) < MIN_TARGET_SIZE
+                });
+                if too_close {
+                    flagged.push(container(node));
+                }
+            }
+            "small targets"
+        };
+
+        // Containers in reading order with their crowded controls
+        let mut crowded: Vec<(String, usize)> = Vec::new();
+        for id in flagged {
+            match crowded.iter_mut().find(|(c, _)| *c == id) {
+                Some((_, count)) => *count += 1,
+                None => crowded.push((id, 1)),
+            }
+        }
+        if boxes.is_empty() {
+            crowded.retain(|(_, count)| *count > MAX_CONTROLS_PER_CONTAINER);
+        }
+
+        let details = if crowded.is_empty() {
+            None
+        } else {
+            let mut shown: Vec<String> = crowded
+                .iter()
+                .take(5)
+                .map(|(id, count)| format!("{} ({} {})", id, count, method))
+                .collect();
+            if crowded.len() > 5 {
+                shown.push(format!("+{} more", crowded.len() - 5));
+            }
+            Some(format!("{} crowded containers: {}", crowded.len(), shown.join(", ")))
+        };
+        ValidationCheck {
+            id: "INTEROP-006".to_string(),
+            name: "Controls are not crowded".to_string(),
+            category: CheckCategory::Interoperability,
+            passed: crowded.is_empty(),
+            details,
+            weight: 0.25,
+            waiver: None,
         }
     }
 }
 
-impl std::fmt::Display for SemanticRole {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+/// Why an image's alt text does not help, if it does not; empty alt
+/// marks a decorative image and is fine
+fn alt_text_problem(alt: &str) -> Option<&'static str> {
+    let alt = alt.trim();
+    let lower = alt.to_lowercase();
+    if alt.chars().count() > MAX_ALT_CHARS {
+        Some("too long")
+    } else if PLACEHOLDER_ALTS.contains(&lower.as_str()) {
+        Some("placeholder")
+    } else if is_file_name(&lower) {
+        Some("file name")
+    } else {
+        None
+    }
+}
+
+/// Whether lowercased alt text is a file name such as `img_1234.jpg` or a
+/// camera default such as `dsc01234`
+fn is_file_name(alt: &str) -> bool {
+    if alt.contains(char::is_whitespace) || alt.is_empty() {
+        return false;
+    }
+    if IMAGE_EXTENSIONS.iter().any(|ext| alt.ends_with(ext)) {
+        return true;
+    }
+    ["img", "dsc", "dscn", "pxl", "screenshot"].iter().any(|prefix| {
+        alt.strip_prefix(prefix).is_some_and(|rest| {
+            rest.trim_start_matches(['_', '-']).starts_with(|c: char| c.is_ascii_digit())
+        })
+    })
+}
+
+/// Whether link text is a phrase like "click here" that needs context
+fn is_generic_link_text(label: &str) -> bool {
+    let text = label
+        .trim()
+        .trim_end_matches(|c: char| c.is_ascii_punctuation() || "…»→›".contains(c))
+        .trim()
+        .to_lowercase();
+    GENERIC_LINK_TEXTS.contains(&text.as_str())
+}
+
+impl SemanticDOM {
+    /// Certify the document in its current state
+    ///
+    /// Same as [`AgentCertification::certify`]. Parsing with
+    /// [`Config::validate`](crate::Config::validate) set (the default)
+    /// already stores the result in [`SemanticDOM::certification`]; call
+    /// this again after editing the document.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<nav><a href="/">Home</a></nav><main><h1>Docs</h1></main>"#;
+    /// let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let score = sdom.certification.as_ref().unwrap().score;
+    ///
+    /// let main = sdom.landmarks[1].clone();
+    /// sdom.remove_node(&main).unwrap();
+    /// assert!(sdom.certify().score < score);
+    ///
+    /// let config = Config::builder().validate(false).build().unwrap();
+    /// assert!(SemanticDOM::parse(html, config).unwrap().certification.is_none());
+    /// ```
+    pub fn certify(&self) -> AgentCertification {
+        AgentCertification::certify(self)
     }
 }
 
@@ -528,11 +1209,161 @@ mod tests {
         assert!(!cert.checks.is_empty());
     }
 
+    #[test]
+    fn test_low_confidence_check() {
+        let html = r#"<main><button>Frobnicate</button><button>Zap</button><a href="/">Home</a></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let cert = AgentCertification::certify(&sdom);
+
+        let check = cert.checks.iter().find(|c| c.id == "INTEROP-003").unwrap();
+        assert!(!check.passed);
+        assert!(check.details.as_deref().unwrap().starts_with("2 low-confidence"));
+    }
+
+    #[test]
+    fn test_duplicate_ids_check() {
+        let html = r#"<main><button id="a">One</button><button id="a">Two</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let cert = AgentCertification::certify(&sdom);
+
+        let check = cert.checks.iter().find(|c| c.id == "STRUCT-004").unwrap();
+        assert!(!check.passed);
+        assert_eq!(check.details.as_deref(), Some("1 duplicate HTML ids: a"));
+    }
+
+    #[test]
+    fn test_framing_check_uses_response_headers() {
+        let body = b"<main><button>Pay</button></main>";
+        let headers = [("X-Frame-Options", "DENY")];
+        let sdom = SemanticDOM::parse_response(body, headers, Config::default()).unwrap();
+        let cert = AgentCertification::certify(&sdom);
+
+        let check = cert.checks.iter().find(|c| c.id == "INTEROP-004").unwrap();
+        assert!(!check.passed);
+        assert_eq!(check.details.as_deref(), Some("X-Frame-Options: DENY"));
+    }
+
+    #[test]
+    fn test_landmark_checks() {
+        let html = r#"<header><h1>Shop</h1></header>
+            <nav><a href="/">Home</a></nav><nav><a href="/">Home</a></nav>
+            <nav aria-label="Legal"><a href="/terms">Terms</a></nav>
+            <main><article><header><h2>News</h2></header></article>
+            <div role="contentinfo">Fine print</div></main><main></main>"#;
+        let cert = SemanticDOM::parse(html, Config::default()).unwrap().certify();
+        let check = |id: &str| cert.checks.iter().find(|c| c.id == id).unwrap().clone();
+
+        let main = check("STRUCT-005");
+        assert!(!main.passed);
+        assert_eq!(main.details.as_deref(), Some("Found 2 main landmarks"));
+        let labels = check("STRUCT-006");
+        let details = labels.details.unwrap();
+        assert!(details.starts_with("3 unlabeled or same-labeled: sdom_nav_"), "{}", details);
+        assert!(details.ends_with("sdom_main_14"), "{}", details);
+        // The article header is scoped; the explicit contentinfo is not
+        let nested = check("STRUCT-007");
+        assert_eq!(nested.details.as_deref(), Some("1 nested: sdom_div_13"));
+
+        let html = r#"<header>Shop</header><nav aria-label="Main"><a href="/">Home</a></nav>
+            <nav aria-label="Legal"><a href="/terms">Terms</a></nav><main>Hi</main>"#;
+        let cert = SemanticDOM::parse(html, Config::default()).unwrap().certify();
+        let passed = |id: &str| cert.checks.iter().find(|c| c.id == id).unwrap().passed;
+        assert!(passed("STRUCT-005") && passed("STRUCT-006") && passed("STRUCT-007"));
+    }
+
+    #[test]
+    fn test_nested_explicit_roles_on_native_tags() {
+        let html = r#"<header><h1>Shop</h1></header><main><article>
+            <header><h2>News</h2></header><p>Story</p>
+            <header role="banner">Promo</header><footer role="contentinfo">Fine print</footer>
+            </article></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let explicit: Vec<&str> = sdom
+            .landmarks
+            .iter()
+            .filter_map(|id| sdom.index.get(id))
+            .filter(|n| n.explicit_role)
+            .map(|n| n.id.as_str())
+            .collect();
+        let cert = sdom.certify();
+        let nested = cert.checks.iter().find(|c| c.id == "STRUCT-007").unwrap();
+        assert!(!nested.passed);
+        assert_eq!(explicit.len(), 2, "{:?}", explicit);
+        assert_eq!(nested.details, Some(format!("2 nested: {}", explicit.join(", "))));
+    }
+
+    #[test]
+    fn test_control_density_check() {
+        let links: String =
+            (0..30).map(|i| format!(r#"<a href="/p{}">Page {}</a>"#, i, i)).collect();
+        let html = format!(
+            r#"<nav id="pages">{}</nav><main><button id="a">A</button><button id="b">B</button>
+            <button id="c">C</button></main>"#,
+            links
+        );
+        let mut sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let density = |sdom: &SemanticDOM| {
+            let cert = sdom.certify();
+            cert.checks.into_iter().find(|c| c.id == "INTEROP-006").unwrap().details
+        };
+        let details = density(&sdom);
+        assert_eq!(details.as_deref(), Some("1 crowded containers: sdom_pages (30 controls)"));
+
+        // With geometry only spacing counts: a and b are 16px icons 20px apart
+        let rect = |x: f64, size: f64| Some(Bounds { x, y: 0.0, width: size, height: size });
+        sdom.set_bounds("sdom_a", rect(0.0, 16.0)).unwrap();
+        sdom.set_bounds("sdom_b", rect(20.0, 16.0)).unwrap();
+        sdom.set_bounds("sdom_c", rect(200.0, 16.0)).unwrap();
+        let details = density(&sdom);
+        let expected = "1 crowded containers: sdom_main_31 (2 small targets)";
+        assert_eq!(details.as_deref(), Some(expected));
+        sdom.set_bounds("sdom_b", rect(40.0, 16.0)).unwrap();
+        assert_eq!(density(&sdom), None);
+    }
+
+    #[test]
+    fn test_text_alternatives_check() {
+        let long = "a".repeat(151);
+        let html = format!(
+            r#"<main><img src="a.jpg" alt="IMG_1234.JPG"><img src="b.jpg" alt="Photo">
+            <img src="c.jpg" alt="{}"><img src="d.jpg" alt=""><img src="e.jpg" alt="DSC-0042">
+            <img src="f.jpg" alt="Team at the 2024 offsite">
+            <a href="/p">Read more »</a><a href="/q">Read more about pricing</a></main>"#,
+            long
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let cert = AgentCertification::certify(&sdom);
+
+        let check = cert.checks.iter().find(|c| c.id == "A11Y-007").unwrap();
+        assert!(!check.passed);
+        let details = check.details.as_deref().unwrap();
+        let expected = "5 unhelpful: sdom_img_2 (file name), sdom_img_3 (placeholder), \
+            sdom_img_4 (too long), sdom_img_6 (file name), sdom_a_8 (generic)";
+        assert_eq!(details, expected);
+        assert_eq!(alt_text_problem("dsc-0042"), Some("file name"));
+        assert!(is_generic_link_text("Click here!"));
+    }
+
+    #[test]
+    fn test_catalog_matches_checks() {
+        let sdom = SemanticDOM::parse("<main></main>", Config::default()).unwrap();
+        let cert = AgentCertification::certify(&sdom);
+        let run: Vec<_> = cert.checks.iter().map(|c| (c.id.as_str(), c.name.as_str())).collect();
+        let listed: Vec<_> = all_checks().iter().map(|c| (c.id, c.name)).collect();
+        assert_eq!(run, listed);
+        for (check, info) in cert.checks.iter().zip(all_checks()) {
+            assert_eq!((check.category, check.weight), (info.category, info.weight), "{}", info.id);
+        }
+    }
+
     #[test]
     fn test_certification_levels() {
         assert!(CertificationLevel::AAA > CertificationLevel::AA);
         assert!(CertificationLevel::AA > CertificationLevel::A);
         assert!(CertificationLevel::A > CertificationLevel::None);
+        assert_eq!(CertificationLevel::for_score(90), CertificationLevel::AAA);
+        assert_eq!(CertificationLevel::for_score(69), CertificationLevel::A);
+        assert_eq!(CertificationLevel::for_score(49), CertificationLevel::None);
     }
 
     #[test]
@@ -0,0 +1,218 @@
+//! Repeated components
+//!
+//! Listing pages repeat one component many times: product cards, table
+//! rows, comments. Enumerating every instance costs tokens without telling
+//! an agent anything new, so sibling elements of the same shape (tag and
+//! classes) are clustered, and summaries describe a cluster once by the
+//! parts each instance has, such as `card ×24 (title, price, add-to-cart)`.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Fewest instances that make a cluster
+pub const MIN_CLUSTER_SIZE: usize = 3;
+
+/// A component repeated among siblings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cluster {
+    /// Name of the component: its first class, else its tag
+    pub name: String,
+    /// Semantic ID of the element holding the instances, if it is a node
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub container: Option<String>,
+    /// What an instance is made of, e.g. `title`, `price`, `add-to-cart`
+    pub parts: Vec<String>,
+    /// IDs of the nodes in each instance, in page order
+    pub instances: Vec<Vec<String>>,
+}
+
+impl Cluster {
+    /// Number of instances
+    pub fn count(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// The cluster as `card ×24 (title, price, add-to-cart)`
+    pub fn summary(&self) -> String {
+        format!("{} ×{} ({})", self.name, self.count(), self.parts.join(", "))
+    }
+}
+
+/// What a node contributes to an instance's parts
+fn part(node: &SemanticNode) -> Option<String> {
+    match node.role {
+        SemanticRole::Heading => Some("title".to_string()),
+        SemanticRole::Image => Some("image".to_string()),
+        SemanticRole::Link => Some("link".to_string()),
+        ref role if role.is_interactable() => {
+            let words: Vec<String> =
+                node.label.split_whitespace().take(3).map(str::to_lowercase).collect();
+            Some(match words.is_empty() {
+                true => role.as_str().to_string(),
+                false => words.join("-"),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Nodes and parts of one instance
+fn instance(
+    element: ElementRef,
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> (Vec<String>, Vec<String>) {
+    let mut ids = Vec::new();
+    let mut parts: Vec<String> = Vec::new();
+    let mut cells = 0;
+    for el in element.descendants().filter_map(ElementRef::wrap) {
+        if matches!(el.value().name(), "td" | "th") {
+            cells += 1;
+        }
+        let Some(node) = nodes.get(&el.id()).and_then(|id| index.get(id)) else {
+            continue;
+        };
+        ids.push(node.id.clone());
+        if let Some(part) = part(node).filter(|p| !parts.contains(p)) {
+            parts.push(part);
+        }
+    }
+    let text: String = element.text().collect::<Vec<_>>().join(" ");
+    if crate::commerce::find_price(&text).is_some() {
+        // Prices sit under the title
+        let at = parts.iter().position(|p| p == "title").map_or(0, |i| i + 1);
+        parts.insert(at, "price".to_string());
+    }
+    if cells > 0 {
+        parts.insert(0, format!("{} cells", cells));
+    }
+    (ids, parts)
+}
+
+/// Find repeated components, given the node created for each element
+pub(crate) fn clusters(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> Vec<Cluster> {
+    let mut clusters = Vec::new();
+    let mut stack = vec![document.root_element()];
+    while let Some(element) = stack.pop() {
+        let children: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+        let mut shapes: IndexMap<(&str, Vec<&str>), Vec<ElementRef>> = IndexMap::new();
+        for child in &children {
+            let mut classes: Vec<&str> = child.value().classes().collect();
+            classes.sort_unstable();
+            shapes.entry((child.value().name(), classes)).or_default().push(*child);
+        }
+        let mut clustered: Vec<NodeId> = Vec::new();
+        for ((tag, _), members) in shapes {
+            if members.len() < MIN_CLUSTER_SIZE {
+                continue;
+            }
+            // Parts most instances share, in order of first appearance
+            let mut counts: IndexMap<String, usize> = IndexMap::new();
+            let mut instances = Vec::new();
+            for member in &members {
+                let (ids, member_parts) = instance(*member, nodes, index);
+                for part in member_parts {
+                    *counts.entry(part).or_default() += 1;
+                }
+                instances.push(ids);
+            }
+            let parts: Vec<String> = counts
+                .into_iter()
+                .filter(|(_, n)| n * 2 > members.len())
+                .map(|(part, _)| part)
+                .collect();
+            // A single link or button per item is a menu or a toolbar, and
+            // items without parts in common are not one component; rows
+            // count by their cells
+            if parts.is_empty() || parts.len() < 2 && tag != "tr" {
+                continue;
+            }
+            clustered.extend(members.iter().map(|m| m.id()));
+            let name = members[0].value().classes().next().unwrap_or(tag);
+            clusters.push(Cluster {
+                name: name.to_string(),
+                container: nodes.get(&element.id()).cloned(),
+                parts,
+                instances,
+            });
+        }
+        // Instances are not searched for clusters of their own
+        let rest = children.into_iter().filter(|c| !clustered.contains(&c.id()));
+        stack.extend(rest.rev());
+    }
+    clusters
+}
+
+impl SemanticDOM {
+    /// Get the components repeated among siblings, in page order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let card = |name: &str| {
+    ///     format!(r#"<div class="card"><h3>{name}</h3><span>$9.99</span>
+    ///         <button>Add to cart</button></div>"#)
+    /// };
+    /// let html = format!("<main>{}{}{}</main>", card("Mug"), card("Cap"), card("Tee"));
+    /// let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+    ///
+    /// let clusters = sdom.clusters();
+    /// assert_eq!(clusters[0].summary(), "card ×3 (title, price, add-to-cart)");
+    /// assert_eq!(clusters[0].instances[1], ["sdom_h3_4", "sdom_button_5"]);
+    /// ```
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// Nodes in clusters past their first instance
+    pub(crate) fn repeated_nodes(&self) -> ahash::AHashSet<&str> {
+        self.clusters
+            .iter()
+            .flat_map(|c| c.instances.iter().skip(1).flatten())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_clusters() {
+        let row = |i: usize| {
+            let view = format!(r#"<a href="/o/{i}">View</a>"#);
+            format!("<tr><td>Order {i}</td><td>Shipped</td><td>{view}</td></tr>")
+        };
+        let comment = |who: &str| {
+            format!(r#"<article class="comment"><a href="/u/{who}">{who}</a>
+                <p>Nice</p><button>Reply</button></article>"#)
+        };
+        let html = format!(
+            r#"<nav><ul><li><a href="/">Home</a></li><li><a href="/a">A</a></li>
+            <li><a href="/b">B</a></li></ul></nav><main><table><tbody>{}</tbody></table>
+            <section>{}</section></main>"#,
+            (1..=4).map(row).collect::<String>(),
+            ["ann", "bob", "cy"].map(comment).concat()
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let summaries: Vec<String> = sdom.clusters().iter().map(Cluster::summary).collect();
+        assert_eq!(summaries, ["tr ×4 (3 cells, link)", "comment ×3 (link, reply)"]);
+
+        let summary = sdom.to_agent_summary();
+        assert!(summary.contains("\nREPEATED: tr ×4 (3 cells, link), comment ×3"), "{}", summary);
+        // Only the first instance's actions are listed
+        assert_eq!(summary.matches("Reply").count(), 1, "{}", summary);
+    }
+}
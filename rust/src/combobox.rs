@@ -0,0 +1,285 @@
+//! Combobox and autocomplete widgets
+//!
+//! A combobox is a text field or button paired with a popup of options: an
+//! ARIA `role="combobox"` controlling a `role="listbox"`, or an `<input
+//! list>` backed by a `<datalist>`. Classifying only the field hides the
+//! popup, so each one is modeled as a [`Combobox`] with its options and
+//! open/closed state, and opening it becomes a state-graph transition.
+
+use crate::parser::SemanticDOM;
+use crate::types::{State, Transition};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// How a combobox completes typed text (`aria-autocomplete`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Autocomplete {
+    /// No completion; options do not depend on the typed text
+    #[default]
+    None,
+    /// The rest of the best match is inserted inline
+    Inline,
+    /// The popup lists matching options
+    List,
+    /// Both inline completion and a filtered list
+    Both,
+}
+
+impl Autocomplete {
+    fn from_attr(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "inline" => Autocomplete::Inline,
+            "list" => Autocomplete::List,
+            "both" => Autocomplete::Both,
+            _ => Autocomplete::None,
+        }
+    }
+}
+
+/// Where a combobox's options come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComboboxSource {
+    /// `role="combobox"` with a `role="listbox"` popup
+    Aria,
+    /// `<input list>` with a `<datalist>`
+    Datalist,
+}
+
+/// An option offered by a combobox popup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboboxOption {
+    /// Visible text of the option
+    pub label: String,
+    /// Submitted value, when it differs from the label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Whether the option is selected or is the active descendant
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+}
+
+/// A field with a popup of options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Combobox {
+    /// ID of the combobox node
+    pub id: String,
+    /// Where the options come from
+    pub source: ComboboxSource,
+    /// How typed text is completed
+    pub autocomplete: Autocomplete,
+    /// Whether the popup is currently open (`aria-expanded`)
+    pub expanded: bool,
+    /// ID of the popup node, when the popup became a node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub popup_id: Option<String>,
+    /// Options currently in the popup; often empty until the user types
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<ComboboxOption>,
+}
+
+/// Find comboboxes among parsed elements, given the node created for each
+pub(crate) fn comboboxes(document: &Html, nodes: &AHashMap<NodeId, String>) -> Vec<Combobox> {
+    let root = document.root_element();
+    let by_id = |id: &str| {
+        root.descendants()
+            .filter_map(ElementRef::wrap)
+            .find(|e| e.value().id() == Some(id))
+    };
+
+    let mut found = Vec::new();
+    for element in root.descendants().filter_map(ElementRef::wrap) {
+        let Some(id) = nodes.get(&element.id()) else {
+            continue;
+        };
+        let el = element.value();
+        let aria = el.attr("role").is_some_and(|r| r.trim().eq_ignore_ascii_case("combobox"));
+        let datalist = el.name() == "input" && el.attr("list").is_some();
+        if !aria && !datalist {
+            continue;
+        }
+
+        let autocomplete = el.attr("aria-autocomplete").map(Autocomplete::from_attr);
+        let combobox = if aria {
+            // ARIA 1.2 names the popup with aria-controls, 1.1 with aria-owns;
+            // older markup nests the listbox inside the combobox
+            let popup = ["aria-controls", "aria-owns"]
+                .iter()
+                .filter_map(|attr| el.attr(attr))
+                .flat_map(str::split_whitespace)
+                .find_map(by_id)
+                .or_else(|| element.descendants().filter_map(ElementRef::wrap).find(is_listbox));
+            let active = el.attr("aria-activedescendant").map(str::trim);
+            Combobox {
+                id: id.clone(),
+                source: ComboboxSource::Aria,
+                autocomplete: autocomplete.unwrap_or_default(),
+                expanded: el.attr("aria-expanded").is_some_and(|v| v.trim() == "true"),
+                popup_id: popup.and_then(|p| nodes.get(&p.id()).cloned()),
+                options: popup.map(|p| listbox_options(p, active)).unwrap_or_default(),
+            }
+        } else {
+            let list = el.attr("list").and_then(|l| by_id(l.trim()));
+            Combobox {
+                id: id.clone(),
+                source: ComboboxSource::Datalist,
+                // Browsers filter datalist suggestions as the user types
+                autocomplete: autocomplete.unwrap_or(Autocomplete::List),
+                expanded: false,
+                popup_id: None,
+                options: list.map(datalist_options).unwrap_or_default(),
+            }
+        };
+        found.push(combobox);
+    }
+    found
+}
+
+fn is_listbox(element: &ElementRef) -> bool {
+    element.value().attr("role").is_some_and(|r| r.trim().eq_ignore_ascii_case("listbox"))
+}
+
+fn listbox_options(popup: ElementRef, active: Option<&str>) -> Vec<ComboboxOption> {
+    popup
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| {
+            e.value().attr("role").is_some_and(|r| r.trim().eq_ignore_ascii_case("option"))
+        })
+        .filter_map(|option| {
+            let el = option.value();
+            let label = text(option)?;
+            let selected = el.attr("aria-selected").is_some_and(|v| v.trim() == "true")
+                || (active.is_some() && el.id() == active);
+            Some(ComboboxOption {
+                value: el.attr("data-value").map(str::to_string).filter(|v| *v != label),
+                label,
+                selected,
+            })
+        })
+        .collect()
+}
+
+fn datalist_options(list: ElementRef) -> Vec<ComboboxOption> {
+    list.descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "option")
+        .filter_map(|option| {
+            let el = option.value();
+            let value = el.attr("value").map(str::to_string);
+            let label = el
+                .attr("label")
+                .map(str::to_string)
+                .or_else(|| text(option))
+                .or_else(|| value.clone())?;
+            Some(ComboboxOption {
+                value: value.filter(|v| *v != label),
+                label,
+                selected: false,
+            })
+        })
+        .collect()
+}
+
+fn text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+impl SemanticDOM {
+    /// Add an open state for each combobox, entered and left by the field
+    pub(crate) fn add_combobox_states(&mut self) {
+        let initial = self.initial_state_id();
+        for combobox in &self.comboboxes {
+            let Some(node) = self.index.get(&combobox.id) else {
+                continue;
+            };
+            let state_id = format!("state_open_{}", combobox.id);
+            self.state_graph.states.push(State {
+                id: state_id.clone(),
+                name: format!("{} (open)", node.label),
+                description: Some(format!("{} options", combobox.options.len())),
+                url_pattern: None,
+                is_initial: false,
+                is_terminal: false,
+                anchor: None,
+            });
+            self.state_graph.transitions.push(Transition {
+                from: initial.clone(),
+                to: state_id.clone(),
+                trigger: combobox.id.clone(),
+                action: Some("open".to_string()),
+                guard: None,
+                request: None,
+                params: IndexMap::new(),
+            });
+            self.state_graph.transitions.push(Transition {
+                from: state_id,
+                to: initial.clone(),
+                trigger: combobox.id.clone(),
+                action: Some("close".to_string()),
+                guard: None,
+                request: None,
+                params: IndexMap::new(),
+            });
+        }
+    }
+
+    /// Get the comboboxes on the page, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Autocomplete, Config, SemanticDOM};
+    ///
+    /// let html = r#"<form>
+    ///     <input name="city" list="cities" aria-label="City">
+    ///     <datalist id="cities"><option value="Oslo"><option value="Rome"></datalist>
+    /// </form>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let combobox = &sdom.comboboxes()[0];
+    /// assert_eq!(combobox.autocomplete, Autocomplete::List);
+    /// assert_eq!(combobox.options[1].label, "Rome");
+    /// ```
+    pub fn comboboxes(&self) -> &[Combobox] {
+        &self.comboboxes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_aria_combobox_with_listbox() {
+        let html = r#"<main><label for="q">Search</label>
+            <input id="q" role="combobox" aria-autocomplete="both" aria-expanded="true"
+                   aria-controls="results" aria-activedescendant="r2">
+            <ul id="results" role="listbox">
+                <li role="option" id="r1" data-value="42">Rust book</li>
+                <li role="option" id="r2">Rust  mug</li>
+            </ul></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let combobox = &sdom.comboboxes()[0];
+        assert_eq!(combobox.source, ComboboxSource::Aria);
+        assert_eq!(combobox.autocomplete, Autocomplete::Both);
+        assert!(combobox.expanded);
+        assert_eq!(combobox.popup_id.as_deref(), Some("sdom_results"));
+        assert_eq!(combobox.options[0].value.as_deref(), Some("42"));
+        assert_eq!(combobox.options[1].label, "Rust mug");
+        assert!(!combobox.options[0].selected && combobox.options[1].selected);
+
+        let graph = &sdom.state_graph;
+        let open = graph.transitions.iter().find(|t| t.action.as_deref() == Some("open")).unwrap();
+        assert_eq!(open.trigger, "sdom_q");
+        assert!(graph.transitions.iter().any(|t| t.from == open.to && t.to == "initial"));
+        assert!(graph.is_deterministic());
+    }
+}
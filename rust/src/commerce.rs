@@ -0,0 +1,424 @@
+//! Product, price and availability extraction for commerce pages
+//!
+//! Structured data (schema.org JSON-LD and microdata) is used when present.
+//! Otherwise products are inferred from add-to-cart buttons and the price
+//! shown in the card that contains them.
+
+use crate::parser::SemanticDOM;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// A price with its currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Price {
+    /// Numeric amount
+    pub amount: f64,
+    /// ISO 4217 currency code, when it could be determined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+/// Stock status of a product
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Availability {
+    /// Available to buy now
+    InStock,
+    /// Not currently available
+    OutOfStock,
+    /// Can be ordered before release
+    PreOrder,
+    /// Can be ordered but ships later
+    BackOrder,
+    /// Only a few left
+    LimitedAvailability,
+    /// No longer sold
+    Discontinued,
+}
+
+/// Where a product's information came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProductSource {
+    /// `<script type="application/ld+json">` schema.org Product
+    JsonLd,
+    /// `itemtype="https://schema.org/Product"` microdata
+    Microdata,
+    /// Inferred from an add-to-cart button and nearby text
+    Heuristic,
+}
+
+/// A product offered on the page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductInfo {
+    /// Product title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Current price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+    /// Stock status
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Availability>,
+    /// ID of the button that adds this product to the cart
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_to_cart_id: Option<String>,
+    /// How the product was detected
+    pub source: ProductSource,
+}
+
+/// Currency symbols and codes recognised in price text
+const CURRENCIES: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("USD", "USD"),
+    ("EUR", "EUR"),
+    ("GBP", "GBP"),
+    ("JPY", "JPY"),
+    ("CAD", "CAD"),
+    ("AUD", "AUD"),
+    ("CHF", "CHF"),
+    ("INR", "INR"),
+];
+
+/// How many ancestors of an add-to-cart button to search for its card
+const MAX_CARD_LEVELS: usize = 5;
+
+/// Find the first price in free text (`$19.99`, `1.299,00 €`, `EUR 5`)
+pub(crate) fn find_price(text: &str) -> Option<Price> {
+    currency_amounts(text).into_iter().find_map(|(code, raw)| {
+        Some(Price {
+            amount: parse_amount(raw)?,
+            currency: Some(code.to_string()),
+        })
+    })
+}
+
+/// Amounts written next to a currency in free text, as the currency code
+/// and the unparsed number, in the order they appear
+pub(crate) fn currency_amounts(text: &str) -> Vec<(&'static str, &str)> {
+    let number_len = |s: &str| {
+        s.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(s.len())
+    };
+    let mut amounts = Vec::new();
+    for (start, _) in text.char_indices() {
+        let rest = &text[start..];
+        if let Some((symbol, code)) = CURRENCIES.iter().find(|(s, _)| rest.starts_with(s)) {
+            // Currency before the amount
+            let after = rest[symbol.len()..].trim_start();
+            if after.starts_with(|c: char| c.is_ascii_digit()) {
+                amounts.push((*code, &after[..number_len(after)]));
+            }
+        }
+        if rest.starts_with(|c: char| c.is_ascii_digit())
+            && !text[..start].ends_with(|c: char| c.is_ascii_digit() || c == '.' || c == ',')
+        {
+            // Amount before the currency
+            let len = number_len(rest);
+            let after = rest[len..].trim_start();
+            if let Some((_, code)) = CURRENCIES.iter().find(|(s, _)| after.starts_with(s)) {
+                amounts.push((*code, &rest[..len]));
+            }
+        }
+    }
+    amounts
+}
+
+/// Parse a number with either `,` or `.` as the decimal separator
+fn parse_amount(raw: &str) -> Option<f64> {
+    let raw = raw.trim_end_matches(['.', ',']);
+    if !raw.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let normalized = match (raw.rfind('.'), raw.rfind(',')) {
+        // Both present: the last one is the decimal separator
+        (Some(dot), Some(comma)) if comma > dot => raw.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => raw.replace(',', ""),
+        // A lone comma followed by exactly two digits is a decimal comma
+        (None, Some(comma)) if raw.len() - comma == 3 && raw.matches(',').count() == 1 => {
+            raw.replace(',', ".")
+        }
+        (None, Some(_)) => raw.replace(',', ""),
+        _ => raw.to_string(),
+    };
+    normalized.parse().ok()
+}
+
+/// Map schema.org availability URLs or free text to [`Availability`]
+pub(crate) fn parse_availability(text: &str) -> Option<Availability> {
+    let text = text.to_lowercase().replace([' ', '-', '_'], "");
+    let value = text.rsplit('/').next().unwrap_or(&text);
+    [
+        ("outofstock", Availability::OutOfStock),
+        ("soldout", Availability::OutOfStock),
+        ("discontinued", Availability::Discontinued),
+        ("preorder", Availability::PreOrder),
+        ("backorder", Availability::BackOrder),
+        ("limitedavailability", Availability::LimitedAvailability),
+        ("instock", Availability::InStock),
+    ]
+    .into_iter()
+    .find(|(needle, _)| value.contains(needle))
+    .map(|(_, availability)| availability)
+}
+
+/// Extract schema.org products from JSON-LD and microdata
+pub(crate) fn structured_products(document: &Html) -> Vec<ProductInfo> {
+    let mut products = Vec::new();
+
+    if let Ok(selector) = Selector::parse(r#"script[type="application/ld+json"]"#) {
+        for script in document.select(&selector) {
+            let json = script.text().collect::<String>();
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&json) {
+                collect_json_ld(&value, &mut products);
+            }
+        }
+    }
+
+    if let Ok(selector) = Selector::parse(r#"[itemtype$="schema.org/Product"]"#) {
+        for item in document.select(&selector) {
+            products.push(microdata_product(item));
+        }
+    }
+
+    products
+}
+
+fn collect_json_ld(value: &serde_json::Value, products: &mut Vec<ProductInfo>) {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_json_ld(v, products)),
+        Value::Object(map) => {
+            let is_product = match map.get("@type") {
+                Some(Value::String(t)) => t == "Product",
+                Some(Value::Array(types)) => types.iter().any(|t| t == "Product"),
+                _ => false,
+            };
+            if is_product {
+                let offer = match map.get("offers") {
+                    Some(Value::Array(offers)) => offers.first(),
+                    other => other,
+                };
+                let field = |key: &str| {
+                    offer.and_then(|o| o.get(key)).and_then(|v| match v {
+                        Value::String(s) => Some(s.clone()),
+                        Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    })
+                };
+                let amount = field("price")
+                    .or_else(|| field("lowPrice"))
+                    .and_then(|p| parse_amount(&p));
+                products.push(ProductInfo {
+                    name: map.get("name").and_then(Value::as_str).map(str::to_string),
+                    price: amount.map(|amount| Price {
+                        amount,
+                        currency: field("priceCurrency"),
+                    }),
+                    availability: field("availability").and_then(|a| parse_availability(&a)),
+                    add_to_cart_id: None,
+                    source: ProductSource::JsonLd,
+                });
+            } else if let Some(graph) = map.get("@graph") {
+                collect_json_ld(graph, products);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn microdata_product(item: ElementRef) -> ProductInfo {
+    let prop = |name: &str| -> Option<String> {
+        let selector = Selector::parse(&format!(r#"[itemprop="{}"]"#, name)).ok()?;
+        let el = item.select(&selector).next()?;
+        let v = el.value();
+        v.attr("content")
+            .or_else(|| v.attr("href"))
+            .map(str::to_string)
+            .or_else(|| Some(el.text().collect::<String>().trim().to_string()))
+            .filter(|s| !s.is_empty())
+    };
+
+    let amount = prop("price")
+        .and_then(|p| parse_amount(p.trim()).or_else(|| find_price(&p).map(|p| p.amount)));
+    ProductInfo {
+        name: prop("name"),
+        price: amount.map(|amount| Price {
+            amount,
+            currency: prop("priceCurrency"),
+        }),
+        availability: prop("availability").and_then(|a| parse_availability(&a)),
+        add_to_cart_id: None,
+        source: ProductSource::Microdata,
+    }
+}
+
+/// Infer a product from the card containing an add-to-cart button
+///
+/// Climbs from the button to the nearest ancestor whose text contains a
+/// price and takes the card's first heading (or link) as the title. If no
+/// price is found nearby, only the button is recorded.
+pub(crate) fn card_product(button: ElementRef, button_id: &str) -> ProductInfo {
+    let card = button
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .take(MAX_CARD_LEVELS)
+        .find(|a| find_price(&a.text().collect::<String>()).is_some());
+
+    let mut product = ProductInfo {
+        name: None,
+        price: None,
+        availability: None,
+        add_to_cart_id: Some(button_id.to_string()),
+        source: ProductSource::Heuristic,
+    };
+    let Some(card) = card else {
+        return product;
+    };
+
+    let text = card.text().collect::<Vec<_>>().join(" ");
+    product.name = ["h1, h2, h3, h4, h5, h6", "a[href]"].iter().find_map(|s| {
+        let selector = Selector::parse(s).ok()?;
+        let el = card.select(&selector).next()?;
+        let name = el.text().collect::<String>().trim().to_string();
+        (!name.is_empty()).then_some(name)
+    });
+    product.price = find_price(&text);
+    product.availability = parse_availability(&text);
+    product
+}
+
+/// Combine structured products with the ones inferred from buttons
+///
+/// Structured data wins; inferred products only contribute their button,
+/// matched by name or, for a single-product page, by position.
+pub(crate) fn merge_products(
+    mut structured: Vec<ProductInfo>,
+    inferred: Vec<ProductInfo>,
+) -> Vec<ProductInfo> {
+    if structured.is_empty() {
+        // A button with no price nearby is not enough to call it a product
+        return inferred.into_iter().filter(|p| p.price.is_some()).collect();
+    }
+
+    if structured.len() == 1 && structured[0].add_to_cart_id.is_none() {
+        structured[0].add_to_cart_id = inferred.first().and_then(|p| p.add_to_cart_id.clone());
+        return structured;
+    }
+
+    for product in &mut structured {
+        product.add_to_cart_id = inferred
+            .iter()
+            .find(|p| p.name.is_some() && p.name == product.name)
+            .and_then(|p| p.add_to_cart_id.clone());
+    }
+    structured
+}
+
+impl SemanticDOM {
+    /// Get the products offered on the page
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Availability, Config, SemanticDOM};
+    ///
+    /// let html = r#"<main><article>
+    ///     <h2>Trail Shoe</h2><span>$89.00</span><p>In stock</p>
+    ///     <button>Add to cart</button>
+    /// </article></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let product = &sdom.products()[0];
+    /// assert_eq!(product.name.as_deref(), Some("Trail Shoe"));
+    /// assert_eq!(product.price.as_ref().unwrap().amount, 89.0);
+    /// assert_eq!(product.availability, Some(Availability::InStock));
+    /// assert!(product.add_to_cart_id.is_some());
+    /// ```
+    pub fn products(&self) -> &[ProductInfo] {
+        &self.products
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_find_price_formats() {
+        let cases = [
+            ("Now $1,299.99!", 1299.99, "USD"),
+            ("Preis: 1.299,00 €", 1299.0, "EUR"),
+            ("£5", 5.0, "GBP"),
+            ("EUR 12,50 incl. VAT", 12.5, "EUR"),
+            ("Only 3 left, 20 USD", 20.0, "USD"),
+        ];
+        for (text, amount, currency) in cases {
+            let price = find_price(text).unwrap_or_else(|| panic!("{}", text));
+            assert_eq!(price.amount, amount, "{}", text);
+            assert_eq!(price.currency.as_deref(), Some(currency), "{}", text);
+        }
+        assert!(find_price("Ships in 3 days").is_none());
+    }
+
+    #[test]
+    fn test_json_ld_product_linked_to_button() {
+        let html = r#"
+            <html><head><script type="application/ld+json">
+            {"@context": "https://schema.org", "@graph": [
+                {"@type": "BreadcrumbList"},
+                {"@type": "Product", "name": "Kettle",
+                 "offers": {"@type": "Offer", "price": 39.5, "priceCurrency": "EUR",
+                            "availability": "https://schema.org/OutOfStock"}}
+            ]}
+            </script></head>
+            <body><main><h1>Kettle</h1><button id="buy">Add to basket</button></main></body></html>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let products = sdom.products();
+
+        assert_eq!(products.len(), 1);
+        let kettle = &products[0];
+        assert_eq!(kettle.source, ProductSource::JsonLd);
+        assert_eq!(
+            kettle.price,
+            Some(Price {
+                amount: 39.5,
+                currency: Some("EUR".to_string())
+            })
+        );
+        assert_eq!(kettle.availability, Some(Availability::OutOfStock));
+        assert_eq!(kettle.add_to_cart_id.as_deref(), Some("sdom_buy"));
+    }
+
+    #[test]
+    fn test_microdata_and_listing_cards() {
+        let html = r#"
+            <div itemscope itemtype="https://schema.org/Product">
+                <span itemprop="name">Lamp</span>
+                <meta itemprop="price" content="25.00"><meta itemprop="priceCurrency" content="USD">
+                <link itemprop="availability" href="https://schema.org/PreOrder">
+            </div>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert_eq!(sdom.products()[0].source, ProductSource::Microdata);
+        assert_eq!(sdom.products()[0].availability, Some(Availability::PreOrder));
+
+        let html = r#"
+            <ul>
+                <li><a href="/a">Mug</a> <b>€8</b> <button>Add to cart</button></li>
+                <li><a href="/b">Plate</a> <b>€12</b> Sold out <button>Add to cart</button></li>
+            </ul>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let names: Vec<_> = sdom.products().iter().map(|p| p.name.clone().unwrap()).collect();
+        assert_eq!(names, vec!["Mug", "Plate"]);
+        assert_eq!(sdom.products()[1].availability, Some(Availability::OutOfStock));
+    }
+}
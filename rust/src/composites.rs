@@ -0,0 +1,338 @@
+//! Tree and grid widgets
+//!
+//! File browsers and data grids are built from `role="tree"`/`treeitem`
+//! and `role="grid"`/`row`/`gridcell` markup. Tree items become nodes, so
+//! a [`Tree`] mirrors their nesting with expansion and selection state, and
+//! expanding or collapsing an item becomes a state-graph transition. Grid
+//! cells stay out of the index; a [`Grid`] lists their text row by row.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticRole, State, Transition};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Roles whose content belongs to a nested tree item rather than this one
+const NESTED: &[&str] = &["group", "treeitem"];
+
+/// An item of a tree widget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeItem {
+    /// ID of the item's node
+    pub id: String,
+    /// Item label
+    pub label: String,
+    /// Nesting level, starting at 1
+    pub level: usize,
+    /// `Some(false)` for a collapsed parent, `None` for a leaf
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded: Option<bool>,
+    /// Whether the item is selected
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+    /// Child items rendered in the markup
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub children: Vec<TreeItem>,
+}
+
+/// A `role="tree"` widget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tree {
+    /// ID of the tree node
+    pub id: String,
+    /// Whether several items can be selected at once
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub multiselectable: bool,
+    /// Top-level items
+    pub items: Vec<TreeItem>,
+}
+
+impl Tree {
+    /// All items, depth first
+    pub fn flatten(&self) -> Vec<&TreeItem> {
+        fn walk<'a>(items: &'a [TreeItem], out: &mut Vec<&'a TreeItem>) {
+            for item in items {
+                out.push(item);
+                walk(&item.children, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.items, &mut out);
+        out
+    }
+}
+
+/// A cell of a grid row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridCell {
+    /// ID of the cell's node, when the cell became one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    /// Cell text
+    pub text: String,
+    /// Whether this is a column or row header
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub header: bool,
+    /// Whether the cell is selected
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+}
+
+/// A row of a grid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridRow {
+    /// ID of the row's node, when the row became one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    /// Cells in column order
+    pub cells: Vec<GridCell>,
+    /// Whether the row is selected
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+    /// Expansion state of a tree grid row, `None` if it cannot expand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded: Option<bool>,
+    /// Nesting level of a tree grid row (`aria-level`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<usize>,
+}
+
+/// A `role="grid"` or `role="treegrid"` widget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grid {
+    /// ID of the grid node
+    pub id: String,
+    /// Whether several rows or cells can be selected at once
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub multiselectable: bool,
+    /// Rows in document order, header rows included
+    pub rows: Vec<GridRow>,
+}
+
+fn has_role(element: ElementRef, roles: &[&str]) -> bool {
+    element
+        .value()
+        .attr("role")
+        .is_some_and(|r| roles.iter().any(|role| r.trim().eq_ignore_ascii_case(role)))
+}
+
+fn is_true(element: ElementRef, attr: &str) -> bool {
+    element.value().attr(attr).is_some_and(|v| v.trim() == "true")
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Label of a tree item from its own text, leaving out nested items
+///
+/// Returns `None` when the item has an explicit label, so the usual label
+/// precedence applies.
+pub(crate) fn treeitem_label(element: ElementRef) -> Option<String> {
+    let el = element.value();
+    if ["data-agent-label", "aria-label", "title"].iter().any(|a| el.attr(a).is_some()) {
+        return None;
+    }
+    fn collect(element: ElementRef, out: &mut String) {
+        for child in element.children() {
+            if let Some(text) = child.value().as_text() {
+                out.push_str(text);
+                out.push(' ');
+            } else if let Some(child) = ElementRef::wrap(child) {
+                if !has_role(child, NESTED) && !matches!(child.value().name(), "ul" | "ol") {
+                    collect(child, out);
+                }
+            }
+        }
+    }
+    let mut text = String::new();
+    collect(element, &mut text);
+    Some(normalize(&text))
+}
+
+fn grid_rows(grid: ElementRef, nodes: &AHashMap<NodeId, String>) -> Vec<GridRow> {
+    grid.descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| has_role(*e, &["row"]) || e.value().name() == "tr")
+        .map(|row| GridRow {
+            id: nodes.get(&row.id()).cloned(),
+            cells: row
+                .descendants()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| {
+                    has_role(*e, &["gridcell", "cell", "columnheader", "rowheader"])
+                        || matches!(e.value().name(), "td" | "th")
+                })
+                .map(|cell| GridCell {
+                    id: nodes.get(&cell.id()).cloned(),
+                    text: normalize(&cell.text().collect::<Vec<_>>().join(" ")),
+                    header: cell.value().name() == "th"
+                        || has_role(cell, &["columnheader", "rowheader"]),
+                    selected: is_true(cell, "aria-selected"),
+                })
+                .collect(),
+            selected: is_true(row, "aria-selected"),
+            expanded: row.value().attr("aria-expanded").map(|v| v.trim() == "true"),
+            level: row.value().attr("aria-level").and_then(|l| l.trim().parse().ok()),
+        })
+        .collect()
+}
+
+impl SemanticDOM {
+    /// Collect tree and grid widgets, given the node created for each element
+    pub(crate) fn extract_composites(&mut self, document: &Html, nodes: &AHashMap<NodeId, String>) {
+        for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+            let Some(id) = nodes.get(&element.id()) else {
+                continue;
+            };
+            let multiselectable = is_true(element, "aria-multiselectable");
+            if has_role(element, &["tree"]) {
+                let items = self.tree_items(id, 1);
+                self.trees.push(Tree { id: id.clone(), multiselectable, items });
+            } else if has_role(element, &["grid", "treegrid"]) {
+                let rows = grid_rows(element, nodes);
+                self.grids.push(Grid { id: id.clone(), multiselectable, rows });
+            }
+        }
+    }
+
+    /// Tree items below a node, looking through non-item nodes in between
+    pub(crate) fn tree_items(&self, id: &str, level: usize) -> Vec<TreeItem> {
+        let Some(node) = self.index.get(id) else {
+            return Vec::new();
+        };
+        let mut items = Vec::new();
+        for child in node.children.iter().filter_map(|c| self.index.get(c)) {
+            if matches!(child.role, SemanticRole::TreeItem) {
+                items.push(TreeItem {
+                    id: child.id.clone(),
+                    label: child.label.clone(),
+                    level,
+                    expanded: child.expanded,
+                    selected: child.selected,
+                    children: self.tree_items(&child.id, level + 1),
+                });
+            } else {
+                items.extend(self.tree_items(&child.id, level));
+            }
+        }
+        items
+    }
+
+    /// Add expand/collapse transitions for expandable tree items
+    pub(crate) fn add_tree_states(&mut self) {
+        let items: Vec<(String, String, bool)> = self
+            .trees
+            .iter()
+            .flat_map(Tree::flatten)
+            .filter_map(|i| Some((i.id.clone(), i.label.clone(), i.expanded?)))
+            .collect();
+        let initial = self.initial_state_id();
+        for (id, label, expanded) in items {
+            let (action, state) = match expanded {
+                true => ("collapse", "collapsed"),
+                false => ("expand", "expanded"),
+            };
+            let state_id = format!("state_{}_{}", state, id);
+            self.state_graph.states.push(State {
+                id: state_id.clone(),
+                name: format!("{} ({})", label, state),
+                description: None,
+                url_pattern: None,
+                is_initial: false,
+                is_terminal: false,
+                anchor: None,
+            });
+            self.state_graph.transitions.push(Transition {
+                from: initial.clone(),
+                to: state_id,
+                trigger: id,
+                action: Some(action.to_string()),
+                guard: None,
+                request: None,
+                params: IndexMap::new(),
+            });
+        }
+    }
+
+    /// Get the tree widgets on the page, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main><ul role="tree" aria-label="Files">
+    ///     <li role="treeitem" aria-expanded="true">src
+    ///         <ul role="group"><li role="treeitem" aria-selected="true">lib.rs</li></ul>
+    ///     </li>
+    ///     <li role="treeitem" aria-expanded="false">docs</li>
+    /// </ul></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let tree = &sdom.trees()[0];
+    /// assert_eq!(tree.items[0].label, "src");
+    /// assert!(tree.items[0].children[0].selected);
+    /// assert_eq!(tree.items[1].expanded, Some(false));
+    /// ```
+    pub fn trees(&self) -> &[Tree] {
+        &self.trees
+    }
+
+    /// Get the grid and tree grid widgets on the page, in document order
+    pub fn grids(&self) -> &[Grid] {
+        &self.grids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_tree_nesting_and_transitions() {
+        let html = r#"<nav><ul role="tree" aria-multiselectable="true">
+            <li role="treeitem" aria-expanded="true"><span>Projects</span>
+              <ul role="group">
+                <li role="treeitem" aria-expanded="false">Archive</li>
+                <li role="treeitem" aria-selected="true">Roadmap</li>
+              </ul>
+            </li>
+        </ul></nav>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let tree = &sdom.trees()[0];
+        assert!(tree.multiselectable);
+        let labels: Vec<(&str, usize)> =
+            tree.flatten().iter().map(|i| (i.label.as_str(), i.level)).collect();
+        assert_eq!(labels, [("Projects", 1), ("Archive", 2), ("Roadmap", 2)]);
+
+        let actions: Vec<&str> = sdom
+            .state_graph
+            .transitions
+            .iter()
+            .filter_map(|t| t.action.as_deref())
+            .collect();
+        assert_eq!(actions, ["collapse", "expand"]);
+    }
+
+    #[test]
+    fn test_grid_rows() {
+        let html = r#"<main><table role="grid" aria-label="Orders">
+            <tr><th>Order</th><th>Total</th></tr>
+            <tr aria-selected="true"><td>#1001</td><td>$20</td></tr>
+            <tr><td>#1002</td><td aria-selected="true">$35</td></tr>
+        </table></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let grid = &sdom.grids()[0];
+        assert_eq!(sdom.get(&grid.id).unwrap().role, SemanticRole::Table);
+        assert_eq!(grid.rows.len(), 3);
+        assert!(grid.rows[0].cells[0].header);
+        assert!(grid.rows[1].selected);
+        assert_eq!(grid.rows[2].cells[1].text, "$35");
+        assert!(grid.rows[2].cells[1].selected);
+    }
+}
@@ -0,0 +1,603 @@
+//! Parser configuration
+//!
+//! `Config` can be built with a struct literal over `Config::default()`,
+//! or with the validating [`ConfigBuilder`] returned by `Config::builder()`.
+
+use crate::intent::IntentClassifier;
+use crate::noise::{NoiseAction, NoiseKind};
+use crate::security::SecurityConfig;
+use crate::types::SemanticRole;
+use crate::{Error, Result};
+use std::sync::Arc;
+
+/// Configuration for SemanticDOM parsing
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Maximum input size in bytes (default: 10MB)
+    pub max_input_size: usize,
+    /// ID prefix for generated semantic IDs
+    pub id_prefix: String,
+    /// Maximum tree depth to parse
+    pub max_depth: usize,
+    /// Maximum number of semantic nodes; parsing stops at the limit
+    pub max_nodes: usize,
+    /// Maximum attributes on a single element; elements with more are
+    /// skipped with a warning, though their children are still parsed
+    pub max_attributes: usize,
+    /// Maximum text gathered for a single node's label, in bytes; longer
+    /// text is cut with a warning
+    pub max_text_length: usize,
+    /// Elements to exclude from parsing
+    pub exclude_tags: Vec<String>,
+    /// If non-empty, only elements with these tags become nodes
+    pub include_tags: Vec<String>,
+    /// Custom tag-to-role mappings, checked before the built-in selectors
+    /// (e.g. `("app-nav", SemanticRole::Navigation)` for custom elements)
+    pub role_overrides: Vec<(String, SemanticRole)>,
+    /// Locale for intent keyword matching (e.g. `"de"`); when unset, the
+    /// element's `lang` attribute or the document language is used
+    pub locale: Option<String>,
+    /// Custom intent classifiers, consulted before the built-in heuristics
+    pub intent_classifiers: Vec<Arc<dyn IntentClassifier>>,
+    /// URL validation rules for link targets
+    pub security: SecurityConfig,
+    /// Absolute URL of the page, used to tell internal links from external
+    /// ones; a `<base href>` in the document is used when unset
+    pub base_url: Option<String>,
+    /// File extensions (lowercase, without the dot) and their MIME types;
+    /// links to these are classified as downloads
+    pub download_types: Vec<(String, String)>,
+    /// Whether to generate state graph
+    pub include_state_graph: bool,
+    /// Whether to record each node's start tag position in the source
+    pub source_spans: bool,
+    /// Which raw attributes to keep on nodes
+    pub preserve_attributes: AttributePolicy,
+    /// Maximum characters of each node's text excerpt; `None` leaves
+    /// excerpts out
+    pub text_excerpts: Option<usize>,
+    /// How to treat ads, social widgets and consent-vendor markup; kinds
+    /// not listed are kept, and later entries win
+    pub noise_filters: Vec<(NoiseKind, NoiseAction)>,
+    /// Whether to infer main and navigation landmarks from plain
+    /// containers on pages that declare none
+    pub infer_landmarks: bool,
+    /// Whether to run certification checks while parsing, storing the
+    /// result in [`SemanticDOM::certification`](crate::SemanticDOM::certification)
+    ///
+    /// On by default, so every parse, update and merge runs all checks.
+    /// Turn it off when parsing many pages without reading certification,
+    /// and call [`SemanticDOM::certify`](crate::SemanticDOM::certify) as
+    /// needed.
+    pub validate: bool,
+    /// IDs of certification checks whose failures are waived on every page
+    /// (e.g. `A11Y-002`); pages can add their own with comments
+    pub ignore_checks: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_input_size: 10 * 1024 * 1024, // 10MB
+            id_prefix: "sdom".to_string(),
+            max_depth: 50,
+            max_nodes: 100_000,
+            max_attributes: 256,
+            max_text_length: 1024 * 1024, // 1MB
+            exclude_tags: vec![
+                "script".to_string(),
+                "style".to_string(),
+                "noscript".to_string(),
+                "template".to_string(),
+            ],
+            include_tags: Vec::new(),
+            role_overrides: Vec::new(),
+            locale: None,
+            intent_classifiers: Vec::new(),
+            security: SecurityConfig::default(),
+            base_url: None,
+            download_types: crate::downloads::default_download_types(),
+            include_state_graph: true,
+            source_spans: false,
+            preserve_attributes: AttributePolicy::None,
+            text_excerpts: None,
+            noise_filters: Vec::new(),
+            infer_landmarks: false,
+            validate: true,
+            ignore_checks: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// What [`Self::noise_filters`] does with a kind of noise
+    pub fn noise_action(&self, kind: NoiseKind) -> NoiseAction {
+        self.noise_filters
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == kind)
+            .map_or(NoiseAction::Keep, |(_, action)| *action)
+    }
+
+    /// Start building a configuration from the defaults
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::Config;
+    ///
+    /// let config = Config::builder()
+    ///     .id_prefix("page")
+    ///     .max_depth(20)
+    ///     .include_state_graph(false)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(config.id_prefix, "page");
+    ///
+    /// assert!(Config::builder().id_prefix("").build().is_err());
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Check the configuration for inconsistent settings
+    pub fn validate_settings(&self) -> Result<()> {
+        if self.max_input_size == 0 {
+            return Err(Error::InvalidConfig("max_input_size must be greater than zero".to_string()));
+        }
+        if self.max_depth == 0 {
+            return Err(Error::InvalidConfig("max_depth must be greater than zero".to_string()));
+        }
+        if self.max_nodes == 0 || self.max_attributes == 0 || self.max_text_length == 0 {
+            return Err(Error::InvalidConfig(
+                "max_nodes, max_attributes and max_text_length must be greater than zero"
+                    .to_string(),
+            ));
+        }
+        if self.text_excerpts == Some(0) {
+            return Err(Error::InvalidConfig("text_excerpts must be greater than zero".to_string()));
+        }
+        if self.id_prefix.trim().is_empty() {
+            return Err(Error::InvalidConfig("id_prefix must not be empty".to_string()));
+        }
+        if self.id_prefix.chars().any(char::is_whitespace) {
+            return Err(Error::InvalidConfig("id_prefix must not contain whitespace".to_string()));
+        }
+        if let Some(base) = &self.base_url {
+            if !url::Url::parse(base).is_ok_and(|u| u.has_host()) {
+                return Err(Error::InvalidConfig(format!(
+                    "base_url '{}' is not an absolute URL",
+                    base
+                )));
+            }
+        }
+        if self.download_types.iter().any(|(ext, _)| ext.is_empty()) {
+            return Err(Error::InvalidConfig(
+                "download extension must not be empty".to_string(),
+            ));
+        }
+        if let AttributePolicy::Allowlist(names) = &self.preserve_attributes {
+            if names.iter().any(|n| n.trim_end_matches('*').is_empty()) {
+                return Err(Error::InvalidConfig(
+                    "attribute allowlist entries must name an attribute or prefix".to_string(),
+                ));
+            }
+        }
+        if self.role_overrides.iter().any(|(tag, _)| tag.trim().is_empty()) {
+            return Err(Error::InvalidConfig("role override tag must not be empty".to_string()));
+        }
+        if let Some(tag) = self
+            .include_tags
+            .iter()
+            .find(|tag| self.exclude_tags.iter().any(|e| e.eq_ignore_ascii_case(tag)))
+        {
+            return Err(Error::InvalidConfig(format!(
+                "tag '{}' is both included and excluded",
+                tag
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Which raw HTML attributes to keep on nodes
+///
+/// The typed fields of [`SemanticNode`](crate::SemanticNode) cover common
+/// attributes; this exposes the rest (`aria-controls`, `formaction`,
+/// `data-*`, ...) in [`SemanticNode::attributes`](crate::SemanticNode).
+/// Values are kept verbatim and are not validated or sanitized.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AttributePolicy {
+    /// Keep no raw attributes
+    #[default]
+    None,
+    /// Keep attributes with these names; a trailing `*` matches a prefix
+    /// (e.g. `aria-*`)
+    Allowlist(Vec<String>),
+    /// Keep every attribute
+    All,
+}
+
+impl AttributePolicy {
+    /// Build an allowlist policy
+    pub fn allowlist<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        AttributePolicy::Allowlist(names.into_iter().map(|n| n.into().to_lowercase()).collect())
+    }
+
+    /// Check whether an attribute should be kept
+    pub fn allows(&self, name: &str) -> bool {
+        match self {
+            AttributePolicy::None => false,
+            AttributePolicy::All => true,
+            AttributePolicy::Allowlist(names) => names.iter().any(|n| match n.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => n == name,
+            }),
+        }
+    }
+}
+
+/// Fluent builder for [`Config`]
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Set the maximum input size in bytes
+    pub fn max_input_size(mut self, size: usize) -> Self {
+        self.config.max_input_size = size;
+        self
+    }
+
+    /// Set the ID prefix for generated semantic IDs
+    pub fn id_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.id_prefix = prefix.into();
+        self
+    }
+
+    /// Set the maximum tree depth to parse
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.config.max_depth = depth;
+        self
+    }
+
+    /// Set the maximum number of semantic nodes
+    pub fn max_nodes(mut self, max: usize) -> Self {
+        self.config.max_nodes = max;
+        self
+    }
+
+    /// Set the maximum number of attributes on a single element
+    pub fn max_attributes(mut self, max: usize) -> Self {
+        self.config.max_attributes = max;
+        self
+    }
+
+    /// Set the maximum text gathered for a single node's label, in bytes
+    pub fn max_text_length(mut self, max: usize) -> Self {
+        self.config.max_text_length = max;
+        self
+    }
+
+    /// Replace the list of excluded tags
+    pub fn exclude_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.exclude_tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add a tag to the excluded list
+    pub fn exclude_tag(mut self, tag: impl Into<String>) -> Self {
+        self.config.exclude_tags.push(tag.into());
+        self
+    }
+
+    /// Replace the list of included tags
+    pub fn include_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.include_tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add a tag to the included list
+    pub fn include_tag(mut self, tag: impl Into<String>) -> Self {
+        self.config.include_tags.push(tag.into());
+        self
+    }
+
+    /// Map a tag name (typically a custom element) to a semantic role
+    pub fn role_override(mut self, tag: impl Into<String>, role: SemanticRole) -> Self {
+        self.config.role_overrides.push((tag.into(), role));
+        self
+    }
+
+    /// Set the locale used for intent keyword matching
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.config.locale = Some(locale.into());
+        self
+    }
+
+    /// Register a custom intent classifier
+    pub fn intent_classifier(mut self, classifier: impl IntentClassifier + 'static) -> Self {
+        self.config.intent_classifiers.push(Arc::new(classifier));
+        self
+    }
+
+    /// Set the URL validation rules for link targets
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SecurityConfig, SemanticDOM};
+    ///
+    /// let config = Config::builder()
+    ///     .security(SecurityConfig::new().with_allowed_protocol("zoommtg"))
+    ///     .build()
+    ///     .unwrap();
+    /// let html = r#"<a href="zoommtg://zoom.us/join?confno=123">Join</a>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    /// assert!(sdom.index.values().any(|n| n.href.is_some()));
+    /// ```
+    pub fn security(mut self, security: SecurityConfig) -> Self {
+        self.config.security = security;
+        self
+    }
+
+    /// Set the absolute URL of the page being parsed
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.config.base_url = Some(url.into());
+        self
+    }
+
+    /// Treat links to files with this extension as downloads
+    ///
+    /// Replaces the MIME type if the extension is already mapped.
+    pub fn download_type(mut self, extension: impl Into<String>, mime: impl Into<String>) -> Self {
+        let extension = extension.into().trim_start_matches('.').to_lowercase();
+        let mime = mime.into();
+        match self.config.download_types.iter_mut().find(|(e, _)| *e == extension) {
+            Some(entry) => entry.1 = mime,
+            None => self.config.download_types.push((extension, mime)),
+        }
+        self
+    }
+
+    /// Set whether to generate the state graph
+    pub fn include_state_graph(mut self, include: bool) -> Self {
+        self.config.include_state_graph = include;
+        self
+    }
+
+    /// Set whether to record source positions on nodes
+    ///
+    /// Offsets refer to the string that was parsed; for
+    /// [`SemanticDOM::parse_bytes`](crate::SemanticDOM::parse_bytes) that is
+    /// the decoded text.
+    pub fn source_spans(mut self, enabled: bool) -> Self {
+        self.config.source_spans = enabled;
+        self
+    }
+
+    /// Set which raw attributes to keep on nodes
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{AttributePolicy, Config, SemanticDOM};
+    ///
+    /// let config = Config::builder()
+    ///     .preserve_attributes(AttributePolicy::allowlist(["aria-controls", "data-*"]))
+    ///     .build()
+    ///     .unwrap();
+    /// let html = r#"<button aria-controls="menu" data-track="nav" class="btn">Menu</button>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let button = sdom.get(&sdom.interactables[0]).unwrap();
+    /// assert_eq!(button.attr("aria-controls"), Some("menu"));
+    /// assert_eq!(button.attr("data-track"), Some("nav"));
+    /// assert_eq!(button.attr("class"), None);
+    /// ```
+    pub fn preserve_attributes(mut self, policy: AttributePolicy) -> Self {
+        self.config.preserve_attributes = policy;
+        self
+    }
+
+    /// Capture text excerpts of up to `max_chars` characters on nodes
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let config = Config::builder().text_excerpts(20).build().unwrap();
+    /// let html = "<article><h2>News</h2><p>Rust 2.0 ships with a new borrow checker</p>";
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let article = sdom.reading_order()[0];
+    /// assert_eq!(article.text_excerpt.as_deref(), Some("Rust 2.0 ships with…"));
+    /// ```
+    pub fn text_excerpts(mut self, max_chars: usize) -> Self {
+        self.config.text_excerpts = Some(max_chars);
+        self
+    }
+
+    /// Drop or demote a kind of noise
+    ///
+    /// See [`SemanticDOM::excluded_count`](crate::SemanticDOM::excluded_count)
+    /// for an example.
+    pub fn noise_filter(mut self, kind: NoiseKind, action: NoiseAction) -> Self {
+        self.config.noise_filters.push((kind, action));
+        self
+    }
+
+    /// Set whether to infer missing main and navigation landmarks
+    ///
+    /// See [`SemanticDOM::inferred_landmarks`](crate::SemanticDOM::inferred_landmarks)
+    /// for an example.
+    pub fn infer_landmarks(mut self, infer: bool) -> Self {
+        self.config.infer_landmarks = infer;
+        self
+    }
+
+    /// Set whether to run certification checks on every parse (default
+    /// on)
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.config.validate = validate;
+        self
+    }
+
+    /// Replace the list of waived certification checks
+    pub fn ignore_checks<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.ignore_checks = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Waive failures of a certification check
+    pub fn ignore_check(mut self, id: impl Into<String>) -> Self {
+        self.config.ignore_checks.push(id.into());
+        self
+    }
+
+    /// Validate the settings and produce the configuration
+    ///
+    /// Tag names are normalized to lowercase.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidConfig`] for a zero size, depth, node, attribute,
+    ///   text or excerpt limit, an empty
+    ///   ID prefix, a tag that is both included and excluded, or a
+    ///   `base_url` that is not absolute
+    pub fn build(mut self) -> Result<Config> {
+        for tag in self
+            .config
+            .exclude_tags
+            .iter_mut()
+            .chain(self.config.include_tags.iter_mut())
+        {
+            *tag = tag.to_lowercase();
+        }
+        for (tag, _) in &mut self.config.role_overrides {
+            *tag = tag.to_lowercase();
+        }
+        self.config.validate_settings()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults_match_default() {
+        let built = Config::builder().build().unwrap();
+        let default = Config::default();
+        assert_eq!(built.max_input_size, default.max_input_size);
+        assert_eq!(built.id_prefix, default.id_prefix);
+        assert_eq!(built.exclude_tags, default.exclude_tags);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_settings() {
+        assert!(matches!(
+            Config::builder().max_depth(0).build(),
+            Err(Error::InvalidConfig(_))
+        ));
+        assert!(Config::builder().max_input_size(0).build().is_err());
+        assert!(Config::builder().id_prefix("  ").build().is_err());
+        assert!(Config::builder().include_tag("SCRIPT").build().is_err());
+    }
+
+    #[test]
+    fn test_attribute_policy() {
+        let policy = AttributePolicy::allowlist(["ARIA-*", "formaction"]);
+        assert!(policy.allows("aria-owns"));
+        assert!(policy.allows("formaction"));
+        assert!(!policy.allows("formmethod"));
+        assert!(AttributePolicy::All.allows("onclick"));
+        assert!(!AttributePolicy::None.allows("id"));
+
+        let html = r#"<form><button formaction="/save" id="b" onclick="x()">Save</button></form>"#;
+        let config = Config::builder().preserve_attributes(AttributePolicy::All).build().unwrap();
+        let sdom = crate::SemanticDOM::parse(html, config).unwrap();
+        let keys: Vec<&String> = sdom.get("sdom_b").unwrap().attributes.keys().collect();
+        assert_eq!(keys, ["formaction", "id", "onclick"]);
+
+        let config = Config::builder()
+            .preserve_attributes(AttributePolicy::allowlist(["*"]))
+            .build();
+        assert!(matches!(config, Err(Error::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_attribute_and_text_limits() {
+        // Crowded elements are skipped, but their children are still parsed
+        let config = Config::builder().max_attributes(2).build().unwrap();
+        let html = r#"<div a="1" b="2" c="3"><button a="1" b="2" c="3">Go</button>
+            <a href="/help">Help</a></div><button>Buy</button>"#;
+        let sdom = crate::SemanticDOM::parse(html, config).unwrap();
+        let labels: Vec<&str> = sdom.index.values().map(|n| n.label.as_str()).collect();
+        assert!(labels.contains(&"Help") && labels.contains(&"Buy"));
+        assert!(!labels.contains(&"Go"));
+        assert!(matches!(
+            sdom.warnings(),
+            [
+                Error::TooManyAttributes { max: 2, actual: 3 },
+                Error::TooManyAttributes { max: 2, actual: 3 }
+            ]
+        ));
+
+        // Long text is cut from labels without failing the parse
+        let config = Config::builder().max_text_length(10).build().unwrap();
+        let html = "<main><h1>0123456789overflow</h1><button>Buy</button></main>";
+        let sdom = crate::SemanticDOM::parse(html, config).unwrap();
+        let heading = sdom.index.values().find(|n| n.role == SemanticRole::Heading).unwrap();
+        assert_eq!(heading.label, "0123456789");
+        assert!(sdom.index.values().any(|n| n.label == "Buy"));
+        assert!(sdom.warnings().iter().any(|w| matches!(w, Error::TextTooLong { max: 10, .. })));
+    }
+
+    #[test]
+    fn test_role_overrides_for_custom_elements() {
+        let config = Config::builder()
+            .role_override("My-Button", SemanticRole::Button)
+            .role_override("app-nav", SemanticRole::Navigation)
+            .build()
+            .unwrap();
+
+        let html = r#"<app-nav><a href="/">Home</a></app-nav><my-button>Save</my-button>"#;
+        let sdom = crate::SemanticDOM::parse(html, config).unwrap();
+
+        assert_eq!(sdom.landmarks.len(), 1);
+        let button = sdom
+            .index
+            .values()
+            .find(|n| n.role == SemanticRole::Button)
+            .unwrap();
+        assert_eq!(button.label, "Save");
+        assert!(button.intent.is_some());
+        assert!(sdom.interactables.contains(&button.id));
+    }
+
+    #[test]
+    fn test_include_tags_restricts_nodes() {
+        let config = Config::builder().include_tags(["BUTTON"]).build().unwrap();
+        assert_eq!(config.include_tags, vec!["button"]);
+
+        let html = r#"<nav><a href="/">Home</a></nav><main><button>Go</button></main>"#;
+        let sdom = crate::SemanticDOM::parse(html, config).unwrap();
+        assert_eq!(sdom.index.len(), 1);
+        assert!(sdom.landmarks.is_empty());
+    }
+}
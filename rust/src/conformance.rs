@@ -0,0 +1,215 @@
+//! Conformance of published documents to the live page
+//!
+//! Sites that publish their own SemanticDOM JSON promise agents that it
+//! describes the page. [`SemanticDOM::conformance`] diffs such a declared
+//! document, loaded with [`SemanticDOM::from_json`], against a parse of the
+//! live HTML and reports declared elements that are gone, renamed or
+//! described differently, so stale metadata is caught before agents act
+//! on it.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// A declared node found in the page under another ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenamedNode {
+    /// ID in the declared document
+    pub declared: String,
+    /// ID in the parsed page
+    pub parsed: String,
+}
+
+/// A field that differs between a declared node and the page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldMismatch {
+    /// ID in the declared document
+    pub id: String,
+    /// Field name as in the JSON, e.g. `label` or `href`
+    pub field: String,
+    /// Declared value
+    pub declared: Option<String>,
+    /// Value in the parsed page
+    pub parsed: Option<String>,
+}
+
+/// How a declared document differs from the page it describes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// Declared node IDs with no counterpart in the page
+    pub missing: Vec<String>,
+    /// Declared nodes found under another ID
+    pub renamed: Vec<RenamedNode>,
+    /// Fields of matched nodes that differ
+    pub mismatches: Vec<FieldMismatch>,
+    /// Page node IDs the declaration leaves out; informational, since a
+    /// declaration may cover only part of the page
+    pub undeclared: Vec<String>,
+}
+
+impl ConformanceReport {
+    /// Check if everything declared is in the page as described
+    pub fn is_conformant(&self) -> bool {
+        self.missing.is_empty() && self.renamed.is_empty() && self.mismatches.is_empty()
+    }
+}
+
+/// Ways to recognize a renamed node, most specific first
+const RENAME_KEYS: [fn(&SemanticNode) -> String; 3] = [
+    |n| format!("{}\0{}\0{}", n.role.as_str(), n.selector, n.label),
+    |n| format!("{}\0{}", n.role.as_str(), n.label),
+    |n| format!("{}\0{}", n.role.as_str(), n.selector),
+];
+
+impl SemanticDOM {
+    /// Compare a declared document against this parse of the live page
+    ///
+    /// Declared nodes match page nodes with the same ID and role, then,
+    /// as renames, page nodes with the same role, selector and label, the
+    /// same role and label, or the same role and selector. Matched nodes
+    /// are compared by label, intent, href and selector, except for the
+    /// labels of landmarks, which follow their contents.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let published = SemanticDOM::parse(
+    ///     r#"<main><a href="/pricing" data-agent-id="pricing">Pricing</a>
+    ///        <button data-agent-id="trial">Start trial</button></main>"#,
+    ///     Config::default(),
+    /// )
+    /// .unwrap()
+    /// .to_json()
+    /// .unwrap();
+    /// let declared = SemanticDOM::from_json(&published, Config::default()).unwrap();
+    ///
+    /// let live = SemanticDOM::parse(
+    ///     r#"<main><a href="/plans" data-agent-id="pricing">Pricing</a>
+    ///        <button data-agent-id="start">Start trial</button></main>"#,
+    ///     Config::default(),
+    /// )
+    /// .unwrap();
+    /// let report = live.conformance(&declared);
+    ///
+    /// assert!(!report.is_conformant());
+    /// assert_eq!(report.renamed[0].parsed, "sdom_start");
+    /// assert_eq!(report.mismatches[0].field, "href");
+    /// assert_eq!(report.mismatches[0].parsed.as_deref(), Some("/plans"));
+    /// ```
+    pub fn conformance(&self, declared: &SemanticDOM) -> ConformanceReport {
+        let mut report = ConformanceReport::default();
+        // Declared ID to page ID
+        let mut matches: AHashMap<&str, &str> = AHashMap::new();
+        let mut taken: AHashSet<&str> = AHashSet::new();
+
+        for node in declared.reading_order() {
+            if let Some(parsed) = self.index.get(&node.id).filter(|p| p.role == node.role) {
+                matches.insert(&node.id, &parsed.id);
+                taken.insert(&parsed.id);
+            }
+        }
+
+        for key in RENAME_KEYS {
+            let mut candidates: AHashMap<String, VecDeque<&str>> = AHashMap::new();
+            for node in self.reading_order() {
+                if !taken.contains(node.id.as_str()) {
+                    candidates.entry(key(node)).or_default().push_back(&node.id);
+                }
+            }
+            for node in declared.reading_order() {
+                if matches.contains_key(node.id.as_str()) {
+                    continue;
+                }
+                if let Some(parsed) = candidates.get_mut(&key(node)).and_then(|q| q.pop_front()) {
+                    matches.insert(&node.id, parsed);
+                    taken.insert(parsed);
+                    report.renamed.push(RenamedNode {
+                        declared: node.id.clone(),
+                        parsed: parsed.to_string(),
+                    });
+                }
+            }
+        }
+
+        for node in declared.reading_order() {
+            let Some(parsed) = matches.get(node.id.as_str()).and_then(|id| self.index.get(*id))
+            else {
+                report.missing.push(node.id.clone());
+                continue;
+            };
+            // Landmark labels fall back to their text, which changes with any
+            // content; their contents are compared node by node instead
+            let label = |n: &SemanticNode| Some(n.label.clone()).filter(|_| !n.role.is_landmark());
+            let fields = [
+                ("label", label(node), label(parsed)),
+                (
+                    "intent",
+                    node.intent.as_ref().map(|i| i.as_str().to_string()),
+                    parsed.intent.as_ref().map(|i| i.as_str().to_string()),
+                ),
+                ("href", node.href.clone(), parsed.href.clone()),
+                ("selector", Some(node.selector.clone()), Some(parsed.selector.clone())),
+            ];
+            for (field, declared, parsed) in fields {
+                if declared != parsed {
+                    report.mismatches.push(FieldMismatch {
+                        id: node.id.clone(),
+                        field: field.to_string(),
+                        declared,
+                        parsed,
+                    });
+                }
+            }
+        }
+
+        report.undeclared = self
+            .order
+            .iter()
+            .filter(|id| !taken.contains(id.as_str()))
+            .cloned()
+            .collect();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_conformance() {
+        let declared_html = r#"<nav><a href="/">Home</a><a href="/faq">FAQ</a></nav>
+            <main><h1>Docs</h1><button data-agent-id="copy">Copy</button>
+            <a href="/old" data-agent-id="legacy">Legacy API</a></main>"#;
+        let json = SemanticDOM::parse(declared_html, Config::default()).unwrap().to_json().unwrap();
+        let declared = SemanticDOM::from_json(&json, Config::default()).unwrap();
+
+        let same = SemanticDOM::parse(declared_html, Config::default()).unwrap();
+        let report = same.conformance(&declared);
+        assert!(report.is_conformant(), "{:?}", report);
+        assert!(report.undeclared.is_empty());
+
+        let live_html = r#"<nav><a href="/">Home</a><a href="/help">Help</a></nav>
+            <main><h1>Docs</h1><button data-agent-id="copy-code">Copy</button>
+            <button>Print</button></main>"#;
+        let live = SemanticDOM::parse(live_html, Config::default()).unwrap();
+        let report = live.conformance(&declared);
+        assert!(!report.is_conformant());
+        assert_eq!(report.missing, ["sdom_legacy"]);
+        assert_eq!(
+            report.renamed,
+            [RenamedNode { declared: "sdom_copy".into(), parsed: "sdom_copy-code".into() }]
+        );
+        let fields: Vec<(&str, &str)> =
+            report.mismatches.iter().map(|m| (m.id.as_str(), m.field.as_str())).collect();
+        assert_eq!(
+            fields,
+            [("sdom_a_3", "label"), ("sdom_a_3", "href"), ("sdom_copy", "selector")]
+        );
+        assert_eq!(report.undeclared.len(), 1);
+    }
+}
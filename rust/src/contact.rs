@@ -0,0 +1,112 @@
+//! Contact information extraction
+//!
+//! Collects email addresses and phone numbers from `mailto:`/`tel:` links
+//! and postal addresses from `<address>` blocks.
+
+use crate::parser::SemanticDOM;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// Contact details published on the page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactInfo {
+    /// Email addresses from `mailto:` links, deduplicated in document order
+    pub emails: Vec<String>,
+    /// Phone numbers from `tel:` links, deduplicated in document order
+    pub phones: Vec<String>,
+    /// Text of `<address>` blocks with whitespace collapsed
+    pub postal: Vec<String>,
+}
+
+impl ContactInfo {
+    /// Check if no contact details were found
+    pub fn is_empty(&self) -> bool {
+        self.emails.is_empty() && self.phones.is_empty() && self.postal.is_empty()
+    }
+
+    /// Record the target of a `mailto:` or `tel:` link
+    pub(crate) fn add_link(&mut self, href: &str) {
+        let href = href.trim();
+        let Some((scheme, rest)) = href.split_once(':') else {
+            return;
+        };
+        // Drop ?subject=... and similar parameters
+        let value = rest.split('?').next().unwrap_or("").trim();
+        if value.is_empty() {
+            return;
+        }
+
+        let list = match scheme.to_ascii_lowercase().as_str() {
+            "mailto" => &mut self.emails,
+            "tel" => &mut self.phones,
+            _ => return,
+        };
+        // mailto: may list several comma-separated recipients
+        for value in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            if !list.iter().any(|v| v == value) {
+                list.push(value.to_string());
+            }
+        }
+    }
+
+    /// Record an `<address>` block
+    pub(crate) fn add_address(&mut self, element: ElementRef) {
+        let text = element
+            .text()
+            .flat_map(str::split_whitespace)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() && !self.postal.contains(&text) {
+            self.postal.push(text);
+        }
+    }
+}
+
+impl SemanticDOM {
+    /// Get the contact details found on the page
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<footer>
+    ///     <a href="mailto:help@example.com?subject=Hi">Email</a>
+    ///     <a href="tel:+1-555-0100">Call</a>
+    ///     <address>1 Main St<br>Springfield</address>
+    /// </footer>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let contact = sdom.contact_info();
+    /// assert_eq!(contact.emails, ["help@example.com"]);
+    /// assert_eq!(contact.phones, ["+1-555-0100"]);
+    /// assert_eq!(contact.postal, ["1 Main St Springfield"]);
+    /// ```
+    pub fn contact_info(&self) -> &ContactInfo {
+        &self.contact
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_deduplicates_and_summarizes() {
+        let html = r#"
+            <header><a href="mailto:sales@example.com,help@example.com">Email</a></header>
+            <footer>
+                <a href="MAILTO:help@example.com">Help</a>
+                <a href="tel:">Empty</a>
+                <address>HQ: 1 Main St</address>
+            </footer>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let contact = sdom.contact_info();
+
+        assert_eq!(contact.emails, ["sales@example.com", "help@example.com"]);
+        assert!(contact.phones.is_empty());
+        assert!(sdom
+            .to_agent_summary()
+            .contains("CONTACT: sales@example.com, help@example.com, 1 address"));
+    }
+}
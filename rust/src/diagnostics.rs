@@ -0,0 +1,190 @@
+//! Explanations for what the parser left out or changed
+//!
+//! Parsing is lossy by design: excluded tags are skipped, unsafe URLs are
+//! dropped, long labels are truncated, and limits cut documents short.
+//! [`Diagnostics`] records each of these as it happens, so users can see
+//! why an element is missing from the result instead of guessing.
+//! Recoverable errors also appear in [`SemanticDOM::warnings`] as typed
+//! [`Error`](crate::Error)s.
+
+use crate::parser::SemanticDOM;
+use serde::{Deserialize, Serialize};
+
+/// What a diagnostic is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    /// An element and its subtree were skipped by `exclude_tags` or
+    /// `include_tags`
+    SkippedElement,
+    /// A URL failed validation and was dropped from its node
+    DroppedUrl,
+    /// A node's text label was truncated
+    TruncatedLabel,
+    /// A subtree was cut off at `max_depth`
+    DepthCutoff,
+    /// Parsing stopped at `max_nodes`
+    NodeLimit,
+    /// Ad, social or consent-vendor markup was dropped or demoted by
+    /// `noise_filters`
+    FilteredNoise,
+    /// Any other recovered problem, such as an unknown `data-agent-role`
+    Warning,
+}
+
+/// A single diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// What the diagnostic is about
+    pub kind: DiagnosticKind,
+    /// The node concerned; for skipped content, its nearest semantic
+    /// ancestor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Tag name of the source element, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element: Option<String>,
+    /// Human-readable explanation
+    pub message: String,
+}
+
+/// Diagnostics collected while parsing, in document order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    entries: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub(crate) fn push(
+        &mut self,
+        kind: DiagnosticKind,
+        node: Option<&str>,
+        element: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.entries.push(Diagnostic {
+            kind,
+            node: node.map(str::to_string),
+            element: element.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    /// Insert a document-level diagnostic before all others
+    pub(crate) fn push_front(&mut self, kind: DiagnosticKind, message: impl Into<String>) {
+        self.entries.insert(
+            0,
+            Diagnostic {
+                kind,
+                node: None,
+                element: None,
+                message: message.into(),
+            },
+        );
+    }
+
+    /// Iterate over all diagnostics
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.entries.iter()
+    }
+
+    pub(crate) fn retain(&mut self, keep: impl FnMut(&mut Diagnostic) -> bool) {
+        self.entries.retain_mut(keep);
+    }
+
+    /// Diagnostics of one kind
+    pub fn of_kind(&self, kind: DiagnosticKind) -> impl Iterator<Item = &Diagnostic> {
+        self.entries.iter().filter(move |d| d.kind == kind)
+    }
+
+    /// Diagnostics concerning a node
+    pub fn for_node<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a Diagnostic> {
+        self.entries.iter().filter(move |d| d.node.as_deref() == Some(id))
+    }
+
+    /// Number of diagnostics
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether nothing was reported
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl SemanticDOM {
+    /// Get the diagnostics explaining what parsing skipped, dropped or
+    /// shortened
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, DiagnosticKind, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <a href="javascript:openMenu()">Menu</a>
+    ///     <aside><a href="/deals">Deals</a></aside>
+    /// </main>"#;
+    /// let config = Config::builder().exclude_tag("aside").build().unwrap();
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let diagnostics = sdom.diagnostics();
+    /// assert_eq!(diagnostics.of_kind(DiagnosticKind::DroppedUrl).count(), 1);
+    /// assert_eq!(diagnostics.of_kind(DiagnosticKind::SkippedElement).count(), 1);
+    /// ```
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_diagnostics_explain_missing_content() {
+        let long = "word ".repeat(20);
+        let html = format!(
+            "<nav><a href=\"/\">Home</a></nav><main><section><button>{}</button></section></main>",
+            long
+        );
+        let config = Config::builder()
+            .include_tags(["main", "section", "button"])
+            .max_depth(2)
+            .build()
+            .unwrap();
+        let sdom = SemanticDOM::parse(&html, config).unwrap();
+        let diagnostics = sdom.diagnostics();
+
+        let kinds: Vec<DiagnosticKind> = diagnostics.iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DiagnosticKind::SkippedElement,
+                DiagnosticKind::SkippedElement,
+                DiagnosticKind::DepthCutoff
+            ]
+        );
+        assert_eq!(diagnostics.iter().next().unwrap().element.as_deref(), Some("nav"));
+
+        let section = sdom.index.values().find(|n| n.depth == 1).unwrap();
+        assert_eq!(diagnostics.for_node(&section.id).count(), 1);
+        assert_eq!(sdom.warnings().len(), 1);
+
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let truncated: Vec<&Diagnostic> =
+            sdom.diagnostics().of_kind(DiagnosticKind::TruncatedLabel).collect();
+        assert_eq!(truncated.len(), 1);
+        assert_eq!(truncated[0].element.as_deref(), Some("button"));
+    }
+}
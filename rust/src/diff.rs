@@ -0,0 +1,636 @@
+//! Incremental re-parsing with stable node IDs
+//!
+//! Agents observing a page across many small mutations need node IDs that
+//! survive re-parsing. This module matches nodes of a fresh parse against
+//! the previous one, carries over the old IDs for matched nodes, and
+//! reports which nodes were added, removed, or modified.
+
+use crate::node_refs::NodeRefs;
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use crate::Result;
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The set of nodes that changed between two parses
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentChanges {
+    /// IDs of nodes present only in the new document
+    pub added: Vec<String>,
+    /// IDs of nodes present only in the old document
+    pub removed: Vec<String>,
+    /// IDs of nodes present in both documents whose content changed
+    pub modified: Vec<String>,
+}
+
+impl DocumentChanges {
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Total number of changed nodes
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.modified.len()
+    }
+}
+
+impl SemanticDOM {
+    /// Re-parse updated HTML, preserving IDs of unchanged nodes
+    ///
+    /// Nodes are matched against the current document first by role,
+    /// selector, label and href, then by role and selector alone (which
+    /// reports them as modified). Matched nodes keep their existing IDs so
+    /// references held by an agent stay valid across mutations.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{SemanticDOM, Config};
+    ///
+    /// let mut sdom = SemanticDOM::parse("<main><button>Save</button></main>", Config::default()).unwrap();
+    /// let save_id = sdom.interactables[0].clone();
+    ///
+    /// let changes = sdom
+    ///     .update("<main><button>Cancel</button><button>Save</button></main>")
+    ///     .unwrap();
+    /// assert_eq!(changes.added.len(), 1);
+    /// assert_eq!(sdom.get(&save_id).unwrap().label, "Save");
+    /// ```
+    pub fn update(&mut self, html: &str) -> Result<DocumentChanges> {
+        // Certified once below, on the final IDs
+        let mut config = self.config.clone();
+        config.validate = false;
+        let mut next = if self.fragment {
+            Self::parse_fragment(html, config)?
+        } else {
+            Self::parse(html, config)?
+        };
+        next.config.validate = self.config.validate;
+
+        let changes = reconcile(self, &mut next);
+        *self = next;
+        if self.config.validate {
+            self.certification = Some(self.certify());
+        }
+        Ok(changes)
+    }
+
+    /// Layer a later snapshot of the same page over this one
+    ///
+    /// Meant for a static parse plus a snapshot taken after scripts ran,
+    /// such as the serialized DOM from a browser. Nodes are matched as in
+    /// [`Self::update`] and keep their existing IDs, but take the
+    /// overlay's data. Nodes only the overlay has are added. Nodes only
+    /// this document has are kept in place, since a capture may cover just
+    /// part of the page; those whose parent is gone are dropped and
+    /// reported as removed. Page details missing from the overlay, such as
+    /// the title, and the state graph of this document are carried over.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let ssr = r#"<main><h1>Shop</h1><div id="app" role="region"></div></main>
+    ///     <footer><a href="/terms">Terms</a></footer>"#;
+    /// let hydrated = r#"<main><h1>Shop</h1>
+    ///     <div id="app" role="region"><button>Add to cart</button></div></main>"#;
+    /// let mut sdom = SemanticDOM::parse(ssr, Config::default()).unwrap();
+    /// let heading = sdom.headings[0].clone();
+    ///
+    /// let overlay = SemanticDOM::parse(hydrated, Config::default()).unwrap();
+    /// let changes = sdom.merge(overlay);
+    ///
+    /// assert_eq!(changes.added.len(), 1);
+    /// assert!(changes.removed.is_empty());
+    /// assert_eq!(sdom.headings, [heading]);
+    /// // The capture left out the footer, which is kept
+    /// let label = |i: usize| sdom.get(&sdom.interactables[i]).unwrap().label.clone();
+    /// assert_eq!([label(0), label(1)], ["Add to cart", "Terms"]);
+    /// ```
+    pub fn merge(&mut self, mut overlay: SemanticDOM) -> DocumentChanges {
+        let mut changes = reconcile(self, &mut overlay);
+        let base = std::mem::replace(self, overlay);
+
+        // Geometry set on the static parse outlives a snapshot without it
+        for node in self.index.values_mut().filter(|n| n.bounds.is_none()) {
+            node.bounds = base.index.get(&node.id).and_then(|b| b.bounds);
+        }
+
+        let mut dropped = Vec::new();
+        for id in std::mem::take(&mut changes.removed) {
+            let Some(node) = base.index.get(&id) else {
+                continue;
+            };
+            if node.parent.as_ref().map_or(true, |p| self.index.contains_key(p)) {
+                self.graft(&base, node);
+            } else {
+                dropped.push(id);
+            }
+        }
+        changes.removed = dropped;
+        self.rebuild_categories();
+
+        self.title = self.title.take().or(base.title);
+        self.lang = self.lang.take().or(base.lang);
+        self.dir = self.dir.take().or(base.dir);
+        self.base_url = self.base_url.take().or(base.base_url);
+
+        // Transitions of dropped nodes go, and so do states only they led
+        // to; the overlay's initial state replaces this one's
+        let mut graph = base.state_graph;
+        graph.map_ids(&mut |id| (!changes.removed.iter().any(|r| r == id)).then(|| id.to_string()));
+        if let (Some(old), Some(new)) = (&graph.initial_state, &self.state_graph.initial_state) {
+            for t in graph.transitions.iter_mut().filter(|t| t.from == *old) {
+                t.from = new.clone();
+            }
+        }
+        let targets: AHashSet<&str> = graph.transitions.iter().map(|t| t.to.as_str()).collect();
+        graph.states.retain(|s| targets.contains(s.id.as_str()));
+        self.state_graph.merge(&graph);
+
+        // The overlay's certification describes the overlay alone
+        self.certification = self.config.validate.then(|| self.certify());
+        changes
+    }
+
+    /// Insert a node of `base` after its nearest earlier sibling present in
+    /// this document, or first under its parent
+    fn graft(&mut self, base: &SemanticDOM, node: &SemanticNode) {
+        let roots: Vec<String>;
+        let siblings = match node.parent.as_ref().and_then(|p| base.index.get(p)) {
+            Some(parent) => &parent.children,
+            None => {
+                let top = base.reading_order().into_iter().filter(|n| n.parent.is_none());
+                roots = top.map(|n| n.id.clone()).collect();
+                &roots
+            }
+        };
+        let before = siblings
+            .iter()
+            .take_while(|s| **s != node.id)
+            .filter(|s| self.index.contains_key(*s))
+            .last();
+
+        let position = match (before, node.parent.as_ref()) {
+            (Some(sibling), _) => {
+                let subtree = self.subtree_ids(sibling);
+                self.order.iter().rposition(|id| subtree.contains(id.as_str()))
+            }
+            (None, Some(parent)) => self.order.iter().position(|id| id == parent),
+            (None, None) => None,
+        }
+        .map_or(0, |p| p + 1);
+
+        let mut node = node.clone();
+        node.children.clear();
+        node.depth = 0;
+        if let Some(parent) = node.parent.as_deref().and_then(|p| self.index.get_mut(p)) {
+            let at = before.and_then(|b| parent.children.iter().position(|c| c == b));
+            parent.children.insert(at.map_or(0, |i| i + 1), node.id.clone());
+            node.depth = parent.depth + 1;
+        }
+        self.order.insert(position, node.id.clone());
+        self.index.insert(node.id.clone(), node);
+    }
+}
+
+/// Rewrite IDs in `next` to match `prev` and compute the change set
+fn reconcile(prev: &SemanticDOM, next: &mut SemanticDOM) -> DocumentChanges {
+    let mut mapping: AHashMap<String, String> = AHashMap::new();
+    let mut matched_old: AHashSet<&str> = AHashSet::new();
+    let mut modified_new: AHashSet<String> = AHashSet::new();
+
+    // Pass 1: exact matches
+    let mut exact: AHashMap<(&SemanticRole, &str, &str, Option<&str>), VecDeque<&str>> =
+        AHashMap::new();
+    for node in prev.order.iter().filter_map(|id| prev.index.get(id)) {
+        exact.entry(exact_key(node)).or_default().push_back(&node.id);
+    }
+    for node in next.order.iter().filter_map(|id| next.index.get(id)) {
+        if let Some(old_id) = exact.get_mut(&exact_key(node)).and_then(|q| q.pop_front()) {
+            matched_old.insert(old_id);
+            mapping.insert(node.id.clone(), old_id.to_string());
+        }
+    }
+
+    // Pass 2: same element, changed content
+    let mut loose: AHashMap<(&SemanticRole, &str), VecDeque<&str>> = AHashMap::new();
+    for node in prev.order.iter().filter_map(|id| prev.index.get(id)) {
+        if !matched_old.contains(node.id.as_str()) {
+            loose
+                .entry((&node.role, node.selector.as_str()))
+                .or_default()
+                .push_back(&node.id);
+        }
+    }
+    for node in next.order.iter().filter_map(|id| next.index.get(id)) {
+        if mapping.contains_key(&node.id) {
+            continue;
+        }
+        let key = (&node.role, node.selector.as_str());
+        if let Some(old_id) = loose.get_mut(&key).and_then(|q| q.pop_front()) {
+            matched_old.insert(old_id);
+            mapping.insert(node.id.clone(), old_id.to_string());
+            modified_new.insert(node.id.clone());
+        }
+    }
+
+    // Exact matches can still differ in intent or accessible name
+    for (new_id, old_id) in &mapping {
+        if let (Some(a), Some(b)) = (prev.index.get(old_id), next.index.get(new_id)) {
+            if !same_content(a, b) {
+                modified_new.insert(new_id.clone());
+            }
+        }
+    }
+
+    // Unmatched new nodes keep their ID unless it belongs to an old node
+    let mut counter = prev.id_counter.max(next.id_counter);
+    let mut taken: AHashSet<String> = mapping.values().cloned().collect();
+    taken.extend(prev.index.keys().cloned());
+    let mut added = Vec::new();
+    for new_id in &next.order {
+        if mapping.contains_key(new_id) {
+            continue;
+        }
+        let final_id = if taken.contains(new_id) {
+            let tag = next
+                .index
+                .get(new_id)
+                .map(|n| selector_tag(&n.selector).to_string())
+                .unwrap_or_default();
+            loop {
+                counter += 1;
+                let candidate = format!("{}_{}_{}", next.config.id_prefix, tag, counter);
+                if !taken.contains(&candidate) && !next.index.contains_key(&candidate) {
+                    break candidate;
+                }
+            }
+        } else {
+            new_id.clone()
+        };
+        taken.insert(final_id.clone());
+        added.push(final_id.clone());
+        mapping.insert(new_id.clone(), final_id);
+    }
+    next.id_counter = counter;
+
+    let removed = prev
+        .order
+        .iter()
+        .filter(|id| !matched_old.contains(id.as_str()))
+        .cloned()
+        .collect();
+
+    let modified = next
+        .order
+        .iter()
+        .filter(|id| modified_new.contains(id.as_str()))
+        .filter_map(|id| mapping.get(id).cloned())
+        .collect();
+
+    rename_ids(next, &mapping);
+
+    DocumentChanges {
+        added,
+        removed,
+        modified,
+    }
+}
+
+fn exact_key(node: &SemanticNode) -> (&SemanticRole, &str, &str, Option<&str>) {
+    (
+        &node.role,
+        node.selector.as_str(),
+        node.label.as_str(),
+        node.href.as_deref(),
+    )
+}
+
+fn same_content(a: &SemanticNode, b: &SemanticNode) -> bool {
+    a.label == b.label
+        && a.role == b.role
+        && a.intent == b.intent
+        && a.href == b.href
+        && a.accessible_name == b.accessible_name
+}
+
+/// Tag name portion of a generated selector (`button.primary` -> `button`)
+fn selector_tag(selector: &str) -> &str {
+    selector
+        .split(['#', '.'])
+        .next()
+        .unwrap_or(selector)
+}
+
+/// Apply an old->new ID mapping to every ID reference in the document
+fn rename_ids(sdom: &mut SemanticDOM, mapping: &AHashMap<String, String>) {
+    let rename = |id: &String| mapping.get(id).cloned().unwrap_or_else(|| id.clone());
+
+    let index = std::mem::take(&mut sdom.index);
+    sdom.index = index
+        .into_iter()
+        .map(|(id, mut node)| {
+            node.id = rename(&id);
+            node.parent = node.parent.as_ref().map(rename);
+            node.children = node.children.iter().map(rename).collect();
+            (node.id.clone(), node)
+        })
+        .collect();
+
+    for list in [
+        &mut sdom.landmarks,
+        &mut sdom.interactables,
+        &mut sdom.headings,
+        &mut sdom.order,
+    ]
+    .into_iter()
+    .chain(sdom.by_role.values_mut())
+    .chain(sdom.by_intent.values_mut())
+    {
+        for id in list.iter_mut() {
+            *id = rename(id);
+        }
+    }
+
+    sdom.map_node_refs(&mut |id| Some(mapping.get(id).cloned().unwrap_or_else(|| id.to_string())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Error};
+
+    #[test]
+    fn test_update_preserves_ids() {
+        let mut sdom = SemanticDOM::parse(
+            r#"<nav><a href="/a">A</a><a href="/b">B</a></nav>"#,
+            Config::default(),
+        )
+        .unwrap();
+        let before: Vec<String> = sdom.interactables.clone();
+
+        let changes = sdom
+            .update(r#"<nav><a href="/new">New</a><a href="/a">A</a><a href="/b">B</a></nav>"#)
+            .unwrap();
+
+        assert_eq!(changes.added.len(), 1);
+        assert!(changes.removed.is_empty());
+        // Only the nav container's text-derived label changed
+        assert_eq!(changes.modified, sdom.landmarks);
+        for id in &before {
+            assert!(sdom.get(id).is_some());
+        }
+        assert_eq!(sdom.get(&before[0]).unwrap().label, "A");
+        assert_eq!(sdom.interactables.len(), 3);
+        assert_eq!(sdom.index.len(), 4);
+    }
+
+    #[test]
+    fn test_update_reports_modified_and_removed() {
+        let mut sdom = SemanticDOM::parse(
+            r#"<main><button>Add to cart</button><a href="/help">Help</a></main>"#,
+            Config::default(),
+        )
+        .unwrap();
+        let id_of = |sdom: &SemanticDOM, role: SemanticRole| {
+            sdom.index.values().find(|n| n.role == role).unwrap().id.clone()
+        };
+        let button = id_of(&sdom, SemanticRole::Button);
+        let link = id_of(&sdom, SemanticRole::Link);
+
+        let changes = sdom
+            .update(r#"<main><button>Added!</button></main>"#)
+            .unwrap();
+
+        assert!(changes.modified.contains(&button));
+        assert_eq!(changes.removed, vec![link]);
+        assert_eq!(sdom.get(&button).unwrap().label, "Added!");
+    }
+
+    #[test]
+    fn test_merge_keeps_static_nodes_in_place() {
+        let ssr = r#"<nav><a id="a" href="/a">A</a><a id="b" href="/b">B</a>
+            <a id="c" href="/c">C</a></nav><main><button>Static</button></main>"#;
+        let mut sdom = SemanticDOM::parse(ssr, Config::default()).unwrap();
+        let ids = sdom.interactables.clone();
+
+        // The capture lost link B and the main region and added a link
+        let overlay = r#"<nav><a id="a" href="/a">A</a><a id="c" href="/c">C</a>
+            <a id="d" href="/d">D</a></nav>"#;
+        let changes = sdom.merge(SemanticDOM::parse(overlay, Config::default()).unwrap());
+
+        let labels: Vec<&str> =
+            sdom.interactables.iter().map(|id| sdom.get(id).unwrap().label.as_str()).collect();
+        assert_eq!(labels, ["A", "B", "C", "D", "Static"]);
+        assert_eq!(sdom.interactables[..3], ids[..3]);
+        assert_eq!(sdom.get(&ids[1]).unwrap().depth, 1);
+        let nav = sdom.get(&sdom.landmarks[0]).unwrap();
+        assert_eq!(nav.children[..3], ids[..3]);
+        assert!(changes.removed.is_empty());
+        // Every link still leads somewhere
+        assert_eq!(sdom.state_graph.transitions.len(), 4);
+    }
+
+    #[test]
+    fn test_update_renames_relations() {
+        let mut sdom = SemanticDOM::parse(
+            r#"<main><button aria-controls="save">Save</button>
+            <section id="save" aria-label="Saved"></section></main>"#,
+            Config::default(),
+        )
+        .unwrap();
+        let save = sdom.interactables[0].clone();
+
+        // The new Cancel button takes the ID the fresh parse gives Save
+        sdom.update(
+            r#"<main><button aria-controls="cancel">Cancel</button>
+            <button aria-controls="save">Save</button>
+            <section id="cancel" aria-label="Cancelled"></section>
+            <section id="save" aria-label="Saved"></section></main>"#,
+        )
+        .unwrap();
+
+        let controlled = |id: &str| -> Vec<String> {
+            let related = sdom.related(id, crate::RelationKind::Controls);
+            related.iter().map(|n| n.label.clone()).collect()
+        };
+        assert_eq!(controlled(&save), ["Saved"]);
+        let cancel = sdom.interactables.iter().find(|id| **id != save).unwrap();
+        assert_eq!(controlled(cancel), ["Cancelled"]);
+        assert!(sdom.relations().iter().all(|r| sdom.get(&r.from).is_some()));
+    }
+
+    #[test]
+    fn test_update_renames_derived_ids() {
+        let old = r##"<input list="cities" aria-label="City">
+            <datalist id="cities"><option value="Oslo"></datalist>
+            <ul role="tree" aria-label="Files"><li role="treeitem" aria-expanded="false">Docs</li>
+            </ul><table role="grid" aria-label="Orders"><tr><td>#1001</td></tr></table>
+            <div role="alert">Order saved</div>
+            <a href="#help">Help</a><div id="help"><button>Ask</button></div>
+            <form action="/subscribe"><input name="email" aria-label="Email">
+            <button>Subscribe</button></form><input type="file" aria-label="Photo">"##;
+        let mut sdom =
+            SemanticDOM::parse(&format!("<main>{}</main>", old), Config::default()).unwrap();
+        // Parsed alone, the inserted elements take the IDs of the old ones
+        let inserted = r##"<input list="colours" aria-label="Colour">
+            <datalist id="colours"><option value="Red"></datalist>
+            <ul role="tree" aria-label="Music"><li role="treeitem" aria-expanded="true">Jazz</li>
+            </ul><table role="grid" aria-label="Returns"><tr><td>#7</td></tr></table>
+            <div role="alert">Return failed</div>
+            <a href="#tips">Tips</a><div id="tips"><button>Read</button></div>
+            <form action="/search"><input name="q" aria-label="Query">
+            <button>Search</button></form><input type="file" aria-label="Scan">"##;
+        sdom.update(&format!("<main>{}{}</main>", inserted, old)).unwrap();
+
+        let label = |id: &str| sdom.get(id).unwrap().label.clone();
+        let state = |id: String| sdom.state_graph.states.iter().find(|s| s.id == id).unwrap();
+        assert_eq!(sdom.comboboxes().len(), 2);
+        for combobox in sdom.comboboxes() {
+            let name = format!("{} (open)", label(&combobox.id));
+            assert_eq!(state(format!("state_open_{}", combobox.id)).name, name);
+        }
+        let trees: Vec<String> = sdom.trees().iter().map(|t| label(&t.id)).collect();
+        assert_eq!(trees, ["Music", "Files"]);
+        for item in sdom.trees().iter().flat_map(|t| t.flatten()) {
+            let toggled = if item.expanded == Some(true) { "collapsed" } else { "expanded" };
+            let name = format!("{} ({})", label(&item.id), toggled);
+            assert_eq!(state(format!("state_{}_{}", toggled, item.id)).name, name);
+        }
+        let grids: Vec<String> = sdom.grids().iter().map(|g| label(&g.id)).collect();
+        assert_eq!(grids, ["Returns", "Orders"]);
+        for notification in sdom.notifications() {
+            assert_eq!(label(notification.id.as_ref().unwrap()), notification.messages[0]);
+        }
+        assert_eq!(sdom.anchor_target("#help").unwrap().label, "Ask");
+        let mut scrolls: Vec<(String, String)> = Vec::new();
+        for state in sdom.state_graph.states.iter().filter(|s| s.anchor.is_some()) {
+            let target = state.anchor.as_ref().unwrap();
+            assert_eq!(state.id, format!("state_at_{}", target));
+            scrolls.push((state.name.clone(), label(target)));
+        }
+        scrolls.sort();
+        assert_eq!(scrolls, [("Help".into(), "Ask".into()), ("Tips".into(), "Read".into())]);
+        let submits: Vec<_> = sdom
+            .state_graph
+            .transitions
+            .iter()
+            .filter(|t| t.action.as_deref() == Some("submit"))
+            .collect();
+        assert_eq!(submits.len(), 2);
+        for t in submits {
+            let request = sdom.submit_request(&t.trigger).unwrap();
+            let field = |r: &crate::FormRequest| label(r.fields[0].node.as_ref().unwrap());
+            let expected = match label(&t.trigger).as_str() {
+                "Subscribe" => ("/subscribe", "Email"),
+                _ => ("/search", "Query"),
+            };
+            assert!(request.action.as_deref().unwrap().ends_with(expected.0));
+            assert_eq!(field(request), expected.1);
+            assert_eq!(field(t.request.as_ref().unwrap()), expected.1);
+        }
+        assert_eq!(sdom.file_inputs().len(), 2);
+        for input in sdom.file_inputs() {
+            let name = format!("{} (file selected)", input.label);
+            assert_eq!(state(format!("state_file_selected_{}", input.id)).name, name);
+        }
+        for transition in &sdom.state_graph.transitions {
+            assert!(sdom.state_graph.states.iter().any(|s| s.id == transition.to));
+        }
+    }
+
+    #[test]
+    fn test_update_renames_listing_ids() {
+        let cards: String = ["One", "Two", "Three"]
+            .map(|t| format!(r#"<li class="hit"><h2><a href="/{t}">{t}</a></h2>Found</li>"#))
+            .concat()
+            + r#"<li><button class="btn" aria-busy="true">More results</button></li>"#;
+        let cards = format!(
+            r#"<select aria-label="Sort by"><option>Newest</option><option>Oldest</option>
+            </select><aside aria-label="Filters">
+            <input type="radio" name="price" value="low" aria-label="Cheap">
+            <input type="radio" name="price" value="high" aria-label="Pricey"></aside>{}"#,
+            cards
+        );
+        let mut sdom =
+            SemanticDOM::parse(&format!("<main><ol>{}</ol></main>", cards), Config::default())
+                .unwrap();
+        // A link ahead of the cards shifts the IDs the fresh parse gives them
+        let html = format!(r#"<main><a href="/new">New</a><ol>{}</ol></main>"#, cards);
+        sdom.update(&html).unwrap();
+
+        let label = |id: &str| sdom.get(id).unwrap().label.clone();
+        let items = &sdom.result_lists()[0].items;
+        assert_eq!(items.len(), 3);
+        for item in items {
+            assert_eq!(label(&item.id), item.title);
+        }
+        let cluster = &sdom.clusters()[0];
+        assert_eq!(cluster.instances.len(), 3);
+        for (instance, item) in cluster.instances.iter().zip(items) {
+            assert!(instance.contains(&item.id), "{:?} {:?}", instance, item);
+        }
+        let loading = &sdom.loading_indicators()[0];
+        assert_eq!(label(loading.id.as_ref().unwrap()), "More results");
+        let facets = sdom.facets();
+        assert_eq!(label(facets.sort[0].id.as_ref().unwrap()), "Sort by");
+        for option in facets.iter().flat_map(|f| &f.options).filter(|o| o.id.is_some()) {
+            assert_eq!(label(option.id.as_ref().unwrap()), option.label);
+        }
+        assert_eq!(facets.filters[0].options.len(), 2);
+    }
+
+    #[test]
+    fn test_update_and_merge_recertify() {
+        let vague = r#"<a href="/terms">Click here</a>"#;
+        let mut sdom =
+            SemanticDOM::parse(&format!("<main>{}</main>", vague), Config::default()).unwrap();
+        let link = sdom.interactables[0].clone();
+        let unhelpful = |sdom: &SemanticDOM| {
+            let cert = sdom.certification.as_ref().unwrap();
+            let check = cert.checks.iter().find(|c| c.id == "A11Y-007").unwrap();
+            check.details.clone().unwrap()
+        };
+
+        // The fresh parse gives the vague link another ID before renaming
+        let html = format!(r#"<main><a href="/">Home</a>{}</main>"#, vague);
+        sdom.update(&html).unwrap();
+        assert_eq!(unhelpful(&sdom), format!("1 unhelpful: {} (generic)", link));
+
+        let overlay = format!(r#"<main><a href="/">Home</a>{}<button>Go</button></main>"#, vague);
+        sdom.merge(SemanticDOM::parse(&overlay, Config::default()).unwrap());
+        assert_eq!(sdom.certification.as_ref().unwrap().stats.interactable_count, 3);
+
+        let config = Config::builder().validate(false).build().unwrap();
+        sdom.merge(SemanticDOM::parse(&overlay, config).unwrap());
+        assert!(sdom.certification.is_none());
+    }
+
+    #[test]
+    fn test_update_renames_warnings_and_diagnostics() {
+        let bad = r#"<a href="javascript:void(0)">Bad</a>"#;
+        let mut sdom =
+            SemanticDOM::parse(&format!("<main>{}</main>", bad), Config::default()).unwrap();
+        let link = sdom.interactables[0].clone();
+
+        // The fresh parse gives the bad link the ID of the new Home link
+        sdom.update(&format!(r#"<main><a href="/">Home</a>{}</main>"#, bad)).unwrap();
+        assert!(matches!(
+            sdom.warnings(),
+            [Error::UrlRejected { node, .. }] if *node == link
+        ));
+        let dropped: Vec<_> = sdom.diagnostics().iter().filter_map(|d| d.node.as_ref()).collect();
+        assert_eq!(dropped, [&link]);
+        assert_eq!(sdom.get(&link).unwrap().label, "Bad");
+    }
+
+    #[test]
+    fn test_update_unchanged() {
+        let html = r#"<main><h1>Title</h1><button>Go</button></main>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let changes = sdom.update(html).unwrap();
+        assert!(changes.is_empty());
+    }
+}
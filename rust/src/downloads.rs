@@ -0,0 +1,243 @@
+//! Downloadable asset detection
+//!
+//! Links are recognized as downloads by the `download` attribute, by a file
+//! extension from [`Config::download_types`](crate::Config::download_types),
+//! or by a `type` attribute naming one of the configured MIME types. File
+//! details are stored in node metadata during parsing.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticRole};
+use indexmap::IndexMap;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// Metadata key holding a download's file extension
+pub(crate) const FILE_TYPE_KEY: &str = "file_type";
+/// Metadata key holding a download's MIME type
+pub(crate) const MIME_TYPE_KEY: &str = "mime_type";
+/// Metadata key holding a download's size hint
+pub(crate) const SIZE_KEY: &str = "file_size";
+
+/// A link to a downloadable file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadLink {
+    /// ID of the link node
+    pub id: String,
+    /// Link label
+    pub label: String,
+    /// Validated link target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+    /// Lowercase file extension, e.g. `"pdf"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<String>,
+    /// MIME type, from the `type` attribute or the extension map
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Size as written on the page, e.g. `"2.4 MB"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_hint: Option<String>,
+}
+
+/// Extension-to-MIME map used when none is configured
+pub(crate) fn default_download_types() -> Vec<(String, String)> {
+    [
+        ("pdf", "application/pdf"),
+        ("zip", "application/zip"),
+        ("gz", "application/gzip"),
+        ("tgz", "application/gzip"),
+        ("tar", "application/x-tar"),
+        ("7z", "application/x-7z-compressed"),
+        ("rar", "application/vnd.rar"),
+        ("doc", "application/msword"),
+        ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+        ("xls", "application/vnd.ms-excel"),
+        ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        ("ppt", "application/vnd.ms-powerpoint"),
+        ("pptx", "application/vnd.openxmlformats-officedocument.presentationml.presentation"),
+        ("odt", "application/vnd.oasis.opendocument.text"),
+        ("csv", "text/csv"),
+        ("epub", "application/epub+zip"),
+        ("dmg", "application/x-apple-diskimage"),
+        ("exe", "application/vnd.microsoft.portable-executable"),
+        ("msi", "application/x-msi"),
+        ("apk", "application/vnd.android.package-archive"),
+        ("iso", "application/x-iso9660-image"),
+    ]
+    .into_iter()
+    .map(|(ext, mime)| (ext.to_string(), mime.to_string()))
+    .collect()
+}
+
+/// File extension of a link target's path, ignoring query and fragment
+fn extension(href: &str) -> Option<String> {
+    let path = href.split(['?', '#']).next().unwrap_or("");
+    let file = path.rsplit('/').next().unwrap_or("");
+    let (_, ext) = file.rsplit_once('.')?;
+    (!ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| ext.to_ascii_lowercase())
+}
+
+/// Check whether a link target points at a configured download type
+pub(crate) fn is_download_href(href: &str, types: &[(String, String)]) -> bool {
+    extension(href).is_some_and(|ext| types.iter().any(|(e, _)| *e == ext))
+}
+
+/// Check whether a link's `type` attribute names a configured MIME type
+pub(crate) fn is_download_mime(element: ElementRef, types: &[(String, String)]) -> bool {
+    element
+        .value()
+        .attr("type")
+        .is_some_and(|t| types.iter().any(|(_, m)| m.eq_ignore_ascii_case(t.trim())))
+}
+
+/// File details of a download link, as metadata entries
+pub(crate) fn file_metadata(
+    element: ElementRef,
+    types: &[(String, String)],
+) -> Vec<(&'static str, String)> {
+    let el = element.value();
+    let declared_mime = el.attr("type").map(|t| t.trim().to_ascii_lowercase());
+
+    let file_type = el
+        .attr("href")
+        .and_then(extension)
+        .or_else(|| el.attr("download").and_then(extension))
+        .or_else(|| {
+            let mime = declared_mime.as_deref()?;
+            types.iter().find(|(_, m)| m == mime).map(|(e, _)| e.clone())
+        });
+    let mime_type = declared_mime.filter(|m| !m.is_empty()).or_else(|| {
+        let ext = file_type.as_deref()?;
+        types.iter().find(|(e, _)| e == ext).map(|(_, m)| m.clone())
+    });
+    let text = format!(
+        "{} {}",
+        element.text().collect::<String>(),
+        el.attr("title").unwrap_or("")
+    );
+
+    let mut entries = Vec::new();
+    if let Some(file_type) = file_type {
+        entries.push((FILE_TYPE_KEY, file_type));
+    }
+    if let Some(mime_type) = mime_type {
+        entries.push((MIME_TYPE_KEY, mime_type));
+    }
+    if let Some(size) = size_hint(&text) {
+        entries.push((SIZE_KEY, size));
+    }
+    entries
+}
+
+/// Find a size like "2.4 MB" or "(512KB)" in link text
+fn size_hint(text: &str) -> Option<String> {
+    const UNITS: &[&str] = &[
+        "bytes", "kb", "mb", "gb", "tb", "kib", "mib", "gib", "ko", "mo", "go",
+    ];
+    let tokens: Vec<&str> = text
+        .split(|c: char| {
+            c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ',' | '|' | '–' | '-')
+        })
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let is_number = |s: &str| {
+        !s.is_empty()
+            && s.chars().any(|c| c.is_ascii_digit())
+            && s.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+    };
+    let is_unit = |s: &str| UNITS.iter().any(|u| s.eq_ignore_ascii_case(u));
+
+    for (i, token) in tokens.iter().enumerate() {
+        // "2.4 MB"
+        if is_number(token) {
+            if let Some(u) = tokens.get(i + 1).filter(|t| is_unit(t)) {
+                return Some(format!("{} {}", token, u));
+            }
+        }
+        // "2.4MB"
+        if let Some(split) = token.find(|c: char| c.is_alphabetic()) {
+            let (number, u) = token.split_at(split);
+            if is_number(number) && is_unit(u) {
+                return Some(format!("{} {}", number, u));
+            }
+        }
+    }
+    None
+}
+
+impl SemanticDOM {
+    /// Get links to downloadable files, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <a href="/files/report.pdf">Annual report (PDF, 2.4 MB)</a>
+    ///     <a href="/about">About</a>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let downloads = sdom.downloads();
+    /// assert_eq!(downloads.len(), 1);
+    /// assert_eq!(downloads[0].file_type.as_deref(), Some("pdf"));
+    /// assert_eq!(downloads[0].mime_type.as_deref(), Some("application/pdf"));
+    /// assert_eq!(downloads[0].size_hint.as_deref(), Some("2.4 MB"));
+    /// ```
+    pub fn downloads(&self) -> Vec<DownloadLink> {
+        let empty = IndexMap::new();
+        self.reading_order()
+            .into_iter()
+            .filter(|n| {
+                matches!(n.role, SemanticRole::Link) && n.intent == Some(SemanticIntent::Download)
+            })
+            .map(|n| {
+                let meta = n.metadata.as_ref().unwrap_or(&empty);
+                DownloadLink {
+                    id: n.id.clone(),
+                    label: n.label.clone(),
+                    href: n.href.clone(),
+                    file_type: meta.get(FILE_TYPE_KEY).cloned(),
+                    mime_type: meta.get(MIME_TYPE_KEY).cloned(),
+                    size_hint: meta.get(SIZE_KEY).cloned(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_size_hints() {
+        assert_eq!(size_hint("Report (PDF, 2.4 MB)").as_deref(), Some("2.4 MB"));
+        assert_eq!(size_hint("Installer [512KB]").as_deref(), Some("512 KB"));
+        assert_eq!(size_hint("Top 10 tips"), None);
+    }
+
+    #[test]
+    fn test_configured_types_and_mime_attribute() {
+        let config = Config::builder()
+            .download_type(".GPX", "application/gpx+xml")
+            .build()
+            .unwrap();
+        let html = r#"
+            <a href="/tracks/ride.gpx?v=2">Ride</a>
+            <a href="/export" type="text/csv">Export</a>
+            <a href="/guide.html">Guide</a>
+            <a href="/logo" download="logo.svg">Logo</a>
+        "#;
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+        let downloads = sdom.downloads();
+
+        assert_eq!(downloads.len(), 3);
+        assert_eq!(downloads[0].file_type.as_deref(), Some("gpx"));
+        assert_eq!(downloads[1].file_type.as_deref(), Some("csv"));
+        assert_eq!(downloads[2].file_type.as_deref(), Some("svg"));
+    }
+}
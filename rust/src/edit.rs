@@ -0,0 +1,441 @@
+//! Mutable document API
+//!
+//! Tools that annotate or correct the parsed model before exporting it
+//! need to edit nodes in place. These methods keep the index, the tree
+//! links, and the landmark/interactable/heading lists consistent.
+
+use crate::noise::NOISE_KEY;
+use crate::parser::SemanticDOM;
+use crate::types::{Bounds, SemanticIntent, SemanticNode, SemanticRole};
+use crate::{Error, Result};
+use ahash::AHashSet;
+
+impl SemanticDOM {
+    /// Insert a new node into the document
+    ///
+    /// The node is linked under `node.parent` (appended as its last child)
+    /// or added as a new root if it has no parent. Its `depth` is derived
+    /// from the parent and any pre-set `children` are ignored.
+    ///
+    /// # Errors
+    /// - [`Error::DuplicateNodeId`] if a node with the same ID exists
+    /// - [`Error::NodeNotFound`] if the parent does not exist
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticNode, SemanticRole};
+    ///
+    /// let mut sdom = SemanticDOM::parse("<main id=\"content\"></main>", Config::default()).unwrap();
+    /// let mut node = SemanticNode::new(
+    ///     "sdom_help".to_string(),
+    ///     "Help".to_string(),
+    ///     SemanticRole::Button,
+    ///     "button.help".to_string(),
+    /// );
+    /// node.parent = Some("sdom_content".to_string());
+    /// sdom.insert_node(node).unwrap();
+    ///
+    /// assert_eq!(sdom.interactables, vec!["sdom_help"]);
+    /// assert_eq!(sdom.get("sdom_help").unwrap().depth, 1);
+    /// ```
+    pub fn insert_node(&mut self, mut node: SemanticNode) -> Result<()> {
+        if self.index.contains_key(&node.id) {
+            return Err(Error::DuplicateNodeId(node.id));
+        }
+
+        node.children.clear();
+        let position = match node.parent.as_deref() {
+            Some(parent_id) => {
+                let parent = self
+                    .index
+                    .get(parent_id)
+                    .ok_or_else(|| Error::NodeNotFound(parent_id.to_string()))?;
+                node.depth = parent.depth + 1;
+
+                // Place the node after the parent's last descendant
+                let subtree = self.subtree_ids(parent_id);
+                self.order
+                    .iter()
+                    .rposition(|id| subtree.contains(id.as_str()))
+                    .map_or(self.order.len(), |p| p + 1)
+            }
+            None => {
+                node.depth = 0;
+                self.order.len()
+            }
+        };
+
+        if let Some(parent) = node.parent.as_deref().and_then(|p| self.index.get_mut(p)) {
+            parent.children.push(node.id.clone());
+        }
+
+        self.order.insert(position, node.id.clone());
+        self.index.insert(node.id.clone(), node);
+        self.rebuild_categories();
+        Ok(())
+    }
+
+    /// Remove a node and all of its descendants
+    ///
+    /// State graph transitions triggered by removed nodes are dropped, as
+    /// are states that are left without incoming transitions and relations
+    /// to or from removed nodes. So are products, notifications, loading
+    /// indicators, grid rows and cells, and form fields of removed nodes,
+    /// along with states that scroll to them; a removed combobox popup is
+    /// unset.
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn remove_node(&mut self, id: &str) -> Result<SemanticNode> {
+        if !self.index.contains_key(id) {
+            return Err(Error::NodeNotFound(id.to_string()));
+        }
+
+        let removed: AHashSet<String> = self.subtree_ids(id).into_iter().map(str::to_string).collect();
+
+        let node = self
+            .index
+            .remove(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        for descendant in &removed {
+            self.index.remove(descendant);
+        }
+
+        if let Some(parent) = node.parent.as_deref().and_then(|p| self.index.get_mut(p)) {
+            parent.children.retain(|c| c != id);
+        }
+        self.prune(&removed);
+
+        Ok(node)
+    }
+
+    /// Copy one node and its descendants into a document of their own
+    ///
+    /// The node becomes the root at depth 0, with its own index, landmark,
+    /// interactable and heading lists. Relations, widgets and state graph
+    /// transitions that involve nodes outside it are dropped, as in
+    /// [`Self::remove_node`]. Page-level details such as the title and
+    /// base URL are kept. Returns `None` if no node has the given ID.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<nav><a href="/">Home</a></nav>
+    ///     <div role="dialog" id="login">
+    ///         <form><input name="email"><button>Sign in</button></form>
+    ///     </div>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let dialog = sdom.subtree("sdom_login").unwrap();
+    ///
+    /// assert_eq!(dialog.index.len(), 4);
+    /// assert_eq!(dialog.get("sdom_login").unwrap().depth, 0);
+    /// assert_eq!(dialog.interactables.len(), 2);
+    /// assert!(dialog.landmarks.iter().all(|id| sdom.get(id).is_some()));
+    /// ```
+    pub fn subtree(&self, id: &str) -> Option<SemanticDOM> {
+        let root = self.index.get(id)?;
+        let kept = self.subtree_ids(id);
+        let removed: AHashSet<String> = self
+            .index
+            .keys()
+            .filter(|k| !kept.contains(k.as_str()))
+            .cloned()
+            .collect();
+        let base = root.depth;
+
+        let mut sub = self.clone();
+        sub.index.retain(|k, _| !removed.contains(k));
+        for node in sub.index.values_mut() {
+            node.depth -= base;
+        }
+        if let Some(root) = sub.index.get_mut(id) {
+            root.parent = None;
+        }
+        sub.prune(&removed);
+        // Entries not tied to a node cannot be placed inside the subtree
+        sub.products.retain(|p| p.add_to_cart_id.is_some());
+        sub.notifications.retain(|n| n.id.is_some());
+        sub.loading.retain(|l| l.id.is_some());
+        if sub.certification.is_some() {
+            sub.certification = Some(sub.certify());
+        }
+        Some(sub)
+    }
+
+    /// Drop everything that refers to nodes no longer in the index
+    fn prune(&mut self, removed: &AHashSet<String>) {
+        self.order.retain(|o| !removed.contains(o));
+        self.rebuild_categories();
+        self.map_node_refs(&mut |id| (!removed.contains(id)).then(|| id.to_string()));
+
+        // States left without incoming transitions go too
+        let graph = &mut self.state_graph;
+        let targets: AHashSet<&str> = graph.transitions.iter().map(|t| t.to.as_str()).collect();
+        graph
+            .states
+            .retain(|s| s.is_initial || targets.contains(s.id.as_str()));
+    }
+
+    /// Change a node's label
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn set_label(&mut self, id: &str, label: impl Into<String>) -> Result<()> {
+        let node = self
+            .index
+            .get_mut(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        node.label = label.into();
+        Ok(())
+    }
+
+    /// Set or clear a node's rendered geometry
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn set_bounds(&mut self, id: &str, bounds: Option<Bounds>) -> Result<()> {
+        let node = self
+            .index
+            .get_mut(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        node.bounds = bounds;
+        Ok(())
+    }
+
+    /// Change a node's role, updating the category lists
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn set_role(&mut self, id: &str, role: SemanticRole) -> Result<()> {
+        let node = self
+            .index
+            .get_mut(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        node.role = role;
+        node.role_confidence = 1.0;
+        node.inferred = false;
+        self.rebuild_categories();
+        Ok(())
+    }
+
+    /// Set or clear a node's intent
+    ///
+    /// A manually set intent is treated as fully confident.
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn set_intent(&mut self, id: &str, intent: Option<SemanticIntent>) -> Result<()> {
+        let node = self
+            .index
+            .get_mut(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        node.intent_confidence = intent.as_ref().map(|_| 1.0);
+        node.intent = intent;
+        self.rebuild_indices();
+        Ok(())
+    }
+
+    /// Collect the IDs of a node and all of its descendants
+    pub(crate) fn subtree_ids(&self, id: &str) -> AHashSet<&str> {
+        let mut ids = AHashSet::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if let Some(node) = self.index.get_key_value(current) {
+                if ids.insert(node.0.as_str()) {
+                    stack.extend(node.1.children.iter().map(String::as_str));
+                }
+            }
+        }
+        ids
+    }
+
+    /// Recompute the landmark, interactable, and heading lists and the role
+    /// indices in document order
+    pub(crate) fn rebuild_categories(&mut self) {
+        self.landmarks.clear();
+        self.interactables.clear();
+        self.headings.clear();
+
+        for id in &self.order {
+            if let Some(node) = self.index.get(id) {
+                if node.role.is_landmark() {
+                    self.landmarks.push(id.clone());
+                }
+                let noise = node.metadata.as_ref().is_some_and(|m| m.contains_key(NOISE_KEY));
+                if node.role.is_interactable() && !noise {
+                    self.interactables.push(id.clone());
+                }
+                if matches!(node.role, SemanticRole::Heading) {
+                    self.headings.push(id.clone());
+                }
+            }
+        }
+        self.rebuild_indices();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    const HTML: &str = r#"
+        <nav id="menu"><a id="home" href="/">Home</a><a id="about" href="/about">About</a></nav>
+        <main id="content"><h1>Title</h1><button id="buy">Buy</button></main>
+    "#;
+
+    #[test]
+    fn test_remove_node_subtree() {
+        let mut sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let transitions = sdom.state_graph.transitions.len();
+
+        let removed = sdom.remove_node("sdom_menu").unwrap();
+        assert_eq!(removed.role, SemanticRole::Navigation);
+        assert!(sdom.get("sdom_home").is_none());
+        assert!(sdom.get("sdom_about").is_none());
+        assert_eq!(sdom.landmarks, vec!["sdom_content"]);
+        assert_eq!(sdom.interactables, vec!["sdom_buy"]);
+        assert_eq!(sdom.state_graph.transitions.len(), transitions - 2);
+        assert_eq!(sdom.state_graph.states.len(), 1);
+
+        assert!(matches!(sdom.remove_node("sdom_menu"), Err(Error::NodeNotFound(_))));
+
+        // Products, notifications and loading indicators go with their nodes
+        let html = r#"<main>
+            <section id="card"><h2>Lamp</h2><span class="price">$20</span>
+                <button id="add">Add to cart</button>
+                <div role="alert" id="added">Added</div>
+                <button id="more" aria-busy="true">Loading</button></section>
+            <div role="status" id="cart">Cart: 0 items</div></main>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert_eq!(sdom.products.len(), 1);
+        assert_eq!(sdom.notifications.len(), 2);
+        assert_eq!(sdom.loading.len(), 1);
+        sdom.remove_node("sdom_card").unwrap();
+        assert!(sdom.products.is_empty());
+        assert_eq!(sdom.notifications.len(), 1);
+        assert!(sdom.loading.is_empty());
+    }
+
+    #[test]
+    fn test_remove_node_drops_request_fields() {
+        let html = r#"<form action="/subscribe" method="post">
+            <input name="email" type="email" aria-label="Email">
+            <input name="name" aria-label="Name" id="name">
+            <button id="go">Subscribe</button></form>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        sdom.remove_node("sdom_name").unwrap();
+
+        let names = |request: &crate::FormRequest| -> Vec<String> {
+            request.fields.iter().map(|f| f.name.clone()).collect()
+        };
+        assert_eq!(names(sdom.submit_request("sdom_go").unwrap()), ["email"]);
+        let submit = sdom.state_graph.transitions.iter().find_map(|t| t.request.as_ref());
+        assert_eq!(names(submit.unwrap()), ["email"]);
+    }
+
+    #[test]
+    fn test_remove_node_clears_combobox_popup() {
+        let html = r#"<main><input id="q" role="combobox" aria-label="Search"
+                   aria-expanded="true" aria-controls="results">
+            <ul id="results" role="listbox"><li role="option">Rust book</li></ul></main>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert_eq!(sdom.comboboxes()[0].popup_id.as_deref(), Some("sdom_results"));
+
+        sdom.remove_node("sdom_results").unwrap();
+        assert_eq!(sdom.comboboxes()[0].popup_id, None);
+    }
+
+    #[test]
+    fn test_remove_node_drops_anchor_states() {
+        let html = r##"<nav><a href="#faq">FAQ</a></nav>
+            <main><section id="faq" aria-label="FAQ"><h2>FAQ</h2></section></main>"##;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let graph = &sdom.state_graph;
+        assert!(graph.states.iter().any(|s| s.id == "state_at_sdom_faq"));
+
+        sdom.remove_node("sdom_faq").unwrap();
+        let graph = &sdom.state_graph;
+        assert!(graph.states.iter().all(|s| s.anchor.is_none()));
+        assert!(graph.states.iter().all(|s| !s.id.starts_with("state_at_")));
+        assert!(graph.transitions.iter().all(|t| !t.to.starts_with("state_at_")));
+        assert!(sdom.anchor_target("#faq").is_none());
+    }
+
+    #[test]
+    fn test_remove_node_drops_grid_cells() {
+        let html = r#"<main><div role="grid" aria-label="Inbox">
+            <div role="row"><div role="gridcell" id="from" tabindex="0">Ann</div>
+                <div role="gridcell" id="subject" tabindex="-1">Hello</div></div>
+            <div role="row" id="second" tabindex="0"><div role="gridcell">Bob</div></div>
+        </div></main>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let rows = &sdom.grids()[0].rows;
+        assert_eq!(rows[0].cells[0].id.as_deref(), Some("sdom_from"));
+        assert_eq!(rows.len(), 2);
+
+        sdom.remove_node("sdom_from").unwrap();
+        sdom.remove_node("sdom_second").unwrap();
+        let rows = &sdom.grids()[0].rows;
+        assert_eq!(rows.len(), 1);
+        let texts: Vec<&str> = rows[0].cells.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["Hello"]);
+    }
+
+    #[test]
+    fn test_subtree_is_self_contained() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let menu = sdom.subtree("sdom_menu").unwrap();
+
+        assert_eq!(menu.reading_order().len(), 3);
+        assert_eq!(menu.get("sdom_menu").unwrap().parent, None);
+        assert_eq!(menu.get("sdom_home").unwrap().depth, 1);
+        assert_eq!(menu.landmarks, vec!["sdom_menu"]);
+        assert!(menu.headings.is_empty());
+        // Only the two links of the menu trigger transitions
+        assert_eq!(menu.state_graph.transitions.len(), 2);
+        assert_eq!(menu.query_by_role(&SemanticRole::Button).len(), 0);
+        assert!(sdom.subtree("sdom_missing").is_none());
+    }
+
+    #[test]
+    fn test_insert_node_keeps_document_order() {
+        let mut sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let mut node = SemanticNode::new(
+            "sdom_contact".to_string(),
+            "Contact".to_string(),
+            SemanticRole::Link,
+            "a.contact".to_string(),
+        );
+        node.parent = Some("sdom_menu".to_string());
+        sdom.insert_node(node.clone()).unwrap();
+
+        assert_eq!(sdom.interactables, vec!["sdom_home", "sdom_about", "sdom_contact", "sdom_buy"]);
+        assert_eq!(sdom.get("sdom_menu").unwrap().children.len(), 3);
+        assert!(matches!(sdom.insert_node(node), Err(Error::DuplicateNodeId(_))));
+
+        let mut orphan = SemanticNode::new(
+            "sdom_x".to_string(),
+            "X".to_string(),
+            SemanticRole::Button,
+            "button".to_string(),
+        );
+        orphan.parent = Some("sdom_missing".to_string());
+        assert!(matches!(sdom.insert_node(orphan), Err(Error::NodeNotFound(_))));
+    }
+
+    #[test]
+    fn test_relabel_and_set_intent() {
+        let mut sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+
+        sdom.set_label("sdom_buy", "Add to cart").unwrap();
+        sdom.set_intent("sdom_buy", Some(SemanticIntent::Create)).unwrap();
+        let node = sdom.get("sdom_buy").unwrap();
+        assert_eq!(node.label, "Add to cart");
+        assert_eq!(node.intent, Some(SemanticIntent::Create));
+
+        sdom.set_role("sdom_content", SemanticRole::Section).unwrap();
+        assert_eq!(sdom.landmarks, vec!["sdom_menu"]);
+    }
+}
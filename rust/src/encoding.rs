@@ -0,0 +1,313 @@
+//! Character encoding detection for byte input
+//!
+//! Fetched pages are frequently not valid UTF-8. This module sniffs the
+//! encoding using a simplified version of the HTML encoding sniffing
+//! algorithm (BOM, then the `Content-Type` charset when the bytes came with
+//! one, then `<meta charset>` prescan, then a UTF-8 validity check) and
+//! decodes the bytes with `encoding_rs`.
+
+use crate::Error;
+use encoding_rs::{DecoderResult, Encoding, UTF_8, WINDOWS_1252};
+use std::borrow::Cow;
+
+/// Number of leading bytes scanned for a `<meta charset>` declaration
+const PRESCAN_LIMIT: usize = 1024;
+
+/// Detect the character encoding of an HTML byte stream
+///
+/// # Detection Order
+/// 1. Byte order mark (UTF-8, UTF-16LE, UTF-16BE)
+/// 2. `<meta charset="...">` or
+///    `<meta http-equiv="content-type" content="...charset=...">` within the
+///    first 1024 bytes
+/// 3. UTF-8 if the input is valid UTF-8, otherwise windows-1252
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::detect_encoding;
+///
+/// let html = b"<html><head><meta charset=\"iso-8859-1\"></head></html>";
+/// assert_eq!(detect_encoding(html), "windows-1252");
+/// assert_eq!(detect_encoding("<p>héllo</p>".as_bytes()), "UTF-8");
+/// ```
+pub fn detect_encoding(bytes: &[u8]) -> &'static str {
+    sniff_encoding(bytes, None).name()
+}
+
+/// Decode HTML bytes to a string using the sniffed encoding
+///
+/// `transport` is the charset given by the `Content-Type` header, if any;
+/// it ranks below a byte order mark and above a `<meta>` declaration.
+/// Malformed sequences are replaced with U+FFFD rather than failing; the
+/// first one is reported as an [`Error::EncodingError`] warning.
+pub(crate) fn decode_html<'a>(
+    bytes: &'a [u8],
+    transport: Option<&str>,
+) -> (Cow<'a, str>, Option<Error>) {
+    let encoding = sniff_encoding(bytes, transport);
+    // `decode` strips a matching BOM and re-sniffs it, so a BOM always wins
+    let (decoded, used, had_errors) = encoding.decode(bytes);
+    let warning = had_errors.then(|| Error::EncodingError {
+        encoding: used.name().to_string(),
+        position: first_malformed(bytes, used).unwrap_or(0),
+    });
+    (decoded, warning)
+}
+
+/// Byte offset of the first malformed sequence
+fn first_malformed(bytes: &[u8], encoding: &'static Encoding) -> Option<usize> {
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let capacity = decoder.max_utf8_buffer_length_without_replacement(bytes.len())?;
+    let mut out = String::with_capacity(capacity);
+    match decoder.decode_to_string_without_replacement(bytes, &mut out, true) {
+        (DecoderResult::Malformed(bad, _), read) => Some(read.saturating_sub(bad as usize)),
+        _ => None,
+    }
+}
+
+/// The `charset` parameter of a `Content-Type` header value
+///
+/// ```text
+/// text/html; charset="Shift_JIS"  ->  Some("Shift_JIS")
+/// ```
+pub(crate) fn content_type_charset(value: &str) -> Option<&str> {
+    value.split(';').skip(1).find_map(|param| {
+        let (name, label) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| label.trim().trim_matches(|c| c == '"' || c == '\''))
+    })
+}
+
+fn sniff_encoding(bytes: &[u8], transport: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    if let Some(encoding) = transport.and_then(|label| Encoding::for_label(label.as_bytes())) {
+        return encoding;
+    }
+
+    if let Some(encoding) = prescan_meta_charset(&bytes[..bytes.len().min(PRESCAN_LIMIT)]) {
+        return encoding;
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Look for a charset declaration in `<meta>` tags
+///
+/// A `charset` attribute counts on its own; a `charset=` parameter in
+/// `content` only with `http-equiv="content-type"`.
+fn prescan_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let lower = bytes.to_ascii_lowercase();
+    let mut pos = 0;
+
+    while let Some(offset) = find(&lower[pos..], b"<meta") {
+        let start = pos + offset + b"<meta".len();
+        let end = lower[start..]
+            .iter()
+            .position(|&b| b == b'>')
+            .map_or(lower.len(), |p| start + p);
+        pos = end;
+        // `<metadata>` and the like are other elements
+        if !lower.get(start).is_some_and(|&b| b == b'/' || b.is_ascii_whitespace()) {
+            continue;
+        }
+
+        let attrs = tag_attributes(&lower[start..end]);
+        let attr = |name: &[u8]| attrs.iter().find(|(n, _)| *n == name).map(|(_, v)| *v);
+        let label = match attr(b"charset") {
+            Some(charset) => Some(trim_ascii(charset)),
+            None if attr(b"http-equiv").map(trim_ascii) == Some(b"content-type") => {
+                attr(b"content").and_then(content_charset)
+            }
+            None => None,
+        };
+
+        if let Some(encoding) = label.and_then(Encoding::for_label) {
+            // A meta declaration can never select UTF-16 (the bytes we just
+            // read as ASCII prove otherwise)
+            if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+                return Some(UTF_8);
+            }
+            if encoding == encoding_rs::X_USER_DEFINED {
+                return Some(WINDOWS_1252);
+            }
+            return Some(encoding);
+        }
+    }
+
+    None
+}
+
+/// Name and value of each attribute in the inside of a tag
+fn tag_attributes(mut rest: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut attrs = Vec::new();
+    loop {
+        let skip = rest
+            .iter()
+            .position(|&b| b != b'/' && !b.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        rest = &rest[skip..];
+        if rest.is_empty() {
+            return attrs;
+        }
+        // An attribute name may start with `=`
+        let name_len = 1 + rest[1..]
+            .iter()
+            .position(|&b| b == b'=' || b == b'/' || b.is_ascii_whitespace())
+            .unwrap_or(rest.len() - 1);
+        let name = &rest[..name_len];
+        rest = trim_ascii_start(&rest[name_len..]);
+
+        let Some(after) = rest.strip_prefix(b"=") else {
+            attrs.push((name, &b""[..]));
+            continue;
+        };
+        let after = trim_ascii_start(after);
+        let (value, next) = match after.first() {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let len = after[1..].iter().position(|&b| b == quote).unwrap_or(after.len() - 1);
+                (&after[1..1 + len], after.get(len + 2..).unwrap_or_default())
+            }
+            _ => {
+                let len = after
+                    .iter()
+                    .position(|b| b.is_ascii_whitespace())
+                    .unwrap_or(after.len());
+                after.split_at(len)
+            }
+        };
+        attrs.push((name, value));
+        rest = next;
+    }
+}
+
+/// The charset named by a `content="text/html; charset=..."` value
+fn content_charset(content: &[u8]) -> Option<&[u8]> {
+    let rest = &content[find(content, b"charset")? + b"charset".len()..];
+    let rest = trim_ascii_start(rest).strip_prefix(b"=")?;
+    let rest = trim_ascii_start(rest);
+    let rest = rest
+        .strip_prefix(b"\"")
+        .or_else(|| rest.strip_prefix(b"'"))
+        .unwrap_or(rest);
+    let len = rest
+        .iter()
+        .position(|&b| matches!(b, b'"' | b'\'' | b';') || b.is_ascii_whitespace())
+        .unwrap_or(rest.len());
+    Some(&rest[..len])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn trim_ascii_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn trim_ascii(bytes: &[u8]) -> &[u8] {
+    let bytes = trim_ascii_start(bytes);
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(0, |p| p + 1);
+    &bytes[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_bom() {
+        assert_eq!(detect_encoding(b"\xEF\xBB\xBF<html></html>"), "UTF-8");
+        assert_eq!(detect_encoding(b"\xFF\xFE<\x00h\x00"), "UTF-16LE");
+        assert_eq!(detect_encoding(b"\xFE\xFF\x00<\x00h"), "UTF-16BE");
+    }
+
+    #[test]
+    fn test_detect_meta_charset() {
+        let html = b"<html><head><meta charset='Shift_JIS'></head></html>";
+        assert_eq!(detect_encoding(html), "Shift_JIS");
+
+        let html = br#"<meta http-equiv="Content-Type" content="text/html; charset=windows-1251">"#;
+        assert_eq!(detect_encoding(html), "windows-1251");
+
+        // UTF-16 declared in meta is treated as UTF-8
+        assert_eq!(detect_encoding(b"<meta charset=utf-16>"), "UTF-8");
+    }
+
+    #[test]
+    fn test_meta_prescan_needs_a_meta_tag() {
+        // Other tags starting with `meta` are not declarations
+        assert_eq!(detect_encoding(b"<metadata charset=\"shift_jis\"><p>ok</p>"), "UTF-8");
+        assert_eq!(detect_encoding(b"<metafoo charset=shift_jis><meta/charset=koi8-r>"), "KOI8-R");
+        assert_eq!(detect_encoding(b"<meta\ncharset=\"shift_jis\">"), "Shift_JIS");
+    }
+
+    #[test]
+    fn test_meta_content_needs_http_equiv() {
+        let html = br#"<meta name="description" content="charset=shift_jis"><p>ok</p>"#;
+        assert_eq!(detect_encoding(html), "UTF-8");
+        let html = br#"<meta http-equiv="refresh" content="5; charset=shift_jis">"#;
+        assert_eq!(detect_encoding(html), "UTF-8");
+
+        let html = br#"<meta content='text/html; charset="koi8-r"' http-equiv=Content-Type>"#;
+        assert_eq!(detect_encoding(html), "KOI8-R");
+        // The charset word inside another attribute's value is not the attribute
+        let html = br#"<meta name="charset=shift_jis" charset="koi8-r">"#;
+        assert_eq!(detect_encoding(html), "KOI8-R");
+    }
+
+    #[test]
+    fn test_fallback_without_declaration() {
+        assert_eq!(detect_encoding(b"<p>plain ascii</p>"), "UTF-8");
+        // 0xE9 is not valid UTF-8 on its own
+        assert_eq!(detect_encoding(b"<p>caf\xE9</p>"), "windows-1252");
+    }
+
+    #[test]
+    fn test_decode_html() {
+        let (decoded, warning) =
+            decode_html(b"<meta charset=\"iso-8859-1\"><p>caf\xE9</p>", None);
+        assert!(decoded.contains("café"));
+        assert!(warning.is_none());
+
+        let html = b"<meta charset=\"utf-8\"><p>ok \xFF bad</p>";
+        let (decoded, warning) = decode_html(html, None);
+        assert!(decoded.contains("ok \u{FFFD} bad"));
+        assert!(matches!(
+            warning,
+            Some(Error::EncodingError { ref encoding, position: 28 }) if encoding == "UTF-8"
+        ));
+    }
+
+    #[test]
+    fn test_transport_charset_precedence() {
+        assert_eq!(content_type_charset("text/html; Charset=\"Shift_JIS\""), Some("Shift_JIS"));
+        assert_eq!(content_type_charset("text/html"), None);
+
+        // The header outranks the meta declaration
+        let html = b"<meta charset=\"utf-8\"><p>caf\xE9</p>";
+        let (decoded, warning) = decode_html(html, Some("iso-8859-1"));
+        assert!(decoded.contains("café"));
+        assert!(warning.is_none());
+
+        // A byte order mark outranks the header, and unknown labels are ignored
+        let (decoded, _) = decode_html(b"\xEF\xBB\xBF<p>caf\xC3\xA9</p>", Some("iso-8859-1"));
+        assert!(decoded.contains("café"));
+        let (decoded, _) = decode_html(b"<meta charset=\"iso-8859-1\">caf\xE9", Some("bogus"));
+        assert!(decoded.contains("café"));
+    }
+}
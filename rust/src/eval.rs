@@ -0,0 +1,221 @@
+//! Measuring how well each output format locates elements
+//!
+//! Shorter formats drop detail: labels are truncated and only the first
+//! few actions are listed. Given pages and the elements an agent should
+//! find on them, [`evaluate_formats`] checks which formats still mention
+//! each target, by node ID or full label, and reports recall per format
+//! next to its token cost, so the format and budget can be chosen from
+//! real pages.
+
+use crate::parser::SemanticDOM;
+use crate::summary::{self, estimate_tokens};
+use serde::{Deserialize, Serialize};
+
+/// An output format that can be evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryFormat {
+    /// Full JSON ([`SemanticDOM::to_json`])
+    Json,
+    /// Agent summary ([`SemanticDOM::to_agent_summary`])
+    Agent,
+    /// One-line summary ([`SemanticDOM::to_one_liner`])
+    OneLiner,
+    /// Navigation summary ([`summary::to_nav_summary`])
+    Nav,
+    /// Screen-reader style summary ([`summary::to_audio_summary`])
+    Audio,
+}
+
+impl SummaryFormat {
+    /// All formats, largest first
+    pub const ALL: [SummaryFormat; 5] = [
+        SummaryFormat::Json,
+        SummaryFormat::Agent,
+        SummaryFormat::Nav,
+        SummaryFormat::Audio,
+        SummaryFormat::OneLiner,
+    ];
+
+    /// Canonical name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryFormat::Json => "json",
+            SummaryFormat::Agent => "agent",
+            SummaryFormat::OneLiner => "one_liner",
+            SummaryFormat::Nav => "nav",
+            SummaryFormat::Audio => "audio",
+        }
+    }
+
+    /// Render a document in this format
+    pub fn render(&self, sdom: &SemanticDOM) -> String {
+        match self {
+            SummaryFormat::Json => sdom.to_json().unwrap_or_default(),
+            SummaryFormat::Agent => sdom.to_agent_summary(),
+            SummaryFormat::OneLiner => sdom.to_one_liner(),
+            SummaryFormat::Nav => summary::to_nav_summary(sdom),
+            SummaryFormat::Audio => summary::to_audio_summary(sdom),
+        }
+    }
+}
+
+/// Recall of one format over the evaluated targets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatRecall {
+    /// The format
+    pub format: SummaryFormat,
+    /// Targets the output mentions
+    pub found: usize,
+    /// Targets evaluated
+    pub total: usize,
+    /// `found / total`, 0.0 when there were no targets
+    pub recall: f64,
+    /// Average estimated tokens per page
+    pub avg_tokens: f64,
+    /// IDs of the targets the output does not mention
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub missed: Vec<String>,
+}
+
+/// Result of evaluating the formats on a set of pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    /// One entry per format, in [`SummaryFormat::ALL`] order
+    pub formats: Vec<FormatRecall>,
+    /// Target IDs that are not nodes of their page, left out of recall
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub unknown_targets: Vec<String>,
+}
+
+impl EvalReport {
+    /// Recall of one format
+    pub fn recall(&self, format: SummaryFormat) -> Option<f64> {
+        self.formats.iter().find(|f| f.format == format).map(|f| f.recall)
+    }
+
+    /// The cheapest format reaching at least `min_recall`
+    pub fn cheapest(&self, min_recall: f64) -> Option<SummaryFormat> {
+        self.formats
+            .iter()
+            .filter(|f| f.total > 0 && f.recall >= min_recall)
+            .min_by(|a, b| a.avg_tokens.total_cmp(&b.avg_tokens))
+            .map(|f| f.format)
+    }
+}
+
+/// Evaluate every format on `(page, target node ID)` pairs
+///
+/// A target counts as found when the output contains its node ID or its
+/// full label.
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{evaluate_formats, Config, SemanticDOM, SummaryFormat};
+///
+/// let html = r#"<main><button id="go">Start the free trial today</button></main>"#;
+/// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let report = evaluate_formats([(&sdom, "sdom_go")]);
+///
+/// assert_eq!(report.recall(SummaryFormat::Json), Some(1.0));
+/// // The one-liner truncates the label
+/// assert_eq!(report.recall(SummaryFormat::OneLiner), Some(0.0));
+/// assert_eq!(report.cheapest(1.0), Some(SummaryFormat::Audio));
+/// ```
+pub fn evaluate_formats<'a>(
+    cases: impl IntoIterator<Item = (&'a SemanticDOM, &'a str)>,
+) -> EvalReport {
+    let mut formats: Vec<FormatRecall> = SummaryFormat::ALL
+        .iter()
+        .map(|&format| FormatRecall {
+            format,
+            found: 0,
+            total: 0,
+            recall: 0.0,
+            avg_tokens: 0.0,
+            missed: Vec::new(),
+        })
+        .collect();
+    let mut unknown_targets = Vec::new();
+    let mut pages: Vec<(&SemanticDOM, Vec<String>)> = Vec::new();
+
+    for (sdom, target) in cases {
+        // Render each page once, however many targets it has
+        let page = match pages.iter().position(|(p, _)| std::ptr::eq(*p, sdom)) {
+            Some(page) => page,
+            None => {
+                let outputs: Vec<String> =
+                    formats.iter().map(|f| f.format.render(sdom)).collect();
+                for (entry, output) in formats.iter_mut().zip(&outputs) {
+                    entry.avg_tokens += estimate_tokens(output) as f64;
+                }
+                pages.push((sdom, outputs));
+                pages.len() - 1
+            }
+        };
+        let Some(node) = sdom.get(target) else {
+            unknown_targets.push(target.to_string());
+            continue;
+        };
+        for (entry, output) in formats.iter_mut().zip(&pages[page].1) {
+            entry.total += 1;
+            let label = !node.label.is_empty() && output.contains(node.label.as_str());
+            if output.contains(node.id.as_str()) || label {
+                entry.found += 1;
+            } else {
+                entry.missed.push(node.id.clone());
+            }
+        }
+    }
+
+    for entry in &mut formats {
+        if entry.total > 0 {
+            entry.recall = entry.found as f64 / entry.total as f64;
+        }
+        if !pages.is_empty() {
+            entry.avg_tokens /= pages.len() as f64;
+        }
+    }
+    EvalReport { formats, unknown_targets }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_recall_per_format() {
+        let shop = r#"<nav><a href="/">Home</a><a href="/cart">Cart</a></nav><main>
+            <button>Buy</button><button>Add to wishlist</button><button>Share</button>
+            <button>Compare</button></main>"#;
+        let blog = r#"<main><a href="/rss">Subscribe via RSS</a></main>"#;
+        let shop = SemanticDOM::parse(shop, Config::default()).unwrap();
+        let blog = SemanticDOM::parse(blog, Config::default()).unwrap();
+        let id = |sdom: &SemanticDOM, label: &str| {
+            sdom.index.values().find(|n| n.label == label).unwrap().id.clone()
+        };
+        let (cart, compare) = (id(&shop, "Cart"), id(&shop, "Compare"));
+        let rss = id(&blog, "Subscribe via RSS");
+
+        let report = evaluate_formats([
+            (&shop, cart.as_str()),
+            (&shop, compare.as_str()),
+            (&blog, rss.as_str()),
+            (&blog, "sdom_missing"),
+        ]);
+        assert_eq!(report.unknown_targets, ["sdom_missing"]);
+        assert_eq!(report.recall(SummaryFormat::Json), Some(1.0));
+        assert_eq!(report.recall(SummaryFormat::Agent), Some(1.0));
+
+        // Only the first three actions make the one-liner, labels cut at 10
+        let one_liner = &report.formats[4];
+        assert_eq!((one_liner.found, one_liner.total), (1, 3));
+        assert_eq!(one_liner.missed, [compare, rss]);
+
+        // The nav summary lists links only
+        assert_eq!(report.formats[2].missed.len(), 1);
+        let tokens = |f: usize| report.formats[f].avg_tokens;
+        assert!(tokens(0) > tokens(1) && tokens(1) > tokens(4));
+    }
+}
@@ -0,0 +1,118 @@
+//! Text excerpts of content nodes
+//!
+//! Labels name a node; they do not say what it contains. With
+//! [`Config::text_excerpts`](crate::Config::text_excerpts) set, nodes other
+//! than controls and headings get a
+//! [`SemanticNode::text_excerpt`](crate::SemanticNode::text_excerpt) taken
+//! from the paragraphs, list items and table cells inside them, so
+//! summaries can quote content instead of only describing structure.
+
+use scraper::ElementRef;
+
+/// Elements whose text makes up an excerpt
+const CONTENT_TAGS: [&str; 4] = ["p", "li", "td", "th"];
+
+/// Text of the content elements in `element`, at most `max_chars`
+/// characters
+///
+/// An element that is itself a paragraph, list item or cell contributes
+/// all its text; otherwise the outermost content elements inside it are
+/// joined in document order. Text of `skip` tags is left out. Cut text
+/// ends in `…` and never splits a character.
+pub(crate) fn text_excerpt(
+    element: ElementRef,
+    max_chars: usize,
+    skip: &[String],
+) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    let mut chars = 0;
+    let mut stack = vec![element];
+    while let Some(el) = stack.pop() {
+        let tag = el.value().name();
+        if CONTENT_TAGS.contains(&tag) {
+            let text = visible_text(el, skip);
+            if !text.is_empty() {
+                chars += text.chars().count() + 1;
+                parts.push(text);
+            }
+            // Enough to fill the excerpt and show that it was cut
+            if chars > max_chars + 1 {
+                break;
+            }
+            continue;
+        }
+        let children: Vec<ElementRef> = el
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| !skip.iter().any(|s| s == c.value().name()))
+            .collect();
+        stack.extend(children.into_iter().rev());
+    }
+    let text = parts.join(" ");
+    (!text.is_empty()).then(|| cut(&text, max_chars))
+}
+
+/// Whitespace-collapsed text of `element` outside `skip` tags
+fn visible_text(element: ElementRef, skip: &[String]) -> String {
+    let mut words: Vec<&str> = Vec::new();
+    for node in element.descendants() {
+        let Some(text) = node.value().as_text() else {
+            continue;
+        };
+        let skipped = node.ancestors().take_while(|a| a.id() != element.id()).any(|a| {
+            a.value().as_element().is_some_and(|e| skip.iter().any(|s| s == e.name()))
+        });
+        if !skipped {
+            words.extend(text.split_whitespace());
+        }
+    }
+    words.join(" ")
+}
+
+/// `text` cut to `max_chars` characters, the last being `…` when cut
+fn cut(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        None => text.to_string(),
+        Some(_) => {
+            let end = text.char_indices().nth(max_chars.saturating_sub(1)).map_or(0, |(i, _)| i);
+            format!("{}…", text[..end].trim_end())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM, SemanticRole};
+
+    #[test]
+    fn test_text_excerpts() {
+        let html = r#"<main><h1>Menu</h1><p>Our  kitchen <script>track()</script>serves
+            crêpes.</p><ul><li>Crêpe suzette</li><li>Galette</li></ul>
+            <table><tr><th>Dish</th><td>€9</td></tr></table>
+            <section><p>Ça va très bien, merci beaucoup</p></section></main>"#;
+        let config = Config::builder().text_excerpts(40).build().unwrap();
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+
+        let main = sdom.query_by_role(&SemanticRole::Main)[0];
+        assert_eq!(
+            main.text_excerpt.as_deref(),
+            Some("Our kitchen serves crêpes. Crêpe suzett…")
+        );
+        assert_eq!(main.text_excerpt.as_ref().unwrap().chars().count(), 40);
+        let section = sdom.query_by_role(&SemanticRole::Section)[0];
+        assert_eq!(section.text_excerpt.as_deref(), Some("Ça va très bien, merci beaucoup"));
+        // Headings and controls are their label
+        assert_eq!(sdom.get(&sdom.headings[0]).unwrap().text_excerpt, None);
+        assert!(sdom
+            .to_agent_summary()
+            .contains("\nCONTENT: main=\"Our kitchen serves crêpes. Crêpe suzett…\"\n"));
+
+        let config = Config::builder().text_excerpts(8).build().unwrap();
+        let sdom = SemanticDOM::parse("<section><p>ééééééééé</p></section>", config).unwrap();
+        let section = sdom.query_by_role(&SemanticRole::Section)[0];
+        assert_eq!(section.text_excerpt.as_deref(), Some("ééééééé…"));
+
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert!(sdom.reading_order().iter().all(|n| n.text_excerpt.is_none()));
+    }
+}
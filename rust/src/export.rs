@@ -0,0 +1,201 @@
+//! Writing documents to files, optionally compressed
+//!
+//! Crawls export thousands of documents, and JSON of a large page runs to
+//! megabytes. [`SemanticDOM::write_to`] writes a document as JSON or an
+//! agent summary, compressed with gzip (feature `gzip`) or zstd (feature
+//! `zstd`), and [`SemanticDOM::read_from`] loads JSON back, recognizing
+//! compressed files by their magic bytes whatever their name.
+
+use crate::parser::SemanticDOM;
+use crate::{Config, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Leading bytes of a zstd frame
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// What to write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Full JSON ([`SemanticDOM::to_json`]), loadable with
+    /// [`SemanticDOM::read_from`]
+    Json,
+    /// Agent summary ([`SemanticDOM::to_agent_summary`])
+    Summary,
+}
+
+/// How to compress a written file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Plain text
+    #[default]
+    None,
+    /// gzip, needs the `gzip` feature
+    Gzip,
+    /// zstd, needs the `zstd` feature
+    Zstd,
+}
+
+impl Compression {
+    /// Compression named by a file extension: `.gz` or `.zst`
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Compression a file's contents start with
+    fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                Ok(encoder.finish()?)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(zstd::encode_all(bytes, 0)?),
+            #[allow(unreachable_patterns)]
+            other => Err(Error::CompressionUnavailable(other.feature())),
+        }
+    }
+
+    fn decompress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Read;
+                let mut out = Vec::new();
+                flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut out)?;
+                Ok(out)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(zstd::decode_all(bytes.as_slice())?),
+            #[allow(unreachable_patterns)]
+            other => Err(Error::CompressionUnavailable(other.feature())),
+        }
+    }
+
+    /// Cargo feature providing the codec
+    fn feature(&self) -> &'static str {
+        match self {
+            Compression::None => "default",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+impl SemanticDOM {
+    /// Write the document to a file
+    ///
+    /// # Errors
+    /// - [`Error::CompressionUnavailable`] if the codec's feature is off
+    /// - [`Error::IoError`] if the file cannot be written
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Compression, Config, ExportFormat, SemanticDOM};
+    ///
+    /// let sdom = SemanticDOM::parse("<main><h1>Hello</h1></main>", Config::default()).unwrap();
+    /// let path = std::env::temp_dir().join("sdom-export-doc.json");
+    /// sdom.write_to(&path, ExportFormat::Json, Compression::None).unwrap();
+    ///
+    /// let loaded = SemanticDOM::read_from(&path, Config::default()).unwrap();
+    /// assert_eq!(loaded.to_agent_summary(), sdom.to_agent_summary());
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn write_to(
+        &self,
+        path: impl AsRef<Path>,
+        format: ExportFormat,
+        compression: Compression,
+    ) -> Result<()> {
+        let text = match format {
+            ExportFormat::Json => self.to_json()?,
+            ExportFormat::Summary => self.to_agent_summary(),
+        };
+        std::fs::write(path, compression.compress(text.as_bytes())?)?;
+        Ok(())
+    }
+
+    /// Load a document written as JSON by [`write_to`](Self::write_to),
+    /// decompressing it if needed
+    ///
+    /// # Errors
+    /// - [`Error::CompressionUnavailable`] if the file is compressed with a
+    ///   codec whose feature is off
+    /// - [`Error::SerializationError`] if the contents are not SemanticDOM
+    ///   JSON
+    /// - [`Error::IoError`] if the file cannot be read
+    pub fn read_from(path: impl AsRef<Path>, config: Config) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let bytes = Compression::detect(&bytes).decompress(bytes)?;
+        let json =
+            String::from_utf8(bytes).map_err(|e| Error::SerializationError(e.to_string()))?;
+        Self::from_json(&json, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sdom-export-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let html = r#"<nav><a href="/">Home</a></nav><main><button>Buy</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let mut codecs = vec![Compression::None];
+        if cfg!(feature = "gzip") {
+            codecs.push(Compression::Gzip);
+        }
+        if cfg!(feature = "zstd") {
+            codecs.push(Compression::Zstd);
+        }
+        for compression in codecs {
+            let path = temp_path(&format!("{:?}.json", compression));
+            sdom.write_to(&path, ExportFormat::Json, compression).unwrap();
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+            let loaded = SemanticDOM::read_from(&path, Config::default()).unwrap();
+            assert_eq!(loaded.to_agent_summary(), sdom.to_agent_summary());
+            std::fs::remove_file(path).unwrap();
+        }
+
+        let path = temp_path("summary.txt.gz");
+        assert_eq!(Compression::from_path(&path), Compression::Gzip);
+        let written = sdom.write_to(&path, ExportFormat::Summary, Compression::from_path(&path));
+        if cfg!(feature = "gzip") {
+            written.unwrap();
+            std::fs::remove_file(path).unwrap();
+        } else {
+            assert!(matches!(written, Err(Error::CompressionUnavailable("gzip"))));
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Validation of externally authored documents
+//!
+//! SemanticDOM JSON is not only written by this crate: teams hand-author
+//! documents for pages they cannot parse, and other tools emit the format.
+//! [`SemanticDOM::validate_external`] checks such a document against the
+//! schema of [`SemanticDOM::to_json`] and the invariants parsed documents
+//! keep, and reports every violation instead of stopping at the first.
+//! [`SemanticDOM::from_json`] loads one for use like a parsed document.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, StateGraph};
+use crate::{Config, Error, Result};
+use ahash::{AHashMap, AHashSet};
+use serde::de::{Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// What rule a document breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ViolationKind {
+    /// Not JSON, or a value of the wrong shape
+    Schema,
+    /// Two nodes share an ID, or a node's key differs from its `id`
+    DuplicateId,
+    /// An ID that names no node
+    DanglingReference,
+    /// A parent and child that do not list each other
+    ParentChildMismatch,
+    /// A node listed as a landmark, interactable or heading without the role
+    RoleMismatch,
+    /// A transition or initial state naming a state that does not exist
+    UnknownState,
+    /// A transition triggered by a node that does not exist
+    MissingTrigger,
+}
+
+/// A rule broken by a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Rule broken
+    pub kind: ViolationKind,
+    /// JSON Pointer to the offending value, e.g. `/nodes/sdom_nav/parent`
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl Violation {
+    fn new(kind: ViolationKind, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Violation { kind, path: path.into(), message: message.into() }
+    }
+}
+
+/// Nodes as written, keeping repeated IDs that a map would merge
+struct Entries(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for Entries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = Entries;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of nodes by ID or a list of nodes")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Entries, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+
+            // Lists are keyed by position until the node's own ID is read
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Entries, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some(node) = seq.next_element::<Value>()? {
+                    entries.push((entries.len().to_string(), node));
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_any(EntriesVisitor)
+    }
+}
+
+/// Top level of a document, as written by [`SemanticDOM::to_json`]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct External {
+    nodes: Entries,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    lang: Option<String>,
+    #[serde(default)]
+    landmarks: Vec<String>,
+    #[serde(default)]
+    interactables: Vec<String>,
+    #[serde(default)]
+    headings: Vec<String>,
+    #[serde(default)]
+    state_graph: Option<Value>,
+}
+
+/// Escape a key for a JSON Pointer
+fn pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+impl SemanticDOM {
+    /// Check a SemanticDOM JSON document written elsewhere
+    ///
+    /// Nodes may be given as a map by ID, as [`Self::to_json`] writes them,
+    /// or as a list. Besides the schema, the checks are that IDs are unique,
+    /// that parents, children and the landmark, interactable and heading
+    /// lists name existing nodes of the right kind, that parents and
+    /// children agree, and that state graph transitions connect existing
+    /// states and are triggered by existing nodes. An empty result means
+    /// the document is valid.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{SemanticDOM, ViolationKind};
+    ///
+    /// let json = r#"{
+    ///     "nodes": {
+    ///         "nav": {"id": "nav", "label": "Menu", "role": "navigation",
+    ///                 "selector": "nav", "depth": 0, "children": ["home", "gone"]},
+    ///         "home": {"id": "home", "label": "Home", "role": "link",
+    ///                  "selector": "a", "depth": 1, "parent": "nav"}
+    ///     },
+    ///     "landmarks": ["nav"]
+    /// }"#;
+    /// let violations = SemanticDOM::validate_external(json);
+    ///
+    /// assert_eq!(violations.len(), 1);
+    /// assert_eq!(violations[0].kind, ViolationKind::DanglingReference);
+    /// assert_eq!(violations[0].path, "/nodes/nav/children/1");
+    /// ```
+    pub fn validate_external(json: &str) -> Vec<Violation> {
+        use ViolationKind::*;
+
+        let document: External = match serde_json::from_str(json) {
+            Ok(document) => document,
+            Err(e) => return vec![Violation::new(Schema, "", e.to_string())],
+        };
+        let mut violations = Vec::new();
+
+        // Nodes that deserialize, with their JSON Pointer
+        let mut nodes: Vec<(String, SemanticNode)> = Vec::new();
+        let mut seen: AHashSet<String> = AHashSet::new();
+        for (key, value) in document.nodes.0 {
+            let path = format!("/nodes/{}", pointer(&key));
+            let node: SemanticNode = match serde_json::from_value(value) {
+                Ok(node) => node,
+                Err(e) => {
+                    violations.push(Violation::new(Schema, path, e.to_string()));
+                    continue;
+                }
+            };
+            let keyed = key.parse::<usize>().is_err();
+            if keyed && key != node.id {
+                let message = format!("key {:?} differs from id {:?}", key, node.id);
+                violations.push(Violation::new(DuplicateId, format!("{}/id", path), message));
+            }
+            if !seen.insert(node.id.clone()) {
+                let message = format!("id {:?} is used by more than one node", node.id);
+                violations.push(Violation::new(DuplicateId, format!("{}/id", path), message));
+                continue;
+            }
+            nodes.push((path, node));
+        }
+        let by_id: AHashMap<&str, &SemanticNode> =
+            nodes.iter().map(|(_, n)| (n.id.as_str(), n)).collect();
+
+        for (path, node) in &nodes {
+            if let Some(parent_id) = &node.parent {
+                let path = format!("{}/parent", path);
+                match by_id.get(parent_id.as_str()) {
+                    None => violations.push(Violation::new(
+                        DanglingReference,
+                        path,
+                        format!("parent {:?} does not exist", parent_id),
+                    )),
+                    Some(parent) if !parent.children.contains(&node.id) => {
+                        violations.push(Violation::new(
+                            ParentChildMismatch,
+                            path,
+                            format!("{:?} does not list {:?} as a child", parent_id, node.id),
+                        ))
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (i, child_id) in node.children.iter().enumerate() {
+                let path = format!("{}/children/{}", path, i);
+                match by_id.get(child_id.as_str()) {
+                    None => violations.push(Violation::new(
+                        DanglingReference,
+                        path,
+                        format!("child {:?} does not exist", child_id),
+                    )),
+                    Some(child) if child.parent.as_ref() != Some(&node.id) => {
+                        violations.push(Violation::new(
+                            ParentChildMismatch,
+                            path,
+                            format!("{:?} does not have {:?} as its parent", child_id, node.id),
+                        ))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        type HasRole = fn(&SemanticNode) -> bool;
+        let lists: [(&str, &[String], HasRole); 3] = [
+            ("landmarks", &document.landmarks, |n| n.role.is_landmark()),
+            ("interactables", &document.interactables, |n| n.role.is_interactable()),
+            ("headings", &document.headings, |n| n.level.is_some()),
+        ];
+        for (list, ids, has_role) in lists {
+            for (i, id) in ids.iter().enumerate() {
+                let path = format!("/{}/{}", list, i);
+                match by_id.get(id.as_str()) {
+                    None => violations.push(Violation::new(
+                        DanglingReference,
+                        path,
+                        format!("{:?} does not exist", id),
+                    )),
+                    Some(node) if !has_role(node) => {
+                        let role = node.role.as_str();
+                        let message = format!("{:?} with role {} is not in {}", id, role, list);
+                        violations.push(Violation::new(RoleMismatch, path, message))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let graph = match document.state_graph.map(serde_json::from_value::<StateGraph>) {
+            None => return violations,
+            Some(Ok(graph)) => graph,
+            Some(Err(e)) => {
+                violations.push(Violation::new(Schema, "/stateGraph", e.to_string()));
+                return violations;
+            }
+        };
+        let states: AHashSet<&str> = graph.states.iter().map(|s| s.id.as_str()).collect();
+        if let Some(initial) = graph.initial_state.as_deref().filter(|s| !states.contains(s)) {
+            violations.push(Violation::new(
+                UnknownState,
+                "/stateGraph/initial_state",
+                format!("state {:?} does not exist", initial),
+            ));
+        }
+        for (i, transition) in graph.transitions.iter().enumerate() {
+            let path = format!("/stateGraph/transitions/{}", i);
+            for (field, state) in [("from", &transition.from), ("to", &transition.to)] {
+                if !states.contains(state.as_str()) {
+                    violations.push(Violation::new(
+                        UnknownState,
+                        format!("{}/{}", path, field),
+                        format!("state {:?} does not exist", state),
+                    ));
+                }
+            }
+            if !by_id.contains_key(transition.trigger.as_str()) {
+                violations.push(Violation::new(
+                    MissingTrigger,
+                    format!("{}/trigger", path),
+                    format!("trigger {:?} does not exist", transition.trigger),
+                ));
+            }
+        }
+        violations
+    }
+
+    /// Load a SemanticDOM JSON document, as written by [`Self::to_json`]
+    ///
+    /// Nodes keep their IDs. JSON maps are unordered, so reading order is
+    /// rebuilt from the tree: top-level nodes in the order of the landmark
+    /// list, then as listed, each followed by its children. The landmark,
+    /// interactable and heading lists are rebuilt from the roles. Only the
+    /// schema is checked, so call [`Self::validate_external`] first for
+    /// documents from elsewhere.
+    ///
+    /// # Errors
+    /// - [`Error::SerializationError`] if `json` does not follow the schema
+    pub fn from_json(json: &str, config: Config) -> Result<Self> {
+        let schema_error = |e: serde_json::Error| Error::SerializationError(e.to_string());
+        let document: External = serde_json::from_str(json).map_err(schema_error)?;
+        let mut sdom = SemanticDOM::empty(config);
+        let mut listed = Vec::with_capacity(document.nodes.0.len());
+        for (_, value) in document.nodes.0 {
+            let node: SemanticNode = serde_json::from_value(value).map_err(schema_error)?;
+            listed.push(node.id.clone());
+            sdom.index.insert(node.id.clone(), node);
+        }
+
+        let mut roots: Vec<&String> = document.landmarks.iter().chain(&listed).collect();
+        roots.retain(|id| sdom.index.get(*id).is_some_and(|n| n.parent.is_none()));
+        let mut seen: AHashSet<&str> = AHashSet::new();
+        let mut stack: Vec<&str> = roots.iter().rev().map(|id| id.as_str()).collect();
+        while let Some(id) = stack.pop() {
+            let Some(node) = sdom.index.get(id).filter(|_| seen.insert(id)) else {
+                continue;
+            };
+            sdom.order.push(id.to_string());
+            stack.extend(node.children.iter().rev().map(String::as_str));
+        }
+        // Nodes whose parent is missing come last
+        for id in listed.iter().filter(|id| !seen.contains(id.as_str())) {
+            sdom.order.push(id.clone());
+        }
+        sdom.id_counter = sdom.index.len();
+        sdom.rebuild_categories();
+        sdom.title = document.title;
+        sdom.lang = document.lang;
+        if let Some(graph) = document.state_graph {
+            sdom.state_graph = serde_json::from_value(graph).map_err(schema_error)?;
+        }
+        Ok(sdom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_parsed_documents_are_valid_and_load() {
+        let html = r#"<title>Hi</title><nav><a href="/about">About</a></nav><main><h1>Hi</h1>
+            <form action="/s"><input name="q"><button>Go</button></form></main>"#;
+        let json = SemanticDOM::parse(html, Config::default()).unwrap().to_json().unwrap();
+        assert_eq!(SemanticDOM::validate_external(&json), []);
+
+        let loaded = SemanticDOM::from_json(&json, Config::default()).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+        assert!(SemanticDOM::from_json(r#"{"nodes": [{"id": 1}]}"#, Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_violations() {
+        let json = r#"{
+            "nodes": [
+                {"id": "main", "label": "Main", "role": "main", "selector": "main",
+                 "depth": 0, "children": ["buy"]},
+                {"id": "buy", "label": "Buy", "role": "button", "selector": "button",
+                 "depth": 1},
+                {"id": "buy", "label": "Again", "role": "button", "selector": "button",
+                 "depth": 1},
+                {"id": "orphan", "label": "X", "role": "link", "selector": "a",
+                 "depth": 1, "parent": "nav"},
+                {"id": "bad", "role": "carousel"}
+            ],
+            "landmarks": ["main", "buy"],
+            "stateGraph": {
+                "schema_version": 2,
+                "states": [{"id": "home", "name": "Home"}],
+                "transitions": [{"from": "home", "to": "cart", "trigger": "checkout"}],
+                "initial_state": "start"
+            }
+        }"#;
+        let violations = SemanticDOM::validate_external(json);
+        let found: Vec<(ViolationKind, &str)> =
+            violations.iter().map(|v| (v.kind, v.path.as_str())).collect();
+        use ViolationKind::*;
+        assert_eq!(
+            found,
+            [
+                (DuplicateId, "/nodes/2/id"),
+                (Schema, "/nodes/4"),
+                (ParentChildMismatch, "/nodes/0/children/0"),
+                (DanglingReference, "/nodes/3/parent"),
+                (RoleMismatch, "/landmarks/1"),
+                (UnknownState, "/stateGraph/initial_state"),
+                (UnknownState, "/stateGraph/transitions/0/to"),
+                (MissingTrigger, "/stateGraph/transitions/0/trigger"),
+            ]
+        );
+
+        let not_json = SemanticDOM::validate_external("<html>");
+        assert_eq!(not_json[0].kind, Schema);
+    }
+}
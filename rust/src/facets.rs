@@ -0,0 +1,529 @@
+//! Sort and filter controls on listing pages
+//!
+//! Product and search listings are browsed by sorting and filtering, not
+//! by reading every result. A `<select>` or radio group named for sorting
+//! becomes a sort [`Facet`]; checkbox groups, radio groups and selects
+//! inside a filter panel (an `aside`, or a container whose `id`/class
+//! mentions filters or facets) become filter facets, named by their
+//! `legend` or the heading above them. Applying an option is a `sort` or
+//! `filter` transition in the state graph, with the query parameter it
+//! sets in [`Transition::params`](crate::Transition::params).
+
+use crate::parser::SemanticDOM;
+use crate::types::{State, Transition};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Words naming a sort control, in `name`, `id`, class or label
+const SORT_WORDS: &[&str] = &[
+    "sort", "order by", "orderby", "order-by", "order_by", "ordenar", "sortieren", "trier",
+    "ordina", "並び替え",
+];
+
+/// Substrings of `id`/class names of filter panels
+const FILTER_MARKERS: &[&str] = &["filter", "facet", "refine"];
+
+/// What a facet does to the listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FacetKind {
+    /// Reorders the results
+    Sort,
+    /// Narrows the results
+    Filter,
+}
+
+/// One choice of a facet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetOption {
+    /// Visible text, without the result count
+    pub label: String,
+    /// Submitted value, when the control has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// ID of the checkbox or radio node; `None` for select options
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Whether the option is currently applied
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+    /// Number of results the option leads to, as in `Red (12)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+/// A sort control or a group of filter options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Facet {
+    /// Whether the facet sorts or filters
+    pub kind: FacetKind,
+    /// Name shown for the facet, such as `Brand` or `Sort by`
+    pub name: String,
+    /// Query parameter the options set, from the controls' `name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// ID of the select or group node, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Whether several options can be applied at once
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub multiple: bool,
+    /// Options in page order
+    pub options: Vec<FacetOption>,
+}
+
+impl Facet {
+    /// Options currently applied
+    pub fn selected(&self) -> impl Iterator<Item = &FacetOption> {
+        self.options.iter().filter(|o| o.selected)
+    }
+}
+
+/// Sort and filter controls of a listing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Facets {
+    /// Sort controls
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub sort: Vec<Facet>,
+    /// Filter groups
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub filters: Vec<Facet>,
+}
+
+impl Facets {
+    /// Check whether the page has no sort or filter controls
+    pub fn is_empty(&self) -> bool {
+        self.sort.is_empty() && self.filters.is_empty()
+    }
+
+    /// Sort facets, then filter facets
+    pub fn iter(&self) -> impl Iterator<Item = &Facet> {
+        self.sort.iter().chain(&self.filters)
+    }
+}
+
+fn text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn is_heading(element: ElementRef) -> bool {
+    matches!(element.value().name(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "legend")
+        || element.value().attr("role").is_some_and(|r| r.trim() == "heading")
+}
+
+/// Visible label of a form control: `aria-label`, else its `<label>`
+fn control_label(document: &Html, control: ElementRef) -> Option<String> {
+    let el = control.value();
+    if let Some(label) = el.attr("aria-label").map(str::trim).filter(|l| !l.is_empty()) {
+        return Some(label.to_string());
+    }
+    let by_for = el.id().and_then(|id| {
+        document.root_element().descendants().filter_map(ElementRef::wrap).find(|e| {
+            e.value().name() == "label" && e.value().attr("for").map(str::trim) == Some(id)
+        })
+    });
+    let wrapping = || {
+        control.ancestors().filter_map(ElementRef::wrap).find(|a| a.value().name() == "label")
+    };
+    by_for.or_else(wrapping).and_then(text)
+}
+
+/// Split a trailing result count off a label: `Red (12)` is `Red` and 12
+fn split_count(label: &str) -> (String, Option<u32>) {
+    let trimmed = label.trim_end();
+    let Some(inner) = trimmed.strip_suffix(')').or_else(|| trimmed.strip_suffix(']')) else {
+        return (label.to_string(), None);
+    };
+    let Some(open) = inner.rfind(['(', '[']) else {
+        return (label.to_string(), None);
+    };
+    match inner[open + 1..].trim().replace(',', "").parse::<u32>() {
+        Ok(count) => (inner[..open].trim_end().to_string(), Some(count)),
+        Err(_) => (label.to_string(), None),
+    }
+}
+
+fn field_name(element: ElementRef) -> Option<String> {
+    let name = element.value().attr("name")?.trim();
+    let name = name.strip_suffix("[]").unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Whether a control, or the group holding it, is named for sorting
+fn is_sort(document: &Html, control: ElementRef, group: Option<&str>) -> bool {
+    let el = control.value();
+    let words = [el.attr("name"), el.id(), el.attr("class"), el.attr("aria-label"), group]
+        .into_iter()
+        .flatten()
+        .map(str::to_string)
+        .chain(control_label(document, control))
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>();
+    words.iter().any(|w| SORT_WORDS.iter().any(|s| w.contains(s)))
+}
+
+/// The filter panel holding an element, and whether it is named as one
+fn filter_panel(element: ElementRef) -> Option<(ElementRef, bool)> {
+    let panels: Vec<(ElementRef, bool)> = element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter_map(|a| {
+            let el = a.value();
+            let named = el
+                .id()
+                .into_iter()
+                .chain(el.classes())
+                .chain(el.attr("aria-label"))
+                .map(str::to_ascii_lowercase)
+                .any(|t| FILTER_MARKERS.iter().any(|m| t.contains(m)));
+            let aside = el.name() == "aside"
+                || el.attr("role").is_some_and(|r| r.trim() == "complementary");
+            (named || aside).then_some((a, named))
+        })
+        .collect();
+    // The outermost panel holds the headings naming its groups
+    panels.iter().rev().find(|(_, named)| *named).or(panels.last()).copied()
+}
+
+/// Name of the group holding a control: the `legend` or `aria-label` of
+/// its fieldset or group, else the closest heading before it in the panel
+fn group_name(control: ElementRef, panel: ElementRef) -> Option<(String, Option<NodeId>)> {
+    for ancestor in control.ancestors().filter_map(ElementRef::wrap) {
+        if ancestor.id() == panel.id() {
+            break;
+        }
+        let el = ancestor.value();
+        let group = el.name() == "fieldset" || el.attr("role").is_some_and(|r| r.trim() == "group");
+        if !group {
+            continue;
+        }
+        let legend = el.attr("aria-label").map(str::to_string).or_else(|| {
+            ancestor
+                .children()
+                .filter_map(ElementRef::wrap)
+                .find(|c| c.value().name() == "legend")
+                .and_then(text)
+        });
+        if let Some(legend) = legend {
+            return Some((legend, Some(ancestor.id())));
+        }
+    }
+    let mut name = None;
+    for element in panel.descendants().filter_map(ElementRef::wrap) {
+        if element.id() == control.id() {
+            break;
+        }
+        if is_heading(element) {
+            name = text(element);
+        }
+    }
+    name.map(|n| (n, None))
+}
+
+/// Options of a `<select>`
+fn select_options(select: ElementRef) -> Vec<FacetOption> {
+    select
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "option")
+        .filter_map(|option| {
+            let el = option.value();
+            let value = el.attr("value").map(str::to_string);
+            let (label, count) = split_count(&text(option).or_else(|| value.clone())?);
+            Some(FacetOption {
+                value: value.filter(|v| !v.is_empty()),
+                label,
+                id: None,
+                selected: el.attr("selected").is_some(),
+                count,
+            })
+        })
+        .collect()
+}
+
+/// Find sort and filter controls, given the node created for each element
+pub(crate) fn facets(document: &Html, nodes: &AHashMap<NodeId, String>) -> Facets {
+    let mut facets = Facets::default();
+    // Checkbox and radio groups, keyed by field or group name, and whether
+    // their panel is named as a filter panel
+    let mut groups: IndexMap<(bool, String), (Facet, bool)> = IndexMap::new();
+    for control in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        let el = control.value();
+        let Some(id) = nodes.get(&control.id()) else {
+            continue;
+        };
+        if el.name() == "select" {
+            let panel = filter_panel(control);
+            let kind = match is_sort(document, control, None) {
+                true => FacetKind::Sort,
+                false if panel.is_some() => FacetKind::Filter,
+                false => continue,
+            };
+            let field = field_name(control);
+            let multiple = el.attr("multiple").is_some();
+            let mut options = select_options(control);
+            // A single select shows its first option until one is chosen
+            if !multiple && !options.iter().any(|o| o.selected) {
+                if let Some(first) = options.first_mut() {
+                    first.selected = true;
+                }
+            }
+            let name = control_label(document, control)
+                .or_else(|| panel.and_then(|(p, _)| group_name(control, p)).map(|(n, _)| n))
+                .or_else(|| field.clone())
+                .unwrap_or_else(|| kind_name(kind).to_string());
+            let facet = Facet {
+                kind,
+                name,
+                field,
+                id: Some(id.clone()),
+                multiple,
+                options,
+            };
+            match kind {
+                FacetKind::Sort => facets.sort.push(facet),
+                FacetKind::Filter => facets.filters.push(facet),
+            }
+            continue;
+        }
+
+        let input_type = el.attr("type").map(|t| t.trim().to_ascii_lowercase());
+        let multiple = match input_type.as_deref() {
+            Some("checkbox") => true,
+            Some("radio") => false,
+            _ => continue,
+        };
+        let panel = filter_panel(control);
+        let group = panel.and_then(|(p, _)| group_name(control, p));
+        let field = field_name(control);
+        let sort = !multiple && is_sort(document, control, group.as_ref().map(|g| g.0.as_str()));
+        let named = panel.is_some_and(|(_, named)| named);
+        if !sort && panel.is_none() {
+            continue;
+        }
+        let (label, count) = match control_label(document, control) {
+            Some(label) => split_count(&label),
+            None => match el.attr("value") {
+                Some(value) => (value.to_string(), None),
+                None => continue,
+            },
+        };
+        let name = group.as_ref().map(|g| g.0.clone()).or_else(|| field.clone());
+        let key = field.clone().or_else(|| name.clone()).unwrap_or_default();
+        let (facet, _) = groups.entry((sort, key)).or_insert_with(|| {
+            let facet = Facet {
+                kind: if sort { FacetKind::Sort } else { FacetKind::Filter },
+                name: name.unwrap_or_else(|| label.clone()),
+                field,
+                id: group.and_then(|(_, g)| nodes.get(&g?).cloned()),
+                multiple,
+                options: Vec::new(),
+            };
+            (facet, named)
+        });
+        facet.options.push(FacetOption {
+            label,
+            value: el.attr("value").map(str::to_string),
+            id: Some(id.clone()),
+            selected: el.attr("checked").is_some(),
+            count,
+        });
+    }
+    for ((sort, _), (facet, named)) in groups {
+        if sort {
+            facets.sort.push(facet);
+        } else if named || facet.options.len() > 1 {
+            // A lone checkbox in a sidebar is more likely a consent box
+            facets.filters.push(facet);
+        }
+    }
+    facets
+}
+
+fn kind_name(kind: FacetKind) -> &'static str {
+    match kind {
+        FacetKind::Sort => "Sort",
+        FacetKind::Filter => "Filter",
+    }
+}
+
+/// State ID fragment for a facet: its field or name, lowercased
+fn slug(facet: &Facet) -> String {
+    let name = facet.field.as_deref().unwrap_or(&facet.name).to_lowercase();
+    name.replace(|c: char| !c.is_alphanumeric(), "_")
+}
+
+impl SemanticDOM {
+    /// Add a sorted or filtered state for each facet, entered by applying
+    /// any of its options
+    pub(crate) fn add_facet_states(&mut self) {
+        let initial = self.initial_state_id();
+        let mut states = Vec::new();
+        let mut transitions = Vec::new();
+        for facet in self.facets.iter() {
+            let (prefix, name, apply) = match facet.kind {
+                FacetKind::Sort => ("sorted", format!("Sorted by {}", facet.name), "sort"),
+                FacetKind::Filter => ("filtered", format!("Filtered by {}", facet.name), "filter"),
+            };
+            let state_id = format!("state_{}_{}", prefix, slug(facet));
+            let mut applied = false;
+            for option in &facet.options {
+                // Choosing the current sort order changes nothing
+                if option.selected && facet.kind == FacetKind::Sort {
+                    continue;
+                }
+                let Some(trigger) = option.id.as_ref().or(facet.id.as_ref()) else {
+                    continue;
+                };
+                let mut params = IndexMap::new();
+                if let Some(field) = &facet.field {
+                    let value = option.value.as_ref().unwrap_or(&option.label);
+                    params.insert(field.clone(), value.clone());
+                }
+                let action = match option.selected {
+                    true => "clear-filter".to_string(),
+                    false => apply.to_string(),
+                };
+                transitions.push(Transition {
+                    from: initial.clone(),
+                    to: state_id.clone(),
+                    trigger: trigger.clone(),
+                    action: Some(action),
+                    guard: Some(format!("{} = {}", facet.name, option.label)),
+                    request: None,
+                    params,
+                });
+                applied = true;
+            }
+            if applied && !states.iter().any(|s: &State| s.id == state_id) {
+                states.push(State {
+                    id: state_id,
+                    name,
+                    description: Some(format!("{} options", facet.options.len())),
+                    url_pattern: None,
+                    is_initial: false,
+                    is_terminal: false,
+                    anchor: None,
+                });
+            }
+        }
+        self.state_graph.states.extend(states);
+        self.state_graph.transitions.extend(transitions);
+    }
+
+    /// Get the sort and filter controls of a listing
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <label for="sort">Sort by</label>
+    ///     <select id="sort" name="sort">
+    ///         <option value="relevance" selected>Relevance</option>
+    ///         <option value="price_asc">Price: low to high</option>
+    ///     </select>
+    ///     <aside class="filters"><h3>Brand</h3>
+    ///         <label><input type="checkbox" name="brand[]" value="acme"> Acme (12)</label>
+    ///         <label><input type="checkbox" name="brand[]" value="zeta"> Zeta (3)</label>
+    ///     </aside>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let facets = sdom.facets();
+    /// assert_eq!(facets.sort[0].name, "Sort by");
+    /// assert_eq!(facets.sort[0].options[1].value.as_deref(), Some("price_asc"));
+    /// let brand = &facets.filters[0];
+    /// assert_eq!((brand.name.as_str(), brand.field.as_deref()), ("Brand", Some("brand")));
+    /// assert_eq!(brand.options[0].label, "Acme");
+    /// assert_eq!(brand.options[0].count, Some(12));
+    ///
+    /// let transitions = &sdom.state_graph.transitions;
+    /// let sort = transitions.iter().find(|t| t.action.as_deref() == Some("sort")).unwrap();
+    /// assert_eq!(sort.params["sort"], "price_asc");
+    /// ```
+    pub fn facets(&self) -> &Facets {
+        &self.facets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_facets() {
+        let html = r#"<main><h1>Shoes</h1>
+            <div class="toolbar"><select name="order" aria-label="Order by">
+              <option value="top">Top rated</option><option value="new">Newest</option>
+            </select></div>
+            <div id="facet-panel">
+              <fieldset><legend>Size</legend>
+                <input type="checkbox" id="s40" name="size" value="40" checked>
+                <label for="s40">40 (1,204)</label>
+                <input type="checkbox" id="s41" name="size" value="41"><label for="s41">41</label>
+              </fieldset>
+              <h3>Price</h3>
+              <label><input type="radio" name="price" value="0-50"> Under $50</label>
+              <label><input type="radio" name="price" value="50-"> $50 and up</label>
+              <h3>Offers</h3>
+              <label><input type="checkbox" name="sale"> On sale</label>
+            </div>
+            <aside><label><input type="checkbox" name="news"> Send me news</label></aside>
+            <form><label><input type="checkbox" name="remember"> Remember me</label></form>
+            </main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let facets = sdom.facets();
+        let names: Vec<(FacetKind, &str, usize)> =
+            facets.iter().map(|f| (f.kind, f.name.as_str(), f.options.len())).collect();
+        assert_eq!(
+            names,
+            [
+                (FacetKind::Sort, "Order by", 2),
+                (FacetKind::Filter, "Size", 2),
+                (FacetKind::Filter, "Price", 2),
+                (FacetKind::Filter, "Offers", 1),
+            ]
+        );
+        let size = &facets.filters[0];
+        assert!(size.multiple);
+        let selected: Vec<(&str, Option<u32>)> =
+            size.selected().map(|o| (o.label.as_str(), o.count)).collect();
+        assert_eq!(selected, [("40", Some(1204))]);
+        assert!(!facets.filters[1].multiple);
+
+        let actions: Vec<(&str, &str, Option<&str>)> = sdom
+            .state_graph
+            .transitions
+            .iter()
+            .filter(|t| t.to.starts_with("state_sorted") || t.to.starts_with("state_filtered"))
+            .map(|t| {
+                let param = t.params.values().next().map(String::as_str);
+                (t.to.as_str(), t.action.as_deref().unwrap(), param)
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                ("state_sorted_order", "sort", Some("new")),
+                ("state_filtered_size", "clear-filter", Some("40")),
+                ("state_filtered_size", "filter", Some("41")),
+                ("state_filtered_price", "filter", Some("0-50")),
+                ("state_filtered_price", "filter", Some("50-")),
+                ("state_filtered_sale", "filter", Some("On sale")),
+            ]
+        );
+        let summary = sdom.to_agent_summary();
+        assert!(
+            summary.contains("\nFACETS: sort=Order by(2), Size(2), Price(2), Offers(1)"),
+            "{}",
+            summary
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! SSRF protections for code that fetches pages
+//!
+//! The crate itself never performs network requests. Services that fetch
+//! URLs on an agent's behalf (CLI fetchers, MCP tools, crawlers) should
+//! route every request through a [`FetchGuard`], which applies the fetch
+//! limits from [`SecurityConfig`]: only http(s) to public addresses on
+//! allowed hosts, a redirect cap, an overall deadline, and limits on the
+//! response size both as received and after decompression.
+
+use crate::security::SecurityConfig;
+use crate::{Error, Result};
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use url::{Host, Url};
+
+/// Host names that always refer to the local machine or network
+const LOCAL_SUFFIXES: &[&str] = &["localhost", ".localhost", ".local", ".internal"];
+
+/// Tracks one fetch (including redirects) against the configured limits
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Error, FetchGuard, SecurityConfig};
+///
+/// let config = SecurityConfig::new();
+/// let mut guard = FetchGuard::new(&config);
+///
+/// assert!(guard.check_url("https://example.com/page").is_ok());
+/// assert!(matches!(
+///     guard.check_url("http://169.254.169.254/latest/meta-data/"),
+///     Err(Error::BlockedAddress { .. })
+/// ));
+/// assert!(guard.record_bytes(config.max_response_size + 1).is_err());
+/// ```
+#[derive(Debug)]
+pub struct FetchGuard<'a> {
+    config: &'a SecurityConfig,
+    started: Instant,
+    redirects: usize,
+    received: usize,
+}
+
+impl<'a> FetchGuard<'a> {
+    /// Start tracking a fetch; the deadline starts now
+    pub fn new(config: &'a SecurityConfig) -> Self {
+        Self {
+            config,
+            started: Instant::now(),
+            redirects: 0,
+            received: 0,
+        }
+    }
+
+    /// Check a URL before requesting it
+    ///
+    /// Rejects non-http(s) schemes, hosts outside the configured allow/deny
+    /// lists, local host names, and IP literals in private, loopback,
+    /// link-local or otherwise reserved ranges. Host names are not resolved
+    /// here; use [`resolve`](Self::resolve) to
+    /// connect only to vetted addresses.
+    ///
+    /// # Errors
+    /// - [`Error::InvalidUrlProtocol`] for schemes other than http/https
+    /// - [`Error::BlockedHost`] for missing, local or disallowed hosts
+    /// - [`Error::BlockedAddress`] for non-public IP literals
+    pub fn check_url(&self, url: &str) -> Result<Url> {
+        let parsed = Url::parse(url.trim()).map_err(|_| Error::BlockedHost(url.to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(Error::InvalidUrlProtocol {
+                protocol: parsed.scheme().to_string(),
+            });
+        }
+
+        self.config.check_host(&parsed)?;
+        match parsed.host() {
+            None => return Err(Error::BlockedHost(url.to_string())),
+            Some(Host::Domain(domain)) => {
+                let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                let local = LOCAL_SUFFIXES
+                    .iter()
+                    .any(|s| domain == s.trim_start_matches('.') || domain.ends_with(s));
+                if local && !self.config.allow_private_networks {
+                    return Err(Error::BlockedHost(domain));
+                }
+            }
+            Some(Host::Ipv4(ip)) => self.check_address(&parsed, IpAddr::V4(ip))?,
+            Some(Host::Ipv6(ip)) => self.check_address(&parsed, IpAddr::V6(ip))?,
+        }
+        self.check_deadline()?;
+        Ok(parsed)
+    }
+
+    /// Resolve a checked URL's host and vet every address
+    ///
+    /// Connecting only to the returned addresses prevents DNS rebinding
+    /// from sneaking a private address past [`check_url`](Self::check_url).
+    ///
+    /// This uses the system resolver, which blocks the calling thread and
+    /// cannot be interrupted: the deadline is checked before resolving, but
+    /// a slow lookup can overrun it. Callers that need a hard limit should
+    /// resolve on a separate thread.
+    ///
+    /// # Errors
+    /// - [`Error::FetchTimeout`] if the deadline has already passed
+    /// - [`Error::BlockedAddress`] if any resolved address is not public
+    /// - [`Error::IoError`] if resolution fails
+    pub fn resolve(&self, url: &Url) -> Result<Vec<SocketAddr>> {
+        self.check_deadline()?;
+        let host = url.host_str().ok_or_else(|| Error::BlockedHost(url.to_string()))?;
+        let port = url.port_or_known_default().unwrap_or(443);
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs: Vec<SocketAddr> = (host, port).to_socket_addrs()?.collect();
+        for addr in &addrs {
+            self.check_address(url, addr.ip())?;
+        }
+        Ok(addrs)
+    }
+
+    /// Account for a redirect and check its target
+    ///
+    /// `location` may be relative to `from`.
+    ///
+    /// # Errors
+    /// - [`Error::TooManyRedirects`] once `max_redirects` is exceeded
+    /// - any error from [`check_url`](Self::check_url) for the target
+    pub fn follow_redirect(&mut self, from: &Url, location: &str) -> Result<Url> {
+        self.redirects += 1;
+        if self.redirects > self.config.max_redirects {
+            return Err(Error::TooManyRedirects {
+                max: self.config.max_redirects,
+            });
+        }
+        let target = from
+            .join(location.trim())
+            .map_err(|_| Error::BlockedHost(location.to_string()))?;
+        self.check_url(target.as_str())
+    }
+
+    /// Account for received body bytes
+    ///
+    /// # Errors
+    /// - [`Error::ResponseTooLarge`] once `max_response_size` is exceeded
+    /// - [`Error::FetchTimeout`] once the deadline has passed
+    pub fn record_bytes(&mut self, len: usize) -> Result<()> {
+        self.received = self.received.saturating_add(len);
+        if self.received > self.config.max_response_size {
+            return Err(Error::ResponseTooLarge {
+                max_size: self.config.max_response_size,
+                actual_size: self.received,
+            });
+        }
+        self.check_deadline()
+    }
+
+    /// Read a decoded (decompressed) body, stopping at `max_decoded_size`
+    ///
+    /// Wrap the gzip/brotli decoder in this so a small compressed payload
+    /// cannot expand without bound. Raw bytes should still be counted with
+    /// [`record_bytes`](Self::record_bytes).
+    ///
+    /// # Errors
+    /// - [`Error::DecodedTooLarge`] once the decoded body exceeds the limit
+    /// - [`Error::IoError`] if reading fails
+    pub fn read_decoded(&self, reader: impl Read) -> Result<Vec<u8>> {
+        let max = self.config.max_decoded_size;
+        let mut body = Vec::new();
+        // Read one byte past the limit to tell "exactly at" from "over"
+        reader.take(max as u64 + 1).read_to_end(&mut body)?;
+        if body.len() > max {
+            return Err(Error::DecodedTooLarge { max_size: max });
+        }
+        Ok(body)
+    }
+
+    /// Time left before the fetch deadline, for socket timeouts
+    pub fn remaining(&self) -> Duration {
+        self.config.fetch_timeout.saturating_sub(self.started.elapsed())
+    }
+
+    /// Check that the fetch deadline has not passed
+    ///
+    /// # Errors
+    /// - [`Error::FetchTimeout`] once the deadline has passed
+    pub fn check_deadline(&self) -> Result<()> {
+        if self.started.elapsed() > self.config.fetch_timeout {
+            return Err(Error::FetchTimeout {
+                timeout_ms: self.config.fetch_timeout.as_millis() as u64,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_address(&self, url: &Url, ip: IpAddr) -> Result<()> {
+        if self.config.allow_private_networks || is_public(ip) {
+            return Ok(());
+        }
+        Err(Error::BlockedAddress {
+            host: url.host_str().unwrap_or_default().to_string(),
+            address: ip,
+        })
+    }
+}
+
+/// Check whether an address is routable on the public internet
+pub(crate) fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match embedded_v4(ip) {
+            Some(v4) => is_public_v4(v4),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+/// IPv4 address an IPv6 one reaches: mapped `::ffff:a.b.c.d`, compatible
+/// `::a.b.c.d`, NAT64 `64:ff9b::a.b.c.d` or 6to4 `2002:aabb:ccdd::`
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let [a, b, c, d, e, f, g, h] = ip.segments();
+    if [a, b, c, d, e, f] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return Some(Ipv4Addr::from((u32::from(g) << 16) | u32::from(h)));
+    }
+    if a == 0x2002 {
+        return Some(Ipv4Addr::from((u32::from(b) << 16) | u32::from(c)));
+    }
+    ip.to_ipv4()
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b == 18 || b == 19))
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Deprecated site-local, fec0::/10
+        || (first & 0xffc0) == 0xfec0
+        // Teredo, 2001::/32, tunnels to an IPv4 address that is hard to vet
+        || (first == 0x2001 && ip.segments()[1] == 0)
+        // Documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_ranges() {
+        let blocked = [
+            "10.1.2.3", "172.20.0.1", "192.168.1.1", "127.0.0.1", "100.100.0.1", "::1", "fd00::1",
+            "fe80::1", "::ffff:10.0.0.1", "::127.0.0.1", "64:ff9b::7f00:1", "64:ff9b::a00:1",
+            "2002:7f00:1::", "2002:c0a8:101::1", "2001:0:4136:e378::1", "fec0::1",
+        ];
+        for blocked in blocked {
+            assert!(!is_public(blocked.parse().unwrap()), "{}", blocked);
+        }
+        let public = [
+            "93.184.216.34", "2606:4700::1111", "::ffff:8.8.8.8", "64:ff9b::808:808",
+            "2002:808:808::",
+        ];
+        for public in public {
+            assert!(is_public(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[test]
+    fn test_resolve_checks_deadline_first() {
+        let config = SecurityConfig { fetch_timeout: Duration::ZERO, ..SecurityConfig::default() };
+        let guard = FetchGuard::new(&config);
+        std::thread::sleep(Duration::from_millis(1));
+        let url = Url::parse("https://example.com/").unwrap();
+        assert!(matches!(guard.resolve(&url), Err(Error::FetchTimeout { timeout_ms: 0 })));
+    }
+
+    #[test]
+    fn test_decoded_size_limit() {
+        let config = SecurityConfig { max_decoded_size: 8, ..SecurityConfig::default() };
+        let guard = FetchGuard::new(&config);
+        assert_eq!(guard.read_decoded(&b"12345678"[..]).unwrap().len(), 8);
+        assert!(matches!(
+            guard.read_decoded(std::io::repeat(b'a')),
+            Err(Error::DecodedTooLarge { max_size: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_redirect_cap_and_local_hosts() {
+        let config = SecurityConfig { max_redirects: 1, ..SecurityConfig::default() };
+        let mut guard = FetchGuard::new(&config);
+        let start = guard.check_url("https://example.com/a").unwrap();
+
+        let next = guard.follow_redirect(&start, "/b").unwrap();
+        assert_eq!(next.as_str(), "https://example.com/b");
+        assert!(matches!(
+            guard.follow_redirect(&next, "/c"),
+            Err(Error::TooManyRedirects { max: 1 })
+        ));
+
+        assert!(matches!(guard.check_url("http://LOCALHOST:8080/"), Err(Error::BlockedHost(_))));
+        assert!(matches!(guard.check_url("http://db.internal/"), Err(Error::BlockedHost(_))));
+        for url in ["http://[::127.0.0.1]/", "http://[64:ff9b::7f00:1]/"] {
+            assert!(matches!(guard.check_url(url), Err(Error::BlockedAddress { .. })), "{}", url);
+        }
+        assert!(matches!(
+            guard.check_url("ftp://example.com/"),
+            Err(Error::InvalidUrlProtocol { .. })
+        ));
+
+        let config = SecurityConfig::new().with_allowed_host("*.example.com");
+        let mut guard = FetchGuard::new(&config);
+        let start = guard.check_url("https://www.example.com/").unwrap();
+        assert!(matches!(
+            guard.follow_redirect(&start, "https://evil.example/"),
+            Err(Error::BlockedHost(host)) if host == "evil.example"
+        ));
+
+        let open = SecurityConfig { allow_private_networks: true, ..SecurityConfig::default() };
+        assert!(FetchGuard::new(&open).check_url("http://127.0.0.1/").is_ok());
+    }
+}
@@ -0,0 +1,186 @@
+//! Inline form validation message extraction
+//!
+//! After a failed submit, pages mark fields with `aria-invalid` and render
+//! error text next to them. Associating that text with the offending field
+//! lets a re-parse tell an agent exactly which inputs need fixing.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+use scraper::ElementRef;
+
+/// Substrings of `id`/`class` marking an element as an error message
+const ERROR_MARKERS: &[&str] = &["error", "invalid"];
+
+/// How many ancestor levels to search for a neighbouring error message
+const MAX_SEARCH_LEVELS: usize = 3;
+
+/// Determine whether a form field is invalid and find its error message
+///
+/// Sources, in order: `aria-errormessage`, error-like elements referenced
+/// by `aria-describedby`, and error-like siblings following the field
+/// (`.error`, `.invalid-feedback`, `role=alert`, ...).
+pub(crate) fn field_error(element: ElementRef) -> (bool, Option<String>) {
+    let el = element.value();
+    let invalid = el
+        .attr("aria-invalid")
+        .is_some_and(|v| !v.eq_ignore_ascii_case("false"));
+
+    if let Some(message) = el
+        .attr("aria-errormessage")
+        .and_then(|ids| referenced_text(element, ids, |_| true))
+    {
+        return (true, Some(message));
+    }
+
+    // aria-describedby also carries hints; only trust error-like targets
+    // unless the field is already marked invalid
+    if let Some(message) = el
+        .attr("aria-describedby")
+        .and_then(|ids| referenced_text(element, ids, |e| invalid || is_error_element(e)))
+    {
+        return (true, Some(message));
+    }
+
+    match following_error(element) {
+        Some(message) => (true, Some(message)),
+        None => (invalid, None),
+    }
+}
+
+/// Text of the first element referenced by an ID list that passes `accept`
+fn referenced_text(
+    element: ElementRef,
+    ids: &str,
+    accept: impl Fn(ElementRef) -> bool,
+) -> Option<String> {
+    let root = element.ancestors().last()?;
+    ids.split_whitespace().find_map(|id| {
+        root.descendants()
+            .filter_map(ElementRef::wrap)
+            .find(|e| e.value().id() == Some(id))
+            .filter(|e| accept(*e))
+            .and_then(element_text)
+    })
+}
+
+/// Find an error message among the siblings that follow the field
+///
+/// Stops at the next form control so one field's message is not attributed
+/// to the next, and climbs a couple of levels for wrappers like
+/// `<label><input></label><span class="error">`.
+fn following_error(element: ElementRef) -> Option<String> {
+    let mut current = element;
+    for _ in 0..MAX_SEARCH_LEVELS {
+        for sibling in current.next_siblings().filter_map(ElementRef::wrap) {
+            if let Some(error) = std::iter::once(sibling)
+                .chain(sibling.descendants().filter_map(ElementRef::wrap))
+                .find(|e| is_error_element(*e))
+            {
+                return element_text(error);
+            }
+            if contains_control(sibling) {
+                return None;
+            }
+        }
+
+        let parent = current.parent().and_then(ElementRef::wrap)?;
+        if matches!(parent.value().name(), "form" | "fieldset" | "body") {
+            return None;
+        }
+        current = parent;
+    }
+    None
+}
+
+fn is_error_element(element: ElementRef) -> bool {
+    let el = element.value();
+    if el.attr("role").is_some_and(|r| r.eq_ignore_ascii_case("alert")) {
+        return true;
+    }
+    el.id()
+        .into_iter()
+        .chain(el.classes())
+        .map(str::to_ascii_lowercase)
+        .any(|t| ERROR_MARKERS.iter().any(|m| t.contains(m)))
+}
+
+fn contains_control(element: ElementRef) -> bool {
+    std::iter::once(element)
+        .chain(element.descendants().filter_map(ElementRef::wrap))
+        .any(|e| matches!(e.value().name(), "input" | "select" | "textarea"))
+}
+
+fn element_text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+impl SemanticDOM {
+    /// Get form fields marked invalid or showing an error message, in
+    /// document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<form>
+    ///     <input type="email" name="email" aria-invalid="true" aria-errormessage="email-err">
+    ///     <p id="email-err">Enter a valid email address</p>
+    /// </form>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let fields = sdom.invalid_fields();
+    /// assert_eq!(fields[0].error_message.as_deref(), Some("Enter a valid email address"));
+    /// ```
+    pub fn invalid_fields(&self) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| n.invalid || n.error_message.is_some())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_messages_attach_to_the_right_field() {
+        let html = r#"
+            <form>
+                <input name="user" aria-label="User">
+                <span class="error">Username is taken</span>
+                <input name="city" aria-label="City">
+                <div><label>Zip <input name="zip" aria-label="Zip"></label></div>
+                <div class="invalid-feedback">Zip must be 5 digits</div>
+                <input name="phone" aria-label="Phone" aria-describedby="phone-hint">
+                <small id="phone-hint">We never share your number</small>
+                <input name="pin" aria-label="PIN" aria-invalid="true">
+            </form>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let errors: Vec<(&str, Option<&str>)> = sdom
+            .invalid_fields()
+            .iter()
+            .map(|n| (n.label.as_str(), n.error_message.as_deref()))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                ("User", Some("Username is taken")),
+                ("Zip", Some("Zip must be 5 digits")),
+                ("PIN", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_summary_line() {
+        let html = r#"<form><input aria-label="Email" aria-invalid="true" aria-describedby="e">
+            <div id="e">Required</div></form>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert!(sdom.to_agent_summary().contains("ERRORS: Email=Required"));
+    }
+}
@@ -0,0 +1,264 @@
+//! Form submissions as state-graph transitions
+//!
+//! Each submit button (or the form itself, for Enter-to-submit) gets a
+//! `submit` transition whose [`FormRequest`] gives the method, target URL
+//! and submitted fields, so agents can predict the request before sending
+//! it. `formaction`/`formmethod` on a button override the form's values.
+
+use crate::parser::SemanticDOM;
+use crate::security::{validate_url_with, SecurityConfig};
+use crate::types::{FormField, FormRequest, State, Transition};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use url::Url;
+
+/// Input types that are not submitted as fields
+const NON_FIELD_TYPES: &[&str] = &["submit", "button", "reset", "image"];
+
+/// A submit control and the request it sends
+#[derive(Debug, Clone)]
+pub(crate) struct FormSubmit {
+    /// Node that triggers the submission: a submit button or the form
+    pub trigger: String,
+    /// Node of the submitted form
+    pub form: String,
+    /// The request sent
+    pub request: FormRequest,
+}
+
+fn is_submit_control(element: ElementRef) -> bool {
+    let el = element.value();
+    let kind = el.attr("type").map(|t| t.trim().to_ascii_lowercase());
+    match el.name() {
+        // Buttons default to submitting their form
+        "button" => matches!(kind.as_deref(), None | Some("submit")),
+        "input" => matches!(kind.as_deref(), Some("submit" | "image")),
+        _ => false,
+    }
+}
+
+/// Controls belonging to a form: descendants, plus elements elsewhere that
+/// name the form with a `form` attribute
+fn form_controls<'a>(document: &'a Html, form: ElementRef<'a>) -> Vec<ElementRef<'a>> {
+    let form_id = form.value().id();
+    document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| matches!(e.value().name(), "input" | "select" | "textarea" | "button"))
+        .filter(|e| match e.value().attr("form") {
+            Some(id) => Some(id.trim()) == form_id,
+            None => e.ancestors().any(|a| a.id() == form.id()),
+        })
+        .collect()
+}
+
+/// Find form submissions, given the node created for each element
+pub(crate) fn form_submits(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+    base_url: Option<&str>,
+    security: &SecurityConfig,
+) -> Vec<FormSubmit> {
+    let base = base_url.and_then(|b| Url::parse(b).ok());
+    let resolve = |action: &str| {
+        let action = validate_url_with(action.trim(), security).ok()?;
+        match &base {
+            Some(base) => base.join(&action).ok().map(String::from),
+            None => Some(action),
+        }
+    };
+
+    let mut submits = Vec::new();
+    for form in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        if form.value().name() != "form" {
+            continue;
+        }
+        let Some(form_id) = nodes.get(&form.id()) else {
+            continue;
+        };
+        let el = form.value();
+        let method = el.attr("method").unwrap_or("get").trim().to_ascii_uppercase();
+        let controls = form_controls(document, form);
+
+        let fields: Vec<FormField> = controls
+            .iter()
+            .filter_map(|control| {
+                let c = control.value();
+                let name = c.attr("name").map(str::trim).filter(|n| !n.is_empty())?;
+                let field_type = match c.name() {
+                    "input" => c.attr("type").unwrap_or("text").trim().to_ascii_lowercase(),
+                    "button" => return None,
+                    other => other.to_string(),
+                };
+                if NON_FIELD_TYPES.contains(&field_type.as_str()) {
+                    return None;
+                }
+                Some(FormField {
+                    name: name.to_string(),
+                    value: c.attr("value").filter(|_| field_type == "hidden").map(str::to_string),
+                    field_type,
+                    required: c.attr("required").is_some(),
+                    node: nodes.get(&control.id()).cloned(),
+                })
+            })
+            .collect();
+
+        let request = FormRequest {
+            method: method.clone(),
+            action: el.attr("action").filter(|a| !a.trim().is_empty()).and_then(resolve),
+            enctype: el
+                .attr("enctype")
+                .filter(|_| method == "POST")
+                .map(|e| e.trim().to_ascii_lowercase()),
+            fields,
+        };
+        // method="dialog" closes a dialog instead of sending a request
+        if !matches!(request.method.as_str(), "GET" | "POST") {
+            continue;
+        }
+
+        let buttons: Vec<(&String, ElementRef)> = controls
+            .iter()
+            .filter(|c| is_submit_control(**c))
+            .filter_map(|c| Some((nodes.get(&c.id())?, *c)))
+            .collect();
+        if buttons.is_empty() {
+            submits.push(FormSubmit {
+                trigger: form_id.clone(),
+                form: form_id.clone(),
+                request,
+            });
+            continue;
+        }
+        for (trigger, button) in buttons {
+            let b = button.value();
+            let mut request = request.clone();
+            if let Some(method) = b.attr("formmethod").map(|m| m.trim().to_ascii_uppercase()) {
+                if method == "GET" {
+                    request.enctype = None;
+                }
+                if matches!(method.as_str(), "GET" | "POST") {
+                    request.method = method;
+                }
+            }
+            if let Some(action) = b.attr("formaction").filter(|a| !a.trim().is_empty()) {
+                request.action = resolve(action);
+            }
+            submits.push(FormSubmit {
+                trigger: trigger.clone(),
+                form: form_id.clone(),
+                request,
+            });
+        }
+    }
+    submits
+}
+
+impl SemanticDOM {
+    /// Add a `submit` transition for each form submission
+    pub(crate) fn add_form_states(&mut self) {
+        let initial = self.initial_state_id();
+        for submit in &self.form_submits {
+            let request = &submit.request;
+            let target = request.action.as_deref().unwrap_or("");
+            let state_id = format!(
+                "state_{}_{}",
+                request.method.to_ascii_lowercase(),
+                target.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            );
+            if !self.state_graph.states.iter().any(|s| s.id == state_id) {
+                self.state_graph.states.push(State {
+                    id: state_id.clone(),
+                    name: format!("{} {}", request.method, target).trim_end().to_string(),
+                    description: None,
+                    url_pattern: request.action.clone(),
+                    is_initial: false,
+                    is_terminal: false,
+                    anchor: None,
+                });
+            }
+            self.state_graph.transitions.push(Transition {
+                from: initial.clone(),
+                to: state_id,
+                trigger: submit.trigger.clone(),
+                action: Some("submit".to_string()),
+                guard: None,
+                request: Some(request.clone()),
+                params: IndexMap::new(),
+            });
+        }
+    }
+
+    /// Get the request a submit button (or form) will send
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let config = Config::builder().base_url("https://shop.example/p/1").build().unwrap();
+    /// let html = r#"<form id="cart" action="/cart" method="post">
+    ///     <input type="hidden" name="sku" value="A-1">
+    ///     <input type="number" name="qty" value="1" required>
+    ///     <button id="add">Add to cart</button>
+    /// </form>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let request = sdom.submit_request("sdom_add").unwrap();
+    /// assert_eq!(request.method, "POST");
+    /// assert_eq!(request.action.as_deref(), Some("https://shop.example/cart"));
+    /// assert_eq!(request.fields[0].value.as_deref(), Some("A-1"));
+    /// assert!(request.fields[1].required);
+    /// ```
+    pub fn submit_request(&self, trigger: &str) -> Option<&FormRequest> {
+        self.form_submits
+            .iter()
+            .find(|s| s.trigger == trigger)
+            .map(|s| &s.request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_submit_transitions() {
+        let html = r#"<main>
+            <form action="/search" aria-label="Search"><input name="q"></form>
+            <form id="post" method="POST" action="/posts" enctype="multipart/form-data">
+                <textarea name="body"></textarea>
+                <button type="button">Preview</button>
+                <button>Publish</button>
+                <button formaction="/drafts" formmethod="get">Save draft</button>
+            </form>
+            <input name="tags" form="post">
+            <dialog open><form method="dialog"><button>OK</button></form></dialog>
+        </main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let submits: Vec<_> = sdom
+            .state_graph
+            .transitions
+            .iter()
+            .filter(|t| t.action.as_deref() == Some("submit"))
+            .collect();
+        assert_eq!(submits.len(), 3);
+
+        // No submit button: the form triggers its own submission
+        let search = submits[0].request.as_ref().unwrap();
+        assert_eq!((search.method.as_str(), search.action.as_deref()), ("GET", Some("/search")));
+        assert_eq!(search.fields[0].name, "q");
+
+        let publish = submits[1].request.as_ref().unwrap();
+        assert_eq!(publish.enctype.as_deref(), Some("multipart/form-data"));
+        let names: Vec<&str> = publish.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["body", "tags"]);
+
+        let draft = submits[2].request.as_ref().unwrap();
+        assert_eq!((draft.method.as_str(), draft.action.as_deref()), ("GET", Some("/drafts")));
+        assert!(sdom.state_graph.is_deterministic());
+    }
+}
@@ -0,0 +1,212 @@
+//! Front-end framework fingerprinting
+//!
+//! Frameworks leave recognizable attributes in rendered markup
+//! (`data-reactroot`, `ng-version`, `data-v-1a2b3c`, `hx-get`, ...). Knowing
+//! which one built a page tells agents what kind of interactivity to expect.
+//! HTMX requests are also modeled as state-graph transitions, since they
+//! describe server round-trips declaratively.
+
+use crate::parser::SemanticDOM;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Metadata key holding an element's HTMX request method
+pub(crate) const HX_METHOD_KEY: &str = "hx-method";
+/// Metadata key holding an element's HTMX request URL
+pub(crate) const HX_URL_KEY: &str = "hx-url";
+/// Metadata key holding the element an HTMX response is swapped into
+pub(crate) const HX_TARGET_KEY: &str = "hx-target";
+/// Metadata key holding an HTMX confirmation prompt
+pub(crate) const HX_CONFIRM_KEY: &str = "hx-confirm";
+
+const HX_METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+
+/// A front-end framework or library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Framework {
+    /// React (including Next.js)
+    React,
+    /// Vue (including Nuxt)
+    Vue,
+    /// Angular or AngularJS
+    Angular,
+    /// Svelte
+    Svelte,
+    /// HTMX
+    Htmx,
+    /// Alpine.js
+    Alpine,
+}
+
+impl Framework {
+    /// Canonical lowercase name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Framework::React => "react",
+            Framework::Vue => "vue",
+            Framework::Angular => "angular",
+            Framework::Svelte => "svelte",
+            Framework::Htmx => "htmx",
+            Framework::Alpine => "alpine",
+        }
+    }
+}
+
+/// Frameworks whose fingerprints appear in the markup
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameworkHints {
+    /// Detected frameworks, in the order their first fingerprint appears
+    pub detected: Vec<Framework>,
+    /// Number of elements that issue HTMX requests
+    pub htmx_requests: usize,
+}
+
+impl FrameworkHints {
+    /// Check whether a framework was detected
+    pub fn has(&self, framework: Framework) -> bool {
+        self.detected.contains(&framework)
+    }
+}
+
+/// Identify the framework an element's markup belongs to
+fn fingerprint(element: ElementRef) -> Option<Framework> {
+    let el = element.value();
+    if let Some(id) = el.id() {
+        match id {
+            "__next" => return Some(Framework::React),
+            "__nuxt" => return Some(Framework::Vue),
+            _ => {}
+        }
+    }
+    if el.classes().any(|c| c.starts_with("svelte-")) {
+        return Some(Framework::Svelte);
+    }
+
+    el.attrs().find_map(|(name, _)| {
+        let name = name.strip_prefix("data-").filter(|n| n.starts_with("hx-")).unwrap_or(name);
+        if name.starts_with("hx-") {
+            Some(Framework::Htmx)
+        } else if matches!(name, "data-reactroot" | "data-reactid") {
+            Some(Framework::React)
+        } else if name.starts_with("ng-")
+            || name.starts_with("_ngcontent-")
+            || name.starts_with("_nghost-")
+        {
+            Some(Framework::Angular)
+        } else if name.starts_with("v-")
+            || name.starts_with("data-v-")
+            || name == "data-server-rendered"
+        {
+            Some(Framework::Vue)
+        } else if name.starts_with("x-data") || name.starts_with("x-on:") {
+            Some(Framework::Alpine)
+        } else {
+            None
+        }
+    })
+}
+
+/// Scan the document for framework fingerprints
+pub(crate) fn framework_hints(document: &Html) -> FrameworkHints {
+    let mut hints = FrameworkHints::default();
+    for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        if let Some(framework) = fingerprint(element) {
+            if !hints.has(framework) {
+                hints.detected.push(framework);
+            }
+        }
+        if htmx_request(element).is_some() {
+            hints.htmx_requests += 1;
+        }
+    }
+    hints
+}
+
+/// HTMX attribute value, also accepting the `data-hx-` spelling
+fn hx_attr<'a>(element: ElementRef<'a>, name: &str) -> Option<&'a str> {
+    let el = element.value();
+    el.attr(&format!("hx-{}", name))
+        .or_else(|| el.attr(&format!("data-hx-{}", name)))
+}
+
+/// The request an element issues, as metadata entries (method, URL, target,
+/// confirmation)
+pub(crate) fn htmx_request(element: ElementRef) -> Option<Vec<(&'static str, String)>> {
+    let (method, url) = HX_METHODS
+        .iter()
+        .find_map(|m| hx_attr(element, m).map(|url| (*m, url.trim())))?;
+
+    let mut entries = vec![
+        (HX_METHOD_KEY, method.to_string()),
+        (HX_URL_KEY, url.to_string()),
+    ];
+    if let Some(target) = hx_attr(element, "target") {
+        entries.push((HX_TARGET_KEY, target.trim().to_string()));
+    }
+    if let Some(confirm) = hx_attr(element, "confirm") {
+        entries.push((HX_CONFIRM_KEY, confirm.trim().to_string()));
+    }
+    Some(entries)
+}
+
+impl SemanticDOM {
+    /// Get the front-end frameworks detected in the markup
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, Framework, SemanticDOM};
+    ///
+    /// let html = r##"<main ng-version="17.0.0">
+    ///     <button hx-post="/cart/items" hx-target="#cart">Add to cart</button>
+    /// </main>"##;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let hints = sdom.frameworks();
+    /// assert_eq!(hints.detected, [Framework::Angular, Framework::Htmx]);
+    ///
+    /// // HTMX requests become state-graph transitions
+    /// let transitions = &sdom.state_graph.transitions;
+    /// assert!(transitions.iter().any(|t| t.action.as_deref() == Some("hx-post")));
+    /// ```
+    pub fn frameworks(&self) -> &FrameworkHints {
+        &self.frameworks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_fingerprints() {
+        let html = r#"<div id="__next"><div data-v-7ba5bd90 class="card svelte-xyz"></div>
+            <div x-data="{ open: false }"></div><a href="/" data-hx-get="/more">More</a></div>"#;
+        let hints = framework_hints(&Html::parse_document(html));
+        assert_eq!(
+            hints.detected,
+            [Framework::React, Framework::Svelte, Framework::Alpine, Framework::Htmx]
+        );
+        assert_eq!(hints.htmx_requests, 1);
+    }
+
+    #[test]
+    fn test_htmx_transitions_replace_link_navigation() {
+        let html = r##"<main>
+            <a href="/page/2" hx-get="/page/2?partial=1" hx-target="#list">Next</a>
+            <button hx-delete="/items/3" hx-confirm="Delete this item?">Delete</button>
+        </main>"##;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let transitions = &sdom.state_graph.transitions;
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].action.as_deref(), Some("hx-get"));
+        assert_eq!(transitions[1].guard.as_deref(), Some("confirm: Delete this item?"));
+        assert!(sdom.state_graph.is_deterministic());
+
+        let state = sdom.state_graph.states.iter().find(|s| s.id == transitions[0].to).unwrap();
+        assert_eq!(state.url_pattern.as_deref(), Some("/page/2?partial=1"));
+        assert_eq!(state.description.as_deref(), Some("Swaps #list"));
+    }
+}
@@ -0,0 +1,70 @@
+//! Snapshot tests over a corpus of anonymized real-world pages
+//!
+//! Fixtures live in `fixtures/pages`; snapshots in `src/snapshots`. Review
+//! changes with `cargo insta review`, or regenerate with
+//! `INSTA_UPDATE=always cargo test golden`.
+
+use crate::{AgentCertification, Config, SemanticDOM};
+use std::fmt::Write;
+
+const PAGES: &[(&str, &str)] = &[
+    ("news", include_str!("../fixtures/pages/news.html")),
+    ("shop", include_str!("../fixtures/pages/shop.html")),
+    ("spa", include_str!("../fixtures/pages/spa.html")),
+    ("signup", include_str!("../fixtures/pages/signup.html")),
+];
+
+/// JSON with object keys sorted, so hash-map order doesn't show in diffs
+fn sorted_json(sdom: &SemanticDOM) -> String {
+    let value: serde_json::Value = serde_json::from_str(&sdom.to_json().unwrap()).unwrap();
+    serde_json::to_string_pretty(&value).unwrap()
+}
+
+fn certification_report(sdom: &SemanticDOM) -> String {
+    let cert = AgentCertification::certify(sdom);
+    let mut report = format!("{} ({})\n", cert.level.name(), cert.score);
+    for check in &cert.checks {
+        let mark = if check.passed { "pass" } else { "FAIL" };
+        let details = check.details.as_deref().unwrap_or("");
+        writeln!(report, "{} {} {}: {}", mark, check.id, check.name, details).unwrap();
+    }
+    report
+}
+
+#[test]
+fn golden_pages() {
+    for (name, html) in PAGES {
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        insta::assert_snapshot!(format!("{}_json", name), sorted_json(&sdom));
+        insta::assert_snapshot!(format!("{}_summary", name), sdom.to_agent_summary());
+        insta::assert_snapshot!(format!("{}_one_liner", name), sdom.to_one_liner());
+        insta::assert_snapshot!(format!("{}_certification", name), certification_report(&sdom));
+    }
+}
+
+#[test]
+fn output_is_byte_identical_across_parses() {
+    // Metadata-bearing nodes: a download link and an HTMX request
+    let extra = r##"<main><a href="/r.pdf" download>Report (PDF, 2 MB)</a>
+        <button hx-post="/like" hx-target="#n" hx-confirm="Sure?">Like</button></main>"##;
+    for html in PAGES.iter().map(|(_, html)| *html).chain([extra]) {
+        let render = || {
+            let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+            let nodes: Vec<String> = sdom
+                .reading_order()
+                .into_iter()
+                .map(|n| serde_json::to_string(n).unwrap())
+                .collect();
+            (
+                sdom.to_json().unwrap(),
+                sdom.to_agent_summary(),
+                sdom.to_one_liner(),
+                crate::to_nav_summary(&sdom),
+                crate::to_audio_summary(&sdom),
+                nodes,
+                sdom.structure_hash(),
+            )
+        };
+        assert_eq!(render(), render());
+    }
+}
@@ -0,0 +1,200 @@
+//! Size and shape of a state graph
+//!
+//! [`StateGraph::metrics`] summarizes how complex a page (or a merged
+//! site graph) is for an agent to navigate: how many choices each state
+//! offers, how many steps the farthest state takes, and which states form
+//! cycles the agent can move around in.
+
+use crate::types::StateGraph;
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Complexity figures for a state graph
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphMetrics {
+    /// Number of states
+    pub state_count: usize,
+    /// Number of transitions
+    pub transition_count: usize,
+    /// Average outgoing transitions of states that have any
+    pub branching_factor: f64,
+    /// Longest shortest path, in transitions, between two connected states
+    pub diameter: usize,
+    /// Strongly connected components: states that can all reach each
+    /// other, in state order; a state without a cycle is its own component
+    pub strongly_connected_components: Vec<Vec<String>>,
+}
+
+impl GraphMetrics {
+    /// Components with more than one state, i.e. the cycles in the graph
+    pub fn cycles(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.strongly_connected_components.iter().filter(|c| c.len() > 1)
+    }
+}
+
+/// Adjacency lists by state index, ignoring transitions to unknown states
+fn adjacency(graph: &StateGraph) -> Vec<Vec<usize>> {
+    let index: AHashMap<&str, usize> =
+        graph.states.iter().enumerate().map(|(i, s)| (s.id.as_str(), i)).collect();
+    let mut edges = vec![Vec::new(); graph.states.len()];
+    for t in &graph.transitions {
+        if let (Some(&from), Some(&to)) = (index.get(t.from.as_str()), index.get(t.to.as_str())) {
+            if !edges[from].contains(&to) {
+                edges[from].push(to);
+            }
+        }
+    }
+    edges
+}
+
+/// Breadth-first distance to the farthest state reachable from `start`
+fn eccentricity(edges: &[Vec<usize>], start: usize) -> usize {
+    let mut distance = vec![usize::MAX; edges.len()];
+    distance[start] = 0;
+    let mut queue = std::collections::VecDeque::from([start]);
+    let mut farthest = 0;
+    while let Some(state) = queue.pop_front() {
+        farthest = farthest.max(distance[state]);
+        for &next in &edges[state] {
+            if distance[next] == usize::MAX {
+                distance[next] = distance[state] + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    farthest
+}
+
+/// Kosaraju's algorithm, iteratively so large site graphs can't overflow
+/// the stack; returns a component number for each state
+fn components(edges: &[Vec<usize>]) -> Vec<usize> {
+    let n = edges.len();
+    let mut reverse = vec![Vec::new(); n];
+    for (from, targets) in edges.iter().enumerate() {
+        for &to in targets {
+            reverse[to].push(from);
+        }
+    }
+
+    // Finish order of a depth-first search
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for root in 0..n {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut stack = vec![(root, 0)];
+        while let Some((state, next)) = stack.last_mut() {
+            match edges[*state].get(*next) {
+                Some(&target) => {
+                    *next += 1;
+                    if !visited[target] {
+                        visited[target] = true;
+                        stack.push((target, 0));
+                    }
+                }
+                None => {
+                    order.push(*state);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    // Sweep the reversed graph in reverse finish order
+    let mut component = vec![usize::MAX; n];
+    let mut count = 0;
+    for &root in order.iter().rev() {
+        if component[root] != usize::MAX {
+            continue;
+        }
+        component[root] = count;
+        let mut stack = vec![root];
+        while let Some(state) = stack.pop() {
+            for &source in &reverse[state] {
+                if component[source] == usize::MAX {
+                    component[source] = count;
+                    stack.push(source);
+                }
+            }
+        }
+        count += 1;
+    }
+    component
+}
+
+impl StateGraph {
+    /// Compute complexity figures for the graph
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <input role="combobox" aria-label="Search" aria-controls="list">
+    ///     <ul id="list" role="listbox"></ul>
+    ///     <a href="/help">Help</a>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let metrics = sdom.state_graph.metrics();
+    ///
+    /// assert_eq!((metrics.state_count, metrics.transition_count), (3, 3));
+    /// assert_eq!(metrics.branching_factor, 1.5);
+    /// assert_eq!(metrics.diameter, 2);
+    /// // Opening and closing the combobox is a cycle
+    /// assert_eq!(metrics.cycles().count(), 1);
+    /// ```
+    pub fn metrics(&self) -> GraphMetrics {
+        let edges = adjacency(self);
+        let branching: Vec<usize> = edges.iter().map(Vec::len).filter(|&n| n > 0).collect();
+
+        let component = components(&edges);
+        let mut sccs: Vec<Vec<String>> = Vec::new();
+        let mut slots: AHashMap<usize, usize> = AHashMap::new();
+        for (state, c) in self.states.iter().zip(&component) {
+            let slot = *slots.entry(*c).or_insert_with(|| {
+                sccs.push(Vec::new());
+                sccs.len() - 1
+            });
+            sccs[slot].push(state.id.clone());
+        }
+
+        GraphMetrics {
+            state_count: self.states.len(),
+            transition_count: self.transitions.len(),
+            branching_factor: match branching.len() {
+                0 => 0.0,
+                n => branching.iter().sum::<usize>() as f64 / n as f64,
+            },
+            diameter: (0..edges.len()).map(|s| eccentricity(&edges, s)).max().unwrap_or(0),
+            strongly_connected_components: sccs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_metrics_of_merged_site_graph() {
+        let home = r#"<nav><a href="/" aria-current="page">Home</a><a href="/docs">Docs</a>
+            <a href="/blog">Blog</a></nav>"#;
+        let docs = r#"<nav><a href="/">Home</a><a href="/docs" aria-current="page">Docs</a>
+            <a href="/docs/api">API</a></nav>"#;
+        let mut graph = SemanticDOM::parse(home, Config::default()).unwrap().state_graph;
+        graph.merge(&SemanticDOM::parse(docs, Config::default()).unwrap().state_graph);
+
+        let metrics = graph.metrics();
+        assert_eq!((metrics.state_count, metrics.transition_count), (4, 4));
+        assert_eq!(metrics.branching_factor, 2.0);
+        // Home -> Docs -> API
+        assert_eq!(metrics.diameter, 2);
+        let cycles: Vec<&Vec<String>> = metrics.cycles().collect();
+        assert_eq!(cycles, [&vec!["state__".to_string(), "state__docs".to_string()]]);
+        assert_eq!(metrics.strongly_connected_components.len(), 3);
+        assert_eq!(StateGraph::new().metrics(), GraphMetrics::default());
+    }
+}
@@ -0,0 +1,129 @@
+//! Versioned serialization of the state graph
+//!
+//! Graphs are stored between runs (crawls, site-level merges), so the JSON
+//! form is a stable schema: field names only change together with
+//! [`STATE_GRAPH_SCHEMA_VERSION`], and loading an older graph upgrades it
+//! one version at a time.
+//!
+//! | Version | Changes |
+//! |---------|---------|
+//! | 1 | `states`, `transitions`, `initial_state`; no `schema_version` field |
+//! | 2 | `schema_version`; optional `anchor` on states, `request` and `params` on transitions |
+//!
+//! State fields are `id`, `name`, `description`, `url_pattern`,
+//! `is_initial`, `is_terminal` and `anchor`; transition fields are `from`,
+//! `to`, `trigger`, `action`, `guard`, `request` and `params`. Optional
+//! fields are left out when empty.
+
+use crate::types::{State, StateGraph, Transition};
+use crate::{Error, Result};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+/// Version of the state graph schema written by this crate
+pub const STATE_GRAPH_SCHEMA_VERSION: u32 = 2;
+
+/// Upgrade steps; `MIGRATIONS[n]` turns a version `n + 1` graph into `n + 2`
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[
+    // Version 2 only added optional fields, which default to empty
+    |_| {},
+];
+
+/// The current schema, as read after migration
+#[derive(Deserialize)]
+struct Stored {
+    states: Vec<State>,
+    transitions: Vec<Transition>,
+    #[serde(default)]
+    initial_state: Option<String>,
+}
+
+/// Upgrade a serialized graph to the current schema
+fn migrate(mut graph: Map<String, Value>) -> std::result::Result<Map<String, Value>, String> {
+    let version = match graph.get("schema_version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("invalid schema_version {}", v))?,
+    };
+    if version > STATE_GRAPH_SCHEMA_VERSION {
+        return Err(format!(
+            "state graph schema version {} is newer than supported version {}",
+            version, STATE_GRAPH_SCHEMA_VERSION
+        ));
+    }
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(&mut graph);
+    }
+    graph.insert("schema_version".to_string(), STATE_GRAPH_SCHEMA_VERSION.into());
+    Ok(graph)
+}
+
+impl<'de> Deserialize<'de> for StateGraph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let graph = match Value::deserialize(deserializer)? {
+            Value::Object(graph) => graph,
+            other => return Err(D::Error::custom(format!("expected a state graph, got {}", other))),
+        };
+        let stored: Stored =
+            serde_json::from_value(Value::Object(migrate(graph).map_err(D::Error::custom)?))
+                .map_err(D::Error::custom)?;
+        Ok(StateGraph {
+            schema_version: STATE_GRAPH_SCHEMA_VERSION,
+            states: stored.states,
+            transitions: stored.transitions,
+            initial_state: stored.initial_state,
+        })
+    }
+}
+
+impl StateGraph {
+    /// Serialize the graph in the current schema
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    /// Load a serialized graph, migrating older schema versions
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{StateGraph, STATE_GRAPH_SCHEMA_VERSION};
+    ///
+    /// // Written before graphs were versioned
+    /// let json = r#"{
+    ///     "states": [{"id": "initial", "name": "Initial", "is_initial": true}],
+    ///     "transitions": [],
+    ///     "initial_state": "initial"
+    /// }"#;
+    /// let graph = StateGraph::from_json(json).unwrap();
+    ///
+    /// assert_eq!(graph.schema_version, STATE_GRAPH_SCHEMA_VERSION);
+    /// assert!(graph.to_json().unwrap().contains(r#""schema_version": 2"#));
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_graph_round_trips_and_rejects_newer_schema() {
+        let html = r##"<nav><a href="/a">A</a><a href="#top">Top</a></nav><h1 id="top">T</h1>"##;
+        let graph = SemanticDOM::parse(html, Config::default()).unwrap().state_graph;
+        let json = graph.to_json().unwrap();
+        let loaded = StateGraph::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+
+        let newer = json.replace(r#""schema_version": 2"#, r#""schema_version": 3"#);
+        let error = StateGraph::from_json(&newer).unwrap_err().to_string();
+        assert!(error.contains("newer than supported version 2"), "{}", error);
+        assert!(StateGraph::from_json(r#"{"schema_version": 0, "states": []}"#).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! GraphQL query layer
+//!
+//! With the `graphql` feature, a parsed document can be queried through an
+//! [`async_graphql`] schema instead of bespoke endpoints: dashboards ask for
+//! exactly the nodes and fields they show. `semantic-dom serve` exposes the
+//! schema over HTTP. The root query type is
+//!
+//! ```graphql
+//! type Query {
+//!   title: String
+//!   lang: String
+//!   url: String
+//!   node(id: ID!): Node
+//!   nodes(role: String, intent: String, first: Int): [Node!]!
+//!   landmarks: [Node!]!
+//!   interactables: [Node!]!
+//!   search(query: String!, first: Int): [SearchHit!]!
+//!   stateGraph: StateGraph!
+//! }
+//! ```
+
+use crate::parser::SemanticDOM;
+use crate::types::{self, SemanticIntent, SemanticRole};
+use crate::{Error, MatchField, Result};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, ID};
+
+/// Schema over one parsed document
+pub type DocumentSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Build the schema for a document
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{graphql, Config, SemanticDOM};
+///
+/// let html = r#"<nav><a href="/docs">Docs</a></nav><main><button>Buy</button></main>"#;
+/// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let schema = graphql::schema(sdom);
+///
+/// let body = r#"{"query": "{ landmarks { role children { label } } }"}"#;
+/// let response = graphql::execute(&schema, body).unwrap();
+/// assert!(response.contains(r#"{"role":"navigation","children":[{"label":"Docs"}]}"#));
+/// ```
+pub fn schema(sdom: SemanticDOM) -> DocumentSchema {
+    async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription).data(sdom).finish()
+}
+
+/// Run a GraphQL request given as its JSON body and return the JSON
+/// response, blocking until it completes
+///
+/// Errors in the query itself are reported inside the response, as
+/// GraphQL clients expect.
+///
+/// # Errors
+/// - [`Error::SerializationError`] if `body` is not a GraphQL request
+pub fn execute(schema: &DocumentSchema, body: &str) -> Result<String> {
+    let request: async_graphql::Request =
+        serde_json::from_str(body).map_err(|e| Error::SerializationError(e.to_string()))?;
+    let response = pollster::block_on(schema.execute(request));
+    serde_json::to_string(&response).map_err(|e| Error::SerializationError(e.to_string()))
+}
+
+fn document<'a>(ctx: &Context<'a>) -> &'a SemanticDOM {
+    ctx.data_unchecked::<SemanticDOM>()
+}
+
+fn nodes_by_id(sdom: &SemanticDOM, ids: &[String]) -> Vec<Node> {
+    ids.iter().filter_map(|id| sdom.get(id)).cloned().map(Node).collect()
+}
+
+/// Root query type
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Page title
+    async fn title<'a>(&self, ctx: &Context<'a>) -> Option<&'a str> {
+        document(ctx).title.as_deref()
+    }
+
+    /// Document language
+    async fn lang<'a>(&self, ctx: &Context<'a>) -> Option<&'a str> {
+        document(ctx).lang.as_deref()
+    }
+
+    /// Absolute URL of the page
+    async fn url<'a>(&self, ctx: &Context<'a>) -> Option<&'a str> {
+        document(ctx).base_url.as_deref()
+    }
+
+    /// A node by ID
+    async fn node(&self, ctx: &Context<'_>, id: ID) -> Option<Node> {
+        document(ctx).get(&id).cloned().map(Node)
+    }
+
+    /// Nodes in reading order, optionally only those with a role or intent
+    async fn nodes(
+        &self,
+        ctx: &Context<'_>,
+        role: Option<String>,
+        intent: Option<String>,
+        first: Option<usize>,
+    ) -> async_graphql::Result<Vec<Node>> {
+        let sdom = document(ctx);
+        let role = role.map(|r| r.parse::<SemanticRole>()).transpose()?;
+        let intent = intent.map(|i| i.parse::<SemanticIntent>()).transpose()?;
+        Ok(sdom
+            .reading_order()
+            .into_iter()
+            .filter(|n| role.as_ref().map_or(true, |r| n.role == *r))
+            .filter(|n| intent.is_none() || n.intent == intent)
+            .take(first.unwrap_or(usize::MAX))
+            .cloned()
+            .map(Node)
+            .collect())
+    }
+
+    /// Landmark nodes
+    async fn landmarks(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let sdom = document(ctx);
+        nodes_by_id(sdom, &sdom.landmarks)
+    }
+
+    /// Nodes an agent can act on
+    async fn interactables(&self, ctx: &Context<'_>) -> Vec<Node> {
+        let sdom = document(ctx);
+        nodes_by_id(sdom, &sdom.interactables)
+    }
+
+    /// Nodes containing every word of `query`, best first
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<usize>,
+    ) -> Vec<SearchHit> {
+        let sdom = document(ctx);
+        sdom.search(&query)
+            .into_iter()
+            .take(first.unwrap_or(usize::MAX))
+            .filter_map(|hit| {
+                let node = sdom.get(&hit.id)?.clone();
+                Some(SearchHit { node: Node(node), hit })
+            })
+            .collect()
+    }
+
+    /// Navigation states and transitions
+    async fn state_graph(&self) -> StateGraph {
+        StateGraph
+    }
+}
+
+/// A semantic node
+pub struct Node(types::SemanticNode);
+
+#[Object]
+impl Node {
+    /// Node ID
+    async fn id(&self) -> ID {
+        ID(self.0.id.clone())
+    }
+
+    /// Human-readable label
+    async fn label(&self) -> &str {
+        &self.0.label
+    }
+
+    /// Canonical role name
+    async fn role(&self) -> &str {
+        self.0.role.as_str()
+    }
+
+    /// Canonical intent name
+    async fn intent(&self) -> Option<&str> {
+        self.0.intent.as_ref().map(SemanticIntent::as_str)
+    }
+
+    /// CSS selector
+    async fn selector(&self) -> &str {
+        &self.0.selector
+    }
+
+    /// Accessible name
+    async fn accessible_name(&self) -> Option<&str> {
+        self.0.accessible_name.as_deref()
+    }
+
+    /// Link target
+    async fn href(&self) -> Option<&str> {
+        self.0.href.as_deref()
+    }
+
+    /// Heading level
+    async fn level(&self) -> Option<u8> {
+        self.0.level
+    }
+
+    /// Depth in the tree
+    async fn depth(&self) -> usize {
+        self.0.depth
+    }
+
+    /// Parent node
+    async fn parent(&self, ctx: &Context<'_>) -> Option<Node> {
+        let parent = self.0.parent.as_deref()?;
+        document(ctx).get(parent).cloned().map(Node)
+    }
+
+    /// Child nodes
+    async fn children(&self, ctx: &Context<'_>) -> Vec<Node> {
+        nodes_by_id(document(ctx), &self.0.children)
+    }
+}
+
+/// A node matching a search
+pub struct SearchHit {
+    node: Node,
+    hit: crate::SearchHit,
+}
+
+#[Object]
+impl SearchHit {
+    /// The matching node
+    async fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// `label` or `accessible_name`
+    async fn field(&self) -> &str {
+        match self.hit.field {
+            MatchField::Label => "label",
+            MatchField::AccessibleName => "accessible_name",
+        }
+    }
+
+    /// Higher is better
+    async fn score(&self) -> f32 {
+        self.hit.score
+    }
+
+    /// The matched text with some context
+    async fn snippet(&self) -> &str {
+        &self.hit.snippet
+    }
+}
+
+/// The document's state graph
+pub struct StateGraph;
+
+#[Object]
+impl StateGraph {
+    /// ID of the initial state
+    async fn initial_state<'a>(&self, ctx: &Context<'a>) -> Option<&'a str> {
+        document(ctx).state_graph.initial_state.as_deref()
+    }
+
+    /// States
+    async fn states(&self, ctx: &Context<'_>) -> Vec<State> {
+        document(ctx).state_graph.states.iter().cloned().map(State).collect()
+    }
+
+    /// Transitions
+    async fn transitions(&self, ctx: &Context<'_>) -> Vec<Transition> {
+        document(ctx).state_graph.transitions.iter().cloned().map(Transition).collect()
+    }
+}
+
+/// A navigation state
+pub struct State(types::State);
+
+#[Object]
+impl State {
+    /// State ID
+    async fn id(&self) -> ID {
+        ID(self.0.id.clone())
+    }
+
+    /// Name
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// URL pattern
+    async fn url_pattern(&self) -> Option<&str> {
+        self.0.url_pattern.as_deref()
+    }
+
+    /// Whether this is the initial state
+    async fn is_initial(&self) -> bool {
+        self.0.is_initial
+    }
+
+    /// Whether this is a terminal state
+    async fn is_terminal(&self) -> bool {
+        self.0.is_terminal
+    }
+}
+
+/// A transition between states
+pub struct Transition(types::Transition);
+
+#[Object]
+impl Transition {
+    /// Source state ID
+    async fn from(&self) -> &str {
+        &self.0.from
+    }
+
+    /// Target state ID
+    async fn to(&self) -> &str {
+        &self.0.to
+    }
+
+    /// The node that triggers it
+    async fn trigger(&self, ctx: &Context<'_>) -> Option<Node> {
+        document(ctx).get(&self.0.trigger).cloned().map(Node)
+    }
+
+    /// Action to perform
+    async fn action(&self) -> Option<&str> {
+        self.0.action.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_queries_and_errors() {
+        let html = r#"<nav><a href="/about">About us</a><a href="/faq">FAQ</a></nav>
+            <main><h1>Welcome</h1><p>Read about our shipping policy.</p>
+            <button data-agent-intent="submit">Send</button></main>"#;
+        let schema = schema(SemanticDOM::parse(html, Config::default()).unwrap());
+        let run = |query: &str| {
+            let body = serde_json::json!({ "query": query }).to_string();
+            let response: serde_json::Value =
+                serde_json::from_str(&execute(&schema, &body).unwrap()).unwrap();
+            response
+        };
+
+        let response = run(r#"{ nodes(role: "link", first: 1) { label parent { role } } }"#);
+        let link = serde_json::json!({ "label": "About us", "parent": { "role": "navigation" } });
+        assert_eq!(response["data"]["nodes"], serde_json::json!([link]));
+
+        let response = run(r#"{ search(query: "shipping") { field node { role } } }"#);
+        assert_eq!(response["data"]["search"][0]["node"]["role"], "main");
+
+        let response = run("{ stateGraph { initialState transitions { to trigger { label } } } }");
+        let graph = &response["data"]["stateGraph"];
+        assert_eq!(graph["initialState"], "initial");
+        let about = serde_json::json!({ "to": "state__about", "trigger": { "label": "About us" } });
+        assert_eq!(graph["transitions"][0], about);
+
+        let response = run(r#"{ nodes(role: "carousel") { id } }"#);
+        assert!(response["errors"][0]["message"].as_str().unwrap().contains("carousel"));
+        assert!(execute(&schema, "not json").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Inline event handlers and non-semantic controls
+//!
+//! Handler attributes (`onclick`, `onsubmit`, ...) are recorded by event
+//! name, never evaluated. Elements with no semantic role but a click-like
+//! handler, a `tabindex`, or a pointer cursor become
+//! [`SemanticRole::Interactive`] nodes, since they are click targets that
+//! would otherwise be invisible to agents. Controls built from generic
+//! elements are flagged with `nonstandard`.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use scraper::node::Element;
+
+/// Events that make an element a click target
+const CLICK_EVENTS: &[&str] = &[
+    "click", "dblclick", "mousedown", "mouseup", "pointerdown", "pointerup", "touchstart",
+    "touchend",
+];
+
+/// Elements that are interactive without any ARIA role
+const NATIVE_CONTROLS: &[&str] = &[
+    "a", "button", "input", "select", "textarea", "summary", "option", "label", "area",
+];
+
+/// Event names of the inline handlers on an element, in attribute order
+pub(crate) fn event_handlers(el: &Element) -> Vec<String> {
+    el.attrs()
+        .filter_map(|(name, _)| name.strip_prefix("on"))
+        .filter(|event| !event.is_empty() && event.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
+/// Check whether an element has an inline click-like handler
+pub(crate) fn has_click_handler(el: &Element) -> bool {
+    CLICK_EVENTS
+        .iter()
+        .any(|event| el.attr(&format!("on{}", event)).is_some())
+}
+
+/// Check whether a generic element looks clickable: a click handler, or a
+/// `div`/`span` made focusable or given a pointer cursor
+pub(crate) fn looks_clickable(el: &Element) -> bool {
+    if has_click_handler(el) {
+        return true;
+    }
+    if !matches!(el.name(), "div" | "span") {
+        return false;
+    }
+    let focusable = el
+        .attr("tabindex")
+        .and_then(|t| t.trim().parse::<i32>().ok())
+        .is_some_and(|t| t >= 0);
+    let pointer = el
+        .attr("style")
+        .is_some_and(|s| s.replace(' ', "").to_ascii_lowercase().contains("cursor:pointer"));
+    focusable || pointer
+}
+
+/// Check whether an interactive role sits on an element that is not a
+/// native control (`<div role="button">`, `<span onclick>`, ...)
+pub(crate) fn is_nonstandard_control(el: &Element, role: &SemanticRole) -> bool {
+    role.is_interactable() && !NATIVE_CONTROLS.contains(&el.name())
+}
+
+impl SemanticDOM {
+    /// Get nodes that are click targets only because of an inline handler
+    ///
+    /// These are `<div onclick>`-style controls: keyboard and assistive
+    /// technology users usually cannot reach them.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticRole};
+    ///
+    /// let html = r#"<main><div onclick="openCart()">Cart</div><button onclick="go()">Go</button></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let targets = sdom.handler_only_targets();
+    /// assert_eq!(targets.len(), 1);
+    /// assert_eq!(targets[0].role, SemanticRole::Interactive);
+    /// assert_eq!(targets[0].event_handlers, ["click"]);
+    /// ```
+    pub fn handler_only_targets(&self) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| {
+                matches!(n.role, SemanticRole::Interactive)
+                    && n.event_handlers.iter().any(|e| CLICK_EVENTS.contains(&e.as_str()))
+            })
+            .collect()
+    }
+
+    /// Get interactive nodes built from generic elements, in document order
+    ///
+    /// Includes handler-only targets, focusable or pointer-cursor `div`s and
+    /// `span`s, and generic elements given an interactive ARIA role. These
+    /// often lack keyboard support, so agents should prefer native controls.
+    pub fn nonstandard_controls(&self) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| n.nonstandard)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AgentCertification, Config, SemanticDOM};
+
+    #[test]
+    fn test_handlers_recorded_and_check_fails() {
+        let html = r#"<form onsubmit="return check()">
+            <input name="q" onchange="suggest()" oninput="x()">
+            <span onmousedown="pick(1)">Pick</span>
+        </form>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let input = sdom.index.values().find(|n| n.input_type.is_some()).unwrap();
+        assert_eq!(input.event_handlers, ["change", "input"]);
+        assert_eq!(sdom.handler_only_targets()[0].label, "Pick");
+
+        let cert = AgentCertification::certify(&sdom);
+        let check = cert.checks.iter().find(|c| c.id == "A11Y-006").unwrap();
+        assert!(!check.passed);
+    }
+
+    #[test]
+    fn test_clickable_generic_elements() {
+        let html = r#"<main>
+            <div tabindex="0">Menu</div>
+            <span style="cursor: pointer">More</span>
+            <div role="button">Close</div>
+            <div tabindex="-1">Not focusable by tab</div>
+            <button>Native</button>
+        </main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let labels: Vec<&str> =
+            sdom.nonstandard_controls().iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, ["Menu", "More", "Close"]);
+        assert!(sdom.handler_only_targets().is_empty());
+        assert_eq!(sdom.nonstandard_controls()[2].role, crate::SemanticRole::Button);
+    }
+}
@@ -0,0 +1,264 @@
+//! Score history across releases
+//!
+//! Certifying the same pages on every release shows whether agent
+//! readiness improves or regresses. [`HistoryStore`] appends one
+//! [`ScoreRecord`] per page and run to a JSON Lines file, which is easy to
+//! commit, diff and load elsewhere, and summarizes the trend of each URL.
+
+use crate::certification::{AgentCertification, CertificationLevel};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Certification of one page at one point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreRecord {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// The certified page
+    pub url: String,
+    /// Numeric score (0-100)
+    pub score: u32,
+    /// Certification level
+    pub level: CertificationLevel,
+    /// IDs of the failed checks, without waived ones
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub failures: Vec<String>,
+}
+
+impl ScoreRecord {
+    /// Record a certification of `url` made now
+    pub fn new(url: impl Into<String>, certification: &AgentCertification) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        ScoreRecord {
+            timestamp,
+            url: url.into(),
+            score: certification.score,
+            level: certification.level,
+            failures: certification
+                .checks
+                .iter()
+                .filter(|c| c.is_failure())
+                .map(|c| c.id.clone())
+                .collect(),
+        }
+    }
+}
+
+/// How one URL's score developed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreTrend {
+    /// The page
+    pub url: String,
+    /// Records of the page
+    pub runs: usize,
+    /// Score of the oldest record
+    pub first_score: u32,
+    /// Score of the record before the latest, if any
+    pub previous_score: Option<u32>,
+    /// Score of the latest record
+    pub latest_score: u32,
+    /// Highest score recorded
+    pub best_score: u32,
+    /// Level of the latest record
+    pub latest_level: CertificationLevel,
+    /// Checks failing now that passed in the previous run
+    pub new_failures: Vec<String>,
+    /// Checks passing now that failed in the previous run
+    pub fixed_failures: Vec<String>,
+}
+
+impl ScoreTrend {
+    /// Score change since the previous run, 0 for a single run
+    pub fn change(&self) -> i64 {
+        i64::from(self.latest_score) - i64::from(self.previous_score.unwrap_or(self.latest_score))
+    }
+}
+
+/// Append-only JSON Lines file of [`ScoreRecord`]s
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// Use the file at `path`; it is created on the first append
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        HistoryStore { path: path.into() }
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record
+    ///
+    /// # Errors
+    /// - [`Error::IoError`] if the file cannot be written
+    pub fn append(&self, record: &ScoreRecord) -> Result<()> {
+        let mut line =
+            serde_json::to_string(record).map_err(|e| Error::SerializationError(e.to_string()))?;
+        line.push('\n');
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// All records in the order they were appended; none if the file does
+    /// not exist yet
+    ///
+    /// # Errors
+    /// - [`Error::IoError`] if the file cannot be read
+    /// - [`Error::SerializationError`] for a line that is not a record
+    pub fn records(&self) -> Result<Vec<ScoreRecord>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| Error::SerializationError(format!("line {}: {}", i + 1, e)))
+            })
+            .collect()
+    }
+
+    /// Trend of every URL, in order of first appearance
+    ///
+    /// Records are ordered by timestamp, so runs appended out of order
+    /// still compare the latest with the one before it.
+    ///
+    /// # Errors
+    /// - as [`Self::records`]
+    pub fn trends(&self) -> Result<Vec<ScoreTrend>> {
+        let mut by_url: Vec<(String, Vec<ScoreRecord>)> = Vec::new();
+        for record in self.records()? {
+            match by_url.iter_mut().find(|(url, _)| *url == record.url) {
+                Some((_, runs)) => runs.push(record),
+                None => by_url.push((record.url.clone(), vec![record])),
+            }
+        }
+
+        let mut trends = Vec::with_capacity(by_url.len());
+        for (url, mut runs) in by_url {
+            runs.sort_by_key(|r| r.timestamp);
+            let (Some(first), Some(latest)) = (runs.first(), runs.last()) else {
+                continue;
+            };
+            let previous = runs.len().checked_sub(2).map(|i| &runs[i]);
+            let diff = |a: &ScoreRecord, b: &ScoreRecord| -> Vec<String> {
+                a.failures.iter().filter(|f| !b.failures.contains(f)).cloned().collect()
+            };
+            trends.push(ScoreTrend {
+                url,
+                runs: runs.len(),
+                first_score: first.score,
+                previous_score: previous.map(|p| p.score),
+                latest_score: latest.score,
+                best_score: runs.iter().map(|r| r.score).max().unwrap_or(latest.score),
+                latest_level: latest.level,
+                new_failures: previous.map(|p| diff(latest, p)).unwrap_or_default(),
+                fixed_failures: previous.map(|p| diff(p, latest)).unwrap_or_default(),
+            });
+        }
+        Ok(trends)
+    }
+
+    /// Text summary with one block per URL
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use semantic_dom_ssg::{Config, HistoryStore, ScoreRecord, SemanticDOM};
+    ///
+    /// let html = std::fs::read_to_string("dist/index.html").unwrap();
+    /// let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+    ///
+    /// let store = HistoryStore::new("agent-readiness.jsonl");
+    /// store.append(&ScoreRecord::new("/", &sdom.certify())).unwrap();
+    /// println!("{}", store.summary().unwrap());
+    /// // / 85 Level AA (+13 since last run, best 85, 4 runs)
+    /// //   fixed: STRUCT-002
+    /// ```
+    ///
+    /// # Errors
+    /// - as [`Self::records`]
+    pub fn summary(&self) -> Result<String> {
+        let mut out = String::new();
+        for trend in self.trends()? {
+            let level = trend.latest_level.name();
+            let _ = write!(out, "{} {} {}", trend.url, trend.latest_score, level);
+            if trend.runs > 1 {
+                let _ = write!(
+                    out,
+                    " ({:+} since last run, best {}, {} runs)",
+                    trend.change(),
+                    trend.best_score,
+                    trend.runs
+                );
+            }
+            out.push('\n');
+            if !trend.fixed_failures.is_empty() {
+                let _ = writeln!(out, "  fixed: {}", trend.fixed_failures.join(", "));
+            }
+            if !trend.new_failures.is_empty() {
+                let _ = writeln!(out, "  new: {}", trend.new_failures.join(", "));
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: u64, url: &str, score: u32, failures: &[&str]) -> ScoreRecord {
+        ScoreRecord {
+            timestamp,
+            url: url.to_string(),
+            score,
+            level: if score >= 80 { CertificationLevel::AA } else { CertificationLevel::A },
+            failures: failures.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_append_and_trends() {
+        let path = std::env::temp_dir().join(format!("sdom-history-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = HistoryStore::new(&path);
+        assert!(store.records().unwrap().is_empty());
+
+        store.append(&record(100, "/", 60, &["STRUCT-002", "A11Y-001"])).unwrap();
+        store.append(&record(100, "/docs", 90, &[])).unwrap();
+        // Appended late, but the run before the latest
+        store.append(&record(300, "/", 85, &["NAV-001"])).unwrap();
+        store.append(&record(200, "/", 72, &["STRUCT-002"])).unwrap();
+        assert_eq!(store.records().unwrap().len(), 4);
+
+        let trends = store.trends().unwrap();
+        let home = &trends[0];
+        assert_eq!((home.runs, home.first_score, home.latest_score), (3, 60, 85));
+        assert_eq!((home.previous_score, home.change()), (Some(72), 13));
+        assert_eq!(home.fixed_failures, ["STRUCT-002"]);
+        assert_eq!(home.new_failures, ["NAV-001"]);
+
+        let summary = store.summary().unwrap();
+        let expected = "/ 85 Level AA (+13 since last run, best 85, 3 runs)\n  \
+            fixed: STRUCT-002\n  new: NAV-001\n/docs 90 Level AA\n";
+        assert_eq!(summary, expected);
+
+        fs::write(&path, "{\"timestamp\": 1}\n").unwrap();
+        let err = store.records().unwrap_err().to_string();
+        assert!(err.contains("line 1"), "{}", err);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+//! Landmarks inferred from layout
+//!
+//! Many pages are built from plain `div`s and declare no landmarks at all,
+//! leaving agents without orientation. With
+//! [`Config::infer_landmarks`](crate::Config::infer_landmarks), a page
+//! without a main or navigation landmark gets one promoted from a plain
+//! container: navigation from a container of mostly links, main from the
+//! container under a heading that holds most of the page's text. Inferred
+//! nodes are flagged [`inferred`](crate::SemanticNode::inferred), and
+//! certification still reports the declared landmark as missing.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+
+/// Confidence in an inferred role
+pub(crate) const INFERRED_CONFIDENCE: f32 = 0.5;
+
+/// Fewest links in an inferred navigation
+const MIN_NAV_LINKS: usize = 3;
+
+/// Least share of a navigation's text inside links
+const NAV_LINK_DENSITY: f32 = 0.7;
+
+/// Least text outside links in an inferred main region, in characters
+const MIN_MAIN_CHARS: usize = 200;
+
+/// Most share of a main region's text inside links
+const MAX_MAIN_LINK_DENSITY: f32 = 0.5;
+
+/// Share of the largest candidate's text a nested main region must keep
+const MAIN_TEXT_SHARE: f32 = 0.75;
+
+/// Text and links in an element's subtree
+#[derive(Default, Clone, Copy)]
+struct Stats {
+    text: usize,
+    link_text: usize,
+    links: usize,
+    headings: usize,
+    landmarks: usize,
+}
+
+impl Stats {
+    fn add(&mut self, other: Stats) {
+        self.text += other.text;
+        self.link_text += other.link_text;
+        self.links += other.links;
+        self.headings += other.headings;
+        self.landmarks += other.landmarks;
+    }
+
+    fn link_density(&self) -> f32 {
+        self.link_text as f32 / self.text.max(1) as f32
+    }
+}
+
+/// An element visited in document order
+struct Visited<'a> {
+    element: ElementRef<'a>,
+    parent: Option<usize>,
+    depth: usize,
+    /// Whether the element is a plain container that may become a landmark
+    /// (outside declared ones, holding none)
+    candidate: bool,
+    stats: Stats,
+}
+
+/// The landmark role an element declares, by tag or `role`
+fn declared_landmark(element: ElementRef) -> Option<SemanticRole> {
+    let el = element.value();
+    let role = match el.attr("role").map(str::trim) {
+        Some("banner") => SemanticRole::Header,
+        Some("contentinfo") => SemanticRole::Footer,
+        Some("complementary") => SemanticRole::Aside,
+        Some(role) => role.parse().ok()?,
+        None => match el.name() {
+            "main" => SemanticRole::Main,
+            "nav" => SemanticRole::Navigation,
+            "header" => SemanticRole::Header,
+            "footer" => SemanticRole::Footer,
+            "aside" => SemanticRole::Aside,
+            "search" => SemanticRole::Search,
+            "section" if el.attr("aria-label").or(el.attr("aria-labelledby")).is_some() => {
+                SemanticRole::Region
+            }
+            _ => return None,
+        },
+    };
+    role.is_landmark().then_some(role)
+}
+
+/// Find plain containers to promote to main and navigation landmarks
+///
+/// Navigation is inferred only when the page declares none, as is main.
+/// Containers inside declared landmarks are left alone.
+pub(crate) fn infer_landmarks(document: &Html) -> AHashMap<NodeId, SemanticRole> {
+    let mut visited: Vec<Visited> = Vec::new();
+    let (mut has_main, mut has_nav) = (false, false);
+    // Element, its parent's index, and whether it is in a landmark or a link
+    let mut stack = vec![(document.root_element(), None, false, false)];
+    while let Some((element, parent, in_landmark, in_link)) = stack.pop() {
+        let el = element.value();
+        if matches!(el.name(), "script" | "style" | "template" | "noscript") {
+            continue;
+        }
+        let declared = declared_landmark(element);
+        has_main |= declared == Some(SemanticRole::Main);
+        has_nav |= declared == Some(SemanticRole::Navigation);
+        let link = el.name() == "a" && el.attr("href").is_some();
+        let mut stats = Stats {
+            links: link as usize,
+            headings: matches!(el.name(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6") as usize,
+            landmarks: declared.is_some() as usize,
+            ..Stats::default()
+        };
+        for text in element.children().filter_map(|c| c.value().as_text()) {
+            let chars: usize = text.split_whitespace().map(|w| w.chars().count() + 1).sum();
+            stats.text += chars;
+            if in_link || link {
+                stats.link_text += chars;
+            }
+        }
+        let index = visited.len();
+        visited.push(Visited {
+            element,
+            parent,
+            depth: parent.map_or(0, |p| visited[p].depth + 1),
+            candidate: !in_landmark
+                && el.name() == "div"
+                && el.attr("role").is_none()
+                && el.attr("data-agent-role").is_none(),
+            stats,
+        });
+        let children: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+        for child in children.into_iter().rev() {
+            stack.push((child, Some(index), in_landmark || declared.is_some(), in_link || link));
+        }
+    }
+    // Children come after their parents, so sums run backwards
+    for i in (0..visited.len()).rev() {
+        if let Some(parent) = visited[i].parent {
+            let stats = visited[i].stats;
+            visited[parent].stats.add(stats);
+        }
+    }
+
+    for v in &mut visited {
+        v.candidate &= v.stats.landmarks == 0;
+    }
+
+    let mut inferred = AHashMap::new();
+    // Navigation: the innermost containers made of links
+    let mut holds_nav = vec![false; visited.len()];
+    if !has_nav {
+        for i in (0..visited.len()).rev() {
+            let v = &visited[i];
+            let nav_like = v.candidate
+                && v.stats.links >= MIN_NAV_LINKS
+                && v.stats.link_density() >= NAV_LINK_DENSITY;
+            if nav_like && !holds_nav[i] {
+                inferred.insert(v.element.id(), SemanticRole::Navigation);
+            }
+            if let Some(parent) = v.parent {
+                holds_nav[parent] |= holds_nav[i] || nav_like;
+            }
+        }
+    }
+    // Main: the innermost container under a heading keeping most of the
+    // text of the largest, and holding no navigation
+    if !has_main {
+        let prose = |v: &Visited| v.stats.text - v.stats.link_text;
+        let candidates: Vec<&Visited> = visited
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| {
+                v.candidate
+                    && !holds_nav[*i]
+                    && !inferred.contains_key(&v.element.id())
+                    && v.stats.headings > 0
+                    && v.stats.link_density() < MAX_MAIN_LINK_DENSITY
+                    && prose(v) >= MIN_MAIN_CHARS
+            })
+            .map(|(_, v)| v)
+            .collect();
+        let largest = candidates.iter().map(|v| prose(v)).max().unwrap_or(0);
+        let main = candidates
+            .into_iter()
+            .filter(|v| prose(v) as f32 >= largest as f32 * MAIN_TEXT_SHARE)
+            .max_by_key(|v| (v.depth, prose(v)));
+        if let Some(main) = main {
+            inferred.insert(main.element.id(), SemanticRole::Main);
+        }
+    }
+    inferred
+}
+
+impl SemanticDOM {
+    /// Get the landmarks inferred by
+    /// [`Config::infer_landmarks`](crate::Config::infer_landmarks)
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticRole};
+    ///
+    /// let config = Config::builder().infer_landmarks(true).build().unwrap();
+    /// let story = "The river rose overnight and the old bridge held. ".repeat(5);
+    /// let html = format!(
+    ///     r#"<div id="top"><a href="/">Home</a><a href="/news">News</a>
+    ///     <a href="/about">About</a></div>
+    ///     <div id="story"><h1>Flood</h1><p>{story}</p></div>"#
+    /// );
+    /// let sdom = SemanticDOM::parse(&html, config).unwrap();
+    ///
+    /// let inferred = sdom.inferred_landmarks();
+    /// assert_eq!(inferred[0].role, SemanticRole::Navigation);
+    /// assert_eq!(inferred[0].selector, "div#top");
+    /// assert_eq!(inferred[1].role, SemanticRole::Main);
+    /// assert_eq!(inferred[1].selector, "div#story");
+    /// ```
+    pub fn inferred_landmarks(&self) -> Vec<&SemanticNode> {
+        self.get_landmarks().into_iter().filter(|n| n.inferred).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_inferred_landmarks() {
+        let prose = "<p>Orders ship within two days of payment. ".repeat(6);
+        let html = format!(
+            r#"<div id="page"><div class="top"><a href="/">Shop</a>
+              <div class="menu"><a href="/new">New</a> <a href="/sale">Sale</a>
+              <a href="/help">Help</a></div></div>
+            <div class="body"><div class="content"><h1>Shipping</h1>{prose}
+              <a href="/returns">Returns</a></div>
+            <div class="side"><h2>Tip</h2><p>Order early.</p></div></div></div>"#
+        );
+        let config = Config::builder().infer_landmarks(true).build().unwrap();
+        let sdom = SemanticDOM::parse(&html, config.clone()).unwrap();
+        let inferred: Vec<(&SemanticRole, &str)> = sdom
+            .inferred_landmarks()
+            .iter()
+            .map(|n| (&n.role, n.selector.as_str()))
+            .collect();
+        assert_eq!(
+            inferred,
+            [(&SemanticRole::Navigation, "div.menu"), (&SemanticRole::Main, "div.content")]
+        );
+        assert!(sdom.inferred_landmarks().iter().all(|n| n.role_confidence < 1.0));
+        assert!(sdom.to_agent_summary().contains("LANDMARKS: nav?(div.menu), main?(div.content)"));
+
+        // Certification tells inferred landmarks from declared ones
+        let report = sdom.certification.as_ref().unwrap();
+        let check = report.checks.iter().find(|c| c.id == "STRUCT-001").unwrap();
+        assert!(!check.passed);
+        assert_eq!(check.details.as_deref(), Some("Found 0 landmarks (2 inferred)"));
+
+        // Declared landmarks are not second-guessed
+        let declared = html.replace(r#"<div class="menu">"#, "<nav>").replace(
+            r#"<a href="/help">Help</a></div>"#,
+            r#"<a href="/help">Help</a></nav>"#,
+        );
+        let sdom = SemanticDOM::parse(&declared, config).unwrap();
+        let roles: Vec<&SemanticRole> =
+            sdom.inferred_landmarks().iter().map(|n| &n.role).collect();
+        assert_eq!(roles, [&SemanticRole::Main]);
+
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        assert!(sdom.landmarks.is_empty());
+    }
+}
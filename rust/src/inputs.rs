@@ -0,0 +1,143 @@
+//! Typed form inputs
+//!
+//! An agent filling a form needs to know more than that a field takes
+//! text: a date field wants `YYYY-MM-DD`, a code may be capped at six
+//! characters, a field may be required. Inputs keep their `type` in
+//! [`SemanticNode::input_type`](crate::SemanticNode::input_type) and their
+//! validation attributes in
+//! [`SemanticNode::constraints`](crate::SemanticNode::constraints).
+//! Summaries tag typed fields, as in `[inp:date]Check-in*` for a required
+//! date.
+
+use crate::types::{InputConstraints, SemanticRole};
+use scraper::node::Element;
+
+/// Format values of an input type are written in, where it implies one
+pub(crate) fn value_format(input_type: &str) -> Option<&'static str> {
+    match input_type {
+        "date" => Some("YYYY-MM-DD"),
+        "time" => Some("HH:MM"),
+        "datetime-local" => Some("YYYY-MM-DDTHH:MM"),
+        "month" => Some("YYYY-MM"),
+        "week" => Some("YYYY-Www"),
+        "color" => Some("#rrggbb"),
+        "email" => Some("name@host"),
+        "url" => Some("scheme://host/path"),
+        _ => None,
+    }
+}
+
+/// Short name of an input type in summaries
+///
+/// Plain text fields have none, nor do email and file fields, whose
+/// intents say as much, or number and range inputs, which show their
+/// value instead.
+pub(crate) fn type_abbrev(input_type: &str) -> Option<&'static str> {
+    match input_type {
+        "date" => Some("date"),
+        "time" => Some("time"),
+        "datetime-local" => Some("datetime"),
+        "month" => Some("month"),
+        "week" => Some("week"),
+        "color" => Some("color"),
+        "tel" => Some("tel"),
+        "url" => Some("url"),
+        "password" => Some("pwd"),
+        _ => None,
+    }
+}
+
+/// Validation constraints of an `input`, `textarea` or `select`
+///
+/// Returns `None` for elements without any.
+pub(crate) fn input_constraints(
+    el: &Element,
+    role: &SemanticRole,
+    input_type: Option<&str>,
+) -> Option<InputConstraints> {
+    let attr = |name: &str| {
+        el.attr(name)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let length = |name: &str| el.attr(name).and_then(|v| v.trim().parse::<u32>().ok());
+    // Sliders and spin buttons carry their bounds as a range value
+    let ranged = matches!(role, SemanticRole::Slider | SemanticRole::SpinButton);
+    let constraints = InputConstraints {
+        required: el.attr("required").is_some() || el.attr("aria-required") == Some("true"),
+        format: input_type.and_then(value_format).map(str::to_string),
+        min: attr("min").filter(|_| !ranged),
+        max: attr("max").filter(|_| !ranged),
+        step: attr("step").filter(|_| !ranged),
+        min_length: length("minlength"),
+        max_length: length("maxlength"),
+        pattern: attr("pattern"),
+        accept: el
+            .attr("accept")
+            .filter(|_| input_type == Some("file"))
+            .map(|a| {
+                a.split(',')
+                    .map(|t| t.trim().to_ascii_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        multiple: el.attr("multiple").is_some(),
+    };
+    (constraints != InputConstraints::default()).then_some(constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM, SemanticIntent, SemanticRole};
+
+    #[test]
+    fn test_typed_inputs() {
+        let html = r#"<form action="/book">
+            <input type="date" name="from" aria-label="Check-in" min="2026-01-01" required>
+            <input type="TIME" name="at" aria-label="Arrival">
+            <input type="url" name="site" aria-label="Website" pattern="https://.*">
+            <input type="color" name="theme" aria-label="Theme">
+            <input type="number" name="guests" aria-label="Guests" min="1" max="8" required>
+            <input type="file" name="id" aria-label="ID scan">
+            <input name="code" aria-label="Code" minlength="6" maxlength="6">
+            <input type="image" src="go.png" alt="Book"></form>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let fields: Vec<(&str, &SemanticRole, Option<&str>)> = sdom
+            .get_interactables()
+            .iter()
+            .map(|n| (n.label.as_str(), &n.role, n.input_type.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("Check-in", &SemanticRole::TextInput, Some("date")),
+                ("Arrival", &SemanticRole::TextInput, Some("time")),
+                ("Website", &SemanticRole::TextInput, Some("url")),
+                ("Theme", &SemanticRole::TextInput, Some("color")),
+                ("Guests", &SemanticRole::SpinButton, Some("number")),
+                ("ID scan", &SemanticRole::Button, Some("file")),
+                ("Code", &SemanticRole::TextInput, Some("text")),
+                ("Book", &SemanticRole::Button, Some("image")),
+            ]
+        );
+
+        let by_label = |label: &str| sdom.index.values().find(|n| n.label == label).unwrap();
+        let date = by_label("Check-in").constraints.clone().unwrap();
+        assert!(date.required);
+        assert_eq!(date.format.as_deref(), Some("YYYY-MM-DD"));
+        assert_eq!(date.min.as_deref(), Some("2026-01-01"));
+        let code = by_label("Code").constraints.clone().unwrap();
+        assert_eq!((code.min_length, code.max_length), (Some(6), Some(6)));
+        // Numeric bounds stay in the range value
+        let guests = by_label("Guests");
+        assert_eq!(guests.constraints.as_ref().unwrap().min, None);
+        assert_eq!(guests.range.as_ref().unwrap().max, Some(8.0));
+        assert_eq!(by_label("Book").intent, Some(SemanticIntent::Submit));
+
+        let summary = sdom.to_agent_summary();
+        assert!(summary.contains("ACTIONS: [inp:date]Check-in*, [inp:time]Arrival"), "{}", summary);
+        assert!(summary.contains(", [inp]Guests*, [up]ID scan"), "{}", summary);
+    }
+}
@@ -0,0 +1,437 @@
+//! Intent keyword dictionaries and pluggable intent classification
+//!
+//! Button intents are inferred from keyword tables for English, Spanish,
+//! German, French, Portuguese, and Japanese, and icon-only buttons from the
+//! names of their icons. An [`IntentClassifier`]
+//! registered on the [`Config`](crate::Config) can extend or replace
+//! these heuristics for other languages or site-specific vocabulary.
+
+use crate::types::{SemanticIntent, SemanticRole};
+
+/// Keyword table: intents checked in order, first substring match wins
+type KeywordTable = &'static [(SemanticIntent, &'static [&'static str])];
+
+const EN: KeywordTable = &[
+    (SemanticIntent::Submit, &["submit", "send"]),
+    (SemanticIntent::Upload, &["upload", "attach"]),
+    (SemanticIntent::Print, &["print"]),
+    (SemanticIntent::Share, &["share"]),
+    (SemanticIntent::Copy, &["copy"]),
+    (SemanticIntent::Bookmark, &["bookmark", "favorite", "favourite"]),
+    (SemanticIntent::Save, &["save"]),
+    (SemanticIntent::Delete, &["delete", "remove"]),
+    (SemanticIntent::Edit, &["edit", "modify"]),
+    (SemanticIntent::Create, &["create", "add", "new"]),
+    (SemanticIntent::Toggle, &["toggle"]),
+    (SemanticIntent::Open, &["open"]),
+    (SemanticIntent::Cancel, &["cancel"]),
+    (SemanticIntent::Close, &["close"]),
+    (SemanticIntent::Expand, &["expand"]),
+    (SemanticIntent::Collapse, &["collapse"]),
+    (SemanticIntent::Play, &["play"]),
+    (SemanticIntent::Pause, &["pause"]),
+    (SemanticIntent::Search, &["search"]),
+];
+
+const ES: KeywordTable = &[
+    (SemanticIntent::Submit, &["enviar", "confirmar"]),
+    (SemanticIntent::Print, &["imprimir"]),
+    (SemanticIntent::Share, &["compartir"]),
+    (SemanticIntent::Copy, &["copiar"]),
+    (SemanticIntent::Bookmark, &["marcador", "favorito"]),
+    (SemanticIntent::Save, &["guardar"]),
+    (SemanticIntent::Delete, &["eliminar", "borrar", "quitar"]),
+    (SemanticIntent::Edit, &["editar", "modificar"]),
+    (SemanticIntent::Create, &["crear", "añadir", "agregar", "nuevo", "nueva"]),
+    (SemanticIntent::Toggle, &["alternar"]),
+    (SemanticIntent::Open, &["abrir"]),
+    (SemanticIntent::Cancel, &["cancelar"]),
+    (SemanticIntent::Close, &["cerrar"]),
+    (SemanticIntent::Expand, &["expandir", "mostrar más"]),
+    (SemanticIntent::Collapse, &["contraer", "mostrar menos"]),
+    (SemanticIntent::Play, &["reproducir"]),
+    (SemanticIntent::Pause, &["pausar"]),
+    (SemanticIntent::Search, &["buscar"]),
+    (SemanticIntent::Download, &["descargar"]),
+    (SemanticIntent::Upload, &["subir archivo", "adjuntar"]),
+];
+
+const DE: KeywordTable = &[
+    (SemanticIntent::Submit, &["absenden", "senden", "bestätigen"]),
+    (SemanticIntent::Print, &["drucken"]),
+    (SemanticIntent::Share, &["teilen"]),
+    (SemanticIntent::Copy, &["kopieren"]),
+    (SemanticIntent::Bookmark, &["lesezeichen", "merken"]),
+    (SemanticIntent::Save, &["speichern"]),
+    (SemanticIntent::Delete, &["löschen", "entfernen"]),
+    (SemanticIntent::Edit, &["bearbeiten", "ändern"]),
+    (SemanticIntent::Create, &["erstellen", "hinzufügen", "neu"]),
+    (SemanticIntent::Toggle, &["umschalten"]),
+    (SemanticIntent::Open, &["öffnen"]),
+    (SemanticIntent::Cancel, &["abbrechen"]),
+    (SemanticIntent::Close, &["schließen"]),
+    (SemanticIntent::Expand, &["ausklappen", "erweitern", "mehr anzeigen"]),
+    (SemanticIntent::Collapse, &["einklappen", "weniger anzeigen"]),
+    (SemanticIntent::Play, &["abspielen", "wiedergeben"]),
+    (SemanticIntent::Pause, &["pausieren"]),
+    (SemanticIntent::Search, &["suchen", "suche"]),
+    (SemanticIntent::Download, &["herunterladen"]),
+    (SemanticIntent::Upload, &["hochladen", "anhängen"]),
+];
+
+const FR: KeywordTable = &[
+    (SemanticIntent::Submit, &["envoyer", "soumettre", "valider"]),
+    (SemanticIntent::Print, &["imprimer"]),
+    (SemanticIntent::Share, &["partager"]),
+    (SemanticIntent::Copy, &["copier"]),
+    (SemanticIntent::Bookmark, &["favori", "signet"]),
+    (SemanticIntent::Save, &["enregistrer"]),
+    (SemanticIntent::Delete, &["supprimer", "retirer", "effacer"]),
+    (SemanticIntent::Edit, &["modifier", "éditer"]),
+    (SemanticIntent::Create, &["créer", "ajouter", "nouveau", "nouvelle"]),
+    (SemanticIntent::Toggle, &["basculer"]),
+    (SemanticIntent::Open, &["ouvrir"]),
+    (SemanticIntent::Cancel, &["annuler"]),
+    (SemanticIntent::Close, &["fermer"]),
+    (SemanticIntent::Expand, &["développer", "afficher plus"]),
+    (SemanticIntent::Collapse, &["réduire", "afficher moins"]),
+    (SemanticIntent::Play, &["lecture", "lire"]),
+    (SemanticIntent::Pause, &["pause"]),
+    (SemanticIntent::Search, &["rechercher", "chercher"]),
+    (SemanticIntent::Download, &["télécharger"]),
+    (SemanticIntent::Upload, &["téléverser", "joindre"]),
+];
+
+const PT: KeywordTable = &[
+    (SemanticIntent::Submit, &["enviar", "confirmar"]),
+    (SemanticIntent::Print, &["imprimir"]),
+    (SemanticIntent::Share, &["compartilhar", "partilhar"]),
+    (SemanticIntent::Copy, &["copiar"]),
+    (SemanticIntent::Bookmark, &["favorito"]),
+    (SemanticIntent::Save, &["salvar", "guardar"]),
+    (SemanticIntent::Delete, &["excluir", "remover", "apagar", "eliminar"]),
+    (SemanticIntent::Edit, &["editar", "alterar"]),
+    (SemanticIntent::Create, &["criar", "adicionar", "novo", "nova"]),
+    (SemanticIntent::Toggle, &["alternar"]),
+    (SemanticIntent::Open, &["abrir"]),
+    (SemanticIntent::Cancel, &["cancelar"]),
+    (SemanticIntent::Close, &["fechar"]),
+    (SemanticIntent::Expand, &["expandir", "mostrar mais"]),
+    (SemanticIntent::Collapse, &["recolher", "mostrar menos"]),
+    (SemanticIntent::Play, &["reproduzir"]),
+    (SemanticIntent::Pause, &["pausar"]),
+    (SemanticIntent::Search, &["pesquisar", "buscar", "procurar"]),
+    (SemanticIntent::Download, &["baixar", "transferir"]),
+    (SemanticIntent::Upload, &["enviar arquivo", "anexar"]),
+];
+
+const JA: KeywordTable = &[
+    (SemanticIntent::Submit, &["送信", "確定", "登録"]),
+    (SemanticIntent::Print, &["印刷"]),
+    (SemanticIntent::Share, &["共有", "シェア"]),
+    (SemanticIntent::Copy, &["コピー"]),
+    (SemanticIntent::Bookmark, &["ブックマーク", "お気に入り"]),
+    (SemanticIntent::Save, &["保存"]),
+    (SemanticIntent::Delete, &["削除"]),
+    (SemanticIntent::Edit, &["編集", "変更"]),
+    (SemanticIntent::Create, &["作成", "追加", "新規"]),
+    (SemanticIntent::Toggle, &["切り替え", "切替"]),
+    (SemanticIntent::Open, &["開く"]),
+    (SemanticIntent::Cancel, &["キャンセル"]),
+    (SemanticIntent::Close, &["閉じる"]),
+    (SemanticIntent::Expand, &["展開", "もっと見る"]),
+    (SemanticIntent::Collapse, &["折りたたむ"]),
+    (SemanticIntent::Play, &["再生"]),
+    (SemanticIntent::Pause, &["一時停止"]),
+    (SemanticIntent::Search, &["検索"]),
+    (SemanticIntent::Download, &["ダウンロード"]),
+    (SemanticIntent::Upload, &["アップロード", "添付"]),
+];
+
+/// Languages with built-in intent keyword tables (ISO 639-1)
+pub const INTENT_LOCALES: &[&str] = &["en", "es", "de", "fr", "pt", "ja"];
+
+fn keyword_table(lang: &str) -> Option<KeywordTable> {
+    // Match on the primary subtag: "pt-BR" -> "pt"
+    let primary = lang.split(['-', '_']).next().unwrap_or(lang).to_ascii_lowercase();
+    match primary.as_str() {
+        "en" => Some(EN),
+        "es" => Some(ES),
+        "de" => Some(DE),
+        "fr" => Some(FR),
+        "pt" => Some(PT),
+        "ja" => Some(JA),
+        _ => None,
+    }
+}
+
+/// Infer an intent from lowercase button text
+///
+/// The table for `lang` is consulted first, then English, since English
+/// UI words are common on otherwise localized pages.
+pub(crate) fn keyword_intent(text: &str, lang: Option<&str>) -> Option<SemanticIntent> {
+    let localized = lang.and_then(keyword_table);
+    localized
+        .into_iter()
+        .chain(std::iter::once(EN))
+        .flat_map(|table| table.iter())
+        .find(|(_, keywords)| keywords.iter().any(|k| text.contains(k)))
+        .map(|(intent, _)| intent.clone())
+}
+
+/// Icon names of utility actions, matched against whole words
+const ICON_WORDS: KeywordTable = &[
+    (SemanticIntent::Print, &["print", "printer"]),
+    (SemanticIntent::Share, &["share"]),
+    (SemanticIntent::Copy, &["copy", "clipboard"]),
+    (SemanticIntent::Bookmark, &["bookmark"]),
+];
+
+/// Infer an intent from the icon inside an unlabelled control
+///
+/// Icon names come from the `id`, classes and `data-icon` of the element
+/// and its descendants, SVG sprite references (`<use href="#icon-print">`)
+/// and ligature icon text (`<span class="material-icons">share</span>`),
+/// split into words on punctuation, so `fa-print` and `content_copy` match.
+pub(crate) fn icon_intent(element: scraper::ElementRef) -> Option<SemanticIntent> {
+    let mut names: Vec<String> = Vec::new();
+    for el in element.descendants().filter_map(scraper::ElementRef::wrap) {
+        let v = el.value();
+        names.extend(v.id().into_iter().chain(v.classes()).map(str::to_string));
+        names.extend(v.attr("data-icon").map(str::to_string));
+        if v.name() == "use" {
+            names.extend(v.attr("href").or_else(|| v.attr("xlink:href")).map(str::to_string));
+        }
+        if v.classes().any(|c| c.starts_with("material-")) {
+            names.push(el.text().collect());
+        }
+    }
+    let words: Vec<String> = names
+        .iter()
+        .flat_map(|n| n.split(|c: char| !c.is_alphanumeric()))
+        .map(str::to_lowercase)
+        .collect();
+    ICON_WORDS
+        .iter()
+        .find(|(_, icons)| words.iter().any(|w| icons.contains(&w.as_str())))
+        .map(|(intent, _)| intent.clone())
+}
+
+/// Read-only view of an element passed to intent classifiers
+#[derive(Debug, Clone, Copy)]
+pub struct ElementContext<'a> {
+    /// Lowercase tag name
+    pub tag: &'a str,
+    /// Role the parser assigned to the element
+    pub role: &'a SemanticRole,
+    /// Label extracted for the element
+    pub label: &'a str,
+    /// Effective language: the configured locale, else the nearest `lang`
+    /// attribute, else the document language
+    pub lang: Option<&'a str>,
+    element: &'a scraper::node::Element,
+}
+
+impl<'a> ElementContext<'a> {
+    pub(crate) fn new(
+        element: &'a scraper::node::Element,
+        role: &'a SemanticRole,
+        label: &'a str,
+        lang: Option<&'a str>,
+    ) -> Self {
+        Self {
+            tag: element.name(),
+            role,
+            label,
+            lang,
+            element,
+        }
+    }
+
+    /// Get the value of an attribute on the element
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.element.attr(name)
+    }
+}
+
+/// A user-supplied intent classifier
+///
+/// Classifiers run in registration order after explicit `data-intent`
+/// attributes and before the built-in keyword heuristics. The first
+/// classifier returning `Some` wins; returning `None` defers to the next
+/// classifier and finally to the built-in heuristics. To replace the
+/// built-ins entirely, return `Some` for every element.
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Config, ElementContext, IntentClassifier, SemanticDOM, SemanticIntent};
+///
+/// struct Dutch;
+///
+/// impl IntentClassifier for Dutch {
+///     fn classify(&self, ctx: &ElementContext) -> Option<SemanticIntent> {
+///         match ctx.label.to_lowercase().as_str() {
+///             "verzenden" => Some(SemanticIntent::Submit),
+///             "verwijderen" => Some(SemanticIntent::Delete),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let config = Config::builder().intent_classifier(Dutch).build().unwrap();
+/// let sdom = SemanticDOM::parse("<button>Verzenden</button>", config).unwrap();
+/// let button = sdom.get(&sdom.interactables[0]).unwrap();
+/// assert_eq!(button.intent, Some(SemanticIntent::Submit));
+/// ```
+pub trait IntentClassifier: Send + Sync {
+    /// Classify the element, or return `None` to defer
+    fn classify(&self, ctx: &ElementContext) -> Option<SemanticIntent>;
+
+    /// Name used in debug output
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+impl std::fmt::Debug for dyn IntentClassifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IntentClassifier({})", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticDOM};
+
+    struct AlwaysAction;
+
+    impl IntentClassifier for AlwaysAction {
+        fn classify(&self, _ctx: &ElementContext) -> Option<SemanticIntent> {
+            Some(SemanticIntent::Action)
+        }
+
+        fn name(&self) -> &str {
+            "always-action"
+        }
+    }
+
+    struct HrefAware;
+
+    impl IntentClassifier for HrefAware {
+        fn classify(&self, ctx: &ElementContext) -> Option<SemanticIntent> {
+            if ctx.role == &SemanticRole::Link && ctx.attr("href")?.starts_with("/cart") {
+                return Some(SemanticIntent::Open);
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn test_classifier_precedence() {
+        let config = Config::builder()
+            .intent_classifier(HrefAware)
+            .intent_classifier(AlwaysAction)
+            .build()
+            .unwrap();
+        assert!(format!("{:?}", config).contains("always-action"));
+
+        let html = r#"
+            <a href="/cart">Cart</a>
+            <button>Delete</button>
+            <button data-intent="close">X</button>
+        "#;
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+        let intents: Vec<_> = sdom
+            .get_interactables()
+            .iter()
+            .map(|n| n.intent.clone().unwrap())
+            .collect();
+
+        // data-intent wins, then classifiers in registration order
+        assert_eq!(
+            intents,
+            vec![SemanticIntent::Open, SemanticIntent::Action, SemanticIntent::Close]
+        );
+    }
+
+    #[test]
+    fn test_keyword_tables() {
+        let cases = [
+            ("enviar", "es", SemanticIntent::Submit),
+            ("löschen", "de", SemanticIntent::Delete),
+            ("rechercher", "fr-CA", SemanticIntent::Search),
+            ("adicionar ao carrinho", "pt-BR", SemanticIntent::Create),
+            ("検索", "ja", SemanticIntent::Search),
+            // English fallback on a localized page
+            ("save", "de", SemanticIntent::Save),
+            ("abbrechen", "de", SemanticIntent::Cancel),
+            ("añadir a favoritos", "es", SemanticIntent::Bookmark),
+            ("copier le lien", "fr", SemanticIntent::Copy),
+        ];
+        for (text, lang, expected) in cases {
+            assert_eq!(keyword_intent(text, Some(lang)), Some(expected), "{}", text);
+        }
+        assert_eq!(keyword_intent("löschen", None), None);
+    }
+
+    #[test]
+    fn test_locale_from_lang_attribute_and_config() {
+        let html = r#"
+            <html lang="de"><body>
+                <button>Löschen</button>
+                <div lang="ja"><button>検索</button></div>
+            </body></html>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let intents: Vec<_> = sdom
+            .get_interactables()
+            .iter()
+            .map(|n| n.intent.clone().unwrap())
+            .collect();
+        assert_eq!(intents, vec![SemanticIntent::Delete, SemanticIntent::Search]);
+
+        let config = Config::builder().locale("es").build().unwrap();
+        let sdom = SemanticDOM::parse("<button>Enviar</button>", config).unwrap();
+        let button = sdom.get(&sdom.interactables[0]).unwrap();
+        assert_eq!(button.intent, Some(SemanticIntent::Submit));
+    }
+
+    #[test]
+    fn test_utility_intents() {
+        let html = r#"<main>
+            <button class="btn"><i class="fa fa-print"></i></button>
+            <button><svg><use href="/icons.svg#share-2"></use></svg></button>
+            <button><span class="material-icons">content_copy</span></button>
+            <button aria-label="Add to bookmarks"><svg class="icon"></svg></button>
+            <button class="btn-spin"><i class="icon-sparkle"></i></button>
+            <button>Checkout</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let intents: Vec<SemanticIntent> =
+            sdom.get_interactables().iter().map(|n| n.intent.clone().unwrap()).collect();
+        assert_eq!(
+            intents,
+            [
+                SemanticIntent::Print,
+                SemanticIntent::Share,
+                SemanticIntent::Copy,
+                SemanticIntent::Bookmark,
+                SemanticIntent::Action,
+                SemanticIntent::Action,
+            ]
+        );
+
+        // Utility actions go last in summaries
+        let summary = crate::to_agent_summary(&sdom);
+        let actions = "ACTIONS: [act]button.btn-spin, [act]Checkout, [print]";
+        assert!(summary.contains(actions), "{}", summary);
+    }
+
+    #[test]
+    fn test_classifier_defers_to_builtin() {
+        let config = Config::builder().intent_classifier(HrefAware).build().unwrap();
+        let sdom = SemanticDOM::parse("<button>Delete</button>", config).unwrap();
+        let button = sdom.get(&sdom.interactables[0]).unwrap();
+        assert_eq!(button.intent, Some(SemanticIntent::Delete));
+    }
+}
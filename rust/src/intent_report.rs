@@ -0,0 +1,117 @@
+//! Intent coverage of interactive elements
+//!
+//! Agents pick controls by intent. [`SemanticDOM::intent_report`] counts
+//! the interactables per [`SemanticIntent`] and lists those left `unknown`
+//! or only guessed, such as a button with unrecognized text falling back to
+//! `action`. Those are where a `data-agent-intent` hint helps most.
+
+use crate::certification::LOW_CONFIDENCE_THRESHOLD;
+use crate::parser::SemanticDOM;
+use crate::types::SemanticIntent;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// How the interactables of a document map to intents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntentReport {
+    /// Interactable nodes
+    pub interactable_count: usize,
+    /// Interactables per known intent, the most common first
+    pub intents: IndexMap<SemanticIntent, usize>,
+    /// IDs of interactables without a known intent, in reading order
+    pub unknown: Vec<String>,
+    /// IDs of interactables whose known intent is a low-confidence guess
+    pub guessed: Vec<String>,
+}
+
+impl IntentReport {
+    /// Share of interactables with a known intent, 1.0 without interactables
+    pub fn coverage(&self) -> f32 {
+        if self.interactable_count == 0 {
+            return 1.0;
+        }
+        1.0 - self.unknown.len() as f32 / self.interactable_count as f32
+    }
+
+    /// Counts as `12 navigate, 2 action, 3 unknown; 2 guessed`
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .intents
+            .iter()
+            .map(|(intent, count)| format!("{} {}", count, intent.as_str()))
+            .collect();
+        if !self.unknown.is_empty() {
+            parts.push(format!("{} unknown", self.unknown.len()));
+        }
+        let mut summary = parts.join(", ");
+        if !self.guessed.is_empty() {
+            summary.push_str(&format!("; {} guessed", self.guessed.len()));
+        }
+        summary
+    }
+}
+
+impl SemanticDOM {
+    /// Count interactables per intent and list the unclassified and guessed
+    /// ones
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticIntent};
+    ///
+    /// let html = r#"<nav><a href="/">Home</a><a href="/faq">FAQ</a></nav>
+    ///     <main><button data-agent-id="frob">Frobnicate</button></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let report = sdom.intent_report();
+    ///
+    /// assert_eq!(report.intents[&SemanticIntent::Navigate], 2);
+    /// // Unrecognized button text falls back to a generic action
+    /// assert_eq!(report.guessed, ["sdom_frob"]);
+    /// assert_eq!(report.summary(), "2 navigate, 1 action; 1 guessed");
+    /// ```
+    pub fn intent_report(&self) -> IntentReport {
+        let mut report = IntentReport::default();
+        for node in self.interactables.iter().filter_map(|id| self.index.get(id)) {
+            report.interactable_count += 1;
+            match &node.intent {
+                Some(intent) if *intent != SemanticIntent::Unknown => {
+                    *report.intents.entry(intent.clone()).or_default() += 1;
+                    if node.intent_confidence.unwrap_or(0.0) < LOW_CONFIDENCE_THRESHOLD {
+                        report.guessed.push(node.id.clone());
+                    }
+                }
+                _ => report.unknown.push(node.id.clone()),
+            }
+        }
+        // Stable, so ties keep the order of first appearance
+        report.intents.sort_by(|_, a, _, b| b.cmp(a));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_intent_report() {
+        let html = r#"<main><button>Frobnicate</button><a href="/a">A</a>
+            <button data-agent-intent="submit">Send</button>
+            <div role="button" data-agent-intent="nonsense">?</div>
+            <button type="submit">Order</button><a href="/b">B</a><a href="/c">C</a></main>"#;
+        let report = SemanticDOM::parse(html, Config::default()).unwrap().intent_report();
+        assert_eq!(report.interactable_count, 7);
+        let counts: Vec<(&str, usize)> =
+            report.intents.iter().map(|(i, n)| (i.as_str(), *n)).collect();
+        assert_eq!(counts, [("navigate", 3), ("submit", 2), ("action", 1)]);
+        assert_eq!(report.unknown, ["sdom_div_5"]);
+        assert_eq!(report.guessed, ["sdom_button_2"]);
+        assert!((report.coverage() - 6.0 / 7.0).abs() < 1e-6);
+        assert_eq!(report.summary(), "3 navigate, 2 submit, 1 action, 1 unknown; 1 guessed");
+
+        let empty = SemanticDOM::parse("<main></main>", Config::default()).unwrap();
+        assert_eq!(empty.intent_report().coverage(), 1.0);
+        assert_eq!(empty.intent_report().summary(), "");
+    }
+}
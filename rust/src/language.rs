@@ -0,0 +1,187 @@
+//! Per-element language and text direction
+//!
+//! `lang` and `dir` are inherited: a node's effective value comes from the
+//! nearest ancestor that declares one. Nodes only store values that differ
+//! from the document's, so [`SemanticDOM::lang_of`] and
+//! [`SemanticDOM::dir_of`] resolve the effective value.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, TextDirection};
+use scraper::ElementRef;
+
+/// Minimum letters in a label before its script is judged
+const MIN_SCRIPT_LETTERS: usize = 4;
+
+/// Writing systems distinguishable from character ranges alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Cjk,
+}
+
+/// Find an attribute on the element or its nearest ancestor
+pub(crate) fn nearest_attr<'a>(element: ElementRef<'a>, name: &str) -> Option<&'a str> {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .find_map(|e| e.value().attr(name))
+}
+
+/// Primary subtag of a language tag, lowercased (`"pt-BR"` -> `"pt"`)
+fn primary_subtag(lang: &str) -> String {
+    lang.trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+/// Check whether a language is written right to left
+pub(crate) fn is_rtl_lang(lang: &str) -> bool {
+    matches!(expected_script(lang), Some(Script::Arabic | Script::Hebrew))
+}
+
+/// Script a language is normally written in, for unambiguous languages
+fn expected_script(lang: &str) -> Option<Script> {
+    let script = match primary_subtag(lang).as_str() {
+        "ar" | "fa" | "ur" | "ps" | "ug" => Script::Arabic,
+        "he" | "iw" | "yi" => Script::Hebrew,
+        "ru" | "uk" | "be" | "bg" | "mk" | "kk" | "ky" | "mn" => Script::Cyrillic,
+        "el" => Script::Greek,
+        "hi" | "mr" | "ne" => Script::Devanagari,
+        "th" => Script::Thai,
+        "zh" | "ja" | "ko" => Script::Cjk,
+        "en" | "de" | "fr" | "es" | "it" | "pt" | "nl" | "sv" | "da" | "no" | "nb" | "nn"
+        | "fi" | "pl" | "cs" | "sk" | "hu" | "ro" | "tr" | "vi" | "id" | "ms" | "ca" | "hr"
+        | "sl" | "et" | "lv" | "lt" | "is" | "ga" => Script::Latin,
+        _ => return None,
+    };
+    Some(script)
+}
+
+fn char_script(c: char) -> Option<Script> {
+    let script = match c as u32 {
+        0x0041..=0x024F if c.is_alphabetic() => Script::Latin,
+        0x0370..=0x03FF => Script::Greek,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0600..=0x06FF | 0x0750..=0x077F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF => Script::Cjk,
+        _ => return None,
+    };
+    Some(script)
+}
+
+/// Script used by most letters of the text, if there are enough of them
+fn dominant_script(text: &str) -> Option<Script> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(char_script) {
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    let total: usize = counts.iter().map(|(_, n)| n).sum();
+    let (script, n) = counts.into_iter().max_by_key(|(_, n)| *n)?;
+    (total >= MIN_SCRIPT_LETTERS && n * 2 > total).then_some(script)
+}
+
+impl SemanticDOM {
+    /// Get the effective language of a node
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, TextDirection};
+    ///
+    /// let html = r#"<html lang="en"><body>
+    ///     <main><button>Save</button></main>
+    ///     <aside lang="ar" dir="rtl"><button>حفظ</button></aside>
+    /// </body></html>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let buttons = sdom.interactables.iter().filter_map(|id| sdom.get(id)).collect::<Vec<_>>();
+    /// assert_eq!(sdom.lang_of(buttons[0]), Some("en"));
+    /// assert_eq!(sdom.lang_of(buttons[1]), Some("ar"));
+    /// assert_eq!(sdom.dir_of(buttons[1]), TextDirection::Rtl);
+    /// ```
+    pub fn lang_of<'a>(&'a self, node: &'a SemanticNode) -> Option<&'a str> {
+        node.lang.as_deref().or(self.lang.as_deref())
+    }
+
+    /// Get the effective text direction of a node (`ltr` when undeclared)
+    pub fn dir_of(&self, node: &SemanticNode) -> TextDirection {
+        node.dir.or(self.dir).unwrap_or(TextDirection::Ltr)
+    }
+
+    /// Nodes whose declared language or direction contradicts their text
+    ///
+    /// Flags labels written mostly in a non-Latin script other than the one
+    /// the effective language uses (e.g. Cyrillic text under `lang="en"`),
+    /// and right-to-left languages explicitly marked `dir="ltr"`. Landmark
+    /// labels aggregate their children's text, so only their direction is
+    /// checked.
+    pub fn lang_mismatches(&self) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|node| {
+                let Some(lang) = self.lang_of(node) else {
+                    return false;
+                };
+                if is_rtl_lang(lang) && node.dir.or(self.dir) == Some(TextDirection::Ltr) {
+                    return true;
+                }
+                if node.role.is_landmark() {
+                    return false;
+                }
+                match (expected_script(lang), dominant_script(&node.label)) {
+                    (Some(expected), Some(found)) => found != Script::Latin && found != expected,
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_inherited_lang_and_mismatches() {
+        let html = r#"<html lang="en-US" dir="ltr"><body>
+            <nav lang="de"><a href="/">Startseite</a><a href="/ru">Русский язык</a></nav>
+            <main><h1>Welcome</h1><button>Login</button><p lang="he"><a href="/he">עברית</a></p></main>
+        </body></html>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let by_label = |label: &str| sdom.index.values().find(|n| n.label == label).unwrap();
+        let start = by_label("Startseite");
+        assert_eq!(sdom.lang_of(start), Some("de"));
+        assert_eq!(sdom.get(&start.parent.clone().unwrap()).unwrap().lang.as_deref(), Some("de"));
+        assert!(by_label("Welcome").lang.is_none());
+
+        let mismatched: Vec<&str> =
+            sdom.lang_mismatches().iter().map(|n| n.label.as_str()).collect();
+        // Cyrillic under lang="de", and Hebrew inheriting the document's dir="ltr"
+        assert_eq!(mismatched, ["Русский язык", "עברית"]);
+
+        let check = crate::AgentCertification::certify(&sdom);
+        let check = check.checks.iter().find(|c| c.id == "A11Y-005").unwrap();
+        assert!(!check.passed);
+    }
+
+    #[test]
+    fn test_dominant_script() {
+        assert_eq!(dominant_script("iPhone 15 Pro"), Some(Script::Latin));
+        assert_eq!(dominant_script("東京タワー"), Some(Script::Cjk));
+        assert_eq!(dominant_script("OK"), None);
+    }
+}
@@ -9,7 +9,7 @@
 //!
 //! - **O(1) Lookup**: Hash-indexed nodes via `AHashMap`
 //! - **State Graph**: Explicit FSM for UI states and transitions
-//! - **Agent Summary**: ~100 tokens vs ~800 for JSON (87% reduction)
+//! - **Agent Summary**: 100-200 tokens for a typical page, a small fraction of the JSON
 //! - **Security**: Input validation, URL sanitization, size limits
 //!
 //! ## Quick Start
@@ -20,12 +20,12 @@
 //! let html = r#"<html><body><main><button>Submit</button></main></body></html>"#;
 //! let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
 //!
-//! // O(1) lookup by iterating index
-//! for (id, node) in &sdom.index {
-//!     println!("{}: {}", id, node.label);
+//! // Nodes in document (reading) order; use `sdom.get(id)` for O(1) lookup
+//! for node in sdom.reading_order() {
+//!     println!("{}: {}", node.id, node.label);
 //! }
 //!
-//! // Token-efficient summary (~100 tokens)
+//! // Token-efficient summary (~100-200 tokens)
 //! let summary = sdom.to_agent_summary();
 //! println!("{}", summary);
 //! ```
@@ -34,8 +34,23 @@
 //!
 //! This crate implements security hardening:
 //! - Input size limits (10MB default)
-//! - URL protocol validation (https, http, file only)
+//! - URL protocol validation (https, http, file, mailto and tel by
+//!   default; javascript, data, vbscript and blob always blocked)
 //! - No script execution (HTML parsing only)
+//!
+//! ## Feature Flags
+//!
+//! - `cli` (default): the `semantic-dom` command-line tool
+//! - `tracing`: emit `tracing` spans for parsing, certification and
+//!   serialization, with output sizes and durations
+//! - `proto`: protobuf encoding of documents in the [`proto`] module, for
+//!   services that exchange them over gRPC
+//! - `graphql`: a GraphQL schema over parsed documents in the [`graphql`]
+//!   module, and the `serve` subcommand that exposes it over HTTP
+//! - `gzip`, `zstd`: compressed files in [`SemanticDOM::write_to`] and
+//!   [`SemanticDOM::read_from`]
+//! - `sqlite`: the `SqliteStore` document store. Like `zstd` it compiles
+//!   C code, here a bundled SQLite, so it is off by default
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
@@ -44,19 +59,151 @@
 mod types;
 mod parser;
 mod certification;
+mod waivers;
+mod site_certification;
+mod history;
 mod summary;
 mod security;
+mod encoding;
+mod diff;
+mod walk;
+mod edit;
+mod node_refs;
+mod config;
+mod intent;
+mod intent_report;
+mod manifest;
+mod well_known;
+mod external;
+mod conformance;
+mod excerpts;
+mod lists;
+mod results;
+mod clusters;
+mod noise;
+mod inference;
+mod inputs;
+mod uploads;
+mod loading;
+mod facets;
+mod locale;
+mod projection;
+mod export;
+mod store;
+mod navigator;
+mod sections;
+mod site_search;
+mod patterns;
+mod overlays;
+mod field_errors;
+mod commerce;
+mod contact;
+mod downloads;
+mod links;
+mod language;
+mod scripting;
+mod frameworks;
+mod handlers;
+mod security_report;
+mod fetch_guard;
+mod metrics;
+mod diagnostics;
+mod spans;
+mod relations;
+mod combobox;
+mod range;
+mod composites;
+mod notifications;
+mod forms;
+mod routes;
+mod graph_schema;
+mod graph_metrics;
+mod eval;
+mod summary_cache;
+mod query;
+mod selectors;
+mod text_search;
+mod annotations;
+mod ax_tree;
+mod aria_snapshot;
+#[cfg(feature = "proto")]
+pub mod proto;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(test)]
+mod golden;
+#[cfg(test)]
+mod proptests;
 
 pub use types::*;
+pub use config::{AttributePolicy, Config, ConfigBuilder};
 pub use parser::SemanticDOM;
-pub use certification::{AgentCertification, CertificationLevel, ValidationCheck};
-pub use summary::{to_agent_summary, to_one_liner, to_nav_summary, to_audio_summary, compare_token_usage, TokenComparison};
-pub use security::{validate_url, SecurityConfig};
+pub use certification::{all_checks, AgentCertification, CertificationLevel, CheckCategory, CheckInfo, ValidationCheck, LOW_CONFIDENCE_THRESHOLD};
+pub use site_certification::{CheckFailure, PageResult, ScoreBucket, SiteCertification};
+pub use history::{HistoryStore, ScoreRecord, ScoreTrend};
+pub use waivers::{Waiver, WaiverSource};
+pub use summary::{to_agent_summary, to_one_liner, to_nav_summary, to_audio_summary, to_landmark_summary, to_list_summary, compare_token_usage, TokenComparison};
+pub use security::{validate_url, validate_url_with, sanitize_string, SecurityConfig};
+pub use fetch_guard::FetchGuard;
+pub use metrics::Metrics;
+pub use diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+pub use relations::{Relation, RelationKind};
+pub use combobox::{Autocomplete, Combobox, ComboboxOption, ComboboxSource};
+pub use composites::{Grid, GridCell, GridRow, Tree, TreeItem};
+pub use notifications::{Notification, NotificationTone, Politeness};
+pub use routes::RouteTemplate;
+pub use graph_schema::STATE_GRAPH_SCHEMA_VERSION;
+pub use graph_metrics::GraphMetrics;
+pub use eval::{evaluate_formats, EvalReport, FormatRecall, SummaryFormat};
+pub use summary_cache::SummaryCache;
+pub use query::DocumentStats;
+pub use text_search::{MatchField, SearchHit, SearchIndex};
+pub use encoding::detect_encoding;
+pub use diff::DocumentChanges;
+pub use walk::{Visitor, WalkControl};
+pub use navigator::Navigator;
+pub use sections::Section;
+pub use site_search::SearchEntry;
+pub use patterns::{PagePattern, PatternKind};
+pub use overlays::{BlockingOverlay, OverlayKind};
+pub use commerce::{Availability, Price, ProductInfo, ProductSource};
+pub use contact::ContactInfo;
+pub use downloads::DownloadLink;
+pub use links::DocumentLink;
+pub use scripting::JsSignals;
+pub use frameworks::{Framework, FrameworkHints};
+pub use security_report::{
+    SecurityFinding, SecurityFindingKind, SecurityHeaders, SecurityReport, Severity,
+};
+pub use intent::{ElementContext, IntentClassifier, INTENT_LOCALES};
+pub use intent_report::IntentReport;
+pub use manifest::{to_agent_manifest, ManifestOptions};
+pub use external::{Violation, ViolationKind};
+pub use conformance::{ConformanceReport, FieldMismatch, RenamedNode};
+pub use lists::ListInfo;
+pub use results::{ResultItem, ResultList, MIN_RESULTS};
+pub use clusters::{Cluster, MIN_CLUSTER_SIZE};
+pub use noise::{NoiseAction, NoiseKind};
+pub use loading::{LoadingIndicator, LoadingKind};
+pub use facets::{Facet, FacetKind, FacetOption, Facets};
+pub use locale::LocaleHints;
+pub use projection::{SerializationProfile, NODE_FIELDS};
+pub use export::{Compression, ExportFormat};
+pub use store::{DocumentKey, DocumentStore, FsStore};
+#[cfg(feature = "sqlite")]
+pub use store::SqliteStore;
+pub use well_known::{
+    CertifiedLevel, LandmarkRef, WellKnownDescriptor, DESCRIPTOR_VERSION, STATE_GRAPH_PATH,
+    WELL_KNOWN_PATH,
+};
 
 use thiserror::Error;
 
 /// Errors that can occur during SemanticDOM operations
-#[derive(Error, Debug)]
+///
+/// Problems the parser can recover from are not returned as errors; they
+/// are collected in [`SemanticDOM::warnings`] and parsing continues.
+#[derive(Error, Debug, Clone)]
 pub enum Error {
     /// Input exceeds maximum size limit
     #[error("Input exceeds maximum size of {max_size} bytes (got {actual_size})")]
@@ -74,53 +221,180 @@ pub enum Error {
         protocol: String,
     },
 
-    /// HTML parsing error
-    #[error("Failed to parse HTML: {0}")]
-    ParseError(String),
+    /// Fetch target resolves to a private or reserved address
+    #[error("Refusing to fetch {host}: {address} is not a public address")]
+    BlockedAddress {
+        /// Host name from the URL
+        host: String,
+        /// The offending address
+        address: std::net::IpAddr,
+    },
 
-    /// IO error
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-}
+    /// Fetch target host is not allowed
+    #[error("Host is not allowed: {0}")]
+    BlockedHost(String),
 
-/// Result type for SemanticDOM operations
-pub type Result<T> = std::result::Result<T, Error>;
+    /// Fetch followed more redirects than allowed
+    #[error("Too many redirects (max {max})")]
+    TooManyRedirects {
+        /// Maximum allowed redirects
+        max: usize,
+    },
+
+    /// Fetch took longer than allowed
+    #[error("Fetch timed out after {timeout_ms} ms")]
+    FetchTimeout {
+        /// Configured timeout in milliseconds
+        timeout_ms: u64,
+    },
+
+    /// Response body exceeds the configured maximum
+    #[error("Response exceeds maximum size of {max_size} bytes (got {actual_size})")]
+    ResponseTooLarge {
+        /// Maximum allowed size
+        max_size: usize,
+        /// Bytes received so far
+        actual_size: usize,
+    },
+
+    /// Decompressed response exceeds the configured maximum
+    #[error("Decoded response exceeds maximum size of {max_size} bytes")]
+    DecodedTooLarge {
+        /// Maximum allowed decoded size
+        max_size: usize,
+    },
+
+    /// Element has more attributes than allowed
+    #[error("Element has {actual} attributes (max {max})")]
+    TooManyAttributes {
+        /// Maximum allowed attributes
+        max: usize,
+        /// Attributes on the element
+        actual: usize,
+    },
+
+    /// Node text exceeds the configured maximum
+    #[error("Node text exceeds maximum length of {max} bytes (got at least {actual})")]
+    TextTooLong {
+        /// Maximum allowed length
+        max: usize,
+        /// Length reached before the text was cut
+        actual: usize,
+    },
+
+    /// CSS selector that failed to compile
+    #[error("Invalid selector {selector:?}: {message}")]
+    SelectorError {
+        /// The selector source
+        selector: String,
+        /// Why it failed
+        message: String,
+    },
+
+    /// Input bytes that are malformed in the detected encoding; decoding
+    /// continues with U+FFFD replacements
+    #[error("Malformed {encoding} input at byte {position}")]
+    EncodingError {
+        /// Encoding the input was decoded as
+        encoding: String,
+        /// Byte offset of the first malformed sequence
+        position: usize,
+    },
+
+    /// Semantic nesting deeper than `Config::max_depth`; deeper subtrees
+    /// are skipped
+    #[error("Nesting below {node} exceeds maximum depth of {max_depth}")]
+    DepthExceeded {
+        /// Maximum allowed depth
+        max_depth: usize,
+        /// ID of the node whose subtree was cut off, or `document`
+        node: String,
+    },
+
+    /// More nodes than `Config::max_nodes`; parsing stops at the limit
+    #[error("Document exceeds maximum of {max_nodes} nodes")]
+    NodeLimitExceeded {
+        /// Maximum allowed nodes
+        max_nodes: usize,
+    },
+
+    /// A node's URL was rejected by validation and dropped
+    #[error("Dropped URL {url:?} on {node}: {reason}")]
+    UrlRejected {
+        /// ID of the node carrying the URL
+        node: String,
+        /// The rejected URL
+        url: String,
+        /// Why validation rejected it
+        #[source]
+        reason: Box<Error>,
+    },
+
+    /// Output could not be serialized
+    #[error("Serialization failed: {0}")]
+    SerializationError(String),
+
+    /// No node with the given ID exists
+    #[error("Node not found: {0}")]
+    NodeNotFound(String),
+
+    /// A node with the given ID already exists
+    #[error("Duplicate node ID: {0}")]
+    DuplicateNodeId(String),
+
+    /// ARIA attribute names an `id` that no element has
+    #[error("{attribute} on {node} refers to missing id {id:?}")]
+    BrokenReference {
+        /// Node carrying the attribute
+        node: String,
+        /// The referencing attribute, e.g. `aria-controls`
+        attribute: &'static str,
+        /// The missing `id`
+        id: String,
+    },
+
+    /// Role name that does not map to a `SemanticRole`
+    #[error("Unknown role: {0}")]
+    UnknownRole(String),
+
+    /// Intent name that does not map to a `SemanticIntent`
+    #[error("Unknown intent: {0}")]
+    UnknownIntent(String),
+
+    /// Field name that `SemanticNode` does not serialize
+    #[error("Unknown node field: {0}")]
+    UnknownField(String),
+
+    /// Inconsistent or out-of-range configuration
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    /// Well-known descriptor that does not follow the format
+    #[error("Invalid descriptor: {0}")]
+    InvalidDescriptor(String),
+
+    /// Compressed input or output without the feature enabling its codec
+    #[error("Compression needs the `{0}` feature")]
+    CompressionUnavailable(&'static str),
 
-/// Configuration for SemanticDOM parsing
-#[derive(Debug, Clone)]
-pub struct Config {
-    /// Maximum input size in bytes (default: 10MB)
-    pub max_input_size: usize,
-    /// ID prefix for generated semantic IDs
-    pub id_prefix: String,
-    /// Maximum tree depth to parse
-    pub max_depth: usize,
-    /// Elements to exclude from parsing
-    pub exclude_tags: Vec<String>,
-    /// Whether to generate state graph
-    pub include_state_graph: bool,
-    /// Whether to run certification checks
-    pub validate: bool,
+    /// Invalid key or failing backend of a `DocumentStore`
+    #[error("Document store error: {0}")]
+    StoreError(String),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    IoError(#[source] std::sync::Arc<std::io::Error>),
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            max_input_size: 10 * 1024 * 1024, // 10MB
-            id_prefix: "sdom".to_string(),
-            max_depth: 50,
-            exclude_tags: vec![
-                "script".to_string(),
-                "style".to_string(),
-                "noscript".to_string(),
-                "template".to_string(),
-            ],
-            include_state_graph: true,
-            validate: true,
-        }
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::IoError(std::sync::Arc::new(error))
     }
 }
 
+/// Result type for SemanticDOM operations
+pub type Result<T> = std::result::Result<T, Error>;
+
 /// Standard reference
 pub const STANDARD: &str = "ISO/IEC-SDOM-SSG-DRAFT-2024";
 
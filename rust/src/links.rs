@@ -0,0 +1,424 @@
+//! Link classification and relationships
+//!
+//! Each link node gets a [`LinkKind`] during parsing. Absolute links are
+//! compared against the page URL from [`Config::base_url`](crate::Config::base_url)
+//! or `<base href>`; without one, every absolute web link counts as external.
+//!
+//! `rel` tokens are kept on link nodes, and document-level `<link>` elements
+//! (canonical, alternate languages, prev/next) are collected as
+//! [`DocumentLink`]s.
+
+use crate::parser::SemanticDOM;
+use crate::security::{validate_url_with, SecurityConfig};
+use crate::types::{AriaCurrent, LinkKind, SemanticNode, SemanticRole};
+use ahash::{AHashMap, AHashSet};
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A `<link>` element from the document head
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentLink {
+    /// Lowercased `rel` tokens
+    pub rel: Vec<String>,
+    /// Link target, resolved against the page URL when it is known
+    pub href: String,
+    /// Language of the target (`hreflang`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hreflang: Option<String>,
+    /// MIME type of the target (`type`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// Advisory title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl DocumentLink {
+    /// Check whether the link has the given `rel` token
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+}
+
+/// Split a `rel` attribute into lowercased tokens
+pub(crate) fn rel_tokens(rel: Option<&str>) -> Vec<String> {
+    rel.map(|r| r.split_whitespace().map(str::to_ascii_lowercase).collect())
+        .unwrap_or_default()
+}
+
+/// Collect `<link rel href>` elements, skipping unsafe targets
+pub(crate) fn document_links(
+    document: &Html,
+    base_url: Option<&str>,
+    security: &SecurityConfig,
+) -> Vec<DocumentLink> {
+    let Ok(selector) = Selector::parse("link[rel][href]") else {
+        return Vec::new();
+    };
+    let base = base_url.and_then(|b| Url::parse(b).ok());
+    let attr = |el: &scraper::node::Element, name: &str| {
+        el.attr(name)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    document
+        .select(&selector)
+        .filter_map(|link| {
+            let el = link.value();
+            let href = validate_url_with(el.attr("href")?.trim(), security).ok()?;
+            let href = match &base {
+                Some(base) => base.join(&href).map(String::from).unwrap_or(href),
+                None => href,
+            };
+            Some(DocumentLink {
+                rel: rel_tokens(el.attr("rel")),
+                href,
+                hreflang: attr(el, "hreflang"),
+                media_type: attr(el, "type"),
+                title: attr(el, "title"),
+            })
+        })
+        .collect()
+}
+
+/// Class names marking the active item of a navigation menu
+const ACTIVE_CLASSES: &[&str] = &["active", "current", "is-active", "is-current", "selected"];
+
+/// Current state of an element from `aria-current`, or for links in a
+/// navigation landmark, an active class on the link or its list item
+pub(crate) fn current_state(element: ElementRef, role: &SemanticRole) -> Option<AriaCurrent> {
+    if let Some(value) = element.value().attr("aria-current") {
+        return AriaCurrent::from_attr(value);
+    }
+    if !matches!(role, SemanticRole::Link) {
+        return None;
+    }
+    let active = |e: ElementRef| {
+        e.value()
+            .classes()
+            .any(|c| ACTIVE_CLASSES.iter().any(|a| c.eq_ignore_ascii_case(a)))
+    };
+    let item = element
+        .parent()
+        .and_then(ElementRef::wrap)
+        .filter(|p| p.value().name() == "li");
+    let in_nav = element.ancestors().filter_map(ElementRef::wrap).any(|a| {
+        a.value().name() == "nav"
+            || a.value().attr("role").is_some_and(|r| r.eq_ignore_ascii_case("navigation"))
+    });
+    (in_nav && (active(element) || item.is_some_and(active))).then_some(AriaCurrent::Page)
+}
+
+/// Resolve the fragments of in-page links to the nodes they point at
+///
+/// A fragment names the element with that `id` (or a legacy `<a name>`).
+/// When that element is not a node itself, the first node inside it is
+/// used, then its nearest ancestor node.
+pub(crate) fn anchor_targets(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> AHashMap<String, String> {
+    let fragments: AHashSet<&str> = index
+        .values()
+        .filter_map(|n| n.href.as_deref()?.strip_prefix('#'))
+        .filter(|f| !f.is_empty())
+        .collect();
+    let mut targets = AHashMap::new();
+    if fragments.is_empty() {
+        return targets;
+    }
+
+    for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        let el = element.value();
+        let name = el.id().or_else(|| el.attr("name").filter(|_| el.name() == "a"));
+        let Some(fragment) = name.filter(|n| fragments.contains(n)) else {
+            continue;
+        };
+        if targets.contains_key(fragment) {
+            continue;
+        }
+        let node = element
+            .descendants()
+            .find_map(|d| nodes.get(&d.id()))
+            .or_else(|| element.ancestors().find_map(|a| nodes.get(&a.id())));
+        if let Some(node) = node {
+            targets.insert(fragment.to_string(), node.clone());
+        }
+    }
+    targets
+}
+
+/// Classify a link target relative to the page URL
+pub(crate) fn classify_link(href: &str, base_url: Option<&str>) -> LinkKind {
+    let href = href.trim();
+    if href.starts_with('#') {
+        return LinkKind::Anchor;
+    }
+
+    let scheme = href.split_once(':').map(|(s, _)| s.to_ascii_lowercase());
+    match scheme.as_deref() {
+        Some("mailto") => return LinkKind::Mailto,
+        Some("tel") => return LinkKind::Tel,
+        _ => {}
+    }
+
+    let base = base_url.and_then(|b| Url::parse(b).ok());
+    let target = match (Url::parse(href), &base) {
+        (Ok(url), _) => url,
+        // Relative reference: resolves against the page, so stays on the site
+        (Err(_), None) if !href.starts_with("//") => return LinkKind::Internal,
+        (Err(_), None) => return LinkKind::External,
+        (Err(_), Some(base)) => match base.join(href) {
+            Ok(url) => url,
+            Err(_) => return LinkKind::Internal,
+        },
+    };
+
+    let Some(base) = base else {
+        return LinkKind::External;
+    };
+    if !same_site(&target, &base) {
+        return LinkKind::External;
+    }
+    // A fragment link spelled out in full still points at this page
+    if target.fragment().is_some() && without_fragment(&target) == without_fragment(&base) {
+        return LinkKind::Anchor;
+    }
+    LinkKind::Internal
+}
+
+/// Same scheme family and host, treating `www.` as equivalent
+fn same_site(a: &Url, b: &Url) -> bool {
+    let host = |u: &Url| {
+        u.host_str()
+            .map(|h| h.trim_start_matches("www.").to_ascii_lowercase())
+    };
+    let web = |u: &Url| matches!(u.scheme(), "http" | "https");
+    web(a) && web(b) && host(a).is_some() && host(a) == host(b)
+}
+
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+impl SemanticDOM {
+    /// Get the document's `<link>` elements, in source order
+    pub fn document_links(&self) -> &[DocumentLink] {
+        &self.document_links
+    }
+
+    /// Get the canonical URL declared with `<link rel="canonical">`
+    pub fn canonical_url(&self) -> Option<&str> {
+        self.document_links
+            .iter()
+            .find(|l| l.has_rel("canonical"))
+            .map(|l| l.href.as_str())
+    }
+
+    /// Get alternate versions of the page, such as translations
+    ///
+    /// Includes `<link rel="alternate">` elements and in-page links with
+    /// `rel="alternate"` and an `hreflang` (language switchers). Feeds and
+    /// other alternates without a language are included from `<link>` only.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<html><head>
+    ///     <link rel="canonical" href="https://example.com/en/">
+    ///     <link rel="alternate" hreflang="de" href="https://example.com/de/">
+    /// </head><body>
+    ///     <nav><a rel="alternate" hreflang="fr" href="https://example.com/fr/">Français</a></nav>
+    /// </body></html>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let alternates = sdom.alternates();
+    /// let langs: Vec<&str> = alternates.iter().filter_map(|a| a.hreflang.as_deref()).collect();
+    /// assert_eq!(langs, ["de", "fr"]);
+    /// assert_eq!(sdom.canonical_url(), Some("https://example.com/en/"));
+    /// ```
+    pub fn alternates(&self) -> Vec<DocumentLink> {
+        let head = self
+            .document_links
+            .iter()
+            .filter(|l| l.has_rel("alternate"))
+            .cloned();
+        let body = self
+            .reading_order()
+            .into_iter()
+            .filter(|n| n.hreflang.is_some() && n.rel.iter().any(|r| r == "alternate"))
+            .filter_map(|n| {
+                Some(DocumentLink {
+                    rel: n.rel.clone(),
+                    href: n.href.clone()?,
+                    hreflang: n.hreflang.clone(),
+                    media_type: None,
+                    title: Some(n.label.clone()).filter(|l| !l.is_empty()),
+                })
+            });
+
+        let mut alternates: Vec<DocumentLink> = Vec::new();
+        for link in head.chain(body) {
+            if !alternates.iter().any(|a| a.href == link.href && a.hreflang == link.hreflang) {
+                alternates.push(link);
+            }
+        }
+        alternates
+    }
+
+    /// Get links that leave the site, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, LinkKind, SemanticDOM};
+    ///
+    /// let config = Config::builder().base_url("https://example.com/docs/").build().unwrap();
+    /// let html = r##"<nav>
+    ///     <a href="/pricing">Pricing</a>
+    ///     <a href="https://www.example.com/blog">Blog</a>
+    ///     <a href="https://github.com/example">GitHub</a>
+    ///     <a href="#install">Install</a>
+    /// </nav>"##;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let external: Vec<&str> = sdom.external_links().iter().map(|n| n.label.as_str()).collect();
+    /// assert_eq!(external, ["GitHub"]);
+    /// assert!(sdom.to_agent_summary().contains("LINKS: 2 internal, 1 anchor, 1 external"));
+    /// ```
+    pub fn external_links(&self) -> Vec<&SemanticNode> {
+        self.links_of_kind(LinkKind::External)
+    }
+
+    /// Get the navigation link marked as the current page
+    ///
+    /// When its target is a site path, that page becomes the state graph's
+    /// initial state.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<nav><ul>
+    ///     <li><a href="/">Home</a></li>
+    ///     <li class="active"><a href="/pricing">Pricing</a></li>
+    /// </ul></nav>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert_eq!(sdom.current_page().unwrap().label, "Pricing");
+    /// assert_eq!(sdom.state_graph.initial_state.as_deref(), Some("state__pricing"));
+    /// ```
+    pub fn current_page(&self) -> Option<&SemanticNode> {
+        let current = |kind: AriaCurrent| {
+            self.reading_order()
+                .into_iter()
+                .find(|n| matches!(n.role, SemanticRole::Link) && n.current == Some(kind))
+        };
+        current(AriaCurrent::Page).or_else(|| current(AriaCurrent::True))
+    }
+
+    /// Get the node an in-page link such as `#pricing` scrolls to
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r##"<nav><a href="#pricing">Pricing</a></nav>
+    /// <main><section id="pricing"><h2>Plans</h2></section></main>"##;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert_eq!(sdom.anchor_target("#pricing").unwrap().id, "sdom_pricing");
+    /// let scroll = &sdom.state_graph.transitions[0];
+    /// assert_eq!(scroll.action.as_deref(), Some("scroll"));
+    /// assert_eq!(scroll.to, "state_at_sdom_pricing");
+    /// ```
+    pub fn anchor_target(&self, href: &str) -> Option<&SemanticNode> {
+        let fragment = href.trim().strip_prefix('#')?;
+        self.index.get(self.anchors.get(fragment)?)
+    }
+
+    /// Get links of the given kind, in document order
+    pub fn links_of_kind(&self, kind: LinkKind) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| n.link_kind == Some(kind))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_link() {
+        let base = Some("https://example.com/docs/page?x=1");
+        assert_eq!(classify_link("#top", base), LinkKind::Anchor);
+        assert_eq!(classify_link("https://example.com/docs/page?x=1#a", base), LinkKind::Anchor);
+        assert_eq!(classify_link("../other", base), LinkKind::Internal);
+        assert_eq!(classify_link("//cdn.example.net/x", base), LinkKind::External);
+        assert_eq!(classify_link("http://WWW.example.com/", base), LinkKind::Internal);
+        assert_eq!(classify_link("MAILTO:a@b.c", base), LinkKind::Mailto);
+        assert_eq!(classify_link("tel:+1555", None), LinkKind::Tel);
+        assert_eq!(classify_link("/about", None), LinkKind::Internal);
+        assert_eq!(classify_link("https://example.com/", None), LinkKind::External);
+    }
+
+    #[test]
+    fn test_rel_tokens_and_relative_document_links() {
+        let config = crate::Config::builder().base_url("https://example.com/a/").build().unwrap();
+        let html = r#"<html><head>
+            <link rel="Next" href="page2">
+            <link rel="stylesheet" href="javascript:alert(1)">
+        </head><body><a href="/ad" rel="nofollow noopener">Ad</a></body></html>"#;
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+
+        assert_eq!(sdom.document_links().len(), 1);
+        assert!(sdom.document_links()[0].has_rel("next"));
+        assert_eq!(sdom.document_links()[0].href, "https://example.com/a/page2");
+        assert_eq!(sdom.links_of_kind(LinkKind::Internal)[0].rel, ["nofollow", "noopener"]);
+    }
+
+    #[test]
+    fn test_base_element_sets_page_url() {
+        let html = r#"<html><head><base href="https://shop.example/"></head>
+            <body><a href="https://shop.example/cart">Cart</a><a href="https://x.test/">X</a></body></html>"#;
+        let sdom = SemanticDOM::parse(html, crate::Config::default()).unwrap();
+        assert_eq!(sdom.base_url.as_deref(), Some("https://shop.example/"));
+        assert_eq!(sdom.external_links().len(), 1);
+        assert_eq!(sdom.links_of_kind(LinkKind::Internal)[0].label, "Cart");
+    }
+
+    #[test]
+    fn test_current_page_is_initial_state() {
+        let html = r#"<nav role="navigation"><ul>
+            <li><a href="/">Home</a></li>
+            <li><a href="/docs" class="is-active">Docs</a></li>
+        </ul></nav>
+        <main><ol><li aria-current="step">Shipping</li></ol>
+            <a href="/docs" class="active">Docs again</a></main>"#;
+        let sdom = SemanticDOM::parse(html, crate::Config::default()).unwrap();
+
+        let current = sdom.current_page().unwrap();
+        assert_eq!(current.current, Some(AriaCurrent::Page));
+        // Outside navigation an active class says nothing
+        let again = sdom.index.values().find(|n| n.label == "Docs again").unwrap();
+        assert_eq!(again.current, None);
+
+        let graph = &sdom.state_graph;
+        assert_eq!(graph.initial_state.as_deref(), Some("state__docs"));
+        let initial = graph.states.iter().find(|s| s.is_initial).unwrap();
+        assert_eq!(initial.name, "Docs");
+        assert_eq!(initial.url_pattern.as_deref(), Some("/docs"));
+        assert!(graph.transitions.iter().all(|t| t.from == "state__docs"));
+        assert_eq!(AriaCurrent::from_attr("false"), None);
+        assert_eq!(AriaCurrent::from_attr("yes"), Some(AriaCurrent::True));
+    }
+}
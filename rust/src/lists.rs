@@ -0,0 +1,115 @@
+//! Lists and their items
+//!
+//! `ul`, `ol` and `li` elements become [`SemanticRole::List`] and
+//! [`SemanticRole::ListItem`] nodes, as do elements with the `list` and
+//! `listitem` roles. On pages such as search results the list is the
+//! content; [`SemanticDOM::lists`] reports each list with its nesting and
+//! items, and [`to_list_summary`](crate::to_list_summary) writes them
+//! compactly.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticRole;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// A list and its items
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListInfo {
+    /// Semantic ID of the list
+    pub id: String,
+    /// Whether the items are numbered (`ol`)
+    pub ordered: bool,
+    /// Lists this one is nested in, 0 for a top-level list
+    pub nesting: usize,
+    /// IDs of the items, in order
+    pub items: Vec<String>,
+}
+
+impl ListInfo {
+    /// Number of items
+    pub fn item_count(&self) -> usize {
+        self.items.len()
+    }
+}
+
+/// Whether an `li` is an item of a plain list
+///
+/// Items of a list repurposed as a listbox, menu or tab list are options
+/// or controls rather than list items.
+pub(crate) fn in_plain_list(element: ElementRef) -> bool {
+    element.parent().and_then(ElementRef::wrap).is_some_and(|parent| {
+        let el = parent.value();
+        matches!(el.name(), "ul" | "ol" | "menu")
+            && el.attr("role").map_or(true, |r| r.trim().eq_ignore_ascii_case("list"))
+    })
+}
+
+impl SemanticDOM {
+    /// Lists in reading order, nested lists after the item holding them
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main><ol id="results">
+    ///     <li><a href="/a">Rust book</a><ul><li>Ownership</li><li>Traits</li></ul></li>
+    ///     <li><a href="/b">Rust by example</a></li>
+    /// </ol></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let lists = sdom.lists();
+    /// assert_eq!(lists.len(), 2);
+    /// assert!(lists[0].ordered);
+    /// assert_eq!(lists[0].item_count(), 2);
+    /// assert_eq!((lists[1].nesting, lists[1].item_count()), (1, 2));
+    /// ```
+    pub fn lists(&self) -> Vec<ListInfo> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| n.role == SemanticRole::List)
+            .map(|list| {
+                let items = list
+                    .children
+                    .iter()
+                    .filter_map(|id| self.index.get(id))
+                    .filter(|n| n.role == SemanticRole::ListItem)
+                    .map(|n| n.id.clone())
+                    .collect();
+                let nesting = std::iter::successors(list.parent.as_deref(), |id| {
+                    self.index.get(*id).and_then(|n| n.parent.as_deref())
+                })
+                .filter(|id| self.index.get(*id).is_some_and(|n| n.role == SemanticRole::List))
+                .count();
+                ListInfo {
+                    id: list.id.clone(),
+                    ordered: list.selector.split(['#', '[', '.']).next() == Some("ol"),
+                    nesting,
+                    items,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_lists() {
+        let html = r#"<nav><ul><li><a href="/">Home</a></li><li><a href="/docs">Docs</a>
+            <ul><li><a href="/docs/start">Start</a></li></ul></li></ul></nav>
+            <main><div role="list"><div role="listitem">One</div></div>
+            <ul role="listbox"><li>Option</li></ul></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let lists: Vec<(usize, usize, bool)> =
+            sdom.lists().iter().map(|l| (l.nesting, l.item_count(), l.ordered)).collect();
+        assert_eq!(lists, [(0, 2, false), (1, 1, false), (0, 1, false)]);
+        // Options of a listbox are not list items
+        assert_eq!(sdom.query_by_role(&SemanticRole::ListItem).len(), 4);
+
+        let link = sdom.interactables.iter().find_map(|id| sdom.get(id)).unwrap();
+        assert_eq!(sdom.get(link.parent.as_deref().unwrap()).unwrap().role, SemanticRole::ListItem);
+    }
+}
@@ -0,0 +1,272 @@
+//! Loading indicators
+//!
+//! A page captured mid-load looks complete to a static parser: the results
+//! behind a spinner or a row of skeleton cards are simply missing. Progress
+//! bars still running, `aria-busy` regions, spinners, skeleton loaders and
+//! "Loading…" messages are reported as
+//! [`LoadingIndicator`](crate::LoadingIndicator)s, and
+//! [`SemanticDOM::appears_loading`] tells an agent to observe the page
+//! again before trusting what is missing from it.
+
+use crate::parser::SemanticDOM;
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Kind of loading indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadingKind {
+    /// An indeterminate or unfinished progress bar
+    Progress,
+    /// A region marked `aria-busy="true"`
+    Busy,
+    /// A spinner or throbber
+    Spinner,
+    /// A skeleton or shimmer placeholder
+    Skeleton,
+    /// A short "Loading…" or "Please wait" message
+    Message,
+}
+
+impl LoadingKind {
+    /// All kinds, in the order summaries list them
+    pub const ALL: [LoadingKind; 5] = [
+        LoadingKind::Progress,
+        LoadingKind::Busy,
+        LoadingKind::Spinner,
+        LoadingKind::Skeleton,
+        LoadingKind::Message,
+    ];
+
+    /// Name used in summaries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoadingKind::Progress => "progress",
+            LoadingKind::Busy => "busy",
+            LoadingKind::Spinner => "spinner",
+            LoadingKind::Skeleton => "skeleton",
+            LoadingKind::Message => "message",
+        }
+    }
+}
+
+/// An element showing that the page is still loading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadingIndicator {
+    /// What kind of indicator it is
+    pub kind: LoadingKind,
+    /// CSS selector for the element, to re-query after waiting
+    pub selector: String,
+    /// ID of the element's node, when it became one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Accessible label or text of the indicator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// `id`/class words (split on `-` and `_`) of spinners
+const SPINNER_WORDS: &[&str] = &["spinner", "loader", "loading", "throbber", "spin", "lds"];
+
+/// `id`/class words of skeleton placeholders
+const SKELETON_WORDS: &[&str] = &["skeleton", "shimmer"];
+
+/// Bootstrap placeholder animations, compared whole
+const SKELETON_CLASSES: &[&str] = &["placeholder-glow", "placeholder-wave"];
+
+/// Starts of loading messages, lowercased
+const LOADING_MESSAGES: &[&str] = &[
+    "loading", "please wait", "one moment", "cargando", "chargement", "wird geladen",
+    "laden", "caricamento", "carregando", "読み込み中", "加载中",
+];
+
+/// Longest text read as a loading message, in characters
+const MAX_MESSAGE_CHARS: usize = 40;
+
+/// Lowercased `id` and class names of an element
+fn tokens(element: ElementRef<'_>) -> impl Iterator<Item = String> + '_ {
+    let el = element.value();
+    el.id().into_iter().chain(el.classes()).map(str::to_ascii_lowercase)
+}
+
+fn has_word(element: ElementRef, words: &[&str]) -> bool {
+    tokens(element).any(|t| t.split(['-', '_']).any(|w| words.contains(&w)))
+}
+
+fn text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Whether a progress bar is indeterminate or not yet full
+fn unfinished(element: ElementRef) -> bool {
+    let el = element.value();
+    let num = |name: &str| el.attr(name).and_then(|v| v.trim().parse::<f64>().ok());
+    let (now, max) = match el.name() {
+        "progress" => (num("value"), num("max").filter(|m| *m > 0.0).unwrap_or(1.0)),
+        _ => (num("aria-valuenow"), num("aria-valuemax").unwrap_or(100.0)),
+    };
+    !now.is_some_and(|now| now >= max)
+}
+
+/// Classify an element as a loading indicator
+fn loading_kind(element: ElementRef) -> Option<LoadingKind> {
+    let el = element.value();
+    let role = el.attr("role").map(|r| r.trim().to_ascii_lowercase());
+    if (el.name() == "progress" || role.as_deref() == Some("progressbar")) && unfinished(element) {
+        return Some(LoadingKind::Progress);
+    }
+    if el.attr("aria-busy").is_some_and(|b| b.trim().eq_ignore_ascii_case("true")) {
+        return Some(LoadingKind::Busy);
+    }
+    // Controls named after a spin are not spinners
+    let control = matches!(el.name(), "a" | "button" | "input" | "select" | "textarea");
+    if !control
+        && (has_word(element, SKELETON_WORDS)
+            || tokens(element).any(|t| SKELETON_CLASSES.contains(&t.as_str())))
+    {
+        return Some(LoadingKind::Skeleton);
+    }
+    if !control && has_word(element, SPINNER_WORDS) {
+        return Some(LoadingKind::Spinner);
+    }
+    // Only the element holding the message, not its ancestors
+    let own_text = element.children().any(|c| {
+        c.value().as_text().is_some_and(|t| !t.trim().is_empty())
+    });
+    let message = own_text
+        && text(element).is_some_and(|t| {
+            let t = t.to_lowercase();
+            t.chars().count() <= MAX_MESSAGE_CHARS
+                && LOADING_MESSAGES.iter().any(|m| t.starts_with(m))
+        });
+    message.then_some(LoadingKind::Message)
+}
+
+/// Whether an element and its subtree are out of view
+fn hidden(element: ElementRef) -> bool {
+    let el = element.value();
+    let style = el.attr("style").unwrap_or("").replace(' ', "").to_ascii_lowercase();
+    matches!(el.name(), "script" | "style" | "template" | "noscript")
+        || el.attr("hidden").is_some()
+        || style.contains("display:none")
+        || style.contains("visibility:hidden")
+}
+
+/// Find loading indicators, given the node created for each element
+///
+/// Only the outermost indicator of a subtree is reported: a skeleton card
+/// is one indicator, not one per grey bar.
+pub(crate) fn loading_indicators(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+) -> Vec<LoadingIndicator> {
+    let mut indicators = Vec::new();
+    let mut stack = vec![document.root_element()];
+    while let Some(element) = stack.pop() {
+        if hidden(element) {
+            continue;
+        }
+        if let Some(kind) = loading_kind(element) {
+            let el = element.value();
+            let label = el
+                .attr("aria-label")
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .or_else(|| text(element));
+            indicators.push(LoadingIndicator {
+                kind,
+                selector: SemanticDOM::build_element_selector(el),
+                id: nodes.get(&element.id()).cloned(),
+                label: label.map(|l| crate::summary::truncate(&l, MAX_MESSAGE_CHARS)),
+            });
+            continue;
+        }
+        let children: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    indicators
+}
+
+impl SemanticDOM {
+    /// Get the loading indicators on the page, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, LoadingKind, SemanticDOM};
+    ///
+    /// let html = r#"<main><h1>Results</h1>
+    ///     <div class="spinner-border" role="status" aria-label="Loading results"></div>
+    ///     <div class="card skeleton"><div class="skeleton-line"></div></div>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let loading = sdom.loading_indicators();
+    /// assert_eq!(loading[0].kind, LoadingKind::Spinner);
+    /// assert_eq!(loading[0].label.as_deref(), Some("Loading results"));
+    /// assert_eq!(loading[1].kind, LoadingKind::Skeleton);
+    /// assert!(sdom.appears_loading());
+    /// ```
+    pub fn loading_indicators(&self) -> &[LoadingIndicator] {
+        &self.loading
+    }
+
+    /// Check whether the page was captured before it finished loading
+    ///
+    /// When true, content may be missing from the snapshot: wait and parse
+    /// the page again before concluding it is not there.
+    pub fn appears_loading(&self) -> bool {
+        !self.loading.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_loading_indicators() {
+        let html = r#"<main aria-busy="false"><h1>Orders</h1>
+            <progress value="1" max="1">Done</progress>
+            <div role="progressbar" aria-valuenow="40" aria-label="Sync"></div>
+            <section aria-busy="true"><ul><li>Order 1</li></ul></section>
+            <div class="loading-overlay"><span class="spinner"></span></div>
+            <div class="placeholder-glow"><span class="placeholder col-6"></span></div>
+            <p>Please wait…</p>
+            <p>Loading times vary by region, so please be patient with large exports.</p>
+            <div class="spinner" hidden></div>
+            <button class="btn-spin-slow">Spin</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let found: Vec<(LoadingKind, &str)> = sdom
+            .loading_indicators()
+            .iter()
+            .map(|i| (i.kind, i.selector.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (LoadingKind::Progress, "div"),
+                (LoadingKind::Busy, "section"),
+                (LoadingKind::Spinner, "div.loading-overlay"),
+                (LoadingKind::Skeleton, "div.placeholder-glow"),
+                (LoadingKind::Message, "p"),
+            ]
+        );
+        assert_eq!(sdom.loading_indicators()[0].label.as_deref(), Some("Sync"));
+        assert!(sdom.appears_loading());
+        let summary = sdom.to_agent_summary();
+        assert!(
+            summary.starts_with("LOADING: 1 progress, 1 busy, 1 spinner, 1 skeleton, 1 message"),
+            "{}",
+            summary
+        );
+
+        let done = SemanticDOM::parse("<main><h1>Orders</h1></main>", Config::default()).unwrap();
+        assert!(!done.appears_loading());
+        assert!(!done.to_agent_summary().contains("LOADING"));
+    }
+}
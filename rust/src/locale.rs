@@ -0,0 +1,199 @@
+//! Number and currency conventions of a page
+//!
+//! `1.299` is a thousand and more in Berlin and barely more than one in
+//! Boston. Agents reading prices or quantities off a page need to know
+//! which convention it follows, so the document's language, the
+//! currencies its prices are in and the decimal separator its numbers use
+//! are gathered into [`LocaleHints`].
+
+use crate::parser::SemanticDOM;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+
+/// Languages writing decimals with a comma (ISO 639-1)
+const DECIMAL_COMMA_LANGS: &[&str] = &[
+    "de", "fr", "es", "it", "pt", "nl", "ru", "uk", "pl", "cs", "sk", "sv", "da", "nb", "nn",
+    "no", "fi", "tr", "el", "hu", "ro", "bg", "hr", "sl", "sr", "lt", "lv", "et", "id", "vi",
+];
+
+/// Language, currency and number conventions the page follows
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleHints {
+    /// Language tag: the `lang` attribute, else `Content-Language` or
+    /// `og:locale`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Uppercased region subtag of the language tag, as in `CH` for `de-CH`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// ISO 4217 codes of the currencies prices are written in, most used
+    /// first
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub currencies: Vec<String>,
+    /// Decimal separator, `.` or `,`: from the numbers on the page when
+    /// they settle it, else from the language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimal_separator: Option<char>,
+    /// Whether the decimal separator was seen in the page's numbers rather
+    /// than assumed from its language
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub decimal_from_text: bool,
+}
+
+impl LocaleHints {
+    /// Parse a number written in the page's convention
+    ///
+    /// Grouping characters (the other separator, spaces, apostrophes) are
+    /// dropped. Without a known decimal separator, a dot is assumed.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<html lang="de-DE"><body><main>
+    ///     <p>Preis: 1.299,00 €</p>
+    /// </main></body></html>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let locale = sdom.locale_hints();
+    /// assert_eq!(locale.region.as_deref(), Some("DE"));
+    /// assert_eq!(locale.currencies, ["EUR"]);
+    /// assert_eq!(locale.decimal_separator, Some(','));
+    /// assert_eq!(locale.parse_number("1.299"), Some(1299.0));
+    /// ```
+    pub fn parse_number(&self, raw: &str) -> Option<f64> {
+        let decimal = self.decimal_separator.unwrap_or('.');
+        let normalized: String = raw
+            .trim()
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == decimal || *c == '-')
+            .map(|c| if c == decimal { '.' } else { c })
+            .collect();
+        normalized.parse().ok()
+    }
+}
+
+/// The decimal separator a written number settles, if any
+///
+/// Only unambiguous numbers count: both separators (the last one is the
+/// decimal), or one separator once followed by exactly two digits.
+fn decimal_in(raw: &str) -> Option<char> {
+    let raw = raw.trim_end_matches(['.', ',']);
+    match (raw.rfind('.'), raw.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        (Some(at), None) | (None, Some(at)) => {
+            let sep = raw[at..].chars().next()?;
+            (raw.matches(sep).count() == 1 && raw.len() - at == 3).then_some(sep)
+        }
+        (None, None) => None,
+    }
+}
+
+/// Numbers in text containing both separators, as in `1,299.00`
+fn grouped_numbers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .filter(|t| t.contains('.') && t.contains(','))
+        .filter(|t| t.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Gather locale hints from the document, given its `lang`
+pub(crate) fn locale_hints(document: &Html, lang: Option<&str>) -> LocaleHints {
+    let meta = |selector: &str, attr: &str| {
+        let selector = scraper::Selector::parse(selector).ok()?;
+        let value = document.select(&selector).next()?.value().attr(attr)?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+    let lang = lang
+        .map(str::to_string)
+        .or_else(|| meta(r#"meta[http-equiv="content-language" i]"#, "content"))
+        .or_else(|| meta(r#"meta[property="og:locale"]"#, "content"))
+        // Content-Language may list several; the first is the main one
+        .map(|l| l.split(',').next().unwrap_or_default().trim().to_string());
+    let mut subtags = lang.iter().flat_map(|l| l.split(['-', '_']));
+    let primary = subtags.next().map(str::to_ascii_lowercase);
+    let region = subtags
+        .find(|s| s.len() == 2 && s.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(str::to_ascii_uppercase);
+
+    let mut currencies: IndexMap<&str, usize> = IndexMap::new();
+    let (mut dots, mut commas) = (0, 0);
+    for node in document.root_element().descendants() {
+        let Node::Text(text) = node.value() else {
+            continue;
+        };
+        let hidden = node.ancestors().filter_map(ElementRef::wrap).any(|a| {
+            matches!(a.value().name(), "script" | "style" | "noscript" | "template")
+        });
+        if hidden {
+            continue;
+        }
+        let amounts = crate::commerce::currency_amounts(text);
+        for (code, _) in &amounts {
+            *currencies.entry(code).or_default() += 1;
+        }
+        let numbers = amounts.iter().map(|(_, raw)| *raw).chain(grouped_numbers(text));
+        for separator in numbers.filter_map(decimal_in) {
+            match separator {
+                '.' => dots += 1,
+                _ => commas += 1,
+            }
+        }
+    }
+    currencies.sort_by(|_, a, _, b| b.cmp(a));
+
+    let from_text = match dots.cmp(&commas) {
+        std::cmp::Ordering::Greater => Some('.'),
+        std::cmp::Ordering::Less => Some(','),
+        std::cmp::Ordering::Equal => None,
+    };
+    let from_lang = primary.map(|p| match DECIMAL_COMMA_LANGS.contains(&p.as_str()) {
+        true => ',',
+        false => '.',
+    });
+    LocaleHints {
+        lang,
+        region,
+        currencies: currencies.into_keys().map(str::to_string).collect(),
+        decimal_separator: from_text.or(from_lang),
+        decimal_from_text: from_text.is_some(),
+    }
+}
+
+impl SemanticDOM {
+    /// Get the language, currencies and decimal separator the page uses
+    pub fn locale_hints(&self) -> &LocaleHints {
+        &self.locale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_locale_hints() {
+        let html = r#"<html><head>
+            <meta property="og:locale" content="en_GB">
+            <script>var price = "$1,00";</script></head><body><main>
+            <p>Now £1,249.50, was £1,399.00. Ships from the US for $12.</p>
+            <p>Population 67,000,000.</p></main></body></html>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let locale = sdom.locale_hints();
+        assert_eq!(locale.lang.as_deref(), Some("en_GB"));
+        assert_eq!(locale.region.as_deref(), Some("GB"));
+        assert_eq!(locale.currencies, ["GBP", "USD"]);
+        assert_eq!(locale.decimal_separator, Some('.'));
+        assert!(locale.decimal_from_text);
+        assert_eq!(locale.parse_number("1,249.50"), Some(1249.5));
+
+        // Without numbers to go by, the language decides
+        let html = r#"<html lang="fr-CA"><body><p>Bonjour</p></body></html>"#;
+        let locale = SemanticDOM::parse(html, Config::default()).unwrap().locale_hints().clone();
+        assert_eq!(locale.decimal_separator, Some(','));
+        assert!(!locale.decimal_from_text);
+        assert_eq!(locale.parse_number("3 499,95"), Some(3499.95));
+        assert!(locale.currencies.is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+//! Agent instruction manifests
+//!
+//! Sites publish guidance for agents as an [llms.txt](https://llmstxt.org)
+//! file: a title, a one-paragraph summary and Markdown sections of links
+//! and notes. [`to_agent_manifest`] writes one from parsed pages, so the
+//! guidance lists the flows and actions the markup actually offers, with
+//! the semantic IDs agents use to reach them. What markup cannot say, such
+//! as how fast agents may crawl, comes from [`ManifestOptions`].
+
+use crate::certification::LOW_CONFIDENCE_THRESHOLD;
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode};
+use std::fmt::Write;
+
+/// Important actions listed per page
+const ACTIONS_PER_PAGE: usize = 10;
+
+/// Site facts for [`to_agent_manifest`] that the markup does not carry
+#[derive(Debug, Clone, Default)]
+pub struct ManifestOptions {
+    /// Site name for the title; defaults to the first page's title
+    pub name: Option<String>,
+    /// Summary of what the site is for; defaults to one built from the
+    /// first page's heading and the detected flows
+    pub purpose: Option<String>,
+    /// Requests per minute agents should stay under
+    pub requests_per_minute: Option<u32>,
+}
+
+/// Whether an action is worth listing: a confident intent that does more
+/// than follow a link or fill in a field
+fn is_important(node: &SemanticNode) -> bool {
+    let routine = matches!(
+        node.intent,
+        None | Some(
+            SemanticIntent::Navigate
+                | SemanticIntent::Input
+                | SemanticIntent::Select
+                | SemanticIntent::Toggle
+                | SemanticIntent::Unknown
+        )
+    );
+    !routine && node.intent_confidence.unwrap_or(0.0) >= LOW_CONFIDENCE_THRESHOLD
+}
+
+/// How a page is named in the manifest
+fn page_name(sdom: &SemanticDOM, position: usize) -> String {
+    sdom.title
+        .clone()
+        .filter(|t| !t.is_empty())
+        .or_else(|| sdom.base_url.clone())
+        .unwrap_or_else(|| format!("Page {}", position + 1))
+}
+
+/// Generate an llms.txt-style manifest for a site's pages
+///
+/// # Format
+/// ```text
+/// # Example Shop
+///
+/// > Example Shop. Agents can log in, search, add to cart.
+///
+/// ## Pages
+/// - [Example Shop](https://shop.example/): 3 landmarks, 14 actions
+///
+/// ## Key flows
+/// - login: `sdom_email`, `sdom_password`, `sdom_sign_in`
+/// - search: `sdom_q`, `sdom_search_go`
+///
+/// ## Important actions
+/// - [submit] Sign in: `sdom_sign_in`
+/// - [create] Add to cart: `sdom_add`
+///
+/// ## Rate hints
+/// - Stay under 30 requests per minute
+/// - 2 forms send POST requests; submit each once and do not retry
+/// ```
+///
+/// With several pages, flows and actions name the page they are on.
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{to_agent_manifest, Config, ManifestOptions, SemanticDOM};
+///
+/// let html = r#"<title>Docs</title><main><h1>Widget docs</h1>
+///     <form action="/search" role="search"><input name="q" data-agent-id="q"></form>
+/// </main>"#;
+/// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let options = ManifestOptions { requests_per_minute: Some(60), ..Default::default() };
+///
+/// let manifest = to_agent_manifest(&[sdom], &options);
+/// assert!(manifest.starts_with("# Docs\n\n> Widget docs. Agents can search.\n"));
+/// assert!(manifest.contains("- search: `sdom_q`\n"));
+/// assert!(manifest.contains("- Stay under 60 requests per minute\n"));
+/// ```
+pub fn to_agent_manifest(pages: &[SemanticDOM], options: &ManifestOptions) -> String {
+    let names: Vec<String> = pages.iter().enumerate().map(|(i, p)| page_name(p, i)).collect();
+    let on_page = |i: usize| match pages.len() {
+        1 => String::new(),
+        _ => format!(" ({})", names[i]),
+    };
+
+    // Flows per page: detected patterns, then the site search
+    let mut flows: Vec<(usize, &'static str, Vec<String>)> = Vec::new();
+    for (i, sdom) in pages.iter().enumerate() {
+        for pattern in sdom.patterns() {
+            flows.push((i, pattern.kind.as_str(), pattern.node_ids));
+        }
+        if let Some(entry) = sdom.search_entry() {
+            let ids = std::iter::once(entry.input_id).chain(entry.submit_id).collect();
+            flows.push((i, "search", ids));
+        }
+    }
+
+    let name = options.name.clone().or_else(|| names.first().cloned());
+    let mut out = format!("# {}\n\n", name.as_deref().unwrap_or("Site"));
+
+    let purpose = options.purpose.clone().unwrap_or_else(|| {
+        let mut tasks: Vec<String> = Vec::new();
+        for (_, kind, _) in &flows {
+            let task = match *kind {
+                "login" => "log in".to_string(),
+                "registration" => "register".to_string(),
+                "newsletter" => "subscribe to the newsletter".to_string(),
+                other => other.replace('-', " "),
+            };
+            if !tasks.contains(&task) {
+                tasks.push(task);
+            }
+        }
+        let heading = pages
+            .first()
+            .and_then(|p| p.headings.iter().filter_map(|id| p.index.get(id)).next())
+            .map(|h| h.label.clone())
+            .or_else(|| name.clone())
+            .unwrap_or_default();
+        if tasks.is_empty() {
+            heading
+        } else {
+            format!("{}. Agents can {}.", heading, tasks.join(", "))
+        }
+    });
+    let _ = writeln!(out, "> {}", purpose);
+
+    out.push_str("\n## Pages\n");
+    for (i, sdom) in pages.iter().enumerate() {
+        let link = match &sdom.base_url {
+            Some(url) => format!("[{}]({})", names[i], url),
+            None => names[i].clone(),
+        };
+        let _ = writeln!(
+            out,
+            "- {}: {} landmarks, {} actions",
+            link,
+            sdom.landmarks.len(),
+            sdom.interactables.len()
+        );
+    }
+
+    if !flows.is_empty() {
+        out.push_str("\n## Key flows\n");
+        for (i, kind, ids) in &flows {
+            let ids: Vec<String> = ids.iter().map(|id| format!("`{}`", id)).collect();
+            let _ = writeln!(out, "- {}{}: {}", kind, on_page(*i), ids.join(", "));
+        }
+    }
+
+    let mut actions = String::new();
+    for (i, sdom) in pages.iter().enumerate() {
+        let mut seen: Vec<(&SemanticIntent, &str)> = Vec::new();
+        for node in sdom.interactables.iter().filter_map(|id| sdom.index.get(id)) {
+            let Some(intent) = node.intent.as_ref().filter(|_| is_important(node)) else {
+                continue;
+            };
+            if seen.len() == ACTIONS_PER_PAGE || seen.contains(&(intent, node.label.as_str())) {
+                continue;
+            }
+            seen.push((intent, &node.label));
+            let _ = writeln!(
+                actions,
+                "- [{}] {}: `{}`{}",
+                intent.as_str(),
+                node.label,
+                node.id,
+                on_page(i)
+            );
+        }
+    }
+    if !actions.is_empty() {
+        out.push_str("\n## Important actions\n");
+        out.push_str(&actions);
+    }
+
+    let mut hints: Vec<String> = Vec::new();
+    if let Some(rpm) = options.requests_per_minute {
+        hints.push(format!("Stay under {} requests per minute", rpm));
+    }
+    // A form with several submit buttons counts once
+    let posts: usize = pages
+        .iter()
+        .map(|p| {
+            let mut forms: Vec<&str> = p
+                .form_submits
+                .iter()
+                .filter(|s| s.request.method == "POST")
+                .map(|s| s.form.as_str())
+                .collect();
+            forms.sort_unstable();
+            forms.dedup();
+            forms.len()
+        })
+        .sum();
+    match posts {
+        0 => {}
+        1 => hints.push("1 form sends a POST request; submit it once and do not retry".into()),
+        n => hints.push(format!(
+            "{} forms send POST requests; submit each once and do not retry",
+            n
+        )),
+    }
+    for (i, _) in pages.iter().enumerate().filter(|(_, p)| p.requires_js()) {
+        hints.push(format!("{} renders content with JavaScript; fetch it rendered", names[i]));
+    }
+    if !hints.is_empty() {
+        out.push_str("\n## Rate hints\n");
+        for hint in hints {
+            let _ = writeln!(out, "- {}", hint);
+        }
+    }
+    out
+}
+
+impl SemanticDOM {
+    /// Generate an llms.txt-style manifest for this page alone
+    ///
+    /// See [`to_agent_manifest`](crate::to_agent_manifest) for the format.
+    pub fn to_agent_manifest(&self) -> String {
+        to_agent_manifest(std::slice::from_ref(self), &ManifestOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_manifest_across_pages() {
+        let shop = r#"<html><head><title>Shop</title></head><body><main><h1>Gadgets</h1>
+            <form method="post" action="/cart"><button data-agent-id="add">Add to cart</button>
+            </form><button>Frobnicate</button>
+            <a href="/manual.pdf" download data-agent-id="manual">Manual</a></main></body></html>"#;
+        let login = r#"<title>Sign in</title><form method="post" action="/session">
+            <input type="email" name="email" data-agent-id="email">
+            <input type="password" name="pw" data-agent-id="pw">
+            <button type="submit" data-agent-id="go">Sign in</button></form>"#;
+        let config = Config::builder().base_url("https://shop.example/").build().unwrap();
+        let pages = [
+            SemanticDOM::parse(shop, config.clone()).unwrap(),
+            SemanticDOM::parse(login, Config::default()).unwrap(),
+        ];
+        let manifest = to_agent_manifest(&pages, &ManifestOptions::default());
+
+        assert!(manifest.starts_with("# Shop\n\n> Gadgets. Agents can add to cart, log in.\n"));
+        assert!(manifest.contains("- [Shop](https://shop.example/): 1 landmarks, 3 actions\n"));
+        assert!(manifest.contains("- Sign in: 0 landmarks, 3 actions\n"));
+        assert!(manifest.contains("- login (Sign in): `sdom_email`, `sdom_pw`, `sdom_go`\n"));
+        assert!(manifest.contains("- [download] Manual: `sdom_manual` (Shop)\n"));
+        // The guessed "Frobnicate" action is left out
+        assert!(!manifest.contains("Frobnicate"), "{}", manifest);
+        assert!(manifest.ends_with(
+            "## Rate hints\n- 2 forms send POST requests; submit each once and do not retry\n"
+        ));
+
+        let single = pages[1].to_agent_manifest();
+        assert!(single.contains("- login: `sdom_email`"), "{}", single);
+    }
+}
@@ -0,0 +1,139 @@
+//! Parse metrics and optional `tracing` instrumentation
+//!
+//! With the `tracing` feature enabled, parsing, certification and
+//! serialization each run inside a `semantic_dom` span whose `op` field
+//! names the operation, and record `count` (nodes, checks or output bytes)
+//! and `elapsed_us` when they finish. Without the feature the
+//! instrumentation compiles away.
+//!
+//! [`SemanticDOM::parse_with_metrics`] reports timings directly, for
+//! services that don't use `tracing`.
+
+use crate::parser::SemanticDOM;
+use crate::{Config, Error, Result};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Size and timing figures for one parse
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metrics {
+    /// Input size in bytes
+    pub input_bytes: usize,
+    /// Semantic nodes indexed
+    pub node_count: usize,
+    /// Landmark nodes
+    pub landmark_count: usize,
+    /// Interactable nodes
+    pub interactable_count: usize,
+    /// State-graph transitions
+    pub transition_count: usize,
+    /// Time spent building the HTML tree
+    pub html_parse: Duration,
+    /// Time spent building the semantic representation
+    pub semantic_build: Duration,
+    /// Total parse time
+    pub total: Duration,
+}
+
+impl Metrics {
+    /// Input throughput in bytes per second, if any time was measured
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let secs = self.total.as_secs_f64();
+        (secs > 0.0).then(|| self.input_bytes as f64 / secs)
+    }
+}
+
+/// Run `f`, recording it as a `tracing` span when the feature is enabled
+///
+/// `count` extracts a size figure from the result for the span.
+#[cfg(feature = "tracing")]
+pub(crate) fn instrument<T>(
+    op: &'static str,
+    f: impl FnOnce() -> T,
+    count: impl Fn(&T) -> usize,
+) -> T {
+    use tracing::field::Empty;
+
+    let span = tracing::debug_span!("semantic_dom", op, count = Empty, elapsed_us = Empty);
+    let _entered = span.enter();
+    let start = Instant::now();
+    let result = f();
+    span.record("count", count(&result));
+    span.record("elapsed_us", start.elapsed().as_micros() as u64);
+    result
+}
+
+/// Run `f`; a no-op wrapper without the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn instrument<T>(
+    _op: &'static str,
+    f: impl FnOnce() -> T,
+    _count: impl Fn(&T) -> usize,
+) -> T {
+    f()
+}
+
+impl SemanticDOM {
+    /// Parse an HTML document and report how long each stage took
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = "<nav><a href=\"/\">Home</a></nav><main><button>Buy</button></main>";
+    /// let (sdom, metrics) = SemanticDOM::parse_with_metrics(html, Config::default()).unwrap();
+    ///
+    /// assert_eq!(metrics.node_count, sdom.index.len());
+    /// assert_eq!(metrics.interactable_count, 2);
+    /// assert!(metrics.total >= metrics.html_parse);
+    /// ```
+    pub fn parse_with_metrics(html: &str, config: Config) -> Result<(Self, Metrics)> {
+        if html.len() > config.max_input_size {
+            return Err(Error::InputTooLarge {
+                max_size: config.max_input_size,
+                actual_size: html.len(),
+            });
+        }
+
+        let start = Instant::now();
+        let document = Html::parse_document(html);
+        let html_parse = start.elapsed();
+        let sdom = Self::from_document(document, Some(html), config)?;
+        let total = start.elapsed();
+
+        let metrics = Metrics {
+            input_bytes: html.len(),
+            node_count: sdom.index.len(),
+            landmark_count: sdom.landmarks.len(),
+            interactable_count: sdom.interactables.len(),
+            transition_count: sdom.state_graph.transitions.len(),
+            html_parse,
+            semantic_build: total.saturating_sub(html_parse),
+            total,
+        };
+        Ok((sdom, metrics))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_match_parse() {
+        let html = "<main><h1>Title</h1><a href=\"/a\">A</a><a href=\"/b\">B</a></main>";
+        let (sdom, metrics) = SemanticDOM::parse_with_metrics(html, Config::default()).unwrap();
+        let plain = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        assert_eq!(sdom.index.len(), plain.index.len());
+        assert_eq!(metrics.input_bytes, html.len());
+        assert_eq!(metrics.landmark_count, 1);
+        assert_eq!(metrics.transition_count, plain.state_graph.transitions.len());
+        assert_eq!(metrics.total, metrics.html_parse + metrics.semantic_build);
+
+        let config = Config { max_input_size: 10, ..Config::default() };
+        assert!(SemanticDOM::parse_with_metrics(html, config).is_err());
+    }
+}
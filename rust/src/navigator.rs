@@ -0,0 +1,193 @@
+//! Cursor-based navigation over the semantic tree
+//!
+//! Mirrors screen-reader quick navigation (next landmark, next heading,
+//! next form control) so agent frameworks can step through a page
+//! deterministically instead of picking nodes out of the index.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use crate::{Error, Result};
+
+/// A cursor that moves through a document's nodes in document order
+///
+/// Movement methods return the node the cursor moved to, or `None` if
+/// there is no matching node in that direction, in which case the cursor
+/// stays where it was. Navigation does not wrap around.
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Config, SemanticDOM};
+///
+/// let html = r#"
+///     <nav aria-label="Site"><a href="/">Home</a></nav>
+///     <main aria-label="Content"><h1>Title</h1><h2>Part</h2><button>Go</button></main>
+/// "#;
+/// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let mut nav = sdom.navigator();
+///
+/// assert_eq!(nav.next_landmark().unwrap().label, "Site");
+/// assert_eq!(nav.next_landmark().unwrap().label, "Content");
+/// assert_eq!(nav.next_heading(Some(2)).unwrap().label, "Part");
+/// assert_eq!(nav.next_interactable().unwrap().label, "Go");
+/// assert!(nav.next_interactable().is_none());
+/// assert_eq!(nav.current().unwrap().label, "Go");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Navigator<'a> {
+    sdom: &'a SemanticDOM,
+    /// Position in document order, `None` before the first node
+    cursor: Option<usize>,
+}
+
+impl<'a> Navigator<'a> {
+    /// Create a navigator positioned before the first node
+    pub fn new(sdom: &'a SemanticDOM) -> Self {
+        Self { sdom, cursor: None }
+    }
+
+    /// The node under the cursor, if the cursor has moved
+    pub fn current(&self) -> Option<&'a SemanticNode> {
+        self.cursor.and_then(|i| self.node_at(i))
+    }
+
+    /// Move the cursor back before the first node
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Move to the next landmark region
+    pub fn next_landmark(&mut self) -> Option<&'a SemanticNode> {
+        self.seek(true, |n| n.role.is_landmark())
+    }
+
+    /// Move to the previous landmark region
+    pub fn prev_landmark(&mut self) -> Option<&'a SemanticNode> {
+        self.seek(false, |n| n.role.is_landmark())
+    }
+
+    /// Move to the next heading, optionally only headings of `level`
+    pub fn next_heading(&mut self, level: Option<u8>) -> Option<&'a SemanticNode> {
+        self.seek(true, |n| is_heading(n, level))
+    }
+
+    /// Move to the previous heading, optionally only headings of `level`
+    pub fn prev_heading(&mut self, level: Option<u8>) -> Option<&'a SemanticNode> {
+        self.seek(false, |n| is_heading(n, level))
+    }
+
+    /// Move to the next interactive element
+    pub fn next_interactable(&mut self) -> Option<&'a SemanticNode> {
+        self.seek(true, |n| n.role.is_interactable())
+    }
+
+    /// Move to the previous interactive element
+    pub fn prev_interactable(&mut self) -> Option<&'a SemanticNode> {
+        self.seek(false, |n| n.role.is_interactable())
+    }
+
+    /// Move the cursor to a specific node
+    ///
+    /// # Errors
+    /// - [`Error::NodeNotFound`] if no node has the given ID
+    pub fn jump_to(&mut self, id: &str) -> Result<&'a SemanticNode> {
+        let position = self
+            .sdom
+            .order
+            .iter()
+            .position(|o| o == id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))?;
+        self.cursor = Some(position);
+        self.node_at(position)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))
+    }
+
+    fn node_at(&self, position: usize) -> Option<&'a SemanticNode> {
+        self.sdom
+            .order
+            .get(position)
+            .and_then(|id| self.sdom.index.get(id))
+    }
+
+    /// Scan from the cursor in one direction for the first matching node
+    fn seek(
+        &mut self,
+        forward: bool,
+        matches: impl Fn(&SemanticNode) -> bool,
+    ) -> Option<&'a SemanticNode> {
+        let len = self.sdom.order.len();
+        let candidates: Box<dyn Iterator<Item = usize>> = match (forward, self.cursor) {
+            (true, None) => Box::new(0..len),
+            (true, Some(c)) => Box::new(c + 1..len),
+            (false, None) => Box::new((0..len).rev()),
+            (false, Some(c)) => Box::new((0..c).rev()),
+        };
+
+        for position in candidates {
+            if let Some(node) = self.node_at(position).filter(|n| matches(n)) {
+                self.cursor = Some(position);
+                return Some(node);
+            }
+        }
+        None
+    }
+}
+
+fn is_heading(node: &SemanticNode, level: Option<u8>) -> bool {
+    matches!(node.role, SemanticRole::Heading) && (level.is_none() || node.level == level)
+}
+
+impl SemanticDOM {
+    /// Create a [`Navigator`] positioned before the first node
+    pub fn navigator(&self) -> Navigator<'_> {
+        Navigator::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, Error, SemanticDOM};
+
+    const HTML: &str = r#"
+        <header aria-label="Banner"><a href="/">Logo</a></header>
+        <main aria-label="Main">
+            <h1>Title</h1>
+            <h2>Intro</h2>
+            <button>One</button>
+            <div role="heading" aria-level="3">Aria heading</div>
+            <h2>Details</h2>
+            <button>Two</button>
+        </main>
+        <footer aria-label="Footer"><a href="/about">About</a></footer>
+    "#;
+
+    #[test]
+    fn test_heading_levels_and_backwards() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let mut nav = sdom.navigator();
+
+        assert_eq!(nav.next_heading(Some(2)).unwrap().label, "Intro");
+        assert_eq!(nav.next_heading(Some(2)).unwrap().label, "Details");
+        assert!(nav.next_heading(Some(2)).is_none());
+        assert_eq!(nav.prev_heading(Some(3)).unwrap().label, "Aria heading");
+        assert_eq!(nav.prev_heading(None).unwrap().label, "Intro");
+        assert_eq!(nav.prev_landmark().unwrap().label, "Main");
+    }
+
+    #[test]
+    fn test_jump_to_and_backwards_from_start() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let mut nav = sdom.navigator();
+
+        // Backwards from the start begins at the end of the document
+        assert_eq!(nav.prev_landmark().unwrap().label, "Footer");
+
+        let two = sdom.interactables[2].clone();
+        assert_eq!(nav.jump_to(&two).unwrap().label, "Two");
+        assert_eq!(nav.prev_interactable().unwrap().label, "One");
+        assert_eq!(nav.next_landmark().unwrap().label, "Footer");
+
+        assert!(matches!(nav.jump_to("missing"), Err(Error::NodeNotFound(_))));
+        nav.reset();
+        assert!(nav.current().is_none());
+    }
+}
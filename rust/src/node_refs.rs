@@ -0,0 +1,299 @@
+//! Node IDs held outside the index
+//!
+//! Widgets, relations, the state graph, warnings and diagnostics refer to
+//! nodes by ID. Renaming nodes ([`SemanticDOM::update`],
+//! [`SemanticDOM::merge`]) and removing them ([`SemanticDOM::remove_node`],
+//! [`SemanticDOM::subtree`]) both go through [`NodeRefs`], so each kind of
+//! reference is handled in one place.
+
+use crate::clusters::{Cluster, MIN_CLUSTER_SIZE};
+use crate::combobox::Combobox;
+use crate::commerce::ProductInfo;
+use crate::composites::{Grid, GridCell, GridRow, Tree, TreeItem};
+use crate::diagnostics::Diagnostic;
+use crate::facets::{Facet, FacetOption, Facets};
+use crate::forms::FormSubmit;
+use crate::loading::LoadingIndicator;
+use crate::notifications::Notification;
+use crate::parser::SemanticDOM;
+use crate::relations::Relation;
+use crate::results::{ResultItem, ResultList};
+use crate::types::{FormField, FormRequest, State, StateGraph, Transition};
+use crate::Error;
+
+/// Maps a node ID to its new ID, or to `None` if the node is gone
+pub(crate) type MapId<'a> = dyn FnMut(&str) -> Option<String> + 'a;
+
+/// Prefixes of state IDs made from a node ID, as in `state_open_{id}`
+const NODE_STATE_PREFIXES: &[&str] = &[
+    "state_open_",
+    "state_expanded_",
+    "state_collapsed_",
+    "state_at_",
+    "state_file_selected_",
+    "state_uploaded_",
+];
+
+/// A value holding node IDs
+pub(crate) trait NodeRefs {
+    /// Map every node ID held, returning `false` if the value no longer
+    /// makes sense because a node it needs is gone
+    fn map_ids(&mut self, map: &mut MapId) -> bool;
+}
+
+/// Map an ID the value cannot do without
+fn required(id: &mut String, map: &mut MapId) -> bool {
+    match map(id) {
+        Some(new) => {
+            *id = new;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Map an ID the value cannot do without once it is set
+fn owning(id: &mut Option<String>, map: &mut MapId) -> bool {
+    id.as_mut().map_or(true, |id| required(id, map))
+}
+
+/// Map an ID that is unset when its node is gone
+fn optional(id: &mut Option<String>, map: &mut MapId) {
+    if let Some(old) = id.take() {
+        *id = map(&old);
+    }
+}
+
+/// Map the values of a list, returning `false` if it lost them all
+fn retain<T: NodeRefs>(items: &mut Vec<T>, map: &mut MapId) -> bool {
+    let before = items.len();
+    items.retain_mut(|item| item.map_ids(map));
+    before == 0 || !items.is_empty()
+}
+
+/// Map a state named after a node
+fn state_id(id: &mut String, map: &mut MapId) -> bool {
+    let Some((prefix, node)) = NODE_STATE_PREFIXES
+        .iter()
+        .find_map(|prefix| Some((*prefix, id.strip_prefix(prefix)?)))
+    else {
+        return true;
+    };
+    match map(node) {
+        Some(new) => {
+            *id = format!("{}{}", prefix, new);
+            true
+        }
+        None => false,
+    }
+}
+
+impl NodeRefs for String {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(self, map)
+    }
+}
+
+impl NodeRefs for ProductInfo {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.add_to_cart_id, map)
+    }
+}
+
+impl NodeRefs for Relation {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.from, map) && owning(&mut self.to, map)
+    }
+}
+
+impl NodeRefs for Combobox {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        optional(&mut self.popup_id, map);
+        required(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for TreeItem {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.id, map) && {
+            retain(&mut self.children, map);
+            true
+        }
+    }
+}
+
+impl NodeRefs for Tree {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.id, map) && {
+            retain(&mut self.items, map);
+            true
+        }
+    }
+}
+
+impl NodeRefs for GridCell {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for GridRow {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map) && retain(&mut self.cells, map)
+    }
+}
+
+impl NodeRefs for Grid {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.id, map) && {
+            retain(&mut self.rows, map);
+            true
+        }
+    }
+}
+
+impl NodeRefs for Notification {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for ResultItem {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for ResultList {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        optional(&mut self.id, map);
+        retain(&mut self.items, map)
+    }
+}
+
+impl NodeRefs for Vec<String> {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        retain(self, map)
+    }
+}
+
+impl NodeRefs for Cluster {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        optional(&mut self.container, map);
+        let before = self.count();
+        self.instances.retain_mut(|instance| instance.map_ids(map));
+        self.count() == before || self.count() >= MIN_CLUSTER_SIZE
+    }
+}
+
+impl NodeRefs for LoadingIndicator {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for FacetOption {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map)
+    }
+}
+
+impl NodeRefs for Facet {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.id, map) && retain(&mut self.options, map)
+    }
+}
+
+impl NodeRefs for Facets {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        retain(&mut self.sort, map);
+        retain(&mut self.filters, map);
+        true
+    }
+}
+
+impl NodeRefs for FormField {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.node, map)
+    }
+}
+
+impl NodeRefs for FormRequest {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        retain(&mut self.fields, map);
+        true
+    }
+}
+
+impl NodeRefs for FormSubmit {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.trigger, map)
+            && required(&mut self.form, map)
+            && self.request.map_ids(map)
+    }
+}
+
+impl NodeRefs for State {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        state_id(&mut self.id, map) && owning(&mut self.anchor, map)
+    }
+}
+
+impl NodeRefs for Transition {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        required(&mut self.trigger, map)
+            && state_id(&mut self.from, map)
+            && state_id(&mut self.to, map)
+            && self.request.as_mut().map_or(true, |request| request.map_ids(map))
+    }
+}
+
+impl NodeRefs for StateGraph {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        retain(&mut self.states, map);
+        retain(&mut self.transitions, map);
+        true
+    }
+}
+
+impl NodeRefs for Error {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        match self {
+            Error::DepthExceeded { node, .. }
+            | Error::UrlRejected { node, .. }
+            | Error::BrokenReference { node, .. } => required(node, map),
+            _ => true,
+        }
+    }
+}
+
+impl NodeRefs for Diagnostic {
+    fn map_ids(&mut self, map: &mut MapId) -> bool {
+        owning(&mut self.node, map)
+    }
+}
+
+impl SemanticDOM {
+    /// Map the node IDs held outside the index, dropping whatever needs a
+    /// node that is gone
+    ///
+    /// The index, the document order and the category lists are left to
+    /// the caller.
+    pub(crate) fn map_node_refs(&mut self, map: &mut MapId) {
+        retain(&mut self.products, map);
+        retain(&mut self.relations, map);
+        retain(&mut self.comboboxes, map);
+        retain(&mut self.trees, map);
+        retain(&mut self.grids, map);
+        retain(&mut self.notifications, map);
+        retain(&mut self.results, map);
+        retain(&mut self.clusters, map);
+        retain(&mut self.loading, map);
+        self.facets.map_ids(map);
+        self.anchors.retain(|_, target| required(target, map));
+        retain(&mut self.form_submits, map);
+        self.state_graph.map_ids(map);
+        retain(&mut self.warnings, map);
+        self.diagnostics.retain(|diagnostic| diagnostic.map_ids(map));
+    }
+}
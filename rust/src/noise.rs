@@ -0,0 +1,205 @@
+//! Ads, social widgets and cookie-vendor markup
+//!
+//! Much of a page's markup is there for someone other than the reader:
+//! ad slots, rows of share buttons, consent-vendor preference centers.
+//! Their links and toggles crowd out the controls an agent is after.
+//! With [`Config::noise_filters`](crate::Config::noise_filters), such
+//! regions are dropped like excluded tags or demoted: kept as nodes marked
+//! `metadata["noise"]`, but left out of the interactables and summaries.
+//! Each region filtered is reported as a
+//! [`FilteredNoise`](crate::DiagnosticKind::FilteredNoise) diagnostic and
+//! counted in [`SemanticDOM::excluded_count`], so nothing disappears
+//! silently. The consent banner itself is never noise: agents have to
+//! dismiss it.
+
+use crate::parser::SemanticDOM;
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// Metadata key marking a node as demoted noise
+pub(crate) const NOISE_KEY: &str = "noise";
+
+/// Kind of noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoiseKind {
+    /// Ad slots and ad iframes
+    Ad,
+    /// Share and follow button rows
+    Social,
+    /// Consent-vendor preference centers and floating settings buttons
+    CookieVendor,
+}
+
+impl NoiseKind {
+    /// All kinds, in the order summaries list them
+    pub const ALL: [NoiseKind; 3] =
+        [NoiseKind::Ad, NoiseKind::Social, NoiseKind::CookieVendor];
+
+    /// Name stored in node metadata and used in summaries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoiseKind::Ad => "ad",
+            NoiseKind::Social => "social",
+            NoiseKind::CookieVendor => "cookie-vendor",
+        }
+    }
+}
+
+/// What to do with a kind of noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseAction {
+    /// Treat it like any other markup
+    Keep,
+    /// Keep its nodes, but not among the interactables
+    Demote,
+    /// Skip it along with its subtree
+    Drop,
+}
+
+/// `id`/class names of ad slots, compared whole
+const AD_TOKENS: &[&str] = &[
+    "ad", "ads", "advert", "advertisement", "ad-slot", "ad-unit", "ad-container", "ad-banner",
+    "sponsored", "adsbygoogle", "google_ads",
+];
+
+/// Substrings of `id`/class names of ad slots
+const AD_MARKERS: &[&str] = &["gpt-ad", "dfp-ad", "taboola", "outbrain"];
+
+/// Hosts serving ads into iframes
+const AD_HOSTS: &[&str] = &[
+    "doubleclick.net", "googlesyndication.com", "amazon-adsystem.com", "adnxs.com", "criteo.",
+];
+
+/// Substrings of `id`/class names of share widgets
+const SOCIAL_MARKERS: &[&str] = &[
+    "share-", "-share", "sharing", "social", "addthis", "sharethis", "shareaholic", "a2a_kit",
+];
+
+/// Share endpoints of social networks
+const SHARE_URLS: &[&str] = &[
+    "facebook.com/sharer", "twitter.com/intent", "x.com/intent", "linkedin.com/shar",
+    "pinterest.com/pin/create", "reddit.com/submit", "api.whatsapp.com/send", "t.me/share",
+];
+
+/// Substrings of `id`/class names of consent-vendor extras
+const COOKIE_VENDOR_MARKERS: &[&str] = &[
+    "onetrust-pc", "ot-pc-", "ot-sdk-btn-floating", "ot-floating-button",
+    "cybotcookiebotdialogdetail", "qc-cmp2-persistent-link", "osano-cm-widget",
+    "cky-preference-center", "cky-btn-revisit",
+];
+
+/// Lowercased `id` and class names of an element
+fn attr_tokens(element: ElementRef<'_>) -> impl Iterator<Item = String> + '_ {
+    let el = element.value();
+    el.id().into_iter().chain(el.classes()).map(str::to_ascii_lowercase)
+}
+
+/// Classify an element as the root of a noise region
+pub(crate) fn noise_kind(element: ElementRef) -> Option<NoiseKind> {
+    let el = element.value();
+    let tokens: Vec<String> = attr_tokens(element).collect();
+    let has = |markers: &[&str]| tokens.iter().any(|t| markers.iter().any(|m| t.contains(m)));
+
+    if has(COOKIE_VENDOR_MARKERS) {
+        return Some(NoiseKind::CookieVendor);
+    }
+    let ad_iframe = el.name() == "iframe"
+        && el.attr("src").is_some_and(|src| AD_HOSTS.iter().any(|h| src.contains(h)));
+    if ad_iframe || has(AD_MARKERS) || tokens.iter().any(|t| AD_TOKENS.contains(&t.as_str())) {
+        return Some(NoiseKind::Ad);
+    }
+    if has(SOCIAL_MARKERS) || is_share_row(element) {
+        return Some(NoiseKind::Social);
+    }
+    None
+}
+
+/// Whether all links in an element, two or more, share the page
+///
+/// A single share link is an action; a row of them is a widget.
+fn is_share_row(element: ElementRef) -> bool {
+    let mut shares = 0;
+    for el in element.descendants().filter_map(ElementRef::wrap) {
+        let Some(href) = el.value().attr("href").filter(|_| el.value().name() == "a") else {
+            continue;
+        };
+        if !SHARE_URLS.iter().any(|u| href.contains(u)) {
+            return false;
+        }
+        shares += 1;
+    }
+    shares >= 2
+}
+
+impl SemanticDOM {
+    /// Number of noise regions dropped or demoted by
+    /// [`Config::noise_filters`](crate::Config::noise_filters)
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, NoiseAction, NoiseKind, SemanticDOM};
+    ///
+    /// let config = Config::builder()
+    ///     .noise_filter(NoiseKind::Ad, NoiseAction::Drop)
+    ///     .noise_filter(NoiseKind::Social, NoiseAction::Demote)
+    ///     .build()
+    ///     .unwrap();
+    /// let html = r#"<main><button>Checkout</button>
+    ///     <div class="ad"><a href="https://ads.example/click">Win a prize</a></div>
+    ///     <div class="share-buttons"><button>Share</button><button>Tweet</button></div>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// assert_eq!(sdom.excluded_count(), 2);
+    /// assert_eq!(sdom.interactables.len(), 1);
+    /// assert!(sdom.to_agent_summary().contains("EXCLUDED: 1 ad, 1 social"));
+    /// ```
+    pub fn excluded_count(&self) -> usize {
+        self.noise.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, DiagnosticKind};
+
+    #[test]
+    fn test_noise_filters() {
+        let html = r#"<div id="onetrust-consent-sdk"><div id="onetrust-banner-sdk">We use
+              cookies <button>Accept all</button></div>
+            <div id="onetrust-pc-sdk"><input type="checkbox"><button>Confirm</button></div></div>
+            <main><header class="header"><a href="/">Home</a></header>
+            <iframe src="https://tpc.googlesyndication.com/safeframe"></iframe>
+            <div class="ad-slot"><a href="/promo">Promo</a></div>
+            <ul><li><a href="https://www.facebook.com/sharer/sharer.php?u=x">Facebook</a></li>
+            <li><a href="https://twitter.com/intent/tweet?url=x">Twitter</a></li></ul>
+            <a href="https://twitter.com/intent/tweet?url=y">Tweet this</a></main>"#;
+        let kept = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert_eq!(kept.excluded_count(), 0);
+        assert_eq!(kept.interactables.len(), 8);
+
+        let config = Config::builder()
+            .noise_filter(NoiseKind::Ad, NoiseAction::Drop)
+            .noise_filter(NoiseKind::Social, NoiseAction::Demote)
+            .noise_filter(NoiseKind::CookieVendor, NoiseAction::Drop)
+            .build()
+            .unwrap();
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+        let labels: Vec<&str> =
+            sdom.get_interactables().iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, ["Accept all", "Home", "Tweet this"]);
+        // The consent banner is still there to dismiss
+        assert_eq!(sdom.blocking_overlays().len(), 1);
+        // Demoted links stay in the tree
+        let facebook = sdom.index.values().find(|n| n.label == "Facebook").unwrap();
+        assert_eq!(facebook.metadata.as_ref().unwrap()[NOISE_KEY], "social");
+
+        assert_eq!(sdom.excluded_count(), 4);
+        let filtered = sdom.diagnostics().of_kind(DiagnosticKind::FilteredNoise).count();
+        assert_eq!(filtered, 4);
+        assert!(sdom.to_agent_summary().contains("\nEXCLUDED: 2 ad, 1 social, 1 cookie-vendor"));
+    }
+}
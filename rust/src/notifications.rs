@@ -0,0 +1,235 @@
+//! Toast and snackbar notification areas
+//!
+//! Pages confirm or reject an action with a short message in a live region
+//! or a fixed-position toast container. These areas are kept apart from
+//! dialogs: they do not block the page, and they are often empty until
+//! something happens, so the container is reported either way and a
+//! re-parse after an action shows the messages in it.
+
+use crate::parser::SemanticDOM;
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Substrings of `id`/`class` used by toast and snackbar libraries
+const TOAST_MARKERS: &[&str] = &[
+    "toast", "snackbar", "notification", "notistack", "notyf", "growl", "flash-message",
+    "flash_message", "sonner",
+];
+
+/// How urgently assistive technology announces changes (`aria-live`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Politeness {
+    /// Announced when the user is idle
+    Polite,
+    /// Announced immediately
+    Assertive,
+}
+
+/// What a notification reports, from its classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationTone {
+    /// The action succeeded
+    Success,
+    /// The action failed
+    Error,
+    /// A warning
+    Warning,
+    /// Neutral information
+    Info,
+}
+
+impl NotificationTone {
+    /// Canonical lowercase name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationTone::Success => "success",
+            NotificationTone::Error => "error",
+            NotificationTone::Warning => "warning",
+            NotificationTone::Info => "info",
+        }
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        let tone = if ["success", "positive", "saved"].iter().any(|m| token.contains(m)) {
+            NotificationTone::Success
+        } else if ["error", "danger", "fail", "negative"].iter().any(|m| token.contains(m)) {
+            NotificationTone::Error
+        } else if token.contains("warn") {
+            NotificationTone::Warning
+        } else if token.contains("info") {
+            NotificationTone::Info
+        } else {
+            return None;
+        };
+        Some(tone)
+    }
+}
+
+/// A toast container, snackbar or status region
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// CSS selector for the container, to re-query after an action
+    pub selector: String,
+    /// ID of the container node, when it became one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// How the region is announced
+    pub politeness: Politeness,
+    /// What the current messages report, when the markup says
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tone: Option<NotificationTone>,
+    /// Messages currently shown, one per toast
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub messages: Vec<String>,
+}
+
+/// Lowercased `id` and class names of an element
+fn tokens(element: ElementRef<'_>) -> impl Iterator<Item = String> + '_ {
+    let el = element.value();
+    el.id().into_iter().chain(el.classes()).map(str::to_ascii_lowercase)
+}
+
+fn role(element: ElementRef) -> Option<String> {
+    element.value().attr("role").map(|r| r.trim().to_ascii_lowercase())
+}
+
+fn is_toast(element: ElementRef) -> bool {
+    tokens(element).any(|t| TOAST_MARKERS.iter().any(|m| t.contains(m)))
+}
+
+/// Announcement politeness of a live region, `None` if it is not one
+fn live_politeness(element: ElementRef) -> Option<Politeness> {
+    match element.value().attr("aria-live").map(|l| l.trim().to_ascii_lowercase()) {
+        Some(live) if live == "assertive" => Some(Politeness::Assertive),
+        Some(live) if live == "polite" => Some(Politeness::Polite),
+        Some(_) => None,
+        None => match role(element).as_deref() {
+            Some("alert") => Some(Politeness::Assertive),
+            Some("status") => Some(Politeness::Polite),
+            _ => None,
+        },
+    }
+}
+
+/// Check whether an element is a notification area
+///
+/// Live regions inside forms are usually field errors, and dialogs block
+/// the page, so neither counts unless it carries a toast class.
+pub(crate) fn is_notification(element: ElementRef) -> bool {
+    if is_toast(element) {
+        return true;
+    }
+    if live_politeness(element).is_none() {
+        return false;
+    }
+    let dialog = element.value().name() == "dialog"
+        || matches!(role(element).as_deref(), Some("dialog" | "alertdialog"));
+    let in_form = element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|a| a.value().name() == "form");
+    !dialog && !in_form
+}
+
+fn text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Find notification areas, given the node created for each element
+pub(crate) fn notifications(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+) -> Vec<Notification> {
+    document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| is_notification(*e))
+        // Report only the outermost area; toasts nest inside containers
+        .filter(|e| !e.ancestors().filter_map(ElementRef::wrap).any(is_notification))
+        .map(|container| {
+            let children: Vec<ElementRef> =
+                container.children().filter_map(ElementRef::wrap).collect();
+            let messages = match children.len() {
+                0 | 1 => text(container).into_iter().collect(),
+                _ => children.into_iter().filter_map(text).collect(),
+            };
+            let tone = std::iter::once(container)
+                .chain(container.descendants().filter_map(ElementRef::wrap))
+                .flat_map(|e| tokens(e).collect::<Vec<_>>())
+                .find_map(|t| NotificationTone::from_token(&t));
+            Notification {
+                selector: SemanticDOM::build_element_selector(container.value()),
+                id: nodes.get(&container.id()).cloned(),
+                politeness: live_politeness(container).unwrap_or(Politeness::Polite),
+                tone,
+                messages,
+            }
+        })
+        .collect()
+}
+
+impl SemanticDOM {
+    /// Get the toast, snackbar and status areas on the page
+    ///
+    /// Empty containers are included: after acting, re-parse the page (or
+    /// use [`update`](Self::update)) and check their messages.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, NotificationTone, SemanticDOM};
+    ///
+    /// let html = r#"<main><button>Add to cart</button></main>
+    /// <div id="toasts" aria-live="polite">
+    ///     <div class="toast toast-success">Added to cart</div>
+    /// </div>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let toasts = sdom.notifications();
+    /// assert_eq!(toasts[0].selector, "div#toasts");
+    /// assert_eq!(toasts[0].tone, Some(NotificationTone::Success));
+    /// assert_eq!(toasts[0].messages, ["Added to cart"]);
+    /// ```
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, OverlayKind};
+
+    #[test]
+    fn test_notification_areas() {
+        let html = r#"<main>
+            <form><input aria-label="Email"><p role="alert">Enter an email</p></form>
+            <div role="status"></div>
+        </main>
+        <div class="snackbar" style="position: fixed; bottom: 0">
+            <span>Privacy settings saved</span><span>Undo?</span>
+        </div>
+        <div role="alert" class="flash-message flash-error">Payment failed</div>
+        <dialog open aria-live="polite"><button>Close</button></dialog>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let areas = sdom.notifications();
+        assert_eq!(areas.len(), 3);
+        assert!(areas[0].messages.is_empty());
+        assert_eq!(areas[1].messages, ["Privacy settings saved", "Undo?"]);
+        assert_eq!(areas[1].tone, None);
+        assert_eq!(areas[2].politeness, Politeness::Assertive);
+        assert_eq!(areas[2].tone, Some(NotificationTone::Error));
+
+        // A fixed snackbar mentioning privacy is not a consent banner
+        let overlays = sdom.blocking_overlays();
+        assert_eq!(overlays.len(), 1);
+        assert_eq!(overlays[0].kind, OverlayKind::Modal);
+    }
+}
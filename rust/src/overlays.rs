@@ -0,0 +1,241 @@
+//! Cookie-banner, consent-dialog and modal detection
+//!
+//! Agents usually have to dismiss these before anything else on the page
+//! responds, so they are surfaced along with the buttons that dismiss them.
+//! Detected elements are tagged with `metadata["overlay"]` during parsing.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode, SemanticRole};
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// Metadata key marking a node as a blocking overlay
+pub(crate) const OVERLAY_KEY: &str = "overlay";
+
+/// Kind of blocking overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayKind {
+    /// Cookie or privacy consent banner/dialog
+    Consent,
+    /// Open modal dialog
+    Modal,
+}
+
+impl OverlayKind {
+    /// Name stored in node metadata and used in summaries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverlayKind::Consent => "consent",
+            OverlayKind::Modal => "modal",
+        }
+    }
+}
+
+/// An overlay that blocks interaction with the rest of the page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockingOverlay {
+    /// ID of the overlay node
+    pub id: String,
+    /// What kind of overlay this is
+    pub kind: OverlayKind,
+    /// Button that accepts/agrees (consent banners)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_id: Option<String>,
+    /// Button that rejects/declines (consent banners)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_id: Option<String>,
+    /// Button that closes the overlay without choosing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_id: Option<String>,
+}
+
+/// Substrings of `id`/`class` used by common consent management platforms
+const CONSENT_MARKERS: &[&str] = &[
+    "cookie",
+    "consent",
+    "gdpr",
+    "onetrust",
+    "cookiebot",
+    "didomi",
+    "truste",
+    "qc-cmp",
+    "usercentrics",
+    "cc-window",
+    "cc-banner",
+];
+
+/// Substrings of `id`/`class` suggesting a floating overlay
+const OVERLAY_MARKERS: &[&str] = &["banner", "modal", "popup", "overlay", "notice"];
+
+const CONSENT_TEXT: &[&str] = &["cookie", "consent", "privacy"];
+
+const ACCEPT_WORDS: &[&str] = &[
+    "accept", "agree", "allow", "got it", "i understand", "ok", "akzeptieren", "accepter",
+    "aceptar", "aceitar",
+];
+const REJECT_WORDS: &[&str] = &[
+    "reject", "decline", "deny", "refuse", "necessary only", "only necessary", "essential only",
+    "ablehnen", "refuser", "rechazar", "rejeitar",
+];
+
+/// Classify an element as a blocking overlay, if it looks like one
+pub(crate) fn overlay_kind(element: ElementRef) -> Option<OverlayKind> {
+    let el = element.value();
+
+    if has_consent_marker(element) {
+        // Report only the outermost element of a consent widget
+        let nested = element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(has_consent_marker);
+        return (!nested).then_some(OverlayKind::Consent);
+    }
+
+    let is_dialog = el.name() == "dialog"
+        || el
+            .attr("role")
+            .is_some_and(|r| r.eq_ignore_ascii_case("dialog") || r.eq_ignore_ascii_case("alertdialog"));
+    // Toasts and snackbars float too, but never block the page
+    if !is_dialog && crate::notifications::is_notification(element) {
+        return None;
+    }
+    let is_fixed = el
+        .attr("style")
+        .is_some_and(|s| s.replace(' ', "").to_ascii_lowercase().contains("position:fixed"));
+    let looks_floating = attr_tokens(element).any(|t| OVERLAY_MARKERS.iter().any(|m| t.contains(m)));
+
+    if (is_dialog || is_fixed || looks_floating) && {
+        let text = element.text().collect::<String>().to_lowercase();
+        CONSENT_TEXT.iter().any(|w| text.contains(w))
+    } {
+        return Some(OverlayKind::Consent);
+    }
+
+    let is_open_modal = (el.name() == "dialog" && el.attr("open").is_some())
+        || (is_dialog && el.attr("aria-modal").is_some_and(|m| m.eq_ignore_ascii_case("true")));
+    is_open_modal.then_some(OverlayKind::Modal)
+}
+
+fn has_consent_marker(element: ElementRef) -> bool {
+    attr_tokens(element).any(|t| CONSENT_MARKERS.iter().any(|m| t.contains(m)))
+}
+
+/// Lowercased `id` and class names of an element
+fn attr_tokens(element: ElementRef<'_>) -> impl Iterator<Item = String> + '_ {
+    let el = element.value();
+    el.id()
+        .into_iter()
+        .chain(el.classes())
+        .map(str::to_ascii_lowercase)
+}
+
+impl SemanticDOM {
+    /// Get overlays that block the page, in document order
+    ///
+    /// Consent banners are reported with the buttons that accept or reject
+    /// them; open modal dialogs with their close button.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, OverlayKind, SemanticDOM};
+    ///
+    /// let html = r#"<div id="cookie-banner">
+    ///     We use cookies. <button>Accept all</button><button>Reject all</button>
+    /// </div>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let overlay = &sdom.blocking_overlays()[0];
+    /// assert_eq!(overlay.kind, OverlayKind::Consent);
+    /// assert_eq!(sdom.get(overlay.accept_id.as_deref().unwrap()).unwrap().label, "Accept all");
+    /// ```
+    pub fn blocking_overlays(&self) -> Vec<BlockingOverlay> {
+        self.reading_order()
+            .into_iter()
+            .filter_map(|node| {
+                let kind = match node.metadata.as_ref()?.get(OVERLAY_KEY)?.as_str() {
+                    "consent" => OverlayKind::Consent,
+                    "modal" => OverlayKind::Modal,
+                    _ => return None,
+                };
+                let controls: Vec<&SemanticNode> = self
+                    .descendants(&node.id)
+                    .into_iter()
+                    .filter(|n| matches!(n.role, SemanticRole::Button | SemanticRole::Link))
+                    .collect();
+                let find = |pred: &dyn Fn(&SemanticNode) -> bool| {
+                    controls.iter().find(|n| pred(n)).map(|n| n.id.clone())
+                };
+
+                Some(BlockingOverlay {
+                    id: node.id.clone(),
+                    kind,
+                    accept_id: find(&|n| label_matches(n, ACCEPT_WORDS)),
+                    reject_id: find(&|n| label_matches(n, REJECT_WORDS)),
+                    close_id: find(&|n| {
+                        n.intent == Some(SemanticIntent::Close)
+                            || matches!(n.label.trim(), "×" | "✕" | "x" | "X")
+                    }),
+                })
+            })
+            .collect()
+    }
+}
+
+fn label_matches(node: &SemanticNode, words: &[&str]) -> bool {
+    let label = node.label.to_lowercase();
+    words.iter().any(|w| {
+        // Short words like "ok" must match whole words
+        if w.len() <= 2 {
+            label.split(|c: char| !c.is_alphanumeric()).any(|t| t == *w)
+        } else {
+            label.contains(w)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_consent_dialog_by_text_and_modal() {
+        let html = r#"
+            <div class="site-popup" style="position: fixed">
+                <p>This site uses cookies to improve your experience.</p>
+                <button>Only necessary</button>
+                <button>OK</button>
+            </div>
+            <dialog open aria-label="Newsletter"><button aria-label="Close">×</button></dialog>
+            <main><button>Accept the terms</button></main>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let overlays = sdom.blocking_overlays();
+        assert_eq!(overlays.len(), 2);
+
+        let consent = &overlays[0];
+        assert_eq!(consent.kind, OverlayKind::Consent);
+        assert_eq!(sdom.get(consent.accept_id.as_deref().unwrap()).unwrap().label, "OK");
+        assert_eq!(sdom.get(consent.reject_id.as_deref().unwrap()).unwrap().label, "Only necessary");
+
+        let modal = &overlays[1];
+        assert_eq!(modal.kind, OverlayKind::Modal);
+        assert!(modal.close_id.is_some() && modal.accept_id.is_none());
+    }
+
+    #[test]
+    fn test_nested_vendor_markup_reported_once() {
+        let html = r#"
+            <div id="onetrust-consent-sdk">
+                <div id="onetrust-banner-sdk"><button id="onetrust-accept-btn-handler">I Agree</button></div>
+            </div>
+            <dialog aria-label="Closed"></dialog>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let overlays = sdom.blocking_overlays();
+        assert_eq!(overlays.len(), 1);
+        assert_eq!(overlays[0].id, "sdom_onetrust-consent-sdk");
+        assert_eq!(overlays[0].accept_id.as_deref(), Some("sdom_onetrust-accept-btn-handler"));
+    }
+}
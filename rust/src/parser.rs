@@ -2,12 +2,40 @@
 //!
 //! Parses HTML documents and builds a semantic representation with
 //! O(1) lookup via hash-indexed nodes.
-
-use crate::security::validate_url;
+//!
+//! ## Agent annotations
+//!
+//! Site authors can annotate elements with `data-agent-*` attributes,
+//! which take precedence over everything the parser would infer:
+//!
+//! | Attribute | Precedence (highest first) |
+//! |-----------|----------------------------|
+//! | `data-agent-id` | `data-agent-id` > `id` > generated (`{prefix}_{tag}_{n}`) |
+//! | `data-agent-role` | `data-agent-role` > `Config::role_overrides` > ARIA `role` > native tag |
+//! | `data-agent-label` | `data-agent-label` > `aria-label` > `title` > placeholder/name > text |
+//! | `data-agent-intent` | `data-agent-intent` > `data-intent` > intent classifiers > heuristics |
+//!
+//! Annotated IDs are prefixed like HTML ids (`data-agent-id="cart"` becomes
+//! `sdom_cart`), and any element carrying a recognized `data-agent-role`
+//! becomes a node even if it is otherwise non-semantic (e.g. a `<div>`).
+
+use crate::diagnostics::{DiagnosticKind, Diagnostics};
+use crate::intent::ElementContext;
+use crate::noise::{NoiseAction, NoiseKind};
+use crate::patterns;
+use crate::security::validate_url_with;
 use crate::types::*;
 use crate::{Config, Error, Result};
 use ahash::AHashMap;
+use indexmap::IndexMap;
 use scraper::{Html, Selector, ElementRef};
+use url::Url;
+
+/// Confidence assigned to intents from user-registered classifiers
+const CLASSIFIER_CONFIDENCE: f32 = 0.9;
+
+/// Labels taken from text content are truncated to this many characters
+const MAX_LABEL_CHARS: usize = 50;
 
 /// The main SemanticDOM structure
 ///
@@ -29,10 +57,72 @@ pub struct SemanticDOM {
     pub title: Option<String>,
     /// Document language
     pub lang: Option<String>,
+    /// Document text direction
+    pub dir: Option<TextDirection>,
+    /// Absolute URL of the page, from the configuration or `<base href>`
+    pub base_url: Option<String>,
+    /// Document-level `<link>` elements such as canonical and alternates
+    pub(crate) document_links: Vec<crate::links::DocumentLink>,
+    /// HTML `id` values used by more than one element, in the order the
+    /// repeats occur
+    pub duplicate_ids: Vec<String>,
+    /// Certification computed at parse time when [`Config::validate`] is
+    /// set; edits made afterwards are not reflected (see [`Self::certify`])
+    pub certification: Option<crate::AgentCertification>,
     /// Configuration used for parsing
-    config: Config,
+    pub(crate) config: Config,
     /// Counter for generating unique IDs
-    id_counter: usize,
+    pub(crate) id_counter: usize,
+    /// Node IDs in the order they were created
+    pub(crate) order: Vec<String>,
+    /// Whether the source was parsed as a fragment
+    pub(crate) fragment: bool,
+    /// Products offered on the page
+    pub(crate) products: Vec<crate::commerce::ProductInfo>,
+    /// Contact details found on the page
+    pub(crate) contact: crate::contact::ContactInfo,
+    /// Signals that the page needs JavaScript to render
+    pub(crate) js_signals: crate::scripting::JsSignals,
+    /// Front-end frameworks detected in the markup
+    pub(crate) frameworks: crate::frameworks::FrameworkHints,
+    /// Mixed-content and insecure-form findings
+    pub(crate) security_report: crate::security_report::SecurityReport,
+    /// Problems recovered from while parsing, in the order they occurred
+    pub(crate) warnings: Vec<Error>,
+    /// What parsing skipped, dropped or shortened
+    pub(crate) diagnostics: Diagnostics,
+    /// ARIA relationships between nodes
+    pub(crate) relations: Vec<crate::relations::Relation>,
+    /// Fields with a popup of options
+    pub(crate) comboboxes: Vec<crate::combobox::Combobox>,
+    /// Tree widgets
+    pub(crate) trees: Vec<crate::composites::Tree>,
+    /// Grid and tree grid widgets
+    pub(crate) grids: Vec<crate::composites::Grid>,
+    /// Toast, snackbar and status areas
+    pub(crate) notifications: Vec<crate::notifications::Notification>,
+    /// Search results and other listings
+    pub(crate) results: Vec<crate::results::ResultList>,
+    /// Components repeated among siblings
+    pub(crate) clusters: Vec<crate::clusters::Cluster>,
+    /// Progress bars, spinners and skeletons still showing
+    pub(crate) loading: Vec<crate::loading::LoadingIndicator>,
+    /// Language, currency and number conventions
+    pub(crate) locale: crate::locale::LocaleHints,
+    /// Sort and filter controls
+    pub(crate) facets: crate::facets::Facets,
+    /// Noise regions dropped or demoted, per kind
+    pub(crate) noise: IndexMap<NoiseKind, usize>,
+    /// Node each in-page link fragment points at
+    pub(crate) anchors: AHashMap<String, String>,
+    /// Form submissions and the requests they send
+    pub(crate) form_submits: Vec<crate::forms::FormSubmit>,
+    /// Node IDs by role, in document order
+    pub(crate) by_role: IndexMap<SemanticRole, Vec<String>>,
+    /// Node IDs by intent, in document order
+    pub(crate) by_intent: IndexMap<SemanticIntent, Vec<String>>,
+    /// Waivers from `sdom-ignore` comments in the page
+    pub(crate) comment_waivers: Vec<crate::waivers::Waiver>,
 }
 
 impl SemanticDOM {
@@ -68,8 +158,104 @@ impl SemanticDOM {
             });
         }
 
-        let document = Html::parse_document(html);
-        let mut sdom = SemanticDOM {
+        Self::from_document(Html::parse_document(html), Some(html), config)
+    }
+
+    /// Parse raw HTML bytes, detecting the character encoding first
+    ///
+    /// The encoding is sniffed from a byte order mark or a `<meta charset>`
+    /// declaration, falling back to UTF-8 (or windows-1252 when the input
+    /// is not valid UTF-8). See [`crate::detect_encoding`].
+    ///
+    /// # Security
+    /// - The size limit applies to the raw bytes, before decoding
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{SemanticDOM, Config};
+    ///
+    /// let html = b"<html><head><meta charset=\"iso-8859-1\"></head>\
+    ///              <body><button>Caf\xE9</button></body></html>";
+    /// let sdom = SemanticDOM::parse_bytes(html, Config::default()).unwrap();
+    /// assert!(sdom.index.values().any(|n| n.label == "Café"));
+    /// ```
+    pub fn parse_bytes(bytes: &[u8], config: Config) -> Result<Self> {
+        Self::parse_bytes_with_charset(bytes, None, config)
+    }
+
+    /// [`Self::parse_bytes`] with the charset from a `Content-Type` header,
+    /// which outranks a `<meta>` declaration
+    pub(crate) fn parse_bytes_with_charset(
+        bytes: &[u8],
+        charset: Option<&str>,
+        config: Config,
+    ) -> Result<Self> {
+        // Security: validate input size
+        if bytes.len() > config.max_input_size {
+            return Err(Error::InputTooLarge {
+                max_size: config.max_input_size,
+                actual_size: bytes.len(),
+            });
+        }
+
+        let (html, warning) = crate::encoding::decode_html(bytes, charset);
+        let mut sdom = Self::from_document(Html::parse_document(&html), Some(&html), config)?;
+        // Decoding happened first, so its warning leads
+        if let Some(warning) = warning {
+            sdom.diagnostics.push_front(DiagnosticKind::Warning, warning.to_string());
+            sdom.warnings.insert(0, warning);
+        }
+        Ok(sdom)
+    }
+
+    /// Parse a partial HTML fragment into a SemanticDOM representation
+    ///
+    /// Intended for markup that is not a full document, such as a single
+    /// component or an HTMX/AJAX partial response. The fragment is parsed
+    /// in a `<body>` context, so no `<html>`/`<body>` wrapper is required,
+    /// and the resulting nodes are indexed exactly as with [`Self::parse`].
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{SemanticDOM, Config};
+    ///
+    /// let partial = r#"<form><input type="text" name="q"><button>Go</button></form>"#;
+    /// let sdom = SemanticDOM::parse_fragment(partial, Config::default()).unwrap();
+    /// assert_eq!(sdom.interactables.len(), 2);
+    /// ```
+    pub fn parse_fragment(html: &str, config: Config) -> Result<Self> {
+        // Security: validate input size
+        if html.len() > config.max_input_size {
+            return Err(Error::InputTooLarge {
+                max_size: config.max_input_size,
+                actual_size: html.len(),
+            });
+        }
+
+        let mut sdom = Self::from_document(Html::parse_fragment(html), Some(html), config)?;
+        sdom.fragment = true;
+        Ok(sdom)
+    }
+
+    /// Build the semantic representation from a parsed HTML tree
+    ///
+    /// `source` is the text `document` was parsed from, used for
+    /// `Config::source_spans`.
+    pub(crate) fn from_document(
+        document: Html,
+        source: Option<&str>,
+        config: Config,
+    ) -> Result<Self> {
+        crate::metrics::instrument(
+            "parse",
+            || Self::build(document, source, config),
+            |sdom| sdom.as_ref().map_or(0, |s| s.index.len()),
+        )
+    }
+
+    /// A document with no nodes
+    pub(crate) fn empty(config: Config) -> Self {
+        SemanticDOM {
             index: AHashMap::new(),
             landmarks: Vec::new(),
             interactables: Vec::new(),
@@ -77,46 +263,158 @@ impl SemanticDOM {
             state_graph: StateGraph::new(),
             title: None,
             lang: None,
+            dir: None,
+            base_url: None,
+            document_links: Vec::new(),
+            duplicate_ids: Vec::new(),
+            certification: None,
             config,
             id_counter: 0,
-        };
+            order: Vec::new(),
+            fragment: false,
+            products: Vec::new(),
+            contact: Default::default(),
+            js_signals: Default::default(),
+            frameworks: Default::default(),
+            security_report: Default::default(),
+            warnings: Vec::new(),
+            diagnostics: Diagnostics::default(),
+            relations: Vec::new(),
+            comboboxes: Vec::new(),
+            trees: Vec::new(),
+            grids: Vec::new(),
+            notifications: Vec::new(),
+            results: Vec::new(),
+            clusters: Vec::new(),
+            loading: Vec::new(),
+            locale: Default::default(),
+            facets: Default::default(),
+            noise: IndexMap::new(),
+            anchors: AHashMap::new(),
+            form_submits: Vec::new(),
+            by_role: IndexMap::new(),
+            by_intent: IndexMap::new(),
+            comment_waivers: Vec::new(),
+        }
+    }
+
+    fn build(document: Html, source: Option<&str>, config: Config) -> Result<Self> {
+        let mut sdom = Self::empty(config);
 
         // Extract document metadata
         sdom.extract_metadata(&document);
+        sdom.duplicate_ids = Self::find_duplicate_ids(&document);
+        sdom.js_signals = crate::scripting::js_signals(&document);
+        sdom.locale = crate::locale::locale_hints(&document, sdom.lang.as_deref());
+        sdom.frameworks = crate::frameworks::framework_hints(&document);
+        sdom.security_report = crate::security_report::scan(&document, sdom.base_url.as_deref());
+        sdom.comment_waivers = crate::waivers::comment_waivers(&document);
 
         // Parse semantic elements using selectors
-        sdom.parse_semantic_elements(&document)?;
+        let spans = match source {
+            Some(source) if sdom.config.source_spans => crate::spans::map_spans(&document, source),
+            _ => AHashMap::new(),
+        };
+        sdom.parse_semantic_elements(&document, &spans)?;
+
+        // Structured product data takes precedence over inferred cards
+        let inferred = std::mem::take(&mut sdom.products);
+        sdom.products = crate::commerce::merge_products(
+            crate::commerce::structured_products(&document),
+            inferred,
+        );
 
         // Build state graph if enabled
         if sdom.config.include_state_graph {
             sdom.build_state_graph();
         }
 
+        if sdom.config.validate {
+            sdom.certification = Some(sdom.certify());
+        }
+
         Ok(sdom)
     }
 
+    /// Collect `id` attribute values that appear on more than one element
+    fn find_duplicate_ids(document: &Html) -> Vec<String> {
+        let Ok(selector) = Selector::parse("[id]") else {
+            return Vec::new();
+        };
+        let mut seen: AHashMap<&str, usize> = AHashMap::new();
+        let mut duplicates = Vec::new();
+        for element in document.select(&selector) {
+            if let Some(id) = element.value().attr("id") {
+                let count = seen.entry(id).or_insert(0);
+                *count += 1;
+                if *count == 2 {
+                    duplicates.push(id.to_string());
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Compile a selector, recording a warning if it is invalid
+    fn selector(&mut self, css: &str) -> Option<Selector> {
+        compile_selector(css).map_err(|e| self.warn(DiagnosticKind::Warning, None, e)).ok()
+    }
+
+    /// Record a recovered error, with a matching diagnostic
+    pub(crate) fn warn(&mut self, kind: DiagnosticKind, node: Option<&str>, error: Error) {
+        self.diagnostics.push(kind, node, None, error.to_string());
+        self.warnings.push(error);
+    }
+
     /// Extract document-level metadata
     fn extract_metadata(&mut self, document: &Html) {
         // Extract title
-        if let Ok(selector) = Selector::parse("title") {
+        if let Some(selector) = self.selector("title") {
             if let Some(title_el) = document.select(&selector).next() {
                 self.title = Some(title_el.text().collect::<String>().trim().to_string());
             }
         }
 
         // Extract language
-        if let Ok(selector) = Selector::parse("html") {
+        if let Some(selector) = self.selector("html") {
             if let Some(html_el) = document.select(&selector).next() {
                 if let Some(lang) = html_el.value().attr("lang") {
                     self.lang = Some(lang.to_string());
                 }
+                self.dir = html_el.value().attr("dir").and_then(TextDirection::from_attr);
             }
         }
+
+        // Page URL for link classification; <base href> may be relative to it
+        let configured = self.config.base_url.as_deref().and_then(|u| Url::parse(u).ok());
+        let declared = self.selector("base[href]").and_then(|selector| {
+            let href = document.select(&selector).next()?.value().attr("href")?;
+            match &configured {
+                Some(base) => base.join(href).ok(),
+                None => Url::parse(href).ok(),
+            }
+        });
+        self.base_url = declared
+            .or(configured)
+            .filter(Url::has_host)
+            .map(String::from);
+
+        self.document_links = crate::links::document_links(
+            document,
+            self.base_url.as_deref(),
+            &self.config.security,
+        );
     }
 
-    /// Parse semantic elements using CSS selectors
-    fn parse_semantic_elements(&mut self, document: &Html) -> Result<()> {
+    /// Parse semantic elements in document order using CSS selectors
+    fn parse_semantic_elements(
+        &mut self,
+        document: &Html,
+        spans: &AHashMap<ego_tree::NodeId, SourceSpan>,
+    ) -> Result<()> {
         // Define selectors for semantic elements
+        // Explicit `role` attributes are resolved before this table, and
+        // the first matching entry wins
         let semantic_selectors = [
             // Landmarks
             ("nav", SemanticRole::Navigation),
@@ -125,26 +423,39 @@ impl SemanticDOM {
             ("footer", SemanticRole::Footer),
             ("aside", SemanticRole::Aside),
             ("article", SemanticRole::Article),
+            ("section[aria-label]", SemanticRole::Region),
+            ("section[aria-labelledby]", SemanticRole::Region),
             ("section", SemanticRole::Section),
-            ("[role=navigation]", SemanticRole::Navigation),
-            ("[role=main]", SemanticRole::Main),
-            ("[role=banner]", SemanticRole::Header),
-            ("[role=contentinfo]", SemanticRole::Footer),
-            ("[role=complementary]", SemanticRole::Aside),
-            ("[role=search]", SemanticRole::Search),
+            ("search", SemanticRole::Search),
             // Interactables
             ("a[href]", SemanticRole::Link),
             ("button", SemanticRole::Button),
             ("input[type=submit]", SemanticRole::Button),
             ("input[type=button]", SemanticRole::Button),
+            ("input[type=reset]", SemanticRole::Button),
+            ("input[type=image]", SemanticRole::Button),
+            ("input[type=file]", SemanticRole::Button),
             ("input[type=text]", SemanticRole::TextInput),
+            ("input:not([type])", SemanticRole::TextInput),
             ("input[type=email]", SemanticRole::TextInput),
             ("input[type=password]", SemanticRole::TextInput),
             ("input[type=search]", SemanticRole::TextInput),
+            ("input[type=tel]", SemanticRole::TextInput),
+            ("input[type=url]", SemanticRole::TextInput),
+            ("input[type=date]", SemanticRole::TextInput),
+            ("input[type=time]", SemanticRole::TextInput),
+            ("input[type=datetime-local]", SemanticRole::TextInput),
+            ("input[type=month]", SemanticRole::TextInput),
+            ("input[type=week]", SemanticRole::TextInput),
+            ("input[type=color]", SemanticRole::TextInput),
             ("input[type=checkbox]", SemanticRole::Checkbox),
             ("input[type=radio]", SemanticRole::Radio),
             ("textarea", SemanticRole::TextInput),
             ("select", SemanticRole::Select),
+            ("input[type=range]", SemanticRole::Slider),
+            ("input[type=number]", SemanticRole::SpinButton),
+            ("progress", SemanticRole::ProgressBar),
+            ("meter", SemanticRole::ProgressBar),
             // Headings
             ("h1", SemanticRole::Heading),
             ("h2", SemanticRole::Heading),
@@ -152,6 +463,10 @@ impl SemanticDOM {
             ("h4", SemanticRole::Heading),
             ("h5", SemanticRole::Heading),
             ("h6", SemanticRole::Heading),
+            // Lists
+            ("ul", SemanticRole::List),
+            ("ol", SemanticRole::List),
+            ("li", SemanticRole::ListItem),
             // Media
             ("img", SemanticRole::Image),
             ("video", SemanticRole::Video),
@@ -159,66 +474,486 @@ impl SemanticDOM {
             // Other semantic
             ("form", SemanticRole::Form),
             ("dialog", SemanticRole::Dialog),
-            ("[role=dialog]", SemanticRole::Dialog),
-            ("[role=alert]", SemanticRole::Alert),
         ];
 
-        for (selector_str, role) in semantic_selectors {
-            if let Ok(selector) = Selector::parse(selector_str) {
-                for element in document.select(&selector) {
-                    self.process_element(element, role.clone())?;
+        let compiled: Vec<(Selector, SemanticRole)> = semantic_selectors
+            .into_iter()
+            .filter_map(|(selector_str, role)| self.selector(selector_str).map(|sel| (sel, role)))
+            .collect();
+
+        // Walk elements in document order, tracking the nearest semantic
+        // ancestor so nodes get parent/child links and depth
+        let mut stack: Vec<(ElementRef, Option<String>, usize)> =
+            vec![(document.root_element(), None, 0)];
+        // Node created for each element, for resolving id references
+        let mut nodes: AHashMap<ego_tree::NodeId, String> = AHashMap::new();
+        // Plain containers promoted to landmarks
+        let inferred = match self.config.infer_landmarks {
+            true => crate::inference::infer_landmarks(document),
+            false => AHashMap::new(),
+        };
+        // Noise regions to take out of the interactables once walked
+        let mut demoted: Vec<(ElementRef, NoiseKind, Option<String>)> = Vec::new();
+
+        while let Some((element, parent, depth)) = stack.pop() {
+            // Skip excluded tags along with their subtrees
+            let tag_name = element.value().name().to_lowercase();
+            if self.config.exclude_tags.contains(&tag_name) {
+                // Plain scripts and styles are expected; report skipped markup
+                if element.children().any(|c| c.value().is_element()) {
+                    self.diagnostics.push(
+                        DiagnosticKind::SkippedElement,
+                        parent.as_deref(),
+                        Some(&tag_name),
+                        format!("<{}> is in exclude_tags; its content was skipped", tag_name),
+                    );
+                }
+                continue;
+            }
+
+            // Filtered noise is dropped here or demoted after the walk
+            if !self.config.noise_filters.is_empty()
+                && !demoted.iter().any(|(region, ..)| {
+                    element.ancestors().any(|a| a.id() == region.id())
+                })
+                && crate::overlays::overlay_kind(element).is_none()
+            {
+                if let Some(kind) = crate::noise::noise_kind(element) {
+                    match self.config.noise_action(kind) {
+                        NoiseAction::Keep => {}
+                        NoiseAction::Demote => demoted.push((element, kind, parent.clone())),
+                        NoiseAction::Drop => {
+                            *self.noise.entry(kind).or_default() += 1;
+                            self.diagnostics.push(
+                                DiagnosticKind::FilteredNoise,
+                                parent.as_deref(),
+                                Some(&tag_name),
+                                format!(
+                                    "<{}> looks like {} markup; it was dropped by noise_filters",
+                                    tag_name,
+                                    kind.as_str()
+                                ),
+                            );
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            // Contact details are collected regardless of node filtering
+            if tag_name == "address" {
+                self.contact.add_address(element);
+            } else if tag_name == "a" {
+                if let Some(href) = element.value().attr("href") {
+                    self.contact.add_link(href);
+                }
+            }
+
+            // Elements stuffed with attributes are walked through, not indexed
+            let attributes = element.value().attrs().count();
+            let crowded = attributes > self.config.max_attributes;
+            if crowded {
+                let error = Error::TooManyAttributes {
+                    max: self.config.max_attributes,
+                    actual: attributes,
+                };
+                self.warn(DiagnosticKind::Warning, parent.as_deref(), error);
+            }
+
+            let included = self.config.include_tags.is_empty()
+                || self.config.include_tags.contains(&tag_name);
+            if !included && compiled.iter().any(|(selector, _)| selector.matches(&element)) {
+                self.diagnostics.push(
+                    DiagnosticKind::SkippedElement,
+                    parent.as_deref(),
+                    Some(&tag_name),
+                    format!("<{}> is not in include_tags", tag_name),
+                );
+            }
+            let overlay = if included {
+                crate::overlays::overlay_kind(element)
+            } else {
+                None
+            };
+            let role = if included && !crowded {
+                element
+                    .value()
+                    .attr("data-agent-role")
+                    .and_then(|r| {
+                        r.trim()
+                            .parse::<SemanticRole>()
+                            .map_err(|e| self.warn(DiagnosticKind::Warning, parent.as_deref(), e))
+                            .ok()
+                    })
+                    .or_else(|| {
+                        self.config
+                            .role_overrides
+                            .iter()
+                            .find(|(tag, _)| tag.eq_ignore_ascii_case(&tag_name))
+                            .map(|(_, role)| role.clone())
+                    })
+                    .or_else(|| {
+                        element
+                            .value()
+                            .attr("role")
+                            .and_then(|r| r.parse::<SemanticRole>().ok())
+                    })
+                    .or_else(|| {
+                        compiled
+                            .iter()
+                            .find(|(selector, role)| {
+                                selector.matches(&element)
+                                    && match role {
+                                        SemanticRole::Region => Self::has_region_name(element),
+                                        _ => true,
+                                    }
+                            })
+                            .map(|(_, role)| role.clone())
+                            .filter(|role| {
+                                *role != SemanticRole::ListItem
+                                    || crate::lists::in_plain_list(element)
+                            })
+                    })
+                    // Consent banners are usually plain divs; treat them as dialogs
+                    .or_else(|| overlay.map(|_| SemanticRole::Dialog))
+                    .or_else(|| inferred.get(&element.id()).cloned())
+                    // So are click targets built from generic elements
+                    .or_else(|| {
+                        crate::handlers::looks_clickable(element.value())
+                            .then_some(SemanticRole::Interactive)
+                    })
+            } else {
+                None
+            };
+
+            // Limits: skip subtrees past max_depth, stop at max_nodes
+            if role.is_some() && depth >= self.config.max_depth {
+                let node = parent.clone().unwrap_or_else(|| "document".to_string());
+                let reported = self.warnings.last().is_some_and(
+                    |w| matches!(w, Error::DepthExceeded { node: n, .. } if *n == node),
+                );
+                if !reported {
+                    let error = Error::DepthExceeded {
+                        max_depth: self.config.max_depth,
+                        node,
+                    };
+                    self.warn(DiagnosticKind::DepthCutoff, parent.as_deref(), error);
+                }
+                continue;
+            }
+            if role.is_some() && self.index.len() >= self.config.max_nodes {
+                let error = Error::NodeLimitExceeded {
+                    max_nodes: self.config.max_nodes,
+                };
+                self.warn(DiagnosticKind::NodeLimit, parent.as_deref(), error);
+                break;
+            }
+
+            // Role declared in markup rather than implied by the tag
+            let explicit_role = role.as_ref().is_some_and(|role| {
+                ["data-agent-role", "role"].iter().any(|attr| {
+                    element
+                        .value()
+                        .attr(attr)
+                        .and_then(|r| r.trim().parse::<SemanticRole>().ok())
+                        .is_some_and(|declared| declared == *role)
+                })
+            });
+
+            let (child_parent, child_depth) = match role {
+                Some(role) => {
+                    let node_id = self.process_element(element, role, parent.as_deref(), depth);
+                    if self.index.get(&node_id).is_some_and(|n| {
+                        matches!(n.role, SemanticRole::Button | SemanticRole::Link)
+                            && patterns::contains_any(&n.label, patterns::ADD_TO_CART_WORDS)
+                    }) {
+                        self.products.push(crate::commerce::card_product(element, &node_id));
+                    }
+                    if let (Some(span), Some(node)) =
+                        (spans.get(&element.id()), self.index.get_mut(&node_id))
+                    {
+                        node.source = Some(*span);
+                    }
+                    if let Some(node) = self.index.get_mut(&node_id) {
+                        node.explicit_role = explicit_role;
+                        if overlay.is_none() && inferred.contains_key(&element.id()) {
+                            node.inferred = true;
+                            node.role_confidence = crate::inference::INFERRED_CONFIDENCE;
+                        }
+                    }
+                    if let (Some(kind), Some(node)) = (overlay, self.index.get_mut(&node_id)) {
+                        node.metadata
+                            .get_or_insert_with(IndexMap::new)
+                            .insert(
+                                crate::overlays::OVERLAY_KEY.to_string(),
+                                kind.as_str().to_string(),
+                            );
+                    }
+                    nodes.insert(element.id(), node_id.clone());
+                    (Some(node_id), depth + 1)
+                }
+                None => (parent, depth),
+            };
+
+            let children: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+            for child in children.into_iter().rev() {
+                stack.push((child, child_parent.clone(), child_depth));
+            }
+        }
+
+        for (region, kind, parent) in demoted {
+            let ids: Vec<&String> = region
+                .descendants()
+                .filter_map(|d| nodes.get(&d.id()))
+                .collect();
+            for id in &ids {
+                if let Some(node) = self.index.get_mut(*id) {
+                    node.metadata
+                        .get_or_insert_with(IndexMap::new)
+                        .insert(crate::noise::NOISE_KEY.to_string(), kind.as_str().to_string());
                 }
             }
+            let before = self.interactables.len();
+            self.interactables.retain(|id| !ids.contains(&id));
+            *self.noise.entry(kind).or_default() += 1;
+            self.diagnostics.push(
+                DiagnosticKind::FilteredNoise,
+                parent.as_deref(),
+                Some(region.value().name()),
+                format!(
+                    "<{}> looks like {} markup; noise_filters left its {} controls out of \
+                     interactables",
+                    region.value().name(),
+                    kind.as_str(),
+                    before - self.interactables.len()
+                ),
+            );
         }
 
+        self.resolve_relations(document, &nodes);
+        self.comboboxes = crate::combobox::comboboxes(document, &nodes);
+        self.extract_composites(document, &nodes);
+        self.notifications = crate::notifications::notifications(document, &nodes);
+        self.loading = crate::loading::loading_indicators(document, &nodes);
+        self.results = crate::results::result_lists(document, &nodes, &self.index);
+        self.clusters = crate::clusters::clusters(document, &nodes, &self.index);
+        self.facets = crate::facets::facets(document, &nodes);
+        self.anchors = crate::links::anchor_targets(document, &nodes, &self.index);
+        self.form_submits = crate::forms::form_submits(
+            document,
+            &nodes,
+            self.base_url.as_deref(),
+            &self.config.security,
+        );
         Ok(())
     }
 
     /// Process a single element and add it to the index
-    fn process_element(&mut self, element: ElementRef, role: SemanticRole) -> Result<()> {
+    ///
+    /// Returns the new node's ID.
+    fn process_element(
+        &mut self,
+        element: ElementRef,
+        role: SemanticRole,
+        parent: Option<&str>,
+        depth: usize,
+    ) -> String {
         let el = element.value();
-
-        // Skip excluded tags
         let tag_name = el.name().to_lowercase();
-        if self.config.exclude_tags.contains(&tag_name) {
-            return Ok(());
-        }
 
-        // Generate unique ID
-        let node_id = self.generate_element_id(&tag_name, el);
+        // Generate unique ID, disambiguating duplicate HTML ids with a suffix
+        let base_id = self.generate_element_id(&tag_name, el);
+        let mut node_id = base_id.clone();
+        let mut suffix = 1;
+        while self.index.contains_key(&node_id) {
+            suffix += 1;
+            node_id = format!("{}_{}", base_id, suffix);
+        }
 
-        // Check if already processed (avoid duplicates)
-        if self.index.contains_key(&node_id) {
-            return Ok(());
+        // Labels gather at most max_text_length bytes of text
+        let max_text = self.config.max_text_length;
+        let mut text_length = 0usize;
+        if element.text().any(|text| {
+            text_length += text.len();
+            text_length > max_text
+        }) {
+            let error = Error::TextTooLong {
+                max: max_text,
+                actual: text_length,
+            };
+            self.warn(DiagnosticKind::Warning, Some(&node_id), error);
         }
 
-        // Extract label from element text content
-        let label = Self::extract_element_label(element);
+        // Extract label from element text content; tree items leave out
+        // the text of nested items
+        let (label, truncated) = match role {
+            SemanticRole::TreeItem => crate::composites::treeitem_label(element)
+                .map(|label| (label, false))
+                .unwrap_or_else(|| Self::extract_element_label(element, max_text)),
+            _ => Self::extract_element_label(element, max_text),
+        };
+        // Landmarks are often named by a heading they point at
+        let labelledby = match role.is_landmark() {
+            true => crate::relations::labelledby_text(element),
+            false => None,
+        };
+        let label = match (&labelledby, el.attr("data-agent-label")) {
+            (Some(name), None) => name.clone(),
+            _ => label,
+        };
+        // Containers routinely aggregate long text; report labels agents act on
+        if truncated && (role.is_interactable() || matches!(role, SemanticRole::Heading)) {
+            self.diagnostics.push(
+                DiagnosticKind::TruncatedLabel,
+                Some(&node_id),
+                Some(&tag_name),
+                format!("Text label truncated to {} characters", MAX_LABEL_CHARS),
+            );
+        }
 
         // Build CSS selector
         let selector = Self::build_element_selector(el);
 
         // Create node
         let mut node = SemanticNode::new(node_id.clone(), label.clone(), role.clone(), selector);
-        node.depth = 0; // We're not tracking depth in selector-based parsing
+        node.depth = depth;
+        node.parent = parent.map(str::to_string);
 
         // Extract intent for interactables
-        if role.is_interactable() {
-            node.intent = Some(Self::determine_element_intent(element, &role));
+        if role.is_interactable() || el.attr("data-agent-intent").is_some() {
+            let (intent, confidence) = self.classify_intent(element, &role, &label);
+            node.intent = Some(intent);
+            node.intent_confidence = Some(confidence);
         }
 
         // Extract href for links
         if matches!(role, SemanticRole::Link) {
             if let Some(href) = el.attr("href") {
-                // Validate URL
-                if let Ok(safe_url) = validate_url(href) {
-                    node.href = Some(safe_url);
+                // Validate URL; rejected URLs are dropped with a warning
+                match validate_url_with(href, &self.config.security) {
+                    Ok(safe_url) => node.href = Some(safe_url),
+                    Err(reason) => {
+                        let error = Error::UrlRejected {
+                            node: node.id.clone(),
+                            url: href.to_string(),
+                            reason: Box::new(reason),
+                        };
+                        self.warn(DiagnosticKind::DroppedUrl, Some(&node.id), error);
+                    }
                 }
             }
         }
 
+        if matches!(role, SemanticRole::Link) {
+            node.link_kind = el
+                .attr("href")
+                .map(|href| crate::links::classify_link(href, self.base_url.as_deref()));
+            node.rel = crate::links::rel_tokens(el.attr("rel"));
+            node.hreflang = el
+                .attr("hreflang")
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string);
+        }
+
+        // File details for download links
+        if matches!(role, SemanticRole::Link) && node.intent == Some(SemanticIntent::Download) {
+            let entries = crate::downloads::file_metadata(element, &self.config.download_types);
+            if !entries.is_empty() {
+                node.metadata
+                    .get_or_insert_with(IndexMap::new)
+                    .extend(entries.into_iter().map(|(k, v)| (k.to_string(), v)));
+            }
+        }
+
+        // Language and direction inherited from the nearest declaring ancestor
+        node.lang = crate::language::nearest_attr(element, "lang")
+            .filter(|lang| !self.lang.as_deref().is_some_and(|doc| doc.eq_ignore_ascii_case(lang)))
+            .map(str::to_string);
+        node.dir = crate::language::nearest_attr(element, "dir")
+            .and_then(TextDirection::from_attr)
+            .filter(|dir| self.dir != Some(*dir));
+
+        node.current = crate::links::current_state(element, &role);
+        node.expanded = match el.attr("aria-expanded").map(str::trim) {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+        node.selected = el.attr("aria-selected").is_some_and(|v| v.trim() == "true");
+
+        node.event_handlers = crate::handlers::event_handlers(el);
+        if self.config.preserve_attributes != crate::AttributePolicy::None {
+            node.attributes = el
+                .attrs()
+                .filter(|(name, _)| self.config.preserve_attributes.allows(name))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+        }
+        node.nonstandard = crate::handlers::is_nonstandard_control(el, &role);
+
+        // Declarative HTMX requests, turned into transitions later
+        if let Some(entries) = crate::frameworks::htmx_request(element) {
+            node.metadata
+                .get_or_insert_with(IndexMap::new)
+                .extend(entries.into_iter().map(|(k, v)| (k.to_string(), v)));
+        }
+
         // Extract accessible name
-        node.accessible_name = Self::extract_element_accessible_name(element);
+        node.accessible_name =
+            labelledby.or_else(|| Self::extract_element_accessible_name(element));
+
+        // Controls and headings are summed up by their label
+        if let Some(max_chars) = self.config.text_excerpts {
+            if !role.is_interactable() && !matches!(role, SemanticRole::Heading) {
+                node.text_excerpt =
+                    crate::excerpts::text_excerpt(element, max_chars, &self.config.exclude_tags);
+            }
+        }
+
+        // Validation state for form fields
+        if matches!(
+            role,
+            SemanticRole::TextInput | SemanticRole::Checkbox | SemanticRole::Radio | SemanticRole::Select
+        ) {
+            (node.invalid, node.error_message) = crate::field_errors::field_error(element);
+        }
+
+        if matches!(
+            role,
+            SemanticRole::Slider | SemanticRole::SpinButton | SemanticRole::ProgressBar
+        ) {
+            node.range = crate::range::range_value(el, &role);
+        }
+
+        if tag_name == "input" {
+            node.input_type = Some(el.attr("type").unwrap_or("text").trim().to_ascii_lowercase());
+        }
+        if matches!(tag_name.as_str(), "input" | "textarea" | "select") {
+            node.constraints =
+                crate::inputs::input_constraints(el, &role, node.input_type.as_deref());
+        }
+
+        // Keyboard shortcuts
+        node.access_key = el
+            .attr("accesskey")
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::to_string);
+        node.key_shortcuts = el
+            .attr("aria-keyshortcuts")
+            .map(|k| k.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+
+        // Heading level from the tag, then aria-level (ARIA default is 2)
+        if matches!(role, SemanticRole::Heading) {
+            node.level = tag_name
+                .strip_prefix('h')
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=6).contains(n))
+                .or_else(|| el.attr("aria-level").and_then(|l| l.trim().parse().ok()))
+                .or(Some(2));
+        }
 
         // Track by category
         if role.is_landmark() {
@@ -230,17 +965,24 @@ impl SemanticDOM {
         if matches!(role, SemanticRole::Heading) {
             self.headings.push(node_id.clone());
         }
+        self.index_node(&node);
+
+        // Link into the semantic tree
+        if let Some(parent_node) = parent.and_then(|p| self.index.get_mut(p)) {
+            parent_node.children.push(node_id.clone());
+        }
 
         // Insert into index (O(1) lookup)
-        self.index.insert(node_id, node);
+        self.order.push(node_id.clone());
+        self.index.insert(node_id.clone(), node);
 
-        Ok(())
+        node_id
     }
 
     /// Generate a unique ID for an element
     fn generate_element_id(&mut self, tag: &str, element: &scraper::node::Element) -> String {
-        // Use existing ID if present
-        if let Some(id) = element.attr("id") {
+        // Use author-declared agent ID or existing ID if present
+        if let Some(id) = element.attr("data-agent-id").or_else(|| element.attr("id")) {
             return format!("{}_{}", self.config.id_prefix, id);
         }
 
@@ -250,36 +992,43 @@ impl SemanticDOM {
     }
 
     /// Extract label from element text content
-    fn extract_element_label(element: ElementRef) -> String {
+    ///
+    /// Returns the label and whether text content had to be truncated.
+    fn extract_element_label(element: ElementRef, max_text: usize) -> (String, bool) {
         let el = element.value();
 
-        // Priority: aria-label > title > text content
+        // Priority: data-agent-label > aria-label > title > text content
+        if let Some(label) = el.attr("data-agent-label") {
+            return (label.to_string(), false);
+        }
+
         if let Some(label) = el.attr("aria-label") {
-            return label.to_string();
+            return (label.to_string(), false);
         }
 
         if let Some(title) = el.attr("title") {
-            return title.to_string();
+            return (title.to_string(), false);
         }
 
-        // For inputs, use placeholder or name
+        // For inputs, use alt text (image buttons), placeholder or name
         if el.name() == "input" {
+            if let Some(alt) = el.attr("alt") {
+                return (alt.to_string(), false);
+            }
             if let Some(placeholder) = el.attr("placeholder") {
-                return placeholder.to_string();
+                return (placeholder.to_string(), false);
             }
             if let Some(name) = el.attr("name") {
-                return name.to_string();
+                return (name.to_string(), false);
             }
         }
 
         // Get text content
-        let text: String = element.text().collect::<String>().trim().to_string();
+        let text = Self::bounded_text(element, max_text).trim().to_string();
         if !text.is_empty() {
-            // Truncate if too long
-            if text.len() > 50 {
-                return format!("{}...", &text[..47]);
-            }
-            return text;
+            let label = crate::summary::truncate(&text, MAX_LABEL_CHARS);
+            let truncated = label != text;
+            return (label, truncated);
         }
 
         // Fallback to tag name with id/class hint
@@ -293,11 +1042,34 @@ impl SemanticDOM {
             }
         }
 
-        label
+        (label, false)
+    }
+
+    /// Text content of an element, cut off after `max` bytes
+    fn bounded_text(element: ElementRef, max: usize) -> String {
+        let mut text = String::new();
+        for chunk in element.text() {
+            if text.len() + chunk.len() > max {
+                let mut end = max - text.len();
+                while !chunk.is_char_boundary(end) {
+                    end -= 1;
+                }
+                text.push_str(&chunk[..end]);
+                break;
+            }
+            text.push_str(chunk);
+        }
+        text
+    }
+
+    /// Whether a `section` has the accessible name that makes it a region
+    fn has_region_name(element: ElementRef) -> bool {
+        element.value().attr("aria-label").is_some_and(|l| !l.trim().is_empty())
+            || crate::relations::labelledby_text(element).is_some()
     }
 
     /// Build a CSS selector for an element
-    fn build_element_selector(element: &scraper::node::Element) -> String {
+    pub(crate) fn build_element_selector(element: &scraper::node::Element) -> String {
         let tag = element.name();
 
         // Use ID if available (most specific)
@@ -306,6 +1078,12 @@ impl SemanticDOM {
             return format!("{}#{}", tag, escaped);
         }
 
+        // Author-declared agent IDs are stable across markup changes
+        if let Some(agent_id) = element.attr("data-agent-id") {
+            let escaped = agent_id.replace('\\', "\\\\").replace('"', "\\\"");
+            return format!("{}[data-agent-id=\"{}\"]", tag, escaped);
+        }
+
         // Build tag + classes
         let mut selector = tag.to_string();
         if let Some(classes) = element.attr("class") {
@@ -349,120 +1127,197 @@ impl SemanticDOM {
         None
     }
 
+    /// Determine the intent for an interactive element
+    ///
+    /// Precedence: explicit `data-intent`, then registered
+    /// [`IntentClassifier`](crate::IntentClassifier)s, then the built-in
+    /// keyword heuristics.
+    fn classify_intent(
+        &self,
+        element: ElementRef,
+        role: &SemanticRole,
+        label: &str,
+    ) -> (SemanticIntent, f32) {
+        let lang = self
+            .config
+            .locale
+            .as_deref()
+            .or_else(|| crate::language::nearest_attr(element, "lang"))
+            .or(self.lang.as_deref());
+
+        let el = element.value();
+        if el.attr("data-agent-intent").is_none() && el.attr("data-intent").is_none() {
+            let ctx = ElementContext::new(element.value(), role, label, lang);
+            for classifier in &self.config.intent_classifiers {
+                if let Some(intent) = classifier.classify(&ctx) {
+                    return (intent, CLASSIFIER_CONFIDENCE);
+                }
+            }
+        }
+
+        Self::determine_element_intent(element, role, lang, &self.config.download_types)
+    }
+
+    /// Check whether a text field looks like a site search box
+    ///
+    /// Matches conventional query parameter names (`q`, `query`, ...) and
+    /// fields inside a search landmark or a form whose action mentions search.
+    fn is_site_search_input(element: ElementRef) -> bool {
+        const QUERY_NAMES: &[&str] = &["q", "query", "s", "search", "keyword", "keywords"];
+        if element
+            .value()
+            .attr("name")
+            .is_some_and(|n| QUERY_NAMES.contains(&n.to_ascii_lowercase().as_str()))
+        {
+            return true;
+        }
+
+        element.ancestors().filter_map(ElementRef::wrap).any(|a| {
+            let a = a.value();
+            a.name() == "search"
+                || a.attr("role").is_some_and(|r| r.eq_ignore_ascii_case("search"))
+                || (a.name() == "form"
+                    && a.attr("action").is_some_and(|act| act.to_ascii_lowercase().contains("search")))
+        })
+    }
+
     /// Determine the user intent for an interactive element
-    fn determine_element_intent(element: ElementRef, role: &SemanticRole) -> SemanticIntent {
+    ///
+    /// Returns the intent with a heuristic confidence: 1.0 for explicit
+    /// attributes, high for structural cues (element type), and lower for
+    /// keyword guesses from the label.
+    fn determine_element_intent(
+        element: ElementRef,
+        role: &SemanticRole,
+        lang: Option<&str>,
+        download_types: &[(String, String)],
+    ) -> (SemanticIntent, f32) {
         let el = element.value();
 
         // Check for explicit intent attributes
-        if let Some(intent) = el.attr("data-intent") {
-            return match intent.to_lowercase().as_str() {
-                "navigate" => SemanticIntent::Navigate,
-                "submit" => SemanticIntent::Submit,
-                "action" => SemanticIntent::Action,
-                "toggle" => SemanticIntent::Toggle,
-                "select" => SemanticIntent::Select,
-                "search" => SemanticIntent::Search,
-                "play" => SemanticIntent::Play,
-                "pause" => SemanticIntent::Pause,
-                "open" => SemanticIntent::Open,
-                "close" => SemanticIntent::Close,
-                "expand" => SemanticIntent::Expand,
-                "collapse" => SemanticIntent::Collapse,
-                "download" => SemanticIntent::Download,
-                "delete" => SemanticIntent::Delete,
-                "edit" => SemanticIntent::Edit,
-                "create" => SemanticIntent::Create,
-                _ => SemanticIntent::Unknown,
+        if let Some(intent) = el.attr("data-agent-intent").or_else(|| el.attr("data-intent")) {
+            return match intent.parse::<SemanticIntent>() {
+                Ok(intent) if intent != SemanticIntent::Unknown => (intent, 1.0),
+                _ => (SemanticIntent::Unknown, 0.0),
             };
         }
 
         // Infer from element type and attributes
         match role {
             SemanticRole::Link => {
+                if el.attr("download").is_some() {
+                    return (SemanticIntent::Download, 1.0);
+                }
                 if let Some(href) = el.attr("href") {
-                    if href.ends_with(".pdf")
-                        || href.ends_with(".zip")
-                        || el.attr("download").is_some()
-                    {
-                        return SemanticIntent::Download;
+                    let scheme = href.split(':').next().unwrap_or("").to_ascii_lowercase();
+                    match scheme.as_str() {
+                        "mailto" => return (SemanticIntent::Email, 1.0),
+                        "tel" => return (SemanticIntent::Phone, 1.0),
+                        _ => {}
+                    }
+                    if crate::downloads::is_download_href(href, download_types) {
+                        return (SemanticIntent::Download, 0.8);
                     }
                 }
-                SemanticIntent::Navigate
+                if crate::downloads::is_download_mime(element, download_types) {
+                    return (SemanticIntent::Download, 0.8);
+                }
+                (SemanticIntent::Navigate, 0.9)
             }
             SemanticRole::Button => {
-                let text = Self::extract_element_label(element).to_lowercase();
+                if el.name() == "input"
+                    && el.attr("type").is_some_and(|t| t.trim().eq_ignore_ascii_case("file"))
+                {
+                    return (SemanticIntent::Upload, 1.0);
+                }
+                // Labels keep MAX_LABEL_CHARS characters of at most four bytes
+                let text =
+                    Self::extract_element_label(element, MAX_LABEL_CHARS * 4).0.to_lowercase();
 
                 // Check type attribute
                 if let Some(btn_type) = el.attr("type") {
-                    if btn_type == "submit" {
-                        return SemanticIntent::Submit;
+                    if matches!(btn_type, "submit" | "image") {
+                        return (SemanticIntent::Submit, 1.0);
                     }
                 }
 
-                // Infer from button text
-                if text.contains("submit") || text.contains("send") || text.contains("save") {
-                    SemanticIntent::Submit
-                } else if text.contains("delete") || text.contains("remove") {
-                    SemanticIntent::Delete
-                } else if text.contains("edit") || text.contains("modify") {
-                    SemanticIntent::Edit
-                } else if text.contains("create") || text.contains("add") || text.contains("new") {
-                    SemanticIntent::Create
-                } else if text.contains("toggle") {
-                    SemanticIntent::Toggle
-                } else if text.contains("open") {
-                    SemanticIntent::Open
-                } else if text.contains("close") || text.contains("cancel") {
-                    SemanticIntent::Close
-                } else if text.contains("expand") {
-                    SemanticIntent::Expand
-                } else if text.contains("collapse") {
-                    SemanticIntent::Collapse
-                } else if text.contains("play") {
-                    SemanticIntent::Play
-                } else if text.contains("pause") {
-                    SemanticIntent::Pause
-                } else if text.contains("search") {
-                    SemanticIntent::Search
-                } else {
-                    SemanticIntent::Action
+                // Infer from button text, then from its icon
+                match crate::intent::keyword_intent(&text, lang) {
+                    Some(intent) => (intent, 0.6),
+                    None => match crate::intent::icon_intent(element) {
+                        Some(intent) => (intent, 0.5),
+                        None => (SemanticIntent::Action, 0.3),
+                    },
                 }
             }
-            SemanticRole::Checkbox | SemanticRole::Radio => SemanticIntent::Toggle,
-            SemanticRole::Select => SemanticIntent::Select,
+            SemanticRole::Checkbox | SemanticRole::Radio => (SemanticIntent::Toggle, 0.95),
+            SemanticRole::Select => (SemanticIntent::Select, 0.95),
+            SemanticRole::Slider | SemanticRole::SpinButton => (SemanticIntent::Input, 0.95),
+            SemanticRole::TreeItem => match el.attr("aria-expanded").map(str::trim) {
+                Some("true") => (SemanticIntent::Collapse, 0.9),
+                Some("false") => (SemanticIntent::Expand, 0.9),
+                _ => (SemanticIntent::Select, 0.9),
+            },
             SemanticRole::TextInput => {
-                if let Some(input_type) = el.attr("type") {
-                    if input_type == "search" {
-                        return SemanticIntent::Search;
-                    }
+                match el.attr("type").map(str::to_ascii_lowercase).as_deref() {
+                    Some("search") => (SemanticIntent::Search, 0.95),
+                    Some("email") => (SemanticIntent::Email, 0.95),
+                    Some("tel") => (SemanticIntent::Phone, 0.95),
+                    _ if Self::is_site_search_input(element) => (SemanticIntent::Search, 0.8),
+                    _ => (SemanticIntent::Input, 0.95),
                 }
-                SemanticIntent::Input
             }
-            _ => SemanticIntent::Unknown,
+            _ => (SemanticIntent::Unknown, 0.0),
         }
     }
 
     /// Build the state graph from navigation elements
     fn build_state_graph(&mut self) {
-        // Create initial state
-        let initial = State {
-            id: "initial".to_string(),
-            name: "Initial".to_string(),
-            description: Some("Initial page state".to_string()),
-            url_pattern: Some("/".to_string()),
-            is_initial: true,
-            is_terminal: false,
+        // Create initial state: the page the current navigation link points
+        // at, when one is marked, else a generic start state
+        let current = self
+            .current_page()
+            .and_then(|n| Some((n.label.clone(), n.href.clone().filter(|h| h.starts_with('/'))?)));
+        let initial = match current {
+            Some((label, href)) => State {
+                id: format!("state_{}", href.replace('/', "_").replace('#', "h_")),
+                name: label,
+                description: Some("Current page".to_string()),
+                url_pattern: Some(href),
+                is_initial: true,
+                is_terminal: false,
+                anchor: None,
+            },
+            None => State {
+                id: "initial".to_string(),
+                name: "Initial".to_string(),
+                description: Some("Initial page state".to_string()),
+                url_pattern: Some("/".to_string()),
+                is_initial: true,
+                is_terminal: false,
+                anchor: None,
+            },
         };
+        self.state_graph.initial_state = Some(initial.id.clone());
         self.state_graph.states.push(initial);
-        self.state_graph.initial_state = Some("initial".to_string());
+        let initial_id = self.initial_state_id();
 
-        // Create states from links
+        // Create states from links; HTMX-enhanced links are handled below
         for link_id in &self.interactables {
             if let Some(node) = self.index.get(link_id) {
+                if Self::htmx_meta(node, crate::frameworks::HX_URL_KEY).is_some() {
+                    continue;
+                }
                 if let Some(href) = &node.href {
-                    // Create a state for internal links
+                    // Create a state for internal links; fragment links
+                    // scroll to their target node instead of loading a page
                     if href.starts_with('/') || href.starts_with('#') {
-                        let state_id = format!("state_{}", href.replace('/', "_").replace('#', "h_"));
+                        let anchor = href.strip_prefix('#').and_then(|f| self.anchors.get(f));
+                        let state_id = match anchor {
+                            Some(target) => format!("state_at_{}", target),
+                            None => format!("state_{}", href.replace('/', "_").replace('#', "h_")),
+                        };
+                        let action = if href.starts_with('#') { "scroll" } else { "navigate" };
                         let state = State {
                             id: state_id.clone(),
                             name: node.label.clone(),
@@ -470,19 +1325,22 @@ impl SemanticDOM {
                             url_pattern: Some(href.clone()),
                             is_initial: false,
                             is_terminal: false,
+                            anchor: anchor.cloned(),
                         };
 
-                        // Avoid duplicates
+                        // Avoid duplicates, including the current page's own link
                         if !self.state_graph.states.iter().any(|s| s.id == state_id) {
                             self.state_graph.states.push(state);
 
                             // Create transition from initial
                             let transition = Transition {
-                                from: "initial".to_string(),
+                                from: initial_id.clone(),
                                 to: state_id,
                                 trigger: link_id.clone(),
-                                action: Some("navigate".to_string()),
+                                action: Some(action.to_string()),
                                 guard: None,
+                                request: None,
+                                params: IndexMap::new(),
                             };
                             self.state_graph.transitions.push(transition);
                         }
@@ -490,6 +1348,64 @@ impl SemanticDOM {
                 }
             }
         }
+
+        // Create states from HTMX requests (hx-get, hx-post, ...)
+        use crate::frameworks::{HX_CONFIRM_KEY, HX_METHOD_KEY, HX_TARGET_KEY, HX_URL_KEY};
+        for node_id in &self.order {
+            let Some(node) = self.index.get(node_id) else {
+                continue;
+            };
+            let (Some(method), Some(url)) =
+                (Self::htmx_meta(node, HX_METHOD_KEY), Self::htmx_meta(node, HX_URL_KEY))
+            else {
+                continue;
+            };
+
+            let state_id = format!(
+                "state_hx_{}_{}",
+                method,
+                url.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            );
+            if !self.state_graph.states.iter().any(|s| s.id == state_id) {
+                self.state_graph.states.push(State {
+                    id: state_id.clone(),
+                    name: if node.label.is_empty() { url.to_string() } else { node.label.clone() },
+                    description: Self::htmx_meta(node, HX_TARGET_KEY)
+                        .map(|t| format!("Swaps {}", t)),
+                    url_pattern: Some(url.to_string()),
+                    is_initial: false,
+                    is_terminal: false,
+                    anchor: None,
+                });
+            }
+            self.state_graph.transitions.push(Transition {
+                from: initial_id.clone(),
+                to: state_id,
+                trigger: node_id.clone(),
+                action: Some(format!("hx-{}", method)),
+                guard: Self::htmx_meta(node, HX_CONFIRM_KEY).map(|c| format!("confirm: {}", c)),
+                request: None,
+                params: IndexMap::new(),
+            });
+        }
+
+        self.add_form_states();
+        self.add_upload_states();
+        self.add_facet_states();
+        self.add_combobox_states();
+        self.add_tree_states();
+    }
+
+    /// ID of the state graph's initial state
+    pub(crate) fn initial_state_id(&self) -> String {
+        self.state_graph
+            .initial_state
+            .clone()
+            .unwrap_or_else(|| "initial".to_string())
+    }
+
+    fn htmx_meta<'a>(node: &'a SemanticNode, key: &str) -> Option<&'a str> {
+        node.metadata.as_ref()?.get(key).map(String::as_str)
     }
 
     /// Get a node by ID in O(1) time
@@ -505,6 +1421,27 @@ impl SemanticDOM {
             .collect()
     }
 
+    /// Get nodes whose role or intent confidence is below `threshold`
+    ///
+    /// These are candidates for re-verification by a human or a vision
+    /// model before an agent acts on them.
+    pub fn low_confidence_nodes(&self, threshold: f32) -> Vec<&SemanticNode> {
+        self.order
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|n| n.role_confidence < threshold || n.intent_confidence.is_some_and(|c| c < threshold))
+            .collect()
+    }
+
+    /// Get nodes that declare keyboard shortcuts, in document order
+    pub fn shortcuts(&self) -> Vec<&SemanticNode> {
+        self.order
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|n| n.access_key.is_some() || !n.key_shortcuts.is_empty())
+            .collect()
+    }
+
     /// Get all interactable nodes
     pub fn get_interactables(&self) -> Vec<&SemanticNode> {
         self.interactables
@@ -515,15 +1452,27 @@ impl SemanticDOM {
 
     /// Convert to JSON string
     pub fn to_json(&self) -> Result<String> {
-        serde_json::to_string_pretty(&self.to_serializable())
-            .map_err(|e| Error::ParseError(e.to_string()))
+        crate::metrics::instrument(
+            "to_json",
+            || {
+                serde_json::to_string_pretty(&self.to_serializable())
+                    .map_err(|e| Error::SerializationError(e.to_string()))
+            },
+            |json| json.as_ref().map_or(0, String::len),
+        )
     }
 
     /// Convert to a serializable structure
-    fn to_serializable(&self) -> serde_json::Value {
-        // Convert AHashMap to a standard collection for serialization
-        let nodes: std::collections::HashMap<String, &SemanticNode> =
-            self.index.iter().map(|(k, v)| (k.clone(), v)).collect();
+    pub(crate) fn to_serializable(&self) -> serde_json::Value {
+        // Nodes in document order; the index itself has no stable order
+        let mut nodes: IndexMap<&str, &SemanticNode> =
+            self.reading_order().into_iter().map(|n| (n.id.as_str(), n)).collect();
+        if nodes.len() < self.index.len() {
+            let mut rest: Vec<&SemanticNode> =
+                self.index.values().filter(|n| !nodes.contains_key(n.id.as_str())).collect();
+            rest.sort_by(|a, b| a.id.cmp(&b.id));
+            nodes.extend(rest.into_iter().map(|n| (n.id.as_str(), n)));
+        }
 
         serde_json::json!({
             "title": self.title,
@@ -536,9 +1485,35 @@ impl SemanticDOM {
         })
     }
 
+    /// Get the problems recovered from while parsing
+    ///
+    /// The parse result is still usable but may be partial: a rejected URL
+    /// is dropped from its node, subtrees past `max_depth` are skipped, and
+    /// parsing stops at `max_nodes`.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, Error, SemanticDOM};
+    ///
+    /// let html = r#"<main><a href="javascript:void(0)">Menu</a></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     &sdom.warnings()[0],
+    ///     Error::UrlRejected { url, .. } if url == "javascript:void(0)"
+    /// ));
+    /// ```
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
     /// Generate token-efficient agent summary
     pub fn to_agent_summary(&self) -> String {
-        crate::summary::to_agent_summary(self)
+        crate::metrics::instrument(
+            "summary",
+            || crate::summary::to_agent_summary(self),
+            String::len,
+        )
     }
 
     /// Generate one-line summary
@@ -547,10 +1522,44 @@ impl SemanticDOM {
     }
 }
 
+/// Compile a CSS selector, with the failure as a typed error
+pub(crate) fn compile_selector(css: &str) -> Result<Selector> {
+    Selector::parse(css).map_err(|e| Error::SelectorError {
+        selector: css.to_string(),
+        message: e.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_limits_return_partial_results_with_warnings() {
+        let nested = format!(
+            "<main>{}<button>Deep</button>{}</main>",
+            "<section>".repeat(4),
+            "</section>".repeat(4)
+        );
+        let config = Config::builder().max_depth(3).build().unwrap();
+        let sdom = SemanticDOM::parse(&nested, config).unwrap();
+        assert_eq!(sdom.index.len(), 3);
+        assert!(matches!(
+            sdom.warnings(),
+            [Error::DepthExceeded { max_depth: 3, node }] if sdom.index.contains_key(node)
+        ));
+
+        let html = r#"<main><button>A</button><button data-agent-role="buton">B</button>
+            <button>C</button></main>"#;
+        let config = Config::builder().max_nodes(2).build().unwrap();
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+        assert_eq!(sdom.index.len(), 2);
+        assert!(matches!(
+            sdom.warnings(),
+            [Error::UnknownRole(role), Error::NodeLimitExceeded { max_nodes: 2 }] if role == "buton"
+        ));
+    }
+
     #[test]
     fn test_parse_simple() {
         let html = r#"
@@ -590,6 +1599,181 @@ mod tests {
         assert!(matches!(result, Err(Error::InputTooLarge { .. })));
     }
 
+    #[test]
+    fn test_parse_bytes_legacy_encoding() {
+        let html = b"<html><head><meta charset=\"windows-1252\"></head>\
+                     <body><a href=\"/\">R\xE9sum\xE9</a></body></html>";
+        let sdom = SemanticDOM::parse_bytes(html, Config::default()).unwrap();
+
+        let link = sdom.index.values().find(|n| n.role == SemanticRole::Link).unwrap();
+        assert_eq!(link.label, "Résumé");
+    }
+
+    #[test]
+    fn test_parse_bytes_size_limit() {
+        let config = Config {
+            max_input_size: 10,
+            ..Default::default()
+        };
+
+        let result = SemanticDOM::parse_bytes(&[b'x'; 20], config);
+        assert!(matches!(result, Err(Error::InputTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_parse_fragment() {
+        let html = r#"<nav><a href="/a">A</a><a href="/b">B</a></nav><button>Load more</button>"#;
+        let sdom = SemanticDOM::parse_fragment(html, Config::default()).unwrap();
+
+        assert_eq!(sdom.landmarks.len(), 1);
+        assert_eq!(sdom.interactables.len(), 3);
+        assert!(sdom.title.is_none());
+        assert!(sdom.get(&sdom.interactables[0]).is_some());
+    }
+
+    #[test]
+    fn test_intent_confidence() {
+        let html = r#"
+            <button data-intent="delete">X</button>
+            <button>Delete</button>
+            <button>Frobnicate</button>
+            <input type="checkbox" name="agree">
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let confidences: Vec<f32> = sdom
+            .get_interactables()
+            .iter()
+            .map(|n| n.intent_confidence.unwrap())
+            .collect();
+
+        assert_eq!(confidences, vec![1.0, 0.6, 0.3, 0.95]);
+        assert!(sdom.get_interactables().iter().all(|n| n.role_confidence == 1.0));
+
+        let low = sdom.low_confidence_nodes(0.5);
+        assert_eq!(low.len(), 1);
+        assert_eq!(low[0].label, "Frobnicate");
+    }
+
+    #[test]
+    fn test_duplicate_ids_disambiguated() {
+        let html = r#"
+            <div id="other"></div>
+            <button id="go">First</button>
+            <button id="go">Second</button>
+            <span id="other"></span>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        assert_eq!(sdom.interactables, vec!["sdom_go", "sdom_go_2"]);
+        assert_eq!(sdom.get("sdom_go_2").unwrap().label, "Second");
+        assert_eq!(sdom.duplicate_ids, vec!["go", "other"]);
+    }
+
+    #[test]
+    fn test_aria_role_attribute() {
+        let html = r#"<div role="banner">Site</div><div role="button" tabindex="0">Open</div>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let roles: Vec<_> = sdom.order.iter().map(|id| sdom.index[id].role.clone()).collect();
+        assert_eq!(roles, vec![SemanticRole::Header, SemanticRole::Button]);
+        assert_eq!(sdom.interactables.len(), 1);
+    }
+
+    #[test]
+    fn test_named_sections_are_regions() {
+        let html = r#"<section aria-labelledby="faq-title"><h2 id="faq-title">Common
+            questions</h2></section><section aria-label=" ">Plain</section>
+            <section aria-labelledby="missing">Also plain</section>
+            <div role="region" aria-label="Cart">Empty</div>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let roles: Vec<_> = sdom.order.iter().map(|id| sdom.index[id].role.clone()).collect();
+        assert_eq!(
+            roles,
+            [
+                SemanticRole::Region,
+                SemanticRole::Heading,
+                SemanticRole::Section,
+                SemanticRole::Section,
+                SemanticRole::Region,
+            ]
+        );
+        let labels: Vec<&str> = sdom.get_landmarks().iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, ["Common questions", "Cart"]);
+        assert_eq!(sdom.get_landmarks()[0].accessible_name.as_deref(), Some("Common questions"));
+    }
+
+    #[test]
+    fn test_fragment_links_scroll_to_their_target() {
+        let html = r##"<nav><a href="#faq">FAQ</a><a href="#gone">Gone</a>
+            <a href="/about">About</a></nav>
+            <main><div id="faq"><button>Ask a question</button></div></main>"##;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let link = |label: &str| {
+            let id = sdom.interactables.iter().find(|id| sdom.index[*id].label == label).unwrap();
+            let t = sdom.state_graph.transitions.iter().find(|t| t.trigger == *id).unwrap();
+            let state = sdom.state_graph.states.iter().find(|s| s.id == t.to).unwrap();
+            (t.action.as_deref().unwrap(), t.to.as_str(), state.anchor.as_deref())
+        };
+
+        let target = sdom.anchor_target("#faq").unwrap();
+        assert_eq!(target.label, "Ask a question");
+        let scroll_to = format!("state_at_{}", target.id);
+        assert_eq!(link("FAQ"), ("scroll", scroll_to.as_str(), Some(target.id.as_str())));
+        // Without a target in the page the fragment still names a state
+        assert_eq!(link("Gone"), ("scroll", "state_h_gone", None));
+        assert_eq!(link("About"), ("navigate", "state__about", None));
+    }
+
+    #[test]
+    fn test_contact_and_save_intents() {
+        let html = r#"
+            <a href="mailto:help@example.com">Email us</a>
+            <a href="tel:+15550100">Call</a>
+            <input type="tel" name="phone">
+            <button>Save draft</button>
+            <button>Cancel</button>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let intents: Vec<_> = sdom
+            .get_interactables()
+            .iter()
+            .map(|n| n.intent.clone().unwrap())
+            .collect();
+        assert_eq!(
+            intents,
+            vec![
+                SemanticIntent::Email,
+                SemanticIntent::Phone,
+                SemanticIntent::Phone,
+                SemanticIntent::Save,
+                SemanticIntent::Cancel
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_agent_attributes() {
+        let html = r#"
+            <main>
+                <div data-agent-id="buy" data-agent-role="button" data-agent-label="Buy now"
+                     data-agent-intent="submit">🛒</div>
+                <button id="x" data-agent-id="close-dialog" aria-label="Dismiss">×</button>
+            </main>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let buy = sdom.get("sdom_buy").unwrap();
+        assert_eq!(buy.role, SemanticRole::Button);
+        assert_eq!(buy.label, "Buy now");
+        assert_eq!(buy.intent, Some(SemanticIntent::Submit));
+        assert_eq!(buy.intent_confidence, Some(1.0));
+        assert_eq!(buy.selector, r#"div[data-agent-id="buy"]"#);
+
+        // data-agent-id takes precedence over id
+        let close = sdom.get("sdom_close-dialog").unwrap();
+        assert_eq!(close.label, "Dismiss");
+        assert!(sdom.get("sdom_x").is_none());
+    }
+
     #[test]
     fn test_url_validation_in_parser() {
         let html = r#"
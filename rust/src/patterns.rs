@@ -0,0 +1,204 @@
+//! Common page flow detection
+//!
+//! Labels recurring flows such as login forms or add-to-cart buttons so
+//! agents can jump straight to the task instead of inferring it from the
+//! raw list of controls. Detection is heuristic and English-keyword based.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode, SemanticRole};
+use serde::{Deserialize, Serialize};
+
+/// Kind of flow a pattern represents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PatternKind {
+    /// Sign-in form with a single password field
+    Login,
+    /// Account creation form
+    Registration,
+    /// Email-only subscription form
+    NewsletterSignup,
+    /// Button that adds a product to the cart
+    AddToCart,
+    /// Control that starts or completes checkout
+    Checkout,
+}
+
+impl PatternKind {
+    /// Short name used in summaries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternKind::Login => "login",
+            PatternKind::Registration => "registration",
+            PatternKind::NewsletterSignup => "newsletter",
+            PatternKind::AddToCart => "add-to-cart",
+            PatternKind::Checkout => "checkout",
+        }
+    }
+}
+
+/// A detected flow and the nodes involved in it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PagePattern {
+    /// What kind of flow this is
+    pub kind: PatternKind,
+    /// ID of the form containing the flow, if it is form-based
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    /// IDs of the controls involved, in document order
+    pub node_ids: Vec<String>,
+}
+
+const REGISTRATION_WORDS: &[&str] = &["sign up", "signup", "register", "create account", "join now"];
+const NEWSLETTER_WORDS: &[&str] = &["subscribe", "newsletter", "sign up", "notify me", "join"];
+pub(crate) const ADD_TO_CART_WORDS: &[&str] = &["add to cart", "add to bag", "add to basket", "buy now"];
+const CHECKOUT_WORDS: &[&str] = &["checkout", "check out", "place order", "pay now", "proceed to payment"];
+
+impl SemanticDOM {
+    /// Detect common flows on the page
+    ///
+    /// Form-based flows (login, registration, newsletter) are reported once
+    /// per form; add-to-cart and checkout are reported once per control.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, PatternKind, SemanticDOM};
+    ///
+    /// let html = r#"<form aria-label="Sign in">
+    ///     <input type="email" name="email"><input type="password" name="pw">
+    ///     <button type="submit">Log in</button>
+    /// </form>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let patterns = sdom.patterns();
+    /// assert_eq!(patterns[0].kind, PatternKind::Login);
+    /// assert_eq!(patterns[0].node_ids.len(), 3);
+    /// ```
+    pub fn patterns(&self) -> Vec<PagePattern> {
+        let mut patterns = Vec::new();
+
+        for node in self.reading_order() {
+            match node.role {
+                SemanticRole::Form => {
+                    if let Some(pattern) = self.classify_form(node) {
+                        patterns.push(pattern);
+                    }
+                }
+                SemanticRole::Button | SemanticRole::Link => {
+                    let kind = if contains_any(&node.label, ADD_TO_CART_WORDS) {
+                        PatternKind::AddToCart
+                    } else if contains_any(&node.label, CHECKOUT_WORDS) {
+                        PatternKind::Checkout
+                    } else {
+                        continue;
+                    };
+                    patterns.push(PagePattern {
+                        kind,
+                        container_id: self
+                            .ancestor_with(node, |a| matches!(a.role, SemanticRole::Form))
+                            .map(|f| f.id.clone()),
+                        node_ids: vec![node.id.clone()],
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        patterns
+    }
+
+    fn classify_form(&self, form: &SemanticNode) -> Option<PagePattern> {
+        let controls: Vec<&SemanticNode> = self
+            .descendants(&form.id)
+            .into_iter()
+            .filter(|n| n.role.is_interactable())
+            .collect();
+
+        let passwords = controls
+            .iter()
+            .filter(|n| n.input_type.as_deref() == Some("password"))
+            .count();
+        let text_fields: Vec<&&SemanticNode> = controls
+            .iter()
+            .filter(|n| matches!(n.role, SemanticRole::TextInput))
+            .collect();
+        let wording: String = std::iter::once(form)
+            .chain(controls.iter().copied().filter(|n| matches!(n.role, SemanticRole::Button)))
+            .map(|n| n.label.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let kind = if passwords >= 2 || (passwords == 1 && contains_any(&wording, REGISTRATION_WORDS)) {
+            PatternKind::Registration
+        } else if passwords == 1 {
+            PatternKind::Login
+        } else if text_fields.len() == 1
+            && text_fields[0].intent == Some(SemanticIntent::Email)
+            && contains_any(&wording, NEWSLETTER_WORDS)
+        {
+            PatternKind::NewsletterSignup
+        } else {
+            return None;
+        };
+
+        Some(PagePattern {
+            kind,
+            container_id: Some(form.id.clone()),
+            node_ids: controls.iter().map(|n| n.id.clone()).collect(),
+        })
+    }
+}
+
+pub(crate) fn contains_any(text: &str, words: &[&str]) -> bool {
+    let text = text.to_lowercase();
+    words.iter().any(|w| text.contains(w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_detects_forms_and_commerce_flows() {
+        let html = r#"
+            <form aria-label="Create account">
+                <input type="email" name="email">
+                <input type="password" name="pw">
+                <input type="password" name="pw2">
+                <button>Sign up</button>
+            </form>
+            <form><input type="email" name="e"><button>Subscribe</button></form>
+            <main>
+                <button>Add to cart</button>
+                <button>Add to Cart</button>
+                <a href="/checkout">Proceed to checkout</a>
+            </main>
+            <form action="/search"><input name="q"><button>Search</button></form>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let kinds: Vec<PatternKind> = sdom.patterns().iter().map(|p| p.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                PatternKind::Registration,
+                PatternKind::NewsletterSignup,
+                PatternKind::AddToCart,
+                PatternKind::AddToCart,
+                PatternKind::Checkout
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flows_summary_line() {
+        let html = r#"
+            <form><input type="text" name="user"><input type="password" name="pw"><button>Go</button></form>
+            <button>Add to cart</button><button>Add to cart</button>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let summary = sdom.to_agent_summary();
+        assert!(summary.contains("FLOWS: login, add-to-cart×2"), "{}", summary);
+    }
+}
@@ -0,0 +1,128 @@
+//! Node field projection for JSON output
+//!
+//! Most consumers read a handful of node fields, yet the JSON output
+//! carries all of them: selectors, source spans, confidences, raw
+//! attributes. A [`SerializationProfile`] picks a preset set of fields,
+//! and [`SemanticDOM::to_json_fields`] takes an explicit list, so output
+//! size is controlled without post-processing.
+
+use crate::parser::SemanticDOM;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Names of the serialized fields of a [`SemanticNode`](crate::SemanticNode)
+pub const NODE_FIELDS: &[&str] = &[
+    "id", "label", "role", "intent", "role_confidence", "intent_confidence", "selector",
+    "accessible_name", "href", "children", "parent", "input_type", "invalid", "error_message",
+    "access_key", "key_shortcuts", "level", "link_kind", "rel", "hreflang", "nonstandard",
+    "inferred", "explicit_role", "event_handlers", "lang", "dir", "source", "current", "expanded",
+    "selected", "range", "constraints", "text_excerpt", "bounds", "attributes", "metadata",
+    "depth",
+];
+
+/// Fields of the minimal profile
+const MINIMAL_FIELDS: &[&str] = &["id", "role", "label", "intent", "href", "children"];
+
+/// Fields the standard profile leaves out: diagnostics and raw markup
+const STANDARD_OMITS: &[&str] = &[
+    "role_confidence", "intent_confidence", "nonstandard", "explicit_role", "event_handlers",
+    "source", "bounds", "attributes", "metadata", "depth",
+];
+
+/// Preset selection of the node fields emitted in JSON
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerializationProfile {
+    /// What an agent needs to act: ID, role, label, intent, link target
+    /// and children
+    Minimal,
+    /// Everything but confidences, source positions, raw attributes and
+    /// metadata
+    Standard,
+    /// Every field
+    #[default]
+    Full,
+}
+
+impl SerializationProfile {
+    /// Node fields emitted under this profile
+    pub fn fields(&self) -> Vec<&'static str> {
+        match self {
+            SerializationProfile::Minimal => MINIMAL_FIELDS.to_vec(),
+            SerializationProfile::Standard => {
+                NODE_FIELDS.iter().copied().filter(|f| !STANDARD_OMITS.contains(f)).collect()
+            }
+            SerializationProfile::Full => NODE_FIELDS.to_vec(),
+        }
+    }
+}
+
+impl SemanticDOM {
+    /// Convert to JSON, emitting the node fields of a profile
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SerializationProfile};
+    ///
+    /// let sdom = SemanticDOM::parse(r#"<main><a href="/a">A</a></main>"#, Config::default())
+    ///     .unwrap();
+    /// let json = sdom.to_json_with(SerializationProfile::Minimal).unwrap();
+    ///
+    /// assert!(json.contains(r#""href": "/a""#));
+    /// assert!(!json.contains("selector"));
+    /// ```
+    pub fn to_json_with(&self, profile: SerializationProfile) -> Result<String> {
+        self.to_json_fields(&profile.fields())
+    }
+
+    /// Convert to JSON, emitting only the given node fields
+    ///
+    /// Returns [`Error::UnknownField`] for a name not in [`NODE_FIELDS`].
+    pub fn to_json_fields(&self, fields: &[&str]) -> Result<String> {
+        if let Some(unknown) = fields.iter().find(|f| !NODE_FIELDS.contains(&f.trim())) {
+            return Err(Error::UnknownField(unknown.to_string()));
+        }
+        let mut value = self.to_serializable();
+        if let Some(nodes) = value.get_mut("nodes").and_then(|n| n.as_object_mut()) {
+            for node in nodes.values_mut().filter_map(|n| n.as_object_mut()) {
+                node.retain(|key, _| fields.iter().any(|f| f.trim() == key));
+            }
+        }
+        serde_json::to_string_pretty(&value).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticNode, SemanticRole};
+
+    #[test]
+    fn test_node_fields_cover_serialization() {
+        // Keep NODE_FIELDS in step with SemanticNode
+        let node = SemanticNode::new("n".into(), "N".into(), SemanticRole::Button, "b".into());
+        let value = serde_json::to_value(&node).unwrap();
+        for key in value.as_object().unwrap().keys() {
+            assert!(NODE_FIELDS.contains(&key.as_str()), "{} missing from NODE_FIELDS", key);
+        }
+
+        let html = r#"<main><button id="go" data-x="1">Go</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let json = sdom.to_json_with(SerializationProfile::Standard).unwrap();
+        assert!(json.contains(r#""selector": "button#go""#));
+        assert!(!json.contains("role_confidence"));
+        assert_eq!(
+            sdom.to_json_with(SerializationProfile::Full).unwrap(),
+            sdom.to_json().unwrap()
+        );
+
+        let json = sdom.to_json_fields(&["id", "intent"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let go = value["nodes"]["sdom_go"].as_object().unwrap();
+        assert_eq!(go.keys().collect::<Vec<_>>(), ["id", "intent"]);
+        assert!(matches!(
+            sdom.to_json_fields(&["id", "colour"]),
+            Err(Error::UnknownField(f)) if f == "colour"
+        ));
+    }
+}
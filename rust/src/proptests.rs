@@ -0,0 +1,100 @@
+//! Property tests for the invariants agents rely on: serialization
+//! round-trips, stable IDs across re-parses, and bounded summaries
+
+use crate::summary::{MAX_SUMMARY_LINES, MAX_SUMMARY_LINE_CHARS};
+use crate::types::{SemanticNode, StateGraph};
+use crate::{Config, SemanticDOM};
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+/// Element tags the generator builds documents from
+const TAGS: &[&str] = &[
+    "nav", "main", "header", "footer", "aside", "section", "form", "div", "p", "h1", "h2", "h3",
+    "button", "a", "label", "ul", "li",
+];
+
+/// Text with ASCII, accented, CJK and RTL characters, so truncation is
+/// exercised on multibyte input
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 éüß日本語עברית]{0,40}"
+}
+
+/// Random markup nested a few levels deep
+fn markup() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        text(),
+        text().prop_map(|t| format!("<input type=\"text\" name=\"{}\">", t.replace(' ', "_"))),
+        (text(), 0..20u8).prop_map(|(t, n)| format!("<a href=\"/p/{}\">{}</a>", n, t)),
+        text().prop_map(|t| format!("<button>{}</button>", t)),
+    ];
+    leaf.prop_recursive(3, 48, 6, |inner| {
+        (prop::sample::select(TAGS), prop::collection::vec(inner, 0..6))
+            .prop_map(|(tag, children)| format!("<{0}>{1}</{0}>", tag, children.concat()))
+    })
+}
+
+fn document() -> impl Strategy<Value = String> {
+    (text(), prop::collection::vec(markup(), 1..5)).prop_map(|(title, body)| {
+        format!("<html><head><title>{}</title></head><body>{}</body></html>", title, body.concat())
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn json_round_trip_is_lossless(html in document()) {
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&sdom.to_json().unwrap()).unwrap();
+
+        let nodes: HashMap<String, SemanticNode> =
+            serde_json::from_value(json["nodes"].clone()).unwrap();
+        let graph: StateGraph = serde_json::from_value(json["stateGraph"].clone()).unwrap();
+
+        prop_assert_eq!(nodes.len(), sdom.index.len());
+        prop_assert_eq!(&serde_json::to_value(&nodes).unwrap(), &json["nodes"]);
+        prop_assert_eq!(&serde_json::to_value(&graph).unwrap(), &json["stateGraph"]);
+    }
+
+    #[test]
+    fn reparsing_unchanged_markup_keeps_ids(html in document()) {
+        let mut sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let ids: Vec<String> = sdom.reading_order().iter().map(|n| n.id.clone()).collect();
+
+        for _ in 0..2 {
+            let changes = sdom.update(&html).unwrap();
+            prop_assert!(changes.is_empty(), "{:?}", changes);
+            let again: Vec<String> = sdom.reading_order().iter().map(|n| n.id.clone()).collect();
+            prop_assert_eq!(&again, &ids);
+        }
+    }
+
+    #[test]
+    fn summaries_stay_within_budget(html in document()) {
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+
+        // Title (30) + three landmarks (4 each) + three actions (15 each),
+        // plus separators and the counts
+        let one_liner = sdom.to_one_liner();
+        let counts = format!("{}L {}A", sdom.landmarks.len(), sdom.interactables.len());
+        prop_assert!(one_liner.chars().count() <= 30 + 14 + 47 + 9 + counts.len(), "{}", one_liner);
+
+        let summary = sdom.to_agent_summary();
+        let lines: Vec<&str> = summary.lines().collect();
+        prop_assert!(lines.len() <= MAX_SUMMARY_LINES, "{}", summary);
+        for line in &lines {
+            prop_assert!(line.chars().count() <= MAX_SUMMARY_LINE_CHARS, "{}", line);
+        }
+        let budget = MAX_SUMMARY_LINES * (MAX_SUMMARY_LINE_CHARS + 1);
+        prop_assert!(summary.chars().count() <= budget, "{}", summary);
+        if let Some(actions) = summary.lines().find_map(|l| l.strip_prefix("ACTIONS: ")) {
+            let actions: Vec<&str> = actions.split(", ").collect();
+            prop_assert!(actions.len() <= 10);
+            // `[intent]` prefix plus a label of at most 20 characters
+            for action in actions {
+                let label = action.split_once(']').map_or(action, |(_, label)| label);
+                prop_assert!(label.chars().count() <= 20, "{}", action);
+            }
+        }
+    }
+}
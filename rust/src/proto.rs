@@ -0,0 +1,591 @@
+//! Protobuf encoding of documents
+//!
+//! With the `proto` feature, a parsed document can be sent between
+//! services as a `SemanticDocument` message. The schema is
+//! `proto/semantic_document.proto`; the types here mirror it with
+//! [`prost`] derives, so no code generation step is needed. Nodes, page
+//! details and the state graph are carried; source spans and the page
+//! analyses (products, comboboxes, the security report, ...) are not, and
+//! a decoded document has them empty.
+
+use crate::parser::SemanticDOM;
+use crate::types::{self, SemanticIntent, SemanticRole};
+use crate::{Config, Error, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A whole document (`semantic_dom_ssg.v1.SemanticDocument`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SemanticDocument {
+    /// Page title
+    #[prost(string, optional, tag = "1")]
+    pub title: Option<String>,
+    /// Document language
+    #[prost(string, optional, tag = "2")]
+    pub lang: Option<String>,
+    /// Absolute URL of the page
+    #[prost(string, optional, tag = "3")]
+    pub base_url: Option<String>,
+    /// Nodes in reading order
+    #[prost(message, repeated, tag = "4")]
+    pub nodes: Vec<SemanticNode>,
+    /// Navigation states and transitions
+    #[prost(message, optional, tag = "5")]
+    pub state_graph: Option<StateGraph>,
+}
+
+/// One node (`semantic_dom_ssg.v1.SemanticNode`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SemanticNode {
+    /// Node ID
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Human-readable label
+    #[prost(string, tag = "2")]
+    pub label: String,
+    /// Canonical role name
+    #[prost(string, tag = "3")]
+    pub role: String,
+    /// Canonical intent name
+    #[prost(string, optional, tag = "4")]
+    pub intent: Option<String>,
+    /// Confidence in the role
+    #[prost(float, tag = "5")]
+    pub role_confidence: f32,
+    /// Confidence in the intent
+    #[prost(float, optional, tag = "6")]
+    pub intent_confidence: Option<f32>,
+    /// CSS selector
+    #[prost(string, tag = "7")]
+    pub selector: String,
+    /// Accessible name
+    #[prost(string, optional, tag = "8")]
+    pub accessible_name: Option<String>,
+    /// Link target
+    #[prost(string, optional, tag = "9")]
+    pub href: Option<String>,
+    /// Child node IDs
+    #[prost(string, repeated, tag = "10")]
+    pub children: Vec<String>,
+    /// Parent node ID
+    #[prost(string, optional, tag = "11")]
+    pub parent: Option<String>,
+    /// `type` of an `<input>`
+    #[prost(string, optional, tag = "12")]
+    pub input_type: Option<String>,
+    /// Heading level
+    #[prost(uint32, optional, tag = "13")]
+    pub level: Option<u32>,
+    /// Depth in the tree
+    #[prost(uint32, tag = "14")]
+    pub depth: u32,
+    /// Rendered geometry
+    #[prost(message, optional, tag = "15")]
+    pub bounds: Option<Bounds>,
+    /// Preserved raw attributes
+    #[prost(btree_map = "string, string", tag = "16")]
+    pub attributes: BTreeMap<String, String>,
+    /// Additional metadata
+    #[prost(btree_map = "string, string", tag = "17")]
+    pub metadata: BTreeMap<String, String>,
+    /// Whether a form field is marked invalid
+    #[prost(bool, tag = "18")]
+    pub invalid: bool,
+    /// Validation error message
+    #[prost(string, optional, tag = "19")]
+    pub error_message: Option<String>,
+    /// Keyboard access key
+    #[prost(string, optional, tag = "20")]
+    pub access_key: Option<String>,
+    /// Keyboard shortcuts
+    #[prost(string, repeated, tag = "21")]
+    pub key_shortcuts: Vec<String>,
+    /// Where a link leads
+    #[prost(string, optional, tag = "22")]
+    pub link_kind: Option<String>,
+    /// `rel` tokens of a link
+    #[prost(string, repeated, tag = "23")]
+    pub rel: Vec<String>,
+    /// Language of the link target
+    #[prost(string, optional, tag = "24")]
+    pub hreflang: Option<String>,
+    /// Interactive node built from a generic element
+    #[prost(bool, tag = "25")]
+    pub nonstandard: bool,
+    /// Events with inline handlers
+    #[prost(string, repeated, tag = "26")]
+    pub event_handlers: Vec<String>,
+    /// Effective language
+    #[prost(string, optional, tag = "27")]
+    pub lang: Option<String>,
+    /// Effective text direction
+    #[prost(string, optional, tag = "28")]
+    pub dir: Option<String>,
+    /// `aria-current` state
+    #[prost(string, optional, tag = "29")]
+    pub current: Option<String>,
+    /// Expansion state
+    #[prost(bool, optional, tag = "30")]
+    pub expanded: Option<bool>,
+    /// Whether the node is selected
+    #[prost(bool, tag = "31")]
+    pub selected: bool,
+    /// Value and bounds of a range widget
+    #[prost(message, optional, tag = "32")]
+    pub range: Option<Range>,
+    /// Text of the paragraphs, list items and cells in the node
+    #[prost(string, optional, tag = "33")]
+    pub text_excerpt: Option<String>,
+    /// Landmark inferred from the layout
+    #[prost(bool, tag = "34")]
+    pub inferred: bool,
+    /// Validation constraints of a form field
+    #[prost(message, optional, tag = "35")]
+    pub constraints: Option<Constraints>,
+    /// Role declared in markup rather than implied by the tag
+    #[prost(bool, tag = "36")]
+    pub explicit_role: bool,
+}
+
+/// Value of a range widget (`semantic_dom_ssg.v1.Range`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Range {
+    /// Minimum value
+    #[prost(double, optional, tag = "1")]
+    pub min: Option<f64>,
+    /// Maximum value
+    #[prost(double, optional, tag = "2")]
+    pub max: Option<f64>,
+    /// Current value
+    #[prost(double, optional, tag = "3")]
+    pub now: Option<f64>,
+    /// Step between allowed values
+    #[prost(double, optional, tag = "4")]
+    pub step: Option<f64>,
+    /// Human-readable value
+    #[prost(string, optional, tag = "5")]
+    pub text: Option<String>,
+}
+
+/// Validation constraints (`semantic_dom_ssg.v1.Constraints`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Constraints {
+    /// Whether a value is required
+    #[prost(bool, tag = "1")]
+    pub required: bool,
+    /// Expected value format
+    #[prost(string, optional, tag = "2")]
+    pub format: Option<String>,
+    /// Least value
+    #[prost(string, optional, tag = "3")]
+    pub min: Option<String>,
+    /// Greatest value
+    #[prost(string, optional, tag = "4")]
+    pub max: Option<String>,
+    /// Granularity of values
+    #[prost(string, optional, tag = "5")]
+    pub step: Option<String>,
+    /// Fewest characters
+    #[prost(uint32, optional, tag = "6")]
+    pub min_length: Option<u32>,
+    /// Most characters
+    #[prost(uint32, optional, tag = "7")]
+    pub max_length: Option<u32>,
+    /// Pattern the value must match
+    #[prost(string, optional, tag = "8")]
+    pub pattern: Option<String>,
+    /// File types a file input takes
+    #[prost(string, repeated, tag = "9")]
+    pub accept: Vec<String>,
+    /// Whether several values may be chosen
+    #[prost(bool, tag = "10")]
+    pub multiple: bool,
+}
+
+/// Rendered geometry (`semantic_dom_ssg.v1.Bounds`)
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Bounds {
+    /// Left edge
+    #[prost(double, tag = "1")]
+    pub x: f64,
+    /// Top edge
+    #[prost(double, tag = "2")]
+    pub y: f64,
+    /// Width
+    #[prost(double, tag = "3")]
+    pub width: f64,
+    /// Height
+    #[prost(double, tag = "4")]
+    pub height: f64,
+}
+
+/// State graph (`semantic_dom_ssg.v1.StateGraph`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StateGraph {
+    /// Schema version of the graph
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    /// States
+    #[prost(message, repeated, tag = "2")]
+    pub states: Vec<State>,
+    /// Transitions
+    #[prost(message, repeated, tag = "3")]
+    pub transitions: Vec<Transition>,
+    /// ID of the initial state
+    #[prost(string, optional, tag = "4")]
+    pub initial_state: Option<String>,
+}
+
+/// A state (`semantic_dom_ssg.v1.State`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct State {
+    /// State ID
+    #[prost(string, tag = "1")]
+    pub id: String,
+    /// Name
+    #[prost(string, tag = "2")]
+    pub name: String,
+    /// Description
+    #[prost(string, optional, tag = "3")]
+    pub description: Option<String>,
+    /// URL pattern
+    #[prost(string, optional, tag = "4")]
+    pub url_pattern: Option<String>,
+    /// Whether this is the initial state
+    #[prost(bool, tag = "5")]
+    pub is_initial: bool,
+    /// Whether this is a terminal state
+    #[prost(bool, tag = "6")]
+    pub is_terminal: bool,
+    /// Node an in-page state scrolls to
+    #[prost(string, optional, tag = "7")]
+    pub anchor: Option<String>,
+}
+
+/// A transition (`semantic_dom_ssg.v1.Transition`)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Transition {
+    /// Source state ID
+    #[prost(string, tag = "1")]
+    pub from: String,
+    /// Target state ID
+    #[prost(string, tag = "2")]
+    pub to: String,
+    /// ID of the node that triggers it
+    #[prost(string, tag = "3")]
+    pub trigger: String,
+    /// Action to perform
+    #[prost(string, optional, tag = "4")]
+    pub action: Option<String>,
+    /// Condition for the transition
+    #[prost(string, optional, tag = "5")]
+    pub guard: Option<String>,
+    /// Route template parameters
+    #[prost(btree_map = "string, string", tag = "6")]
+    pub params: BTreeMap<String, String>,
+}
+
+/// Lowercase name of a unit enum, as in JSON output
+fn token<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// Parse a name written by [`token`]
+fn from_token<T: DeserializeOwned>(name: String) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(name))
+        .map_err(|e| Error::SerializationError(e.to_string()))
+}
+
+impl From<&types::SemanticNode> for SemanticNode {
+    fn from(node: &types::SemanticNode) -> Self {
+        SemanticNode {
+            id: node.id.clone(),
+            label: node.label.clone(),
+            role: node.role.as_str().to_string(),
+            intent: node.intent.as_ref().map(|i| i.as_str().to_string()),
+            role_confidence: node.role_confidence,
+            intent_confidence: node.intent_confidence,
+            selector: node.selector.clone(),
+            accessible_name: node.accessible_name.clone(),
+            href: node.href.clone(),
+            children: node.children.clone(),
+            parent: node.parent.clone(),
+            input_type: node.input_type.clone(),
+            level: node.level.map(u32::from),
+            depth: node.depth as u32,
+            bounds: node.bounds.map(|b| Bounds {
+                x: b.x,
+                y: b.y,
+                width: b.width,
+                height: b.height,
+            }),
+            attributes: node.attributes.clone().into_iter().collect(),
+            metadata: node.metadata.clone().unwrap_or_default().into_iter().collect(),
+            invalid: node.invalid,
+            error_message: node.error_message.clone(),
+            access_key: node.access_key.clone(),
+            key_shortcuts: node.key_shortcuts.clone(),
+            link_kind: node.link_kind.map(|k| k.as_str().to_string()),
+            rel: node.rel.clone(),
+            hreflang: node.hreflang.clone(),
+            nonstandard: node.nonstandard,
+            event_handlers: node.event_handlers.clone(),
+            lang: node.lang.clone(),
+            dir: node.dir.as_ref().map(token),
+            current: node.current.as_ref().map(token),
+            expanded: node.expanded,
+            selected: node.selected,
+            range: node.range.clone().map(|r| Range {
+                min: r.min,
+                max: r.max,
+                now: r.now,
+                step: r.step,
+                text: r.text,
+            }),
+            text_excerpt: node.text_excerpt.clone(),
+            inferred: node.inferred,
+            explicit_role: node.explicit_role,
+            constraints: node.constraints.clone().map(|c| Constraints {
+                required: c.required,
+                format: c.format,
+                min: c.min,
+                max: c.max,
+                step: c.step,
+                min_length: c.min_length,
+                max_length: c.max_length,
+                pattern: c.pattern,
+                accept: c.accept,
+                multiple: c.multiple,
+            }),
+        }
+    }
+}
+
+impl TryFrom<SemanticNode> for types::SemanticNode {
+    type Error = Error;
+
+    fn try_from(node: SemanticNode) -> Result<Self> {
+        let role: SemanticRole = node.role.parse()?;
+        let mut out = types::SemanticNode::new(node.id, node.label, role, node.selector);
+        out.intent = node.intent.map(|i| i.parse::<SemanticIntent>()).transpose()?;
+        out.role_confidence = node.role_confidence;
+        out.intent_confidence = node.intent_confidence;
+        out.accessible_name = node.accessible_name;
+        out.href = node.href;
+        out.children = node.children;
+        out.parent = node.parent;
+        out.input_type = node.input_type;
+        out.level = node.level.and_then(|l| u8::try_from(l).ok());
+        out.depth = node.depth as usize;
+        out.bounds = node.bounds.map(|b| types::Bounds {
+            x: b.x,
+            y: b.y,
+            width: b.width,
+            height: b.height,
+        });
+        out.attributes = node.attributes.into_iter().collect();
+        out.metadata = (!node.metadata.is_empty()).then(|| node.metadata.into_iter().collect());
+        out.invalid = node.invalid;
+        out.error_message = node.error_message;
+        out.access_key = node.access_key;
+        out.key_shortcuts = node.key_shortcuts;
+        out.link_kind = node.link_kind.map(from_token).transpose()?;
+        out.rel = node.rel;
+        out.hreflang = node.hreflang;
+        out.nonstandard = node.nonstandard;
+        out.event_handlers = node.event_handlers;
+        out.lang = node.lang;
+        out.dir = node.dir.map(from_token).transpose()?;
+        out.current = node.current.map(from_token).transpose()?;
+        out.expanded = node.expanded;
+        out.selected = node.selected;
+        out.range = node.range.map(|r| types::RangeValue {
+            min: r.min,
+            max: r.max,
+            now: r.now,
+            step: r.step,
+            text: r.text,
+        });
+        out.text_excerpt = node.text_excerpt;
+        out.inferred = node.inferred;
+        out.explicit_role = node.explicit_role;
+        out.constraints = node.constraints.map(|c| types::InputConstraints {
+            required: c.required,
+            format: c.format,
+            min: c.min,
+            max: c.max,
+            step: c.step,
+            min_length: c.min_length,
+            max_length: c.max_length,
+            pattern: c.pattern,
+            accept: c.accept,
+            multiple: c.multiple,
+        });
+        Ok(out)
+    }
+}
+
+impl From<&types::StateGraph> for StateGraph {
+    fn from(graph: &types::StateGraph) -> Self {
+        StateGraph {
+            schema_version: graph.schema_version,
+            states: graph
+                .states
+                .iter()
+                .map(|s| State {
+                    id: s.id.clone(),
+                    name: s.name.clone(),
+                    description: s.description.clone(),
+                    url_pattern: s.url_pattern.clone(),
+                    is_initial: s.is_initial,
+                    is_terminal: s.is_terminal,
+                    anchor: s.anchor.clone(),
+                })
+                .collect(),
+            transitions: graph
+                .transitions
+                .iter()
+                .map(|t| Transition {
+                    from: t.from.clone(),
+                    to: t.to.clone(),
+                    trigger: t.trigger.clone(),
+                    action: t.action.clone(),
+                    guard: t.guard.clone(),
+                    params: t.params.clone().into_iter().collect(),
+                })
+                .collect(),
+            initial_state: graph.initial_state.clone(),
+        }
+    }
+}
+
+impl From<StateGraph> for types::StateGraph {
+    fn from(graph: StateGraph) -> Self {
+        types::StateGraph {
+            schema_version: crate::STATE_GRAPH_SCHEMA_VERSION,
+            states: graph
+                .states
+                .into_iter()
+                .map(|s| types::State {
+                    id: s.id,
+                    name: s.name,
+                    description: s.description,
+                    url_pattern: s.url_pattern,
+                    is_initial: s.is_initial,
+                    is_terminal: s.is_terminal,
+                    anchor: s.anchor,
+                })
+                .collect(),
+            transitions: graph
+                .transitions
+                .into_iter()
+                .map(|t| types::Transition {
+                    from: t.from,
+                    to: t.to,
+                    trigger: t.trigger,
+                    action: t.action,
+                    guard: t.guard,
+                    request: None,
+                    params: t.params.into_iter().collect(),
+                })
+                .collect(),
+            initial_state: graph.initial_state,
+        }
+    }
+}
+
+impl SemanticDOM {
+    /// Convert to the protobuf message
+    pub fn to_proto(&self) -> SemanticDocument {
+        SemanticDocument {
+            title: self.title.clone(),
+            lang: self.lang.clone(),
+            base_url: self.base_url.clone(),
+            nodes: self.reading_order().into_iter().map(SemanticNode::from).collect(),
+            state_graph: Some(StateGraph::from(&self.state_graph)),
+        }
+    }
+
+    /// Encode as protobuf bytes
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<nav><a href="/docs">Docs</a></nav><main><h1>Guide</h1></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let bytes = sdom.to_protobuf();
+    /// let decoded = SemanticDOM::from_protobuf(&bytes, Config::default()).unwrap();
+    /// assert_eq!(decoded.to_agent_summary(), sdom.to_agent_summary());
+    /// ```
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        prost::Message::encode_to_vec(&self.to_proto())
+    }
+
+    /// Build a document from the protobuf message
+    ///
+    /// # Errors
+    /// - [`Error::UnknownRole`] or [`Error::UnknownIntent`] for names this
+    ///   version does not know
+    pub fn from_proto(document: SemanticDocument, config: Config) -> Result<Self> {
+        let mut sdom = SemanticDOM::empty(config);
+        sdom.title = document.title;
+        sdom.lang = document.lang;
+        sdom.base_url = document.base_url;
+        for node in document.nodes {
+            let node = types::SemanticNode::try_from(node)?;
+            sdom.order.push(node.id.clone());
+            sdom.index.insert(node.id.clone(), node);
+        }
+        sdom.id_counter = sdom.index.len();
+        sdom.rebuild_categories();
+        sdom.state_graph = document.state_graph.map(Into::into).unwrap_or_default();
+        Ok(sdom)
+    }
+
+    /// Decode protobuf bytes
+    ///
+    /// # Errors
+    /// - [`Error::SerializationError`] if the bytes are not a valid message
+    /// - as [`Self::from_proto`]
+    pub fn from_protobuf(bytes: &[u8], config: Config) -> Result<Self> {
+        let document: SemanticDocument = prost::Message::decode(bytes)
+            .map_err(|e| Error::SerializationError(e.to_string()))?;
+        Self::from_proto(document, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AttributePolicy;
+
+    #[test]
+    fn test_round_trip_keeps_nodes_and_graph() {
+        let html = r#"<nav><a href="/p/1" data-agent-intent="download">One</a></nav>
+            <main><h2>Title</h2><input type="email" name="email">
+            <button aria-expanded="false" aria-current="step" dir="rtl">Menu</button></main>"#;
+        let policy = AttributePolicy::allowlist(["name"]);
+        let config = Config::builder().preserve_attributes(policy).build().unwrap();
+        let mut sdom = SemanticDOM::parse(html, config.clone()).unwrap();
+        let link = sdom.interactables[0].clone();
+        let rect = types::Bounds { x: 1.0, y: 2.0, width: 3.0, height: 4.0 };
+        sdom.set_bounds(&link, Some(rect)).unwrap();
+
+        let decoded = SemanticDOM::from_protobuf(&sdom.to_protobuf(), config).unwrap();
+        assert_eq!(decoded.to_json().unwrap(), sdom.to_json().unwrap());
+        assert_eq!(decoded.get(&link).unwrap().bounds, Some(rect));
+        assert_eq!(decoded.state_graph.to_json().unwrap(), sdom.state_graph.to_json().unwrap());
+
+        let mut bad = sdom.to_proto();
+        bad.nodes[0].role = "carousel".to_string();
+        let result = SemanticDOM::from_proto(bad, Config::default());
+        assert!(matches!(result, Err(Error::UnknownRole(_))));
+        assert!(SemanticDOM::from_protobuf(&[0xff], Config::default()).is_err());
+    }
+}
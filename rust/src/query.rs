@@ -0,0 +1,128 @@
+//! Role and intent indices
+//!
+//! Nodes are grouped by role and by intent as they are parsed, so category
+//! queries and counts read a list instead of scanning the whole index. The
+//! groups are kept in document order and rebuilt after edits.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode, SemanticRole};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Node counts of a document
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentStats {
+    /// All semantic nodes
+    pub node_count: usize,
+    /// Landmark nodes
+    pub landmark_count: usize,
+    /// Interactable nodes
+    pub interactable_count: usize,
+    /// Heading nodes
+    pub heading_count: usize,
+    /// Nodes per role, in order of first appearance
+    pub role_counts: IndexMap<SemanticRole, usize>,
+    /// Nodes per intent, in order of first appearance
+    pub intent_counts: IndexMap<SemanticIntent, usize>,
+}
+
+impl DocumentStats {
+    /// Number of nodes with a role
+    pub fn role(&self, role: &SemanticRole) -> usize {
+        self.role_counts.get(role).copied().unwrap_or(0)
+    }
+
+    /// Number of nodes with an intent
+    pub fn intent(&self, intent: &SemanticIntent) -> usize {
+        self.intent_counts.get(intent).copied().unwrap_or(0)
+    }
+}
+
+impl SemanticDOM {
+    /// Add a newly parsed node to the role and intent indices
+    pub(crate) fn index_node(&mut self, node: &SemanticNode) {
+        self.by_role.entry(node.role.clone()).or_default().push(node.id.clone());
+        if let Some(intent) = &node.intent {
+            self.by_intent.entry(intent.clone()).or_default().push(node.id.clone());
+        }
+    }
+
+    /// Rebuild the role and intent indices from the nodes in document order
+    pub(crate) fn rebuild_indices(&mut self) {
+        self.by_role.clear();
+        self.by_intent.clear();
+        let order = std::mem::take(&mut self.order);
+        for id in &order {
+            if let Some(node) = self.index.get(id).cloned() {
+                self.index_node(&node);
+            }
+        }
+        self.order = order;
+    }
+
+    fn lookup<'a>(&'a self, ids: Option<&'a Vec<String>>) -> Vec<&'a SemanticNode> {
+        ids.into_iter().flatten().filter_map(|id| self.index.get(id)).collect()
+    }
+
+    /// Get the nodes with a role, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticIntent, SemanticRole};
+    ///
+    /// let html = r#"<main><button>Save</button><a href="/">Home</a><button>Undo</button></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let buttons = sdom.query_by_role(&SemanticRole::Button);
+    /// assert_eq!(buttons[1].label, "Undo");
+    /// assert_eq!(sdom.query_by_intent(&SemanticIntent::Navigate)[0].label, "Home");
+    /// assert_eq!(sdom.stats().role(&SemanticRole::Button), 2);
+    /// ```
+    pub fn query_by_role(&self, role: &SemanticRole) -> Vec<&SemanticNode> {
+        self.lookup(self.by_role.get(role))
+    }
+
+    /// Get the nodes with an intent, in document order
+    pub fn query_by_intent(&self, intent: &SemanticIntent) -> Vec<&SemanticNode> {
+        self.lookup(self.by_intent.get(intent))
+    }
+
+    /// Count nodes by category
+    pub fn stats(&self) -> DocumentStats {
+        DocumentStats {
+            node_count: self.index.len(),
+            landmark_count: self.landmarks.len(),
+            interactable_count: self.interactables.len(),
+            heading_count: self.headings.len(),
+            role_counts: self.by_role.iter().map(|(r, ids)| (r.clone(), ids.len())).collect(),
+            intent_counts: self.by_intent.iter().map(|(i, ids)| (i.clone(), ids.len())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_indices_follow_updates_and_edits() {
+        let html = r#"<nav><a href="/">Home</a></nav><main><button>Buy</button></main>"#;
+        let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let buy = sdom.query_by_role(&SemanticRole::Button)[0].id.clone();
+
+        // The update renames the fresh parse's IDs back to the old ones
+        sdom.update(&html.replace("Buy", "Buy now")).unwrap();
+        assert_eq!(sdom.query_by_role(&SemanticRole::Button)[0].id, buy);
+
+        sdom.set_role(&buy, SemanticRole::Link).unwrap();
+        sdom.set_intent(&buy, Some(SemanticIntent::Download)).unwrap();
+        assert!(sdom.query_by_role(&SemanticRole::Button).is_empty());
+        assert_eq!(sdom.query_by_intent(&SemanticIntent::Download)[0].id, buy);
+
+        let stats = sdom.stats();
+        assert_eq!((stats.role(&SemanticRole::Link), stats.role(&SemanticRole::Button)), (2, 0));
+        let roles: Vec<&SemanticRole> = stats.role_counts.keys().collect();
+        assert_eq!(roles[..2], [&SemanticRole::Navigation, &SemanticRole::Link]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Values of sliders, spin buttons and progress bars
+//!
+//! `aria-valuemin/max/now/text` take precedence over native attributes,
+//! which fill in the rest with the HTML defaults (a range input spans
+//! 0–100 and starts at the midpoint, a `<progress>` runs to 1.0, ...).
+
+use crate::types::{RangeValue, SemanticRole};
+use scraper::node::Element;
+
+/// Read the value and bounds of a range widget
+pub(crate) fn range_value(el: &Element, role: &SemanticRole) -> Option<RangeValue> {
+    let num = |name: &str| {
+        el.attr(name)
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite())
+    };
+    let native = match (el.name(), role) {
+        ("input", SemanticRole::Slider) => {
+            let min = num("min").unwrap_or(0.0);
+            let max = num("max").unwrap_or(100.0).max(min);
+            RangeValue {
+                min: Some(min),
+                max: Some(max),
+                now: Some(num("value").unwrap_or(min + (max - min) / 2.0).clamp(min, max)),
+                step: Some(num("step").unwrap_or(1.0)),
+                text: None,
+            }
+        }
+        ("input", _) => RangeValue {
+            min: num("min"),
+            max: num("max"),
+            now: num("value"),
+            step: num("step"),
+            text: None,
+        },
+        // Without a value, a progress element is indeterminate
+        ("progress", _) => {
+            let max = num("max").filter(|m| *m > 0.0).unwrap_or(1.0);
+            RangeValue {
+                min: Some(0.0),
+                max: Some(max),
+                now: num("value").map(|v| v.clamp(0.0, max)),
+                ..RangeValue::default()
+            }
+        }
+        ("meter", _) => {
+            let min = num("min").unwrap_or(0.0);
+            let max = num("max").unwrap_or(1.0).max(min);
+            RangeValue {
+                min: Some(min),
+                max: Some(max),
+                now: Some(num("value").unwrap_or(0.0).clamp(min, max)),
+                ..RangeValue::default()
+            }
+        }
+        // ARIA sliders and progress bars default to 0-100
+        (_, SemanticRole::Slider | SemanticRole::ProgressBar) => RangeValue {
+            min: Some(0.0),
+            max: Some(100.0),
+            ..RangeValue::default()
+        },
+        _ => RangeValue::default(),
+    };
+
+    let value = RangeValue {
+        min: num("aria-valuemin").or(native.min),
+        max: num("aria-valuemax").or(native.max),
+        now: num("aria-valuenow").or(native.now),
+        step: native.step,
+        text: el
+            .attr("aria-valuetext")
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string),
+    };
+    (value != RangeValue::default()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM, SemanticRole};
+
+    #[test]
+    fn test_range_values() {
+        let html = r#"<main>
+            <input type="range" aria-label="Volume" min="0" max="10" value="7">
+            <input type="range" aria-label="Zoom">
+            <div role="slider" aria-label="Rating" aria-valuenow="3" aria-valuemax="5"
+                 aria-valuetext="3 of 5 stars" tabindex="0"></div>
+            <input type="number" name="qty" min="1" value="2">
+            <progress aria-label="Upload" value="0.45"></progress>
+            <progress aria-label="Loading"></progress>
+        </main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let range = |label: &str| {
+            let node = sdom.index.values().find(|n| n.label == label).unwrap();
+            (node.role.clone(), node.range.clone().unwrap())
+        };
+
+        let (role, volume) = range("Volume");
+        assert_eq!(role, SemanticRole::Slider);
+        assert_eq!((volume.now, volume.fraction()), (Some(7.0), Some(0.7)));
+        assert_eq!(range("Zoom").1.now, Some(50.0));
+
+        let (role, rating) = range("Rating");
+        assert_eq!(role, SemanticRole::Slider);
+        assert_eq!((rating.min, rating.max), (Some(0.0), Some(5.0)));
+        assert_eq!(rating.display().as_deref(), Some("3 of 5 stars"));
+
+        let (role, qty) = range("qty");
+        assert_eq!(role, SemanticRole::SpinButton);
+        assert_eq!((qty.min, qty.max, qty.now), (Some(1.0), None, Some(2.0)));
+
+        assert_eq!(range("Upload").1.fraction(), Some(0.45));
+        assert_eq!(range("Loading").1.now, None);
+
+        let summary = sdom.to_agent_summary();
+        assert!(summary.contains("[inp]Volume=7"), "{}", summary);
+        assert!(summary.contains("PROGRESS: Upload=45%"), "{}", summary);
+    }
+}
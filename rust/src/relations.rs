@@ -0,0 +1,181 @@
+//! ARIA relationships between nodes
+//!
+//! `aria-controls`, `aria-owns` and `aria-activedescendant` point at other
+//! elements by HTML `id`. After parsing they are resolved into typed
+//! [`Relation`]s, so agents can tell which panel a tab shows, which popup a
+//! combobox opens, and which option is currently active. References to
+//! missing elements are reported as [`Error::BrokenReference`] warnings.
+
+use crate::diagnostics::DiagnosticKind;
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+use crate::Error;
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// The ARIA attribute a relationship comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationKind {
+    /// `aria-controls`: the source changes the target's content or visibility
+    Controls,
+    /// `aria-owns`: the target is a logical child of the source
+    Owns,
+    /// `aria-activedescendant`: the target is the source's focused item
+    ActiveDescendant,
+}
+
+impl RelationKind {
+    /// All relationship kinds, in resolution order
+    pub const ALL: [RelationKind; 3] =
+        [RelationKind::Controls, RelationKind::Owns, RelationKind::ActiveDescendant];
+
+    /// The attribute declaring this relationship
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            RelationKind::Controls => "aria-controls",
+            RelationKind::Owns => "aria-owns",
+            RelationKind::ActiveDescendant => "aria-activedescendant",
+        }
+    }
+}
+
+/// A resolved reference from one node to another element
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relation {
+    /// Node declaring the reference
+    pub from: String,
+    /// Kind of reference
+    pub kind: RelationKind,
+    /// HTML `id` named by the attribute
+    pub target_id: String,
+    /// Node for the referenced element, or `None` when that element did
+    /// not become a semantic node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+}
+
+/// Text of the elements named by an element's `aria-labelledby`, in the
+/// order listed; `None` when they are missing or empty
+pub(crate) fn labelledby_text(element: ElementRef) -> Option<String> {
+    let ids = element.value().attr("aria-labelledby")?;
+    let root = element.ancestors().last()?;
+    let elements: Vec<ElementRef> = root.descendants().filter_map(ElementRef::wrap).collect();
+    let words: Vec<&str> = ids
+        .split_whitespace()
+        .filter_map(|id| elements.iter().find(|e| e.value().id() == Some(id)))
+        .flat_map(|e| e.text().flat_map(str::split_whitespace))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+impl SemanticDOM {
+    /// Resolve ARIA references on parsed elements, given the node created
+    /// for each element
+    pub(crate) fn resolve_relations(&mut self, document: &Html, nodes: &AHashMap<NodeId, String>) {
+        // The first element with an id wins, as with getElementById
+        let mut targets: AHashMap<&str, NodeId> = AHashMap::new();
+        for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+            if let Some(id) = element.value().id() {
+                targets.entry(id).or_insert(element.id());
+            }
+        }
+
+        for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+            let Some(from) = nodes.get(&element.id()) else {
+                continue;
+            };
+            for kind in RelationKind::ALL {
+                let Some(value) = element.value().attr(kind.attribute()) else {
+                    continue;
+                };
+                for target_id in value.split_whitespace() {
+                    let Some(target) = targets.get(target_id) else {
+                        let error = Error::BrokenReference {
+                            node: from.clone(),
+                            attribute: kind.attribute(),
+                            id: target_id.to_string(),
+                        };
+                        self.warn(DiagnosticKind::Warning, Some(from), error);
+                        continue;
+                    };
+                    self.relations.push(Relation {
+                        from: from.clone(),
+                        kind,
+                        target_id: target_id.to_string(),
+                        to: nodes.get(target).cloned(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Get the ARIA relationships between nodes, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, RelationKind, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <div role="tab" id="t1" aria-controls="p1">Specs</div>
+    ///     <div role="tabpanel" id="p1">Weight: 1kg</div>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let relation = &sdom.relations()[0];
+    /// assert_eq!(relation.kind, RelationKind::Controls);
+    /// assert_eq!(relation.to.as_deref(), Some("sdom_p1"));
+    /// ```
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    /// Get the nodes a node refers to with the given kind of relationship
+    pub fn related(&self, id: &str, kind: RelationKind) -> Vec<&SemanticNode> {
+        self.relations
+            .iter()
+            .filter(|r| r.from == id && r.kind == kind)
+            .filter_map(|r| self.index.get(r.to.as_deref()?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticRole};
+
+    #[test]
+    fn test_relations_resolved_by_id() {
+        let html = r#"<main>
+            <input role="combobox" id="city" aria-controls="cities" aria-activedescendant="opt2">
+            <ul role="listbox" id="cities"><li id="opt1">Oslo</li><li id="opt2">Rome</li></ul>
+            <button aria-controls="menu drawer" aria-owns="">Menu</button>
+            <nav id="menu"><a href="/">Home</a></nav>
+        </main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let kinds: Vec<(RelationKind, &str)> =
+            sdom.relations().iter().map(|r| (r.kind, r.target_id.as_str())).collect();
+        assert_eq!(
+            kinds,
+            [
+                (RelationKind::Controls, "cities"),
+                (RelationKind::ActiveDescendant, "opt2"),
+                (RelationKind::Controls, "menu"),
+            ]
+        );
+        // Plain list items are not nodes, so the reference keeps only the id
+        assert_eq!(sdom.relations()[1].to, None);
+
+        let listbox = sdom.related("sdom_city", RelationKind::Controls);
+        assert_eq!(listbox[0].role, SemanticRole::Select);
+
+        assert!(matches!(
+            sdom.warnings(),
+            [Error::BrokenReference { attribute: "aria-controls", id, .. }] if id == "drawer"
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+//! Search results and other listings
+//!
+//! A results page is dozens of links, but only one per result matters:
+//! the title link, with the snippet beside it saying what it leads to.
+//! Results are recognized as at least [`MIN_RESULTS`] sibling cards of the
+//! same shape (tag and classes), each with a title link and most with
+//! text besides the title. Navigation, headers and footers are skipped;
+//! their menus repeat links without describing them.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Fewest similar cards that make a result list
+pub const MIN_RESULTS: usize = 3;
+
+/// Longest snippet kept, in characters
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// One result of a listing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultItem {
+    /// Semantic ID of the title link
+    pub id: String,
+    /// Title, the text of the title link
+    pub title: String,
+    /// Where the title link leads
+    pub href: String,
+    /// Text of the card besides the title
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub snippet: Option<String>,
+}
+
+/// A list of results, such as search hits or articles in a category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultList {
+    /// CSS selector of the element holding the cards
+    pub selector: String,
+    /// Semantic ID of that element, if it is a node
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<String>,
+    /// Results in page order
+    pub items: Vec<ResultItem>,
+}
+
+fn is_heading(element: ElementRef) -> bool {
+    matches!(element.value().name(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6")
+        || element.value().attr("role").is_some_and(|r| r.trim() == "heading")
+}
+
+/// Regions whose repeated links are menus rather than results
+fn is_chrome(element: ElementRef) -> bool {
+    matches!(element.value().name(), "nav" | "header" | "footer" | "menu")
+        || element.value().attr("role").is_some_and(|r| {
+            matches!(r.trim(), "navigation" | "banner" | "contentinfo" | "menu" | "menubar")
+        })
+}
+
+/// Whitespace-collapsed text of `root`, leaving out elements `skip` matches
+fn text_without(root: ElementRef, skip: impl Fn(ElementRef) -> bool) -> String {
+    let mut words: Vec<&str> = Vec::new();
+    let mut stack = vec![*root];
+    while let Some(node) = stack.pop() {
+        if let Some(text) = node.value().as_text() {
+            words.extend(text.split_whitespace());
+            continue;
+        }
+        if let Some(element) = ElementRef::wrap(node) {
+            if skip(element) || matches!(element.value().name(), "script" | "style" | "template") {
+                continue;
+            }
+        }
+        let children: Vec<_> = node.children().collect();
+        stack.extend(children.into_iter().rev());
+    }
+    words.join(" ")
+}
+
+/// The result a card shows: its title link, preferably in a heading
+fn result_item(
+    card: ElementRef,
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> Option<ResultItem> {
+    let links: Vec<ElementRef> = card
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "a" && e.value().attr("href").is_some())
+        .collect();
+    // Headings hold the title link, or the other way round
+    let in_heading = |link: &&ElementRef| {
+        link.ancestors()
+            .take_while(|a| a.id() != card.id())
+            .chain(link.descendants())
+            .filter_map(ElementRef::wrap)
+            .any(is_heading)
+    };
+    let link = links.iter().find(in_heading).or(links.first())?;
+    // Links dropped as unsafe are not offered as results
+    let node = nodes.get(&link.id()).and_then(|id| index.get(id))?;
+    let href = node.href.clone()?;
+    let title = text_without(*link, |_| false);
+    let title = if title.is_empty() { node.label.clone() } else { title };
+    if title.is_empty() {
+        return None;
+    }
+    let snippet = text_without(card, |e| e.id() == link.id() || is_heading(e));
+    let snippet = Some(crate::summary::truncate(&snippet, MAX_SNIPPET_CHARS))
+        .filter(|s| !s.is_empty());
+    Some(ResultItem { id: node.id.clone(), title, href, snippet })
+}
+
+/// The results among a container's children, if they form a listing
+fn result_list(
+    container: ElementRef,
+    children: &[ElementRef],
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> Option<ResultList> {
+    // Cards of one listing share their tag and classes
+    let mut shapes: IndexMap<(&str, Vec<&str>), Vec<ResultItem>> = IndexMap::new();
+    for child in children {
+        let mut classes: Vec<&str> = child.value().classes().collect();
+        classes.sort_unstable();
+        if let Some(item) = result_item(*child, nodes, index) {
+            shapes.entry((child.value().name(), classes)).or_default().push(item);
+        }
+    }
+    let items = shapes.into_values().rev().max_by_key(Vec::len)?;
+    let described = items.iter().filter(|i| i.snippet.is_some()).count();
+    (items.len() >= MIN_RESULTS && described * 2 > items.len()).then(|| ResultList {
+        selector: SemanticDOM::build_element_selector(container.value()),
+        id: nodes.get(&container.id()).cloned(),
+        items,
+    })
+}
+
+/// Find result listings, given the node created for each element
+pub(crate) fn result_lists(
+    document: &Html,
+    nodes: &AHashMap<NodeId, String>,
+    index: &AHashMap<String, SemanticNode>,
+) -> Vec<ResultList> {
+    let mut lists = Vec::new();
+    let mut stack = vec![document.root_element()];
+    while let Some(element) = stack.pop() {
+        if is_chrome(element) {
+            continue;
+        }
+        let children: Vec<ElementRef> = element.children().filter_map(ElementRef::wrap).collect();
+        // Cards of a listing are not searched for listings of their own
+        match result_list(element, &children, nodes, index) {
+            Some(list) => lists.push(list),
+            None => stack.extend(children.into_iter().rev()),
+        }
+    }
+    lists
+}
+
+impl SemanticDOM {
+    /// Get the result listings on the page, such as search hits
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main><div id="hits">
+    ///   <div class="hit"><h3><a href="/rust">Rust</a></h3><p>A systems language.</p></div>
+    ///   <div class="hit"><h3><a href="/go">Go</a></h3><p>Simple and fast.</p></div>
+    ///   <div class="hit"><h3><a href="/zig">Zig</a></h3><p>No hidden control flow.</p></div>
+    /// </div></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let results = &sdom.result_lists()[0];
+    /// assert_eq!(results.selector, "div#hits");
+    /// assert_eq!(results.items[1].title, "Go");
+    /// assert_eq!(results.items[1].href, "/go");
+    /// assert_eq!(results.items[1].snippet.as_deref(), Some("Simple and fast."));
+    /// ```
+    pub fn result_lists(&self) -> &[ResultList] {
+        &self.results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_result_lists() {
+        let html = r#"<nav><ul><li><a href="/">Home</a> Start here</li>
+            <li><a href="/a">A</a> About</li><li><a href="/b">B</a> Blog</li></ul></nav>
+            <main><ol>
+              <li class="result"><a href="/1"><h2>First</h2></a>
+                <a href="/1/cache">Cached</a><span>The first hit</span></li>
+              <li class="result"><h2><a href="/2">Second</a></h2>
+                <ul class="tags"><li><a href="/t/x">x</a></li><li><a href="/t/y">y</a></li>
+                <li><a href="/t/z">z</a></li></ul></li>
+              <li class="result"><h2><a href="/3">Third</a></h2>The third hit</li>
+              <li class="ad"><a href="javascript:void(0)">Sponsored</a> Buy now</li>
+            </ol>
+            <div><a href="/x">More</a><a href="/y">Older</a><a href="/z">Newer</a></div></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let lists = sdom.result_lists();
+        assert_eq!(lists.len(), 1, "{:?}", lists);
+        let titles: Vec<(&str, &str, Option<&str>)> = lists[0]
+            .items
+            .iter()
+            .map(|i| (i.title.as_str(), i.href.as_str(), i.snippet.as_deref()))
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                ("First", "/1", Some("Cached The first hit")),
+                ("Second", "/2", Some("x y z")),
+                ("Third", "/3", Some("The third hit")),
+            ]
+        );
+        assert_eq!(lists[0].id.as_deref(), Some("sdom_ol_10"));
+    }
+}
@@ -0,0 +1,345 @@
+//! Route templates for site-level state graphs
+//!
+//! A graph merged from many pages of a catalog has one state per product
+//! URL. [`StateGraph::templated`] collapses URLs that differ only in an
+//! identifier segment (`/product/123`, `/product/456`) into a single state
+//! with a [`RouteTemplate`] URL (`/product/{id}`), and records the values
+//! on the transitions into it, so the graph grows with the number of page
+//! types rather than the number of pages.
+
+use crate::types::{State, StateGraph, Transition};
+use ahash::{AHashMap, AHashSet};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Distinct URLs needed before hyphenated segments are treated as slugs;
+/// two pages under one parent (`/docs/get-started`, `/docs/self-hosting`)
+/// are often just siblings
+const MIN_SLUG_PAGES: usize = 3;
+
+/// A URL pattern with `{name}` placeholders for path segments
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::RouteTemplate;
+///
+/// let route = RouteTemplate::parse("/product/{id}/reviews");
+/// assert_eq!(route.params, ["id"]);
+///
+/// let params = route.matches("/product/42/reviews?page=2").unwrap();
+/// assert_eq!(params["id"], "42");
+/// assert!(route.matches("/product/42").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteTemplate {
+    /// The pattern, e.g. `/product/{id}`
+    pub pattern: String,
+    /// Parameter names in path order
+    pub params: Vec<String>,
+}
+
+impl RouteTemplate {
+    /// Parse a pattern; segments written `{name}` become parameters
+    pub fn parse(pattern: &str) -> Self {
+        let (_, segments, _) = split_url(pattern);
+        let params = segments.iter().filter_map(|s| param_name(s)).map(str::to_string).collect();
+        RouteTemplate { pattern: pattern.to_string(), params }
+    }
+
+    /// Extract the parameters of a URL matching the template
+    ///
+    /// The origin, if any, must match exactly; the query string and
+    /// fragment of `url` are ignored unless the pattern has its own.
+    pub fn matches(&self, url: &str) -> Option<IndexMap<String, String>> {
+        let (origin, segments, suffix) = split_url(&self.pattern);
+        let (url_origin, url_segments, url_suffix) = split_url(url);
+        if origin != url_origin
+            || segments.len() != url_segments.len()
+            || (!suffix.is_empty() && suffix != url_suffix)
+        {
+            return None;
+        }
+        let mut params = IndexMap::new();
+        for (segment, value) in segments.iter().zip(&url_segments) {
+            match param_name(segment) {
+                Some(name) if !value.is_empty() => {
+                    params.insert(name.to_string(), value.to_string());
+                }
+                Some(_) => return None,
+                None if segment != value => return None,
+                None => {}
+            }
+        }
+        Some(params)
+    }
+}
+
+fn param_name(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}').filter(|n| !n.is_empty())
+}
+
+/// Split a URL into origin (empty for a path), path segments and the
+/// query/fragment suffix
+fn split_url(url: &str) -> (&str, Vec<&str>, &str) {
+    let path_start = match url.find("://") {
+        Some(scheme) => url[scheme + 3..].find('/').map_or(url.len(), |p| scheme + 3 + p),
+        None => 0,
+    };
+    let (origin, rest) = url.split_at(path_start);
+    let (path, suffix) = rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+    let segments = path.trim_matches('/').split('/').filter(|s| !s.is_empty()).collect();
+    (origin, segments, suffix)
+}
+
+/// Whether a path segment looks like a generated identifier: a number, a
+/// UUID or a hex hash
+fn is_identifier(segment: &str) -> bool {
+    let hex = |s: &str| s.chars().all(|c| c.is_ascii_hexdigit());
+    let uuid = segment.len() == 36
+        && segment.split('-').map(str::len).eq([8, 4, 4, 4, 12])
+        && hex(&segment.replace('-', ""));
+    let hash = segment.len() >= 8
+        && hex(segment)
+        && segment.chars().any(|c| c.is_ascii_digit())
+        && segment.chars().any(|c| c.is_ascii_alphabetic());
+    segment.chars().all(|c| c.is_ascii_digit()) || uuid || hash
+}
+
+/// Build a pattern from a URL, replacing the marked segments
+fn pattern(url: &str, dynamic: &[Option<&str>]) -> String {
+    let (origin, segments, suffix) = split_url(url);
+    let mut seen: AHashMap<&str, usize> = AHashMap::new();
+    let path: Vec<String> = segments
+        .iter()
+        .zip(dynamic)
+        .map(|(segment, kind)| match kind {
+            Some(kind) => {
+                let n = seen.entry(kind).or_default();
+                *n += 1;
+                match *n {
+                    1 => format!("{{{}}}", kind),
+                    n => format!("{{{}{}}}", kind, n),
+                }
+            }
+            None => segment.to_string(),
+        })
+        .collect();
+    format!("{}/{}{}", origin, path.join("/"), suffix)
+}
+
+/// Group URLs into templates; returns a pattern for each templated URL
+fn route_patterns(urls: &[&str]) -> AHashMap<String, String> {
+    let mut groups: IndexMap<String, Vec<&str>> = IndexMap::new();
+    for url in urls {
+        let (_, segments, _) = split_url(url);
+        let dynamic: Vec<Option<&str>> =
+            segments.iter().map(|s| is_identifier(s).then_some("id")).collect();
+        if dynamic.iter().any(Option::is_some) {
+            groups.entry(pattern(url, &dynamic)).or_default().push(url);
+        }
+    }
+    let mut patterns = AHashMap::new();
+    for (pattern, members) in &groups {
+        if members.iter().collect::<AHashSet<_>>().len() >= 2 {
+            for url in members {
+                patterns.insert(url.to_string(), pattern.clone());
+            }
+        }
+    }
+
+    // Slugs: hyphenated last segments under a shared, non-root parent
+    let mut slugs: IndexMap<String, AHashSet<&str>> = IndexMap::new();
+    for url in urls.iter().filter(|u| !patterns.contains_key(**u)) {
+        let (_, segments, _) = split_url(url);
+        if segments.len() >= 2 && segments.last().is_some_and(|s| s.contains('-')) {
+            let mut dynamic = vec![None; segments.len()];
+            dynamic[segments.len() - 1] = Some("slug");
+            slugs.entry(pattern(url, &dynamic)).or_default().insert(url);
+        }
+    }
+    for (pattern, members) in slugs {
+        if members.len() >= MIN_SLUG_PAGES {
+            for url in members {
+                patterns.insert(url.to_string(), pattern.clone());
+            }
+        }
+    }
+    patterns
+}
+
+fn same_transition(a: &Transition, b: &Transition) -> bool {
+    a.from == b.from && a.to == b.to && a.trigger == b.trigger && a.action == b.action
+}
+
+impl StateGraph {
+    /// Merge another page's graph into this one
+    ///
+    /// States with the same ID are the same page and are kept once, as are
+    /// identical transitions. The initial state stays this graph's. Page
+    /// graphs share IDs only when their URLs agree, so parse each page with
+    /// its current navigation link marked (`aria-current="page"`) for the
+    /// pages to connect.
+    pub fn merge(&mut self, other: &StateGraph) {
+        for state in &other.states {
+            if !self.states.iter().any(|s| s.id == state.id) {
+                let is_initial = self.initial_state.is_none() && state.is_initial;
+                self.states.push(State { is_initial, ..state.clone() });
+            }
+        }
+        for transition in &other.transitions {
+            if !self.transitions.iter().any(|t| same_transition(t, transition)) {
+                self.transitions.push(transition.clone());
+            }
+        }
+        if self.initial_state.is_none() {
+            self.initial_state = other.initial_state.clone();
+        }
+    }
+
+    /// Collapse states for dynamic routes into templated states
+    ///
+    /// URLs that differ only in numeric, UUID or hash segments become one
+    /// state with an `{id}` placeholder; three or more hyphenated last
+    /// segments under the same parent become `{slug}`. Transitions into a
+    /// templated state carry the segment values as [`Transition::params`].
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <a href="/product/123">Red shoes</a>
+    ///     <a href="/product/456">Blue hat</a>
+    ///     <a href="/cart">Cart</a>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let graph = sdom.state_graph.templated();
+    ///
+    /// let urls: Vec<_> = graph.states.iter().filter_map(|s| s.url_pattern.as_deref()).collect();
+    /// assert_eq!(urls, ["/", "/product/{id}", "/cart"]);
+    /// assert_eq!(graph.transitions[1].params["id"], "456");
+    /// ```
+    pub fn templated(&self) -> StateGraph {
+        // Anchor states are scroll positions, not routes
+        let urls: Vec<&str> = self
+            .states
+            .iter()
+            .filter(|s| s.anchor.is_none())
+            .filter_map(|s| s.url_pattern.as_deref())
+            .collect();
+        let patterns = route_patterns(&urls);
+
+        let mut graph = StateGraph::new();
+        let mut renamed: AHashMap<&str, (String, &str)> = AHashMap::new();
+        for state in &self.states {
+            let url = state.url_pattern.as_deref().filter(|_| state.anchor.is_none());
+            let Some((url, pattern)) = url.and_then(|u| Some((u, patterns.get(u)?))) else {
+                graph.states.push(state.clone());
+                continue;
+            };
+            let id =
+                format!("state_{}", pattern.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+            renamed.insert(&state.id, (id.clone(), url));
+            match graph.states.iter_mut().find(|s| s.id == id) {
+                Some(existing) => {
+                    existing.is_initial |= state.is_initial;
+                    existing.is_terminal &= state.is_terminal;
+                }
+                None => graph.states.push(State {
+                    id,
+                    name: pattern.clone(),
+                    description: None,
+                    url_pattern: Some(pattern.clone()),
+                    is_initial: state.is_initial,
+                    is_terminal: state.is_terminal,
+                    anchor: None,
+                }),
+            }
+        }
+
+        let rename = |id: &str| renamed.get(id).map_or(id.to_string(), |(new, _)| new.clone());
+        for transition in &self.transitions {
+            let mut transition = transition.clone();
+            if let Some((to, url)) = renamed.get(transition.to.as_str()) {
+                let route = RouteTemplate::parse(patterns[*url].as_str());
+                transition.params = route.matches(url).unwrap_or_default();
+                transition.to = to.clone();
+            }
+            transition.from = rename(&transition.from);
+            let duplicate = graph.transitions.iter().any(|t| {
+                same_transition(t, &transition) && t.params == transition.params
+            });
+            if !duplicate {
+                graph.transitions.push(transition);
+            }
+        }
+        graph.initial_state = self.initial_state.as_deref().map(rename);
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_merged_catalog_graph_is_templated() {
+        let listing = r#"<nav><a href="/shop" aria-current="page">Shop</a></nav><main>
+            <a href="/product/123">Mug</a><a href="/product/456">Shirt</a>
+            <a href="/guides/brewing-tea">Tea</a><a href="/guides/cold-brew">Cold</a>
+            <a href="/guides/pour-over">Pour</a><a href="/guides/faq">FAQ</a>
+        </main>"#;
+        let product = r#"<nav><a href="/shop">Shop</a></nav><main>
+            <a href="/product/789" aria-current="page">Lamp</a>
+            <a href="/orders/0b6a59f4-7c2e-4e6b-9a43-1f1d2f0c9e11/items/2">Order</a>
+            <a href="/orders/0b6a59f4-7c2e-4e6b-9a43-1f1d2f0c9e11/items/3">Refund</a>
+        </main>"#;
+        let mut graph = SemanticDOM::parse(listing, Config::default()).unwrap().state_graph;
+        graph.merge(&SemanticDOM::parse(product, Config::default()).unwrap().state_graph);
+        assert_eq!(graph.initial_state.as_deref(), Some("state__shop"));
+        assert_eq!(graph.states.iter().filter(|s| s.is_initial).count(), 1);
+
+        let graph = graph.templated();
+        let urls: Vec<&str> =
+            graph.states.iter().filter_map(|s| s.url_pattern.as_deref()).collect();
+        assert_eq!(
+            urls,
+            [
+                "/shop",
+                "/product/{id}",
+                "/guides/{slug}",
+                "/guides/faq",
+                "/orders/{id}/items/{id2}"
+            ]
+        );
+
+        // The product page's own state folds into the template
+        let product = &graph.states[1];
+        assert!(!product.is_initial);
+        let from_product: Vec<&Transition> =
+            graph.transitions.iter().filter(|t| t.from == product.id).collect();
+        assert_eq!(from_product.len(), 3);
+        assert_eq!(from_product[1].to, from_product[2].to);
+        assert_eq!(from_product[2].params["id2"], "3");
+
+        let ids: Vec<&str> = graph
+            .transitions
+            .iter()
+            .filter(|t| t.to == product.id)
+            .map(|t| t.params["id"].as_str())
+            .collect();
+        assert_eq!(ids, ["123", "456"]);
+        assert!(graph.is_deterministic());
+    }
+
+    #[test]
+    fn test_route_template_matches() {
+        let route = RouteTemplate::parse("https://shop.example/p/{id}");
+        assert_eq!(route.matches("https://shop.example/p/9").unwrap()["id"], "9");
+        assert!(route.matches("https://other.example/p/9").is_none());
+        assert!(route.matches("/p/9").is_none());
+        assert!(!is_identifier("about") && !is_identifier("deadbeef"));
+        assert!(is_identifier("3f2a9c1b"));
+    }
+}
@@ -0,0 +1,184 @@
+//! JavaScript dependency detection
+//!
+//! Client-rendered apps ship an almost empty body with a mount point such
+//! as `<div id="root"></div>`. Parsing such markup statically yields next to
+//! nothing, so agents need to know to render the page in a browser first.
+
+use crate::parser::SemanticDOM;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+
+/// `id` values conventionally used as framework mount points
+const MOUNT_IDS: &[&str] = &[
+    "root", "app", "__next", "__nuxt", "___gatsby", "svelte", "react-root", "app-root",
+];
+
+/// Attributes marking a framework mount point
+const MOUNT_ATTRS: &[&str] = &["data-reactroot", "ng-app", "data-v-app", "ng-version"];
+
+/// Text shorter than this is not considered content
+const MIN_CONTENT_CHARS: usize = 20;
+
+/// Body text shorter than this counts as a thin page
+const THIN_BODY_CHARS: usize = 200;
+
+/// Signals that a page depends on JavaScript to render its content
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsSignals {
+    /// Selectors of framework mount points without content (e.g. `#root`)
+    pub empty_mount_points: Vec<String>,
+    /// Text of the first `<noscript>` with a message for the reader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noscript_message: Option<String>,
+    /// Number of `<script>` elements
+    pub script_count: usize,
+    /// Characters of visible body text
+    pub text_length: usize,
+}
+
+impl JsSignals {
+    /// Check whether static parsing is likely to miss the page content
+    pub fn requires_js(&self) -> bool {
+        !self.empty_mount_points.is_empty()
+            || (self.text_length < THIN_BODY_CHARS
+                && (self.noscript_message.is_some() || self.script_count > 0))
+    }
+}
+
+/// Collect JavaScript dependency signals from a parsed document
+pub(crate) fn js_signals(document: &Html) -> JsSignals {
+    let select = |css: &str| Selector::parse(css).ok();
+
+    let empty_mount_points = select("body *")
+        .map(|selector| {
+            document
+                .select(&selector)
+                .filter_map(|e| {
+                    let el = e.value();
+                    let mount = el
+                        .id()
+                        .filter(|id| MOUNT_IDS.contains(id))
+                        .map(|id| format!("#{}", id))
+                        .or_else(|| {
+                            MOUNT_ATTRS
+                                .iter()
+                                .find(|a| el.attr(a).is_some())
+                                .map(|a| format!("[{}]", a))
+                        })?;
+                    (visible_text(e).chars().count() < MIN_CONTENT_CHARS).then_some(mount)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let noscript_message = select("noscript").and_then(|selector| {
+        document.select(&selector).find_map(|e| {
+            // With scripting enabled, html5ever keeps <noscript> content as raw text
+            let raw: String = e.text().collect();
+            let text = if raw.contains('<') {
+                Html::parse_fragment(&raw).root_element().text().collect()
+            } else {
+                raw
+            };
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            (text.chars().count() >= MIN_CONTENT_CHARS).then_some(text)
+        })
+    });
+
+    let script_count = select("script")
+        .map(|selector| document.select(&selector).count())
+        .unwrap_or(0);
+    let text_length = select("body")
+        .and_then(|selector| document.select(&selector).next())
+        .map(|body| visible_text(body).chars().count())
+        .unwrap_or(0);
+
+    JsSignals {
+        empty_mount_points,
+        noscript_message,
+        script_count,
+        text_length,
+    }
+}
+
+/// Whitespace-collapsed text, skipping scripts, styles and `<noscript>`
+fn visible_text(element: ElementRef) -> String {
+    let mut words = Vec::new();
+    for node in element.descendants() {
+        let Node::Text(text) = node.value() else {
+            continue;
+        };
+        let hidden = node.ancestors().filter_map(ElementRef::wrap).any(|a| {
+            matches!(a.value().name(), "script" | "style" | "noscript" | "template")
+        });
+        if !hidden {
+            words.extend(text.split_whitespace());
+        }
+    }
+    words.join(" ")
+}
+
+impl SemanticDOM {
+    /// Check whether the page appears to need JavaScript to render
+    ///
+    /// True for empty framework mount points (`#root`, `#app`, `#__next`,
+    /// ...), or a thin body alongside scripts or a `<noscript>` message.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<html><body>
+    ///     <noscript>You need to enable JavaScript to run this app.</noscript>
+    ///     <div id="root"></div>
+    ///     <script src="/static/js/main.js"></script>
+    /// </body></html>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert!(sdom.requires_js());
+    /// assert_eq!(sdom.js_signals().empty_mount_points, ["#root"]);
+    /// ```
+    pub fn requires_js(&self) -> bool {
+        self.js_signals.requires_js()
+    }
+
+    /// Get the signals behind [`requires_js`](Self::requires_js)
+    pub fn js_signals(&self) -> &JsSignals {
+        &self.js_signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_server_rendered_app_does_not_require_js() {
+        let body = "<p>Plenty of server-rendered text about the product.</p>".repeat(5);
+        let html = format!(
+            r#"<html><body><noscript><img src="/pixel.gif"></noscript>
+            <div id="__next"><main>{}</main></div><script src="/app.js"></script></body></html>"#,
+            body
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+
+        assert!(!sdom.requires_js());
+        assert!(sdom.js_signals().noscript_message.is_none());
+        assert_eq!(sdom.js_signals().script_count, 1);
+        assert!(!sdom.to_agent_summary().contains("JS:"));
+    }
+
+    #[test]
+    fn test_thin_page_with_noscript_message() {
+        let html = r#"<html><body><noscript><p>Please enable JavaScript to continue.</p></noscript>
+            <div class="shell"></div></body></html>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        assert!(sdom.requires_js());
+        assert_eq!(
+            sdom.js_signals().noscript_message.as_deref(),
+            Some("Please enable JavaScript to continue.")
+        );
+        assert!(sdom.to_agent_summary().contains("JS: required"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Heading-based document sections
+//!
+//! Groups the content that follows each heading so agents can scope a
+//! query to one part of a page ("summarize the Pricing section").
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticNode, SemanticRole};
+use serde::{Deserialize, Serialize};
+
+/// The content governed by a single heading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    /// ID of the heading node that starts the section
+    pub heading_id: String,
+    /// Heading level (1-6)
+    pub level: u8,
+    /// IDs of the nodes in the section, in document order
+    ///
+    /// Includes nested subsections and their headings.
+    pub child_ids: Vec<String>,
+}
+
+impl SemanticDOM {
+    /// Group nodes into sections keyed by heading
+    ///
+    /// A section runs from its heading up to the next heading of the same
+    /// or a higher level, and never extends past the end of the element
+    /// that contains the heading (e.g. a footer after `<main>` is not part
+    /// of the last section in `<main>`). Content before the first heading
+    /// belongs to no section.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <h2>Features</h2><a href="/tour">Tour</a>
+    ///     <h2>Pricing</h2><button>Buy</button>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let pricing = sdom
+    ///     .sections()
+    ///     .into_iter()
+    ///     .find(|s| sdom.get(&s.heading_id).is_some_and(|h| h.label == "Pricing"))
+    ///     .unwrap();
+    /// let labels: Vec<_> = pricing.child_ids.iter().map(|id| sdom.get(id).unwrap().label.as_str()).collect();
+    /// assert_eq!(labels, ["Buy"]);
+    /// ```
+    pub fn sections(&self) -> Vec<Section> {
+        let nodes = self.reading_order();
+        let mut sections = Vec::new();
+
+        for (start, heading) in nodes.iter().enumerate() {
+            if !matches!(heading.role, SemanticRole::Heading) {
+                continue;
+            }
+            let level = heading.level.unwrap_or(2);
+
+            let child_ids = nodes[start + 1..]
+                .iter()
+                .take_while(|n| {
+                    let ends_section = matches!(n.role, SemanticRole::Heading)
+                        && n.level.unwrap_or(2) <= level;
+                    !ends_section && self.within(n, heading.parent.as_deref())
+                })
+                .map(|n| n.id.clone())
+                .collect();
+
+            sections.push(Section {
+                heading_id: heading.id.clone(),
+                level,
+                child_ids,
+            });
+        }
+
+        sections
+    }
+
+    /// Check whether `node` is a descendant of `ancestor` (`None` is the document)
+    fn within(&self, node: &SemanticNode, ancestor: Option<&str>) -> bool {
+        let Some(ancestor) = ancestor else {
+            return true;
+        };
+        let mut current = node.parent.as_deref();
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.index.get(id).and_then(|n| n.parent.as_deref());
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    fn labels(sdom: &SemanticDOM, ids: &[String]) -> Vec<String> {
+        ids.iter().map(|id| sdom.get(id).unwrap().label.clone()).collect()
+    }
+
+    #[test]
+    fn test_nested_sections_and_container_boundary() {
+        let html = r#"
+            <main>
+                <h1>Product</h1>
+                <h2>Pricing</h2>
+                <button>Monthly</button>
+                <h3>Enterprise</h3>
+                <a href="/contact">Contact sales</a>
+                <h2>FAQ</h2>
+                <a href="/faq">More</a>
+            </main>
+            <footer aria-label="Footer"><a href="/legal">Legal</a></footer>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let sections = sdom.sections();
+        assert_eq!(sections.len(), 4);
+
+        let product = &sections[0];
+        assert_eq!(product.level, 1);
+        assert_eq!(
+            labels(&sdom, &product.child_ids),
+            ["Pricing", "Monthly", "Enterprise", "Contact sales", "FAQ", "More"]
+        );
+
+        let pricing = &sections[1];
+        assert_eq!(
+            labels(&sdom, &pricing.child_ids),
+            ["Monthly", "Enterprise", "Contact sales"]
+        );
+        assert_eq!(labels(&sdom, &sections[2].child_ids), ["Contact sales"]);
+        assert_eq!(labels(&sdom, &sections[3].child_ids), ["More"]);
+    }
+}
@@ -5,12 +5,14 @@
 //! protocol allow-listing.
 
 use crate::{Error, Result};
+use std::time::Duration;
 use url::Url;
 
-/// Allowed URL protocols for security
-const ALLOWED_PROTOCOLS: &[&str] = &["https", "http", "file"];
+/// URL protocols allowed by default
+const ALLOWED_PROTOCOLS: &[&str] = &["https", "http", "file", "mailto", "tel"];
 
-/// Dangerous protocols that must be blocked
+/// Dangerous protocols that are always blocked, even if allowed in a
+/// [`SecurityConfig`]
 const BLOCKED_PROTOCOLS: &[&str] = &["javascript", "data", "vbscript", "blob"];
 
 /// Security configuration for SemanticDOM operations
@@ -18,12 +20,29 @@ const BLOCKED_PROTOCOLS: &[&str] = &["javascript", "data", "vbscript", "blob"];
 pub struct SecurityConfig {
     /// Maximum input size in bytes
     pub max_input_size: usize,
-    /// Allowed URL protocols
+    /// Allowed URL protocols (lowercase, without the colon)
     pub allowed_protocols: Vec<String>,
-    /// Whether to validate URLs
+    /// Whether to enforce `allowed_protocols`; dangerous protocols stay
+    /// blocked either way
     pub validate_urls: bool,
     /// Maximum URL length
     pub max_url_length: usize,
+    /// Maximum redirects a fetch may follow
+    pub max_redirects: usize,
+    /// Overall time limit for a fetch, including redirects
+    pub fetch_timeout: Duration,
+    /// Maximum response body size in bytes, as received
+    pub max_response_size: usize,
+    /// Maximum response body size in bytes after gzip/brotli decoding
+    pub max_decoded_size: usize,
+    /// Whether fetches may reach private, loopback and link-local addresses
+    pub allow_private_networks: bool,
+    /// Host globs (`example.com`, `*.example.com`) that URLs must match;
+    /// empty allows any host
+    pub allowed_hosts: Vec<String>,
+    /// Host globs that URLs must not match; takes precedence over
+    /// `allowed_hosts`
+    pub blocked_hosts: Vec<String>,
 }
 
 impl Default for SecurityConfig {
@@ -33,6 +52,13 @@ impl Default for SecurityConfig {
             allowed_protocols: ALLOWED_PROTOCOLS.iter().map(|s| s.to_string()).collect(),
             validate_urls: true,
             max_url_length: 2048,
+            max_redirects: 5,
+            fetch_timeout: Duration::from_secs(10),
+            max_response_size: 10 * 1024 * 1024, // 10MB
+            max_decoded_size: 50 * 1024 * 1024,  // 50MB
+            allow_private_networks: false,
+            allowed_hosts: Vec::new(),
+            blocked_hosts: Vec::new(),
         }
     }
 }
@@ -49,6 +75,94 @@ impl SecurityConfig {
         self
     }
 
+    /// Allow an additional URL protocol, such as a custom app scheme
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{validate_url_with, SecurityConfig};
+    ///
+    /// let config = SecurityConfig::new().with_allowed_protocol("slack");
+    /// assert!(validate_url_with("slack://open?team=T123", &config).is_ok());
+    ///
+    /// // Dangerous protocols cannot be allowed
+    /// let config = config.with_allowed_protocol("javascript");
+    /// assert!(validate_url_with("javascript:alert(1)", &config).is_err());
+    /// ```
+    pub fn with_allowed_protocol(mut self, protocol: impl Into<String>) -> Self {
+        let protocol = protocol.into().trim_end_matches(':').to_lowercase();
+        if !self.allowed_protocols.contains(&protocol) {
+            self.allowed_protocols.push(protocol);
+        }
+        self
+    }
+
+    /// Replace the list of allowed URL protocols
+    pub fn with_allowed_protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_protocols = protocols
+            .into_iter()
+            .map(|p| p.into().trim_end_matches(':').to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Restrict URLs to hosts matching a glob; may be called repeatedly
+    ///
+    /// `*` matches any run of characters, so `*.example.com` matches
+    /// subdomains but not `example.com` itself. Matching is
+    /// case-insensitive.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{validate_url_with, SecurityConfig};
+    ///
+    /// let config = SecurityConfig::new()
+    ///     .with_allowed_host("example.com")
+    ///     .with_allowed_host("*.example.com")
+    ///     .with_blocked_host("admin.example.com");
+    ///
+    /// assert!(validate_url_with("https://shop.example.com/cart", &config).is_ok());
+    /// assert!(validate_url_with("https://admin.example.com/", &config).is_err());
+    /// assert!(validate_url_with("https://example.org/", &config).is_err());
+    /// // Relative URLs stay on the page's own host
+    /// assert!(validate_url_with("/checkout", &config).is_ok());
+    /// ```
+    pub fn with_allowed_host(mut self, pattern: impl Into<String>) -> Self {
+        self.allowed_hosts.push(pattern.into().to_lowercase());
+        self
+    }
+
+    /// Reject URLs whose host matches a glob; may be called repeatedly
+    pub fn with_blocked_host(mut self, pattern: impl Into<String>) -> Self {
+        self.blocked_hosts.push(pattern.into().to_lowercase());
+        self
+    }
+
+    /// Check a host name against `allowed_hosts` and `blocked_hosts`
+    pub fn is_host_allowed(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_lowercase();
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let matches = |pattern: &String| glob_match(pattern.as_bytes(), host.as_bytes());
+        !self.blocked_hosts.iter().any(matches)
+            && (self.allowed_hosts.is_empty() || self.allowed_hosts.iter().any(matches))
+    }
+
+    /// Check a parsed URL's host, if it has one
+    pub(crate) fn check_host(&self, url: &Url) -> Result<()> {
+        match url.host_str() {
+            Some(host) if !self.is_host_allowed(host) => Err(Error::BlockedHost(host.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate a URL against these settings; see [`validate_url_with`]
+    pub fn validate_url(&self, url: &str) -> Result<String> {
+        validate_url_with(url, self)
+    }
+
     /// Validate input size against the configured limit
     pub fn validate_input_size(&self, size: usize) -> Result<()> {
         if size > self.max_input_size {
@@ -61,7 +175,9 @@ impl SecurityConfig {
     }
 }
 
-/// Validate a URL against security rules
+/// Validate a URL against the default security rules
+///
+/// Equivalent to [`validate_url_with`] and a default [`SecurityConfig`].
 ///
 /// # Arguments
 /// * `url` - The URL string to validate
@@ -71,7 +187,7 @@ impl SecurityConfig {
 /// * `Err(Error)` - If the URL has a disallowed protocol
 ///
 /// # Security
-/// - Only allows https, http, and file protocols
+/// - Only allows https, http, file, mailto, and tel protocols
 /// - Blocks javascript:, data:, vbscript:, and blob: URLs
 /// - Allows relative URLs (starting with / or ./)
 ///
@@ -84,11 +200,39 @@ impl SecurityConfig {
 /// assert!(validate_url("javascript:alert(1)").is_err());
 /// ```
 pub fn validate_url(url: &str) -> Result<String> {
+    validate_url_with(url, &SecurityConfig::default())
+}
+
+/// Validate a URL against a [`SecurityConfig`]
+///
+/// Dangerous protocols (javascript:, data:, vbscript:, blob:) are always
+/// rejected. Other absolute URLs must use one of
+/// `config.allowed_protocols`, unless `config.validate_urls` is off. Hosts
+/// of absolute and protocol-relative URLs are always checked against
+/// `config.allowed_hosts` and `config.blocked_hosts`.
+pub fn validate_url_with(url: &str, config: &SecurityConfig) -> Result<String> {
     // Empty URLs are allowed (no-op)
     if url.is_empty() {
         return Ok(String::new());
     }
 
+    // Protocol-relative URLs name a host. Browsers strip leading control
+    // characters and spaces and any tabs or newlines, and read a
+    // backslash as a slash, so `/\evil.test` leads to evil.test too
+    let resolved: String = url
+        .trim_start_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    let mut start = resolved.chars();
+    let slash = |c: Option<char>| matches!(c, Some('/' | '\\'));
+    if slash(start.next()) && slash(start.next()) {
+        if let Ok(parsed) = Url::parse(&format!("https://{}", start.as_str())) {
+            config.check_host(&parsed)?;
+        }
+        return Ok(url.to_string());
+    }
+
     // Relative URLs are safe
     if url.starts_with('/') || url.starts_with("./") || url.starts_with("../") {
         return Ok(url.to_string());
@@ -112,9 +256,10 @@ pub fn validate_url(url: &str) -> Result<String> {
             }
 
             // Check against allowed protocols
-            if !ALLOWED_PROTOCOLS.contains(&protocol.as_str()) {
+            if config.validate_urls && !config.allowed_protocols.contains(&protocol) {
                 return Err(Error::InvalidUrlProtocol { protocol });
             }
+            config.check_host(&parsed)?;
 
             Ok(parsed.to_string())
         }
@@ -134,6 +279,31 @@ pub fn validate_url(url: &str) -> Result<String> {
     }
 }
 
+/// Match a lowercase host against a glob where `*` matches any run of
+/// characters
+fn glob_match(pattern: &[u8], host: &[u8]) -> bool {
+    let (mut p, mut h) = (0, 0);
+    // Position of the last `*` and the host position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while h < host.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, h));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == host[h] {
+            p += 1;
+            h += 1;
+        } else if let Some((sp, sh)) = star {
+            // Let the last `*` absorb one more character
+            p = sp + 1;
+            h = sh + 1;
+            star = Some((sp, sh + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
 /// Sanitize a string for safe output
 ///
 /// Removes or escapes potentially dangerous characters
@@ -208,6 +378,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_url_with_config() {
+        assert!(validate_url("mailto:help@example.com").is_ok());
+        assert!(validate_url("myapp://open").is_err());
+
+        let config = SecurityConfig::new().with_allowed_protocols(["https", "myapp:"]);
+        assert!(validate_url_with("myapp://open", &config).is_ok());
+        assert!(validate_url_with("http://example.com", &config).is_err());
+
+        let config = SecurityConfig::new().with_blocked_host("*.tracker.example");
+        assert!(matches!(
+            validate_url_with("//px.tracker.example/p.gif", &config),
+            Err(Error::BlockedHost(host)) if host == "px.tracker.example"
+        ));
+        assert!(validate_url_with("https://tracker.example/", &config).is_ok());
+
+        // Forms browsers resolve to another host
+        let config = SecurityConfig::new().with_allowed_host("*.example.com");
+        let bypasses = [
+            "/\\evil.test/x",
+            "\\\\evil.test/x",
+            "\\/evil.test/x",
+            " //evil.test/",
+            "/\t/evil.test",
+        ];
+        for url in bypasses {
+            let result = validate_url_with(url, &config);
+            assert!(matches!(&result, Err(Error::BlockedHost(h)) if h == "evil.test"), "{:?}", url);
+        }
+        assert!(validate_url_with("//cdn.example.com/a.js", &config).is_ok());
+        assert!(validate_url_with("/checkout", &config).is_ok());
+        assert!(glob_match(b"a*c*e", b"abcxcde"));
+        assert!(!glob_match(b"*.example.com", b"example.com"));
+    }
+
     #[test]
     fn test_security_config_input_size() {
         let config = SecurityConfig::new().with_max_input_size(100);
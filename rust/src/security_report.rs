@@ -0,0 +1,408 @@
+//! Page-level security findings
+//!
+//! Reports mixed content and insecure forms, which matter to agents that
+//! submit credentials or payment details on a user's behalf. Findings that
+//! depend on the page URL need [`Config::base_url`](crate::Config::base_url)
+//! or a `<base href>`.
+//!
+//! The report also carries the page's declared security policies
+//! ([`SecurityHeaders`]): Content-Security-Policy, X-Frame-Options and
+//! Referrer-Policy. `<meta>` equivalents are read from the markup; response
+//! headers are merged in by [`SemanticDOM::parse_response`] and take
+//! precedence.
+
+use crate::parser::SemanticDOM;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Subresource attributes checked for mixed content, and whether loading
+/// them insecurely is active (can run code or restyle the page)
+const SUBRESOURCES: &[(&str, &str, bool)] = &[
+    ("script[src]", "src", true),
+    ("link[rel~=stylesheet][href]", "href", true),
+    ("iframe[src]", "src", true),
+    ("object[data]", "data", true),
+    ("embed[src]", "src", true),
+    ("img[src]", "src", false),
+    ("video[src]", "src", false),
+    ("audio[src]", "src", false),
+    ("source[src]", "src", false),
+];
+
+/// What a security finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecurityFindingKind {
+    /// An https page loads a subresource over http
+    MixedContent,
+    /// A form on an https page submits to an http URL
+    InsecureFormAction,
+    /// A form with a password field submits over http
+    InsecurePasswordForm,
+}
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational; e.g. passive mixed content such as images
+    Low,
+    /// Data may be exposed in transit
+    Medium,
+    /// Credentials exposed or page integrity compromised
+    High,
+}
+
+/// A single security finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityFinding {
+    /// What the finding is about
+    pub kind: SecurityFindingKind,
+    /// How serious it is
+    pub severity: Severity,
+    /// Tag name of the offending element
+    pub element: String,
+    /// The insecure URL
+    pub url: String,
+}
+
+/// Security policies declared by a page, from response headers or `<meta>`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityHeaders {
+    /// Content-Security-Policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_security_policy: Option<String>,
+    /// X-Frame-Options (only honored as a response header)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_frame_options: Option<String>,
+    /// Referrer-Policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrer_policy: Option<String>,
+    /// Whether the CSP came from `<meta http-equiv>`, where `frame-ancestors`
+    /// is ignored by browsers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub csp_from_meta: bool,
+}
+
+impl SecurityHeaders {
+    /// Sources of the CSP `frame-ancestors` directive, if it is in effect
+    pub fn frame_ancestors(&self) -> Option<Vec<&str>> {
+        if self.csp_from_meta {
+            return None;
+        }
+        csp_directive(self.content_security_policy.as_deref()?, "frame-ancestors")
+    }
+
+    /// Check whether the page refuses to be embedded by other origins
+    ///
+    /// `frame-ancestors` supersedes X-Frame-Options when both are present.
+    /// Agents that render pages in their own frames cannot embed these.
+    pub fn blocks_framing(&self) -> bool {
+        if let Some(sources) = self.frame_ancestors() {
+            return sources.iter().all(|s| matches!(*s, "'none'" | "'self'"));
+        }
+        self.x_frame_options.as_deref().is_some_and(|x| {
+            let x = x.trim();
+            x.eq_ignore_ascii_case("deny") || x.eq_ignore_ascii_case("sameorigin")
+        })
+    }
+
+    /// Check whether the referrer policy sends full URLs to other origins,
+    /// possibly over http
+    pub fn leaks_referrer(&self) -> bool {
+        // The last recognized token wins; treat the whole list conservatively
+        self.referrer_policy.as_deref().is_some_and(|policy| {
+            policy
+                .split(',')
+                .map(str::trim)
+                .any(|p| p.eq_ignore_ascii_case("unsafe-url"))
+        })
+    }
+
+    /// Merge response headers, which take precedence over `<meta>` values
+    ///
+    /// Header names are matched case-insensitively; other headers are ignored.
+    pub fn merge_headers<'h>(&mut self, headers: impl IntoIterator<Item = (&'h str, &'h str)>) {
+        for (name, value) in headers {
+            let value = Some(value.trim().to_string());
+            if name.eq_ignore_ascii_case("content-security-policy") {
+                self.content_security_policy = value;
+                self.csp_from_meta = false;
+            } else if name.eq_ignore_ascii_case("x-frame-options") {
+                self.x_frame_options = value;
+            } else if name.eq_ignore_ascii_case("referrer-policy") {
+                self.referrer_policy = value;
+            }
+        }
+    }
+}
+
+/// Sources listed for a CSP directive (first occurrence wins, as in browsers)
+fn csp_directive<'a>(policy: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    policy.split(';').find_map(|directive| {
+        let mut tokens = directive.split_whitespace();
+        tokens
+            .next()
+            .filter(|d| d.eq_ignore_ascii_case(name))
+            .map(|_| tokens.collect())
+    })
+}
+
+/// Security findings for a page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityReport {
+    /// Findings, most severe first
+    pub findings: Vec<SecurityFinding>,
+    /// Declared security policies
+    #[serde(default)]
+    pub headers: SecurityHeaders,
+}
+
+impl SecurityReport {
+    /// Check whether there are no findings
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Highest severity among the findings
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+}
+
+/// Scan a document for mixed content and insecure forms
+pub(crate) fn scan(document: &Html, base_url: Option<&str>) -> SecurityReport {
+    let mut report = SecurityReport {
+        headers: meta_policies(document),
+        ..SecurityReport::default()
+    };
+    let Some(base) = base_url.and_then(|b| Url::parse(b).ok()) else {
+        return report;
+    };
+    let https_page = base.scheme() == "https";
+    let insecure = |value: &str| base.join(value.trim()).ok().filter(|u| u.scheme() == "http");
+
+    if https_page {
+        for (css, attr, active) in SUBRESOURCES {
+            let Ok(selector) = Selector::parse(css) else {
+                continue;
+            };
+            for element in document.select(&selector) {
+                if let Some(url) = element.value().attr(attr).and_then(insecure) {
+                    report.findings.push(SecurityFinding {
+                        kind: SecurityFindingKind::MixedContent,
+                        severity: if *active { Severity::High } else { Severity::Low },
+                        element: element.value().name().to_string(),
+                        url: url.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    if let Ok(selector) = Selector::parse("form") {
+        for form in document.select(&selector) {
+            // A missing action submits to the page itself
+            let action = form.value().attr("action").unwrap_or("");
+            let Some(url) = insecure(action) else {
+                continue;
+            };
+            let finding = |kind, severity| SecurityFinding {
+                kind,
+                severity,
+                element: "form".to_string(),
+                url: url.to_string(),
+            };
+            if has_password_field(form) {
+                report
+                    .findings
+                    .push(finding(SecurityFindingKind::InsecurePasswordForm, Severity::High));
+            } else if https_page {
+                report
+                    .findings
+                    .push(finding(SecurityFindingKind::InsecureFormAction, Severity::Medium));
+            }
+        }
+    }
+
+    // Most severe first
+    report.findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    report
+}
+
+/// Policies declared with `<meta http-equiv>` and `<meta name="referrer">`
+fn meta_policies(document: &Html) -> SecurityHeaders {
+    let mut headers = SecurityHeaders::default();
+    let Ok(selector) = Selector::parse("meta[content]") else {
+        return headers;
+    };
+    for meta in document.select(&selector) {
+        let el = meta.value();
+        let content = el.attr("content").unwrap_or("").trim().to_string();
+        let is = |attr, name: &str| {
+            el.attr(attr).is_some_and(|v| v.trim().eq_ignore_ascii_case(name))
+        };
+        if is("http-equiv", "content-security-policy")
+            && headers.content_security_policy.is_none()
+        {
+            headers.content_security_policy = Some(content);
+            headers.csp_from_meta = true;
+        } else if is("name", "referrer") {
+            headers.referrer_policy = Some(content);
+        }
+    }
+    headers
+}
+
+fn has_password_field(form: ElementRef) -> bool {
+    form.descendants().filter_map(ElementRef::wrap).any(|e| {
+        e.value().name() == "input"
+            && e.value().attr("type").is_some_and(|t| t.eq_ignore_ascii_case("password"))
+    })
+}
+
+impl SemanticDOM {
+    /// Get mixed-content and insecure-form findings for the page
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SecurityFindingKind, SemanticDOM, Severity};
+    ///
+    /// let config = Config::builder().base_url("https://shop.example/").build().unwrap();
+    /// let html = r#"<main>
+    ///     <script src="http://cdn.example/app.js"></script>
+    ///     <form action="http://shop.example/login">
+    ///         <input type="password" name="pw"><button>Log in</button>
+    ///     </form>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let report = sdom.security_report();
+    /// assert_eq!(report.findings.len(), 2);
+    /// assert_eq!(report.findings[0].kind, SecurityFindingKind::MixedContent);
+    /// assert_eq!(report.max_severity(), Some(Severity::High));
+    /// ```
+    pub fn security_report(&self) -> &SecurityReport {
+        &self.security_report
+    }
+
+    /// Parse a fetched response body along with its HTTP headers
+    ///
+    /// Like [`parse_bytes`](Self::parse_bytes), and additionally records the
+    /// response's security headers in the [`SecurityReport`]. A `charset`
+    /// in the `Content-Type` header takes precedence over a `<meta charset>`
+    /// declaration in the body.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let headers = [
+    ///     ("Content-Security-Policy", "default-src 'self'; frame-ancestors 'none'"),
+    ///     ("Referrer-Policy", "strict-origin-when-cross-origin"),
+    /// ];
+    /// let body = b"<main><h1>Account</h1></main>";
+    /// let sdom = SemanticDOM::parse_response(body, headers, Config::default()).unwrap();
+    ///
+    /// let policies = &sdom.security_report().headers;
+    /// assert_eq!(policies.frame_ancestors(), Some(vec!["'none'"]));
+    /// assert!(policies.blocks_framing());
+    /// ```
+    pub fn parse_response<'h>(
+        body: &[u8],
+        headers: impl IntoIterator<Item = (&'h str, &'h str)>,
+        mut config: crate::Config,
+    ) -> crate::Result<Self> {
+        let headers: Vec<(&str, &str)> = headers.into_iter().collect();
+        let charset = headers
+            .iter()
+            .rev()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .and_then(|(_, value)| crate::encoding::content_type_charset(value));
+
+        // The framing and referrer checks depend on the headers, so certify
+        // once they are merged
+        let validate = config.validate;
+        config.validate = false;
+        let mut sdom = Self::parse_bytes_with_charset(body, charset, config)?;
+        sdom.config.validate = validate;
+        sdom.security_report.headers.merge_headers(headers);
+        if validate {
+            sdom.certification = Some(sdom.certify());
+        }
+        Ok(sdom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_findings_depend_on_page_scheme() {
+        let html = Html::parse_document(
+            r#"<img src="http://img.example/a.png"><img src="/b.png">
+            <form action="/search"><input name="q"></form>
+            <form action="http://other.example/subscribe"><input name="email"></form>
+            <form><input type="password"></form>"#,
+        );
+
+        let https = scan(&html, Some("https://example.com/"));
+        let kinds: Vec<(SecurityFindingKind, Severity)> =
+            https.findings.iter().map(|f| (f.kind, f.severity)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (SecurityFindingKind::InsecureFormAction, Severity::Medium),
+                (SecurityFindingKind::MixedContent, Severity::Low),
+            ]
+        );
+
+        // On an http page only the password form is reported
+        let http = scan(&html, Some("http://example.com/login"));
+        assert_eq!(http.findings.len(), 1);
+        assert_eq!(http.findings[0].kind, SecurityFindingKind::InsecurePasswordForm);
+        assert_eq!(http.findings[0].url, "http://example.com/login");
+
+        assert!(scan(&html, None).is_clean());
+    }
+
+    #[test]
+    fn test_meta_policies_and_header_precedence() {
+        let html = Html::parse_document(
+            r#"<head><meta http-equiv="Content-Security-Policy" content="frame-ancestors 'none'">
+            <meta name="referrer" content="unsafe-url"></head>"#,
+        );
+        let mut headers = scan(&html, None).headers;
+        // frame-ancestors is ignored in <meta>
+        assert_eq!(headers.frame_ancestors(), None);
+        assert!(!headers.blocks_framing());
+        assert!(headers.leaks_referrer());
+
+        headers.merge_headers([
+            ("x-frame-options", "SAMEORIGIN"),
+            ("Referrer-Policy", "no-referrer"),
+        ]);
+        assert!(headers.blocks_framing());
+        assert!(!headers.leaks_referrer());
+
+        // frame-ancestors overrides X-Frame-Options
+        headers.merge_headers([(
+            "content-security-policy",
+            "script-src 'self'; frame-ancestors 'self' https://agent.example",
+        )]);
+        assert!(!headers.csp_from_meta);
+        assert!(!headers.blocks_framing());
+    }
+
+    #[test]
+    fn test_parse_response_uses_content_type_charset() {
+        let body = b"<meta charset=\"utf-8\"><main><button>Caf\xE9</button></main>";
+        let headers = [("Content-Type", "text/html; charset=ISO-8859-1")];
+        let sdom = SemanticDOM::parse_response(body, headers, crate::Config::default()).unwrap();
+        assert!(sdom.index.values().any(|n| n.label == "Café"));
+        assert!(sdom.warnings.is_empty(), "{:?}", sdom.warnings);
+        assert!(sdom.certification.is_some());
+    }
+}
@@ -0,0 +1,225 @@
+//! Resolving stored selectors back to nodes
+//!
+//! Each node keeps the CSS selector it was built from (`tag#id`,
+//! `tag[data-agent-id="…"]` or `tag.class.class`). An agent holding a
+//! selector from an earlier session can re-bind it to the current model
+//! with [`SemanticDOM::get_by_selector`]. Selectors that are written
+//! differently but mean the same, such as other quoting, escaping, class
+//! order or a leading ancestor path, match after normalization.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+
+/// A compound selector reduced to comparable parts
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    /// Sorted by name
+    attributes: Vec<(String, String)>,
+    /// Sorted
+    classes: Vec<String>,
+}
+
+impl Compound {
+    /// Whether `self`, parsed from a query, selects a node stored as `stored`
+    fn matches(&self, stored: &Compound) -> bool {
+        self.tag.as_ref().map_or(true, |tag| stored.tag.as_ref() == Some(tag))
+            && self.id == stored.id
+            && self.attributes == stored.attributes
+            && self.classes == stored.classes
+    }
+}
+
+/// The last compound selector, after any ancestors and combinators
+fn last_compound(selector: &str) -> &str {
+    let (mut start, mut depth, mut quote, mut escaped) = (0, 0, None, false);
+    for (i, c) in selector.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() && depth > 0 => quote = Some(c),
+            '[' if quote.is_none() => depth += 1,
+            ']' if quote.is_none() => depth -= 1,
+            // A hex escape may end in one space, which belongs to it
+            ' ' if ends_in_hex_escape(&selector[..i]) => {}
+            c if quote.is_none() && depth == 0 && (c.is_whitespace() || ">+~".contains(c)) => {
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    &selector[start..]
+}
+
+/// Whether `text` ends inside an unterminated `\hex` escape
+fn ends_in_hex_escape(text: &str) -> bool {
+    match text.rfind('\\') {
+        Some(pos) => {
+            let digits = &text[pos + 1..];
+            (1..=6).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => false,
+    }
+}
+
+/// Read a possibly escaped identifier, returning it unescaped and the rest
+fn identifier(input: &str) -> (String, &str) {
+    let mut out = String::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        match c {
+            '\\' => {
+                chars.next();
+                let mut hex = String::new();
+                while let Some(&(_, h)) = chars.peek() {
+                    if hex.len() < 6 && h.is_ascii_hexdigit() {
+                        hex.push(h);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if hex.is_empty() {
+                    if let Some((_, escaped)) = chars.next() {
+                        out.push(escaped);
+                    }
+                } else {
+                    let code = u32::from_str_radix(&hex, 16).ok();
+                    out.extend(code.and_then(char::from_u32));
+                    if chars.peek().is_some_and(|&(_, s)| s == ' ') {
+                        chars.next();
+                    }
+                }
+            }
+            '#' | '.' | '[' | ']' | '=' | ':' => return (out, &input[i..]),
+            c if c.is_whitespace() => return (out, &input[i..]),
+            c => {
+                out.push(c);
+                chars.next();
+            }
+        }
+    }
+    (out, "")
+}
+
+/// Parse a compound selector; pseudo-classes and other attribute
+/// operators are not supported
+fn parse(selector: &str) -> Option<Compound> {
+    let mut compound = Compound::default();
+    let (tag, mut rest) = identifier(last_compound(selector.trim()));
+    if tag != "*" && !tag.is_empty() {
+        compound.tag = Some(tag.to_ascii_lowercase());
+    }
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '#' => {
+                let (id, tail) = identifier(rest);
+                if compound.id.replace(id).is_some() {
+                    return None;
+                }
+                rest = tail;
+            }
+            '.' => {
+                let (class, tail) = identifier(rest);
+                compound.classes.push(class);
+                rest = tail;
+            }
+            '[' => {
+                let (name, tail) = identifier(rest.trim_start());
+                let tail = tail.trim_start().strip_prefix('=')?.trim_start();
+                let (value, tail) = match tail.chars().next()? {
+                    q @ ('"' | '\'') => quoted(&tail[1..], q)?,
+                    _ => identifier(tail),
+                };
+                rest = tail.trim_start().strip_prefix(']')?;
+                compound.attributes.push((name.to_ascii_lowercase(), value));
+            }
+            _ => return None,
+        }
+    }
+    compound.attributes.sort();
+    compound.classes.sort();
+    // A bare tag or `*` would match nearly everything
+    let specific = compound.id.is_some()
+        || !compound.attributes.is_empty()
+        || !compound.classes.is_empty();
+    specific.then_some(compound)
+}
+
+/// Read a quoted string up to the closing `quote`, unescaping backslashes
+fn quoted(input: &str, quote: char) -> Option<(String, &str)> {
+    let mut out = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?.1),
+            c if c == quote => return Some((out, &input[i + 1..])),
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+impl SemanticDOM {
+    /// Find the node a CSS selector was recorded for
+    ///
+    /// An exact match on [`SemanticNode::selector`] wins; otherwise the
+    /// selector is normalized, so `#buy`, `main > button#buy` and
+    /// `BUTTON#buy` all find the node stored as `button#buy`. When several
+    /// nodes share a selector the first in document order is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <button id="buy">Buy</button>
+    ///     <a class="nav primary" href="/">Home</a>
+    ///     <button data-agent-id="checkout">Checkout</button>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// assert_eq!(sdom.get_by_selector("button#buy").unwrap().label, "Buy");
+    /// assert_eq!(sdom.get_by_selector("main > #buy").unwrap().label, "Buy");
+    /// assert_eq!(sdom.get_by_selector("a.primary.nav").unwrap().label, "Home");
+    /// let checkout = sdom.get_by_selector("[data-agent-id='checkout']").unwrap();
+    /// assert_eq!(checkout.label, "Checkout");
+    /// assert!(sdom.get_by_selector("a.nav").is_none());
+    /// ```
+    pub fn get_by_selector(&self, selector: &str) -> Option<&SemanticNode> {
+        let nodes = || self.order.iter().filter_map(|id| self.index.get(id));
+        if let Some(node) = nodes().find(|n| n.selector == selector) {
+            return Some(node);
+        }
+        let query = parse(selector)?;
+        nodes().find(|n| parse(&n.selector).is_some_and(|stored| query.matches(&stored)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_escaped_selectors_round_trip() {
+        let html = r#"<main><button id="1st">One</button><a id="a.b" href="/">Dot</a>
+            <button data-agent-id='say "hi"'>Hi</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        for node in sdom.index.values() {
+            assert_eq!(sdom.get_by_selector(&node.selector).unwrap().id, node.id);
+        }
+        assert_eq!(sdom.get_by_selector("button#\\31st").unwrap().label, "One");
+        assert_eq!(sdom.get_by_selector("main #\\31 st").unwrap().label, "One");
+        assert_eq!(sdom.get_by_selector("body a#a\\.b").unwrap().label, "Dot");
+        assert_eq!(sdom.get_by_selector(r#"[data-agent-id="say \"hi\""]"#).unwrap().label, "Hi");
+        assert!(sdom.get_by_selector("button:first-child").is_none());
+        assert!(sdom.get_by_selector("").is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! Certification across many pages
+//!
+//! A crawl, or CI over a site's templates, yields one
+//! [`AgentCertification`] per page. [`SiteCertification::aggregate`] rolls
+//! them up into one report: how scores are spread, which checks fail most
+//! often and which pages need work first. Pages are identified by their
+//! position in the input, so callers keep their own list of URLs or
+//! template names.
+
+use crate::certification::{AgentCertification, CertificationLevel, ValidationCheck};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Pages listed in [`SiteCertification::worst_pages`]
+const WORST_PAGE_COUNT: usize = 5;
+
+/// Width of each [`ScoreBucket`]
+const BUCKET_WIDTH: u32 = 10;
+
+/// Failures listed in the Markdown report
+const REPORTED_FAILURES: usize = 10;
+
+/// Pages scoring within a range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreBucket {
+    /// Lowest score in the bucket
+    pub min: u32,
+    /// Highest score in the bucket, inclusive
+    pub max: u32,
+    /// Pages scoring in the range
+    pub pages: usize,
+}
+
+/// A check and how many pages fail it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckFailure {
+    /// Check identifier, e.g. `STRUCT-002`
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Pages failing the check
+    pub pages: usize,
+}
+
+/// Result of one page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageResult {
+    /// Position of the page in the aggregated input
+    pub page: usize,
+    /// Numeric score (0-100)
+    pub score: u32,
+    /// Certification level
+    pub level: CertificationLevel,
+    /// IDs of the failed checks, without waived ones
+    pub failed: Vec<String>,
+}
+
+/// Certification results of many pages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiteCertification {
+    /// Pages aggregated
+    pub page_count: usize,
+    /// Average score, 0.0 without pages
+    pub mean_score: f64,
+    /// Median score, 0.0 without pages
+    pub median_score: f64,
+    /// Lowest score
+    pub min_score: u32,
+    /// Highest score
+    pub max_score: u32,
+    /// Pages per certification level, including levels no page reached
+    pub levels: BTreeMap<CertificationLevel, usize>,
+    /// Pages per score range, `0-9` up to `90-100`
+    pub distribution: Vec<ScoreBucket>,
+    /// Failed checks, the most common first; waived failures are left out
+    pub common_failures: Vec<CheckFailure>,
+    /// The lowest scoring pages, worst first
+    pub worst_pages: Vec<PageResult>,
+}
+
+impl SiteCertification {
+    /// Aggregate the certifications of a site's pages
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SiteCertification};
+    ///
+    /// let pages = [
+    ///     r#"<nav><a href="/">Home</a></nav><main><h1>Docs</h1></main>"#,
+    ///     r#"<div><h1>Contact</h1><a href="/">Home</a></div>"#,
+    ///     r#"<div><button>Go</button></div>"#,
+    /// ];
+    /// let certs = pages
+    ///     .iter()
+    ///     .map(|html| SemanticDOM::parse(html, Config::default()).unwrap().certify())
+    ///     .collect();
+    ///
+    /// let site = SiteCertification::aggregate(certs);
+    /// assert_eq!(site.page_count, 3);
+    /// // Neither of the last two pages has a <main>
+    /// let no_main = site.common_failures.iter().find(|f| f.id == "STRUCT-002").unwrap();
+    /// assert_eq!(no_main.pages, 2);
+    /// assert_eq!(site.worst_pages.last().unwrap().page, 0);
+    /// assert!(site.to_markdown().contains("| STRUCT-002 | Has main content region | 2 (67%) |"));
+    /// ```
+    pub fn aggregate(certifications: Vec<AgentCertification>) -> Self {
+        let page_count = certifications.len();
+        let mut scores: Vec<u32> = certifications.iter().map(|c| c.score).collect();
+        scores.sort_unstable();
+        let mean_score = if page_count == 0 {
+            0.0
+        } else {
+            scores.iter().map(|&s| f64::from(s)).sum::<f64>() / page_count as f64
+        };
+        let median_score = match page_count {
+            0 => 0.0,
+            n if n % 2 == 1 => f64::from(scores[n / 2]),
+            n => (f64::from(scores[n / 2 - 1]) + f64::from(scores[n / 2])) / 2.0,
+        };
+
+        let mut levels: BTreeMap<CertificationLevel, usize> = [
+            CertificationLevel::None,
+            CertificationLevel::A,
+            CertificationLevel::AA,
+            CertificationLevel::AAA,
+        ]
+        .into_iter()
+        .map(|level| (level, 0))
+        .collect();
+        let mut distribution: Vec<ScoreBucket> = (0..10)
+            .map(|i| ScoreBucket {
+                min: i * BUCKET_WIDTH,
+                max: if i == 9 { 100 } else { (i + 1) * BUCKET_WIDTH - 1 },
+                pages: 0,
+            })
+            .collect();
+        // In first-seen order, which the stable sort keeps for ties
+        let mut failures: Vec<CheckFailure> = Vec::new();
+        let mut pages: Vec<PageResult> = Vec::with_capacity(page_count);
+
+        for (page, cert) in certifications.into_iter().enumerate() {
+            *levels.entry(cert.level).or_default() += 1;
+            let bucket = (cert.score / BUCKET_WIDTH).min(9) as usize;
+            distribution[bucket].pages += 1;
+            let mut failed = Vec::new();
+            for check in cert.checks.into_iter().filter(ValidationCheck::is_failure) {
+                match failures.iter_mut().find(|f| f.id == check.id) {
+                    Some(failure) => failure.pages += 1,
+                    None => failures.push(CheckFailure {
+                        id: check.id.clone(),
+                        name: check.name,
+                        pages: 1,
+                    }),
+                }
+                failed.push(check.id);
+            }
+            pages.push(PageResult { page, score: cert.score, level: cert.level, failed });
+        }
+
+        failures.sort_by_key(|f| std::cmp::Reverse(f.pages));
+        pages.sort_by_key(|p| (p.score, p.page));
+        pages.truncate(WORST_PAGE_COUNT);
+
+        SiteCertification {
+            page_count,
+            mean_score,
+            median_score,
+            min_score: scores.first().copied().unwrap_or(0),
+            max_score: scores.last().copied().unwrap_or(0),
+            levels,
+            distribution,
+            common_failures: failures,
+            worst_pages: pages,
+        }
+    }
+
+    /// Serialize the report to JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    /// Render the report as Markdown, e.g. for a CI job summary
+    ///
+    /// Only score ranges with pages and the ten most common failures are
+    /// listed.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Site Certification\n\n");
+        let _ = writeln!(
+            out,
+            "{} pages, mean score {:.1}, median {:.1}, range {}-{}",
+            self.page_count, self.mean_score, self.median_score, self.min_score, self.max_score
+        );
+
+        out.push_str("\n## Levels\n\n| Level | Pages |\n| --- | --- |\n");
+        for (level, count) in self.levels.iter().rev() {
+            let _ = writeln!(out, "| {} {} | {} |", level.badge(), level.name(), count);
+        }
+
+        out.push_str("\n## Score distribution\n\n| Score | Pages |\n| --- | --- |\n");
+        for bucket in self.distribution.iter().rev().filter(|b| b.pages > 0) {
+            let _ = writeln!(out, "| {}-{} | {} |", bucket.min, bucket.max, bucket.pages);
+        }
+
+        if !self.common_failures.is_empty() {
+            out.push_str("\n## Most common failures\n\n");
+            out.push_str("| Check | Name | Pages |\n| --- | --- | --- |\n");
+            for failure in self.common_failures.iter().take(REPORTED_FAILURES) {
+                let share = failure.pages as f64 / self.page_count as f64 * 100.0;
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} ({:.0}%) |",
+                    failure.id, failure.name, failure.pages, share
+                );
+            }
+        }
+
+        if !self.worst_pages.is_empty() {
+            out.push_str("\n## Worst pages\n\n");
+            out.push_str("| Page | Score | Level | Failed checks |\n| --- | --- | --- | --- |\n");
+            for page in &self.worst_pages {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    page.page,
+                    page.score,
+                    page.level.name(),
+                    page.failed.join(", ")
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certification::{CertificationStats, CheckCategory};
+
+    fn cert(score: u32, failed: &[&str]) -> AgentCertification {
+        let checks: Vec<ValidationCheck> = failed
+            .iter()
+            .map(|id| ValidationCheck {
+                id: id.to_string(),
+                name: format!("Check {}", id),
+                category: CheckCategory::Structure,
+                passed: false,
+                details: None,
+                weight: 1.0,
+                waiver: None,
+            })
+            .collect();
+        AgentCertification {
+            level: if score >= 90 { CertificationLevel::AAA } else { CertificationLevel::A },
+            score,
+            stats: CertificationStats {
+                total_checks: checks.len(),
+                passed_checks: 0,
+                waived_checks: 0,
+                landmark_count: 0,
+                interactable_count: 0,
+                heading_count: 0,
+                completeness: 0.0,
+            },
+            checks,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_statistics() {
+        let site = SiteCertification::aggregate(vec![
+            cert(100, &[]),
+            cert(55, &["B"]),
+            cert(50, &["A", "B"]),
+            cert(95, &["A"]),
+            cert(50, &["B"]),
+            cert(70, &[]),
+        ]);
+        assert_eq!(site.page_count, 6);
+        assert_eq!((site.min_score, site.max_score), (50, 100));
+        assert_eq!(site.mean_score, 70.0);
+        assert_eq!(site.median_score, 62.5);
+        assert_eq!(site.levels[&CertificationLevel::AAA], 2);
+        assert_eq!(site.levels[&CertificationLevel::None], 0);
+        let buckets: Vec<(u32, usize)> =
+            site.distribution.iter().filter(|b| b.pages > 0).map(|b| (b.min, b.pages)).collect();
+        assert_eq!(buckets, [(50, 3), (70, 1), (90, 2)]);
+
+        let failures: Vec<(&str, usize)> =
+            site.common_failures.iter().map(|f| (f.id.as_str(), f.pages)).collect();
+        assert_eq!(failures, [("B", 3), ("A", 2)]);
+        let worst: Vec<usize> = site.worst_pages.iter().map(|p| p.page).collect();
+        assert_eq!(worst, [2, 4, 1, 5, 3]);
+        assert_eq!(site.worst_pages[0].failed, ["A", "B"]);
+
+        let markdown = site.to_markdown();
+        assert!(markdown.contains("6 pages, mean score 70.0, median 62.5, range 50-100"));
+        assert!(markdown.contains("| 90-100 | 2 |\n| 70-79 | 1 |\n| 50-59 | 3 |\n"));
+        assert!(markdown.contains("| 2 | 50 | Level A | A, B |"));
+        let json: serde_json::Value = serde_json::from_str(&site.to_json().unwrap()).unwrap();
+        assert_eq!(json["levels"]["AAA"], 2);
+    }
+
+    #[test]
+    fn test_aggregate_empty() {
+        let site = SiteCertification::aggregate(Vec::new());
+        assert_eq!((site.page_count, site.mean_score, site.max_score), (0, 0.0, 0));
+        assert!(site.worst_pages.is_empty());
+        assert!(!site.to_markdown().contains("Worst pages"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Site search detection
+//!
+//! "Search the site for X" is one of the most common agent tasks, so the
+//! search box and its submit control are exposed directly rather than
+//! leaving agents to hunt for them among the interactables.
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode, SemanticRole};
+use serde::{Deserialize, Serialize};
+
+/// The controls an agent needs to run a site search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchEntry {
+    /// ID of the text field that takes the query
+    pub input_id: String,
+    /// ID of the button that submits the search, if there is one
+    /// (otherwise pressing Enter in the field submits it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit_id: Option<String>,
+    /// ID of the enclosing search landmark or form, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+}
+
+impl SemanticDOM {
+    /// Find the page's site search box and its submit button
+    ///
+    /// Search fields are text inputs classified with the `Search` intent:
+    /// `input[type=search]`, fields named like a query parameter (`q`,
+    /// `query`, ...), and fields inside a search landmark or a form whose
+    /// action mentions search. A field inside a search landmark is preferred.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<form action="/search"><input name="q"><button>Go</button></form>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let entry = sdom.search_entry().unwrap();
+    /// assert_eq!(sdom.get(entry.submit_id.as_deref().unwrap()).unwrap().label, "Go");
+    /// ```
+    pub fn search_entry(&self) -> Option<SearchEntry> {
+        let candidates: Vec<&SemanticNode> = self
+            .reading_order()
+            .into_iter()
+            .filter(|n| {
+                matches!(n.role, SemanticRole::TextInput)
+                    && n.intent == Some(SemanticIntent::Search)
+            })
+            .collect();
+
+        let input = candidates
+            .iter()
+            .find(|n| self.ancestor_with(n, |a| matches!(a.role, SemanticRole::Search)).is_some())
+            .or_else(|| candidates.first())?;
+
+        let container = self.ancestor_with(input, |a| {
+            matches!(a.role, SemanticRole::Search | SemanticRole::Form)
+        });
+        let submit_id = container.and_then(|c| {
+            self.descendants(&c.id)
+                .into_iter()
+                .find(|n| matches!(n.role, SemanticRole::Button))
+                .map(|n| n.id.clone())
+        });
+
+        Some(SearchEntry {
+            input_id: input.id.clone(),
+            submit_id,
+            container_id: container.map(|c| c.id.clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_prefers_search_landmark() {
+        let html = r#"
+            <form action="/subscribe"><input name="email" type="email"><button>Join</button></form>
+            <form action="/find"><input name="query" aria-label="Filter"></form>
+            <div role="search">
+                <input type="search" aria-label="Search site">
+                <button>Search</button>
+            </div>
+        "#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let entry = sdom.search_entry().unwrap();
+
+        assert_eq!(sdom.get(&entry.input_id).unwrap().label, "Search site");
+        assert_eq!(sdom.get(entry.submit_id.as_deref().unwrap()).unwrap().label, "Search");
+        assert_eq!(sdom.landmarks, vec![entry.container_id.unwrap()]);
+    }
+
+    #[test]
+    fn test_no_search_entry() {
+        let html = r#"<form action="/login"><input name="user"><button>Log in</button></form>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert!(sdom.search_entry().is_none());
+
+        let sdom = SemanticDOM::parse(r#"<input name="q">"#, Config::default()).unwrap();
+        let entry = sdom.search_entry().unwrap();
+        assert!(entry.submit_id.is_none() && entry.container_id.is_none());
+    }
+}
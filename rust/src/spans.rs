@@ -0,0 +1,182 @@
+//! Source positions for elements
+//!
+//! html5ever does not track positions, so start tags are located with a
+//! lightweight scan of the source and matched to elements in document
+//! order. Elements the parser creates implicitly (`<html>`, `<body>`,
+//! `<tbody>`, ...) only match a tag actually present at that point, and
+//! tags the parser dropped are skipped, so the mapping stays aligned on
+//! malformed markup.
+
+use crate::types::SourceSpan;
+use ahash::AHashMap;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+
+/// Elements whose content is raw text rather than markup
+const RAW_TEXT: &[&str] = &[
+    "script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes", "noscript",
+];
+
+/// Elements the HTML parser inserts when the source omits them
+const IMPLIED: &[&str] = &["html", "head", "body", "tbody", "colgroup"];
+
+/// A start tag in the source: lowercase name, start and end offsets
+struct StartTag {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// Map each element of `document` to its start tag in `source`
+pub(crate) fn map_spans(document: &Html, source: &str) -> AHashMap<NodeId, SourceSpan> {
+    let tags = start_tags(source);
+    let lines = LineIndex::new(source);
+    let mut spans = AHashMap::new();
+    let mut cursor = 0;
+
+    for element in document.root_element().descendants().filter_map(ElementRef::wrap) {
+        let name = element.value().name();
+        let found = if IMPLIED.contains(&name) {
+            tags.get(cursor).filter(|t| t.name == name).map(|_| cursor)
+        } else {
+            tags[cursor..].iter().position(|t| t.name == name).map(|i| cursor + i)
+        };
+        if let Some(index) = found {
+            let tag = &tags[index];
+            let (line, column) = lines.position(source, tag.start);
+            spans.insert(
+                element.id(),
+                SourceSpan {
+                    start: tag.start,
+                    end: tag.end,
+                    line,
+                    column,
+                },
+            );
+            cursor = index + 1;
+        }
+    }
+    spans
+}
+
+/// Find start tags in source order, skipping comments, doctypes, end tags
+/// and raw-text content
+fn start_tags(source: &str) -> Vec<StartTag> {
+    let bytes = source.as_bytes();
+    let mut tags = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = bytes[pos..].iter().position(|&b| b == b'<') {
+        let start = pos + offset;
+        let rest = &bytes[start + 1..];
+        if rest.starts_with(b"!--") {
+            pos = find(bytes, start + 4, b"-->").map_or(bytes.len(), |i| i + 3);
+        } else if rest.first().is_some_and(|b| matches!(b, b'!' | b'?' | b'/')) {
+            pos = find(bytes, start, b">").map_or(bytes.len(), |i| i + 1);
+        } else if rest.first().is_some_and(u8::is_ascii_alphabetic) {
+            let name_len = rest
+                .iter()
+                .position(|b| b.is_ascii_whitespace() || matches!(b, b'/' | b'>'))
+                .unwrap_or(rest.len());
+            let name = source[start + 1..start + 1 + name_len].to_ascii_lowercase();
+            let end = tag_end(bytes, start + 1 + name_len);
+            pos = end;
+            if RAW_TEXT.contains(&name.as_str()) {
+                let close = format!("</{}", name);
+                pos = find_ignore_case(bytes, end, close.as_bytes()).unwrap_or(bytes.len());
+            }
+            tags.push(StartTag { name, start, end });
+        } else {
+            pos = start + 1;
+        }
+    }
+    tags
+}
+
+/// Offset just past the `>` closing a tag, honoring quoted attribute values
+fn tag_end(bytes: &[u8], mut pos: usize) -> usize {
+    let mut quote = None;
+    // Quotes only open a value right after `=`; elsewhere they are literal
+    let mut after_equals = false;
+    while pos < bytes.len() {
+        let b = bytes[pos];
+        match quote {
+            None if b == b'>' => return pos + 1,
+            None if after_equals && matches!(b, b'"' | b'\'') => quote = Some(b),
+            Some(q) if b == q => quote = None,
+            _ => {}
+        }
+        if !b.is_ascii_whitespace() {
+            after_equals = quote.is_none() && b == b'=';
+        }
+        pos += 1;
+    }
+    bytes.len()
+}
+
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|i| from + i)
+}
+
+fn find_ignore_case(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|i| from + i)
+}
+
+/// Byte offsets of line starts, for offset to line/column conversion
+struct LineIndex(Vec<usize>);
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex(starts)
+    }
+
+    fn position(&self, source: &str, offset: usize) -> (usize, usize) {
+        let line = self.0.partition_point(|&start| start <= offset);
+        let line_start = self.0[line - 1];
+        let column = source[line_start..offset].chars().count() + 1;
+        (line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_spans_point_at_start_tags() {
+        let html = "<!-- <nav> in a comment -->\n<main>\n  <script>if (a<b) {}</script>\n  \
+                    <h1 title=\"a > b\">Título</h1><table><tr><td><a href=\"/x\" title=it's>X</a>\
+                    </td></tr></table>\n</main>";
+        let config = Config::builder().source_spans(true).build().unwrap();
+        let sdom = SemanticDOM::parse(html, config).unwrap();
+
+        for node in sdom.index.values() {
+            let span = node.source.expect("every node has a span");
+            let tag = &html[span.start..span.end];
+            assert!(tag.starts_with('<') && tag.ends_with('>'), "{}", tag);
+        }
+        let heading = sdom.get(&sdom.headings[0]).unwrap();
+        let span = heading.source.unwrap();
+        assert_eq!((span.line, span.column), (4, 3));
+        assert_eq!(&html[span.start..span.end], "<h1 title=\"a > b\">");
+
+        // Implied <tbody> doesn't shift later elements
+        let link = sdom.index.values().find(|n| n.href.is_some()).unwrap();
+        let span = link.source.unwrap();
+        assert_eq!(&html[span.start..span.end], "<a href=\"/x\" title=it's>");
+
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert!(sdom.index.values().all(|n| n.source.is_none()));
+    }
+}
@@ -0,0 +1,396 @@
+//! Persisting parsed documents
+//!
+//! Crawlers and multi-document services keep many parsed pages, often
+//! several versions of each. A [`DocumentStore`] saves documents by URL and
+//! a hash naming the version (a content hash, an ETag), so consumers do not
+//! each invent a layout. [`FsStore`] keeps them as files in a directory and
+//! `SqliteStore` (feature `sqlite`) in one database file.
+
+use crate::export::{Compression, ExportFormat};
+use crate::parser::SemanticDOM;
+use crate::{Config, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Longest accepted version hash
+const MAX_HASH_LEN: usize = 128;
+
+/// Name of the file holding the URL in each URL's directory of an
+/// [`FsStore`]
+const URL_FILE: &str = "url";
+
+/// A stored document's URL and version hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DocumentKey {
+    /// URL the document was parsed from
+    pub url: String,
+    /// Version of the document: ASCII letters, digits, `-` and `_`
+    pub hash: String,
+}
+
+impl DocumentKey {
+    /// Key of version `hash` of the document at `url`
+    pub fn new(url: impl Into<String>, hash: impl Into<String>) -> Self {
+        DocumentKey { url: url.into(), hash: hash.into() }
+    }
+
+    /// Check the key can name a file and a database row alike
+    fn validate(&self) -> Result<()> {
+        let valid_hash = !self.hash.is_empty()
+            && self.hash.len() <= MAX_HASH_LEN
+            && self.hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if self.url.is_empty() {
+            return Err(Error::StoreError("empty URL".into()));
+        }
+        if !valid_hash {
+            return Err(Error::StoreError(format!("invalid hash: {:?}", self.hash)));
+        }
+        Ok(())
+    }
+}
+
+/// Storage of parsed documents by URL and version hash
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Config, DocumentKey, DocumentStore, FsStore, SemanticDOM};
+///
+/// let dir = std::env::temp_dir().join("sdom-store-doc");
+/// let store = FsStore::new(&dir);
+/// let sdom = SemanticDOM::parse("<main><h1>Docs</h1></main>", Config::default()).unwrap();
+///
+/// let key = DocumentKey::new("https://example.com/docs", "v1");
+/// store.put(&key, &sdom).unwrap();
+/// assert_eq!(store.versions("https://example.com/docs").unwrap(), [key.clone()]);
+///
+/// let loaded = store.get(&key, Config::default()).unwrap().unwrap();
+/// assert_eq!(loaded.title, sdom.title);
+/// assert!(store.delete(&key).unwrap());
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// ```
+pub trait DocumentStore {
+    /// Store a document, replacing one with the same key
+    fn put(&self, key: &DocumentKey, sdom: &SemanticDOM) -> Result<()>;
+
+    /// Load a document, or `None` if no document has the key
+    fn get(&self, key: &DocumentKey, config: Config) -> Result<Option<SemanticDOM>>;
+
+    /// Keys of all stored documents, ordered by URL, then hash
+    fn list(&self) -> Result<Vec<DocumentKey>>;
+
+    /// Remove a document; returns whether it was stored
+    fn delete(&self, key: &DocumentKey) -> Result<bool>;
+
+    /// Keys of the stored versions of one URL, ordered by hash
+    fn versions(&self, url: &str) -> Result<Vec<DocumentKey>> {
+        Ok(self.list()?.into_iter().filter(|k| k.url == url).collect())
+    }
+}
+
+/// FNV-1a, which unlike `DefaultHasher` stays the same across builds
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Documents as JSON files in a directory
+///
+/// Each URL gets a subdirectory, named by a hash of the URL and holding
+/// the URL in a `url` file, with one `{hash}.json` file per version. With
+/// [`with_compression`](Self::with_compression), versions are written as
+/// `.json.gz` or `.json.zst`; any of them is read back.
+#[derive(Debug, Clone)]
+pub struct FsStore {
+    root: PathBuf,
+    compression: Compression,
+}
+
+impl FsStore {
+    /// Use the directory at `root`; it is created on the first put
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsStore { root: root.into(), compression: Compression::None }
+    }
+
+    /// Compress documents written from now on
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Path of the directory
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    fn url_dir(&self, url: &str) -> PathBuf {
+        self.root.join(format!("{:016x}", fnv1a(url)))
+    }
+
+    /// Version files in a URL's directory, by hash; none if it does not
+    /// hold `url`
+    fn versions_in(dir: &Path, url: &str) -> Result<Vec<(String, PathBuf)>> {
+        match fs::read_to_string(dir.join(URL_FILE)) {
+            Ok(stored) if stored == url => {}
+            Ok(_) => return Ok(Vec::new()),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        }
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if let Some((hash, _)) = name.split_once(".json") {
+                files.push((hash.to_string(), path));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn find(&self, key: &DocumentKey) -> Result<Option<PathBuf>> {
+        let files = Self::versions_in(&self.url_dir(&key.url), &key.url)?;
+        Ok(files.into_iter().find(|(hash, _)| *hash == key.hash).map(|(_, path)| path))
+    }
+}
+
+impl DocumentStore for FsStore {
+    fn put(&self, key: &DocumentKey, sdom: &SemanticDOM) -> Result<()> {
+        key.validate()?;
+        let dir = self.url_dir(&key.url);
+        fs::create_dir_all(&dir)?;
+        match fs::read_to_string(dir.join(URL_FILE)) {
+            Ok(stored) if stored != key.url => {
+                return Err(Error::StoreError(format!(
+                    "{} and {} hash to the same directory",
+                    stored, key.url
+                )));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => fs::write(dir.join(URL_FILE), &key.url)?,
+            Err(e) => return Err(e.into()),
+        }
+        // A version written with other compression is replaced, not kept
+        if let Some(old) = self.find(key)? {
+            fs::remove_file(old)?;
+        }
+        let extension = match self.compression {
+            Compression::None => "json",
+            Compression::Gzip => "json.gz",
+            Compression::Zstd => "json.zst",
+        };
+        let path = dir.join(format!("{}.{}", key.hash, extension));
+        sdom.write_to(path, ExportFormat::Json, self.compression)
+    }
+
+    fn get(&self, key: &DocumentKey, config: Config) -> Result<Option<SemanticDOM>> {
+        key.validate()?;
+        self.find(key)?.map(|path| SemanticDOM::read_from(path, config)).transpose()
+    }
+
+    fn list(&self) -> Result<Vec<DocumentKey>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut keys = Vec::new();
+        for entry in entries {
+            let dir = entry?.path();
+            let url = match fs::read_to_string(dir.join(URL_FILE)) {
+                Ok(url) => url,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            for (hash, _) in Self::versions_in(&dir, &url)? {
+                keys.push(DocumentKey::new(url.clone(), hash));
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &DocumentKey) -> Result<bool> {
+        key.validate()?;
+        let Some(path) = self.find(key)? else {
+            return Ok(false);
+        };
+        fs::remove_file(path)?;
+        let dir = self.url_dir(&key.url);
+        if Self::versions_in(&dir, &key.url)?.is_empty() {
+            fs::remove_dir_all(dir)?;
+        }
+        Ok(true)
+    }
+
+    fn versions(&self, url: &str) -> Result<Vec<DocumentKey>> {
+        let files = Self::versions_in(&self.url_dir(url), url)?;
+        Ok(files.into_iter().map(|(hash, _)| DocumentKey::new(url, hash)).collect())
+    }
+}
+
+/// Documents as JSON in an SQLite database
+///
+/// Documents live in a `documents (url, hash, json)` table, created when
+/// the database is opened.
+///
+/// [`HistoryStore`](crate::HistoryStore) keeps to JSON Lines because it is
+/// always built. This store is behind the opt-in `sqlite` feature, so only
+/// builds that ask for it compile the bundled SQLite, which needs a C
+/// compiler but no system library.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+fn sql_error(error: rusqlite::Error) -> Error {
+    Error::StoreError(error.to_string())
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStore {
+    /// Open the database at `path`, creating it if needed
+    ///
+    /// # Errors
+    /// - [`Error::StoreError`] if it cannot be opened or is not a
+    ///   database
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(rusqlite::Connection::open(path).map_err(sql_error)?)
+    }
+
+    /// Open a database held in memory, gone when the store is dropped
+    ///
+    /// # Errors
+    /// - [`Error::StoreError`] if SQLite cannot allocate it
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(rusqlite::Connection::open_in_memory().map_err(sql_error)?)
+    }
+
+    fn init(conn: rusqlite::Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS documents (
+                url TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                json TEXT NOT NULL,
+                PRIMARY KEY (url, hash)
+            )",
+        )
+        .map_err(sql_error)?;
+        Ok(SqliteStore { conn })
+    }
+
+    fn keys(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<DocumentKey>> {
+        let mut statement = self.conn.prepare(sql).map_err(sql_error)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(DocumentKey::new(row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(sql_error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(sql_error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DocumentStore for SqliteStore {
+    fn put(&self, key: &DocumentKey, sdom: &SemanticDOM) -> Result<()> {
+        key.validate()?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO documents (url, hash, json) VALUES (?1, ?2, ?3)",
+                (&key.url, &key.hash, sdom.to_json()?),
+            )
+            .map_err(sql_error)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &DocumentKey, config: Config) -> Result<Option<SemanticDOM>> {
+        use rusqlite::OptionalExtension;
+        key.validate()?;
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT json FROM documents WHERE url = ?1 AND hash = ?2",
+                (&key.url, &key.hash),
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(sql_error)?;
+        json.map(|json| SemanticDOM::from_json(&json, config)).transpose()
+    }
+
+    fn list(&self) -> Result<Vec<DocumentKey>> {
+        self.keys("SELECT url, hash FROM documents ORDER BY url, hash", ())
+    }
+
+    fn delete(&self, key: &DocumentKey) -> Result<bool> {
+        key.validate()?;
+        let deleted = self
+            .conn
+            .execute("DELETE FROM documents WHERE url = ?1 AND hash = ?2", (&key.url, &key.hash))
+            .map_err(sql_error)?;
+        Ok(deleted > 0)
+    }
+
+    fn versions(&self, url: &str) -> Result<Vec<DocumentKey>> {
+        self.keys("SELECT url, hash FROM documents WHERE url = ?1 ORDER BY hash", [url])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Put, replace, list, get and delete through any store
+    fn exercise(store: &dyn DocumentStore) {
+        let home = SemanticDOM::parse("<main><h1>Home</h1></main>", Config::default()).unwrap();
+        let docs = SemanticDOM::parse(
+            r#"<nav><a href="/">Home</a></nav><main><h1>Docs</h1></main>"#,
+            Config::default(),
+        )
+        .unwrap();
+        let v1 = DocumentKey::new("https://a.test/", "v1");
+        let v2 = DocumentKey::new("https://a.test/", "v2");
+        let other = DocumentKey::new("https://a.test/docs?page=2", "0f3a");
+
+        assert!(store.list().unwrap().is_empty());
+        store.put(&v2, &home).unwrap();
+        store.put(&v1, &home).unwrap();
+        store.put(&other, &home).unwrap();
+        // Same key again replaces the document
+        store.put(&other, &docs).unwrap();
+
+        assert_eq!(store.list().unwrap(), [v1.clone(), v2.clone(), other.clone()]);
+        assert_eq!(store.versions("https://a.test/").unwrap(), [v1.clone(), v2.clone()]);
+        let loaded = store.get(&other, Config::default()).unwrap().unwrap();
+        assert_eq!(loaded.to_agent_summary(), docs.to_agent_summary());
+        assert!(store.get(&DocumentKey::new("https://b.test/", "v1"), Config::default())
+            .unwrap()
+            .is_none());
+
+        assert!(store.delete(&v1).unwrap());
+        assert!(!store.delete(&v1).unwrap());
+        assert_eq!(store.list().unwrap(), [v2, other]);
+        assert!(matches!(
+            store.put(&DocumentKey::new("https://a.test/", "../x"), &home),
+            Err(Error::StoreError(_))
+        ));
+    }
+
+    #[test]
+    fn test_fs_store() {
+        let dir = std::env::temp_dir().join(format!("sdom-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        exercise(&FsStore::new(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_store() {
+        exercise(&SqliteStore::open_in_memory().unwrap());
+    }
+}
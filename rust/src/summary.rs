@@ -1,73 +1,323 @@
 //! Token-efficient summary formats for AI agents
 //!
-//! Provides ultra-compact output formats optimized for LLM consumption,
-//! reducing token usage by ~87% compared to JSON.
+//! Provides compact plain-text formats optimized for LLM consumption, a
+//! small fraction of the size of the JSON output.
 
 use crate::parser::SemanticDOM;
-use crate::types::{SemanticIntent, SemanticRole};
+use crate::types::{SemanticIntent, SemanticNode, SemanticRole};
+
+/// Most lines an agent summary has: one per section
+pub(crate) const MAX_SUMMARY_LINES: usize = 20;
+
+/// Longest line of an agent summary, in characters
+pub(crate) const MAX_SUMMARY_LINE_CHARS: usize = 400;
 
 /// Generate a token-efficient agent summary
 ///
-/// This format uses approximately 100 tokens compared to ~800 for JSON,
-/// an 87% reduction in token usage.
+/// Sections with nothing to report are left out, so a typical page comes
+/// to 100-200 tokens. Each section lists a bounded number of entries with
+/// truncated labels, and every line is cut at 400 characters, so even a
+/// page with all 20 sections stays under about 2,000 tokens.
 ///
 /// # Format
 /// ```text
+/// JS: required (empty #root)
+/// LOADING: 1 spinner, 3 skeleton
+/// OVERLAYS: consent(accept:Accept all, reject:Reject)
 /// LANDMARKS: nav(#main-nav), main(#content), footer(#footer)
+/// CONTENT: main="Spring sale: 20% off all…"
 /// ACTIONS: [submit]#login-btn, [navigate]a.nav-link, [toggle]#menu-btn
+/// REPEATED: card ×24 (title, price, add-to-cart)
+/// FACETS: sort=Sort by(4), Brand(12), Size(8)
+/// EXCLUDED: 3 ad, 1 social
+/// INTENTS: 12 navigate, 2 submit, 1 action, 1 unknown; 1 guessed
+/// ERRORS: Email=Enter a valid email address
+/// TOASTS: [success]Added to cart
+/// PROGRESS: Upload=45%
+/// FLOWS: login, add-to-cart×3
+/// SHORTCUTS: Control+K=Search, accesskey:h=Home
+/// CONTACT: help@example.com, +1-555-0100, 1 address
+/// LINKS: 12 internal, 3 external, 1 mailto
 /// STATE: initial -> home, about, contact
 /// ```
 ///
+/// Landmarks inferred by `Config::infer_landmarks` end in `?`, as in
+/// `main?(div.page)`. Utility actions (print, share, copy, bookmark) are
+/// listed after the others, so they are the first left out. INTENTS only
+/// appears when ACTIONS leaves some interactables out or some intents are
+/// guesses.
+///
 /// # Arguments
 /// * `sdom` - The SemanticDOM to summarize
 ///
 /// # Returns
 /// A compact string summary
 pub fn to_agent_summary(sdom: &SemanticDOM) -> String {
-    let mut lines = Vec::new();
+    let mut lines = Vec::with_capacity(MAX_SUMMARY_LINES);
 
     // Title line
     if let Some(title) = &sdom.title {
         lines.push(format!("PAGE: {}", title));
     }
 
+    // Static parsing misses client-rendered content
+    if sdom.requires_js() {
+        let signals = sdom.js_signals();
+        match signals.empty_mount_points.first() {
+            Some(mount) => lines.push(format!("JS: required (empty {})", mount)),
+            None => lines.push("JS: required".to_string()),
+        }
+    }
+
+    // Content behind loading indicators is missing from the snapshot
+    if sdom.appears_loading() {
+        let counts: Vec<String> = crate::loading::LoadingKind::ALL
+            .iter()
+            .map(|kind| (kind, sdom.loading.iter().filter(|l| l.kind == *kind).count()))
+            .filter(|(_, n)| *n > 0)
+            .map(|(kind, n)| format!("{} {}", n, kind.as_str()))
+            .collect();
+        lines.push(format!("LOADING: {}", counts.join(", ")));
+    }
+
+    // Overlays line first: agents must dismiss these before anything else
+    let overlays: Vec<String> = sdom
+        .blocking_overlays()
+        .into_iter()
+        .map(|o| {
+            let buttons: Vec<String> = [
+                ("accept", &o.accept_id),
+                ("reject", &o.reject_id),
+                ("close", &o.close_id),
+            ]
+            .into_iter()
+            .filter_map(|(name, id)| {
+                let node = sdom.index.get(id.as_deref()?)?;
+                Some(format!("{}:{}", name, node.label))
+            })
+            .collect();
+            format!("{}({})", o.kind.as_str(), buttons.join(", "))
+        })
+        .collect();
+
+    if !overlays.is_empty() {
+        lines.push(format!("OVERLAYS: {}", overlays.join(", ")));
+    }
+
     // Landmarks line
     let landmarks: Vec<String> = sdom
         .landmarks
         .iter()
         .filter_map(|id| sdom.index.get(id))
-        .map(|n| format!("{}({})", role_abbrev(&n.role), selector_short(&n.selector)))
+        .map(|n| {
+            let guess = if n.inferred { "?" } else { "" };
+            format!("{}{}({})", role_abbrev(&n.role), guess, selector_short(&n.selector))
+        })
         .collect();
 
     if !landmarks.is_empty() {
         lines.push(format!("LANDMARKS: {}", landmarks.join(", ")));
     }
 
-    // Actions line (interactables)
-    let actions: Vec<String> = sdom
+    // Content line: quotes from landmarks, when excerpts were captured
+    let quotes: Vec<String> = sdom
+        .landmarks
+        .iter()
+        .filter_map(|id| sdom.index.get(id))
+        .filter_map(|n| Some(format!("{}={:?}", role_abbrev(&n.role), n.text_excerpt.as_ref()?)))
+        .collect();
+
+    if !quotes.is_empty() {
+        lines.push(format!("CONTENT: {}", quotes.join(", ")));
+    }
+
+    // Actions line (interactables); repeated components list theirs once
+    let repeated = sdom.repeated_nodes();
+    let mut nodes: Vec<&SemanticNode> = sdom
         .interactables
         .iter()
+        .filter(|id| !repeated.contains(id.as_str()))
         .filter_map(|id| sdom.index.get(id))
-        .map(|n| {
-            let intent = n
-                .intent
-                .as_ref()
-                .map(intent_abbrev)
-                .unwrap_or_else(|| "act".to_string());
-            let label = if n.label.len() > 20 {
-                format!("{}...", &n.label[..17])
-            } else {
-                n.label.clone()
-            };
-            format!("[{}]{}", intent, label)
-        })
+        .collect();
+    nodes.sort_by_key(|n| is_utility(n));
+    let actions: Vec<String> = nodes
+        .into_iter()
+        .map(action_entry)
         .take(10) // Limit to 10 actions for brevity
         .collect();
+    let listed = actions.len();
 
     if !actions.is_empty() {
         lines.push(format!("ACTIONS: {}", actions.join(", ")));
     }
 
+    // Repeated line: components described once instead of per instance
+    let clusters: Vec<String> = sdom.clusters().iter().map(|c| c.summary()).take(5).collect();
+
+    if !clusters.is_empty() {
+        lines.push(format!("REPEATED: {}", clusters.join(", ")));
+    }
+
+    // Facets line: sort and filter controls, with their option counts
+    let facets: Vec<String> = sdom
+        .facets
+        .iter()
+        .map(|f| {
+            let kind = if f.kind == crate::facets::FacetKind::Sort { "sort=" } else { "" };
+            format!("{}{}({})", kind, truncate(&f.name, 20), f.options.len())
+        })
+        .take(8)
+        .collect();
+
+    if !facets.is_empty() {
+        lines.push(format!("FACETS: {}", facets.join(", ")));
+    }
+
+    // Excluded line: noise regions filtered out, so none go unnoticed
+    let excluded: Vec<String> = crate::noise::NoiseKind::ALL
+        .iter()
+        .filter_map(|kind| Some(format!("{} {}", sdom.noise.get(kind)?, kind.as_str())))
+        .collect();
+
+    if !excluded.is_empty() {
+        lines.push(format!("EXCLUDED: {}", excluded.join(", ")));
+    }
+
+    // Intents line: what the actions do, and how many are unclassified,
+    // when the actions line does not already show it all
+    let intents = sdom.intent_report();
+    if listed < intents.interactable_count || !intents.guessed.is_empty() {
+        lines.push(format!("INTENTS: {}", intents.summary()));
+    }
+
+    // Errors line: invalid form fields after a failed submit
+    let errors: Vec<String> = sdom
+        .invalid_fields()
+        .into_iter()
+        .map(|n| match &n.error_message {
+            Some(message) => format!("{}={}", truncate(&n.label, 20), truncate(message, 40)),
+            None => truncate(&n.label, 20),
+        })
+        .take(10)
+        .collect();
+
+    if !errors.is_empty() {
+        lines.push(format!("ERRORS: {}", errors.join(", ")));
+    }
+
+    // Toasts line: messages currently shown in notification areas
+    let toasts: Vec<String> = sdom
+        .notifications()
+        .iter()
+        .flat_map(|n| {
+            n.messages.iter().map(move |m| match n.tone {
+                Some(tone) => format!("[{}]{}", tone.as_str(), truncate(m, 40)),
+                None => truncate(m, 40),
+            })
+        })
+        .take(5)
+        .collect();
+
+    if !toasts.is_empty() {
+        lines.push(format!("TOASTS: {}", toasts.join(", ")));
+    }
+
+    // Progress line: determinate progress bars and meters
+    let progress: Vec<String> = sdom
+        .reading_order()
+        .into_iter()
+        .filter(|n| matches!(n.role, SemanticRole::ProgressBar))
+        .filter_map(|n| {
+            let range = n.range.as_ref()?;
+            let value = match (&range.text, range.fraction()) {
+                (Some(text), _) => text.clone(),
+                (None, Some(fraction)) => format!("{}%", (fraction * 100.0).round()),
+                (None, None) => range.now?.to_string(),
+            };
+            Some(format!("{}={}", truncate(&n.label, 20), value))
+        })
+        .take(5)
+        .collect();
+
+    if !progress.is_empty() {
+        lines.push(format!("PROGRESS: {}", progress.join(", ")));
+    }
+
+    // Flows line: detected patterns grouped by kind, in first-seen order
+    let mut flows: Vec<(&'static str, usize)> = Vec::new();
+    for pattern in sdom.patterns() {
+        let kind = pattern.kind.as_str();
+        match flows.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => flows.push((kind, 1)),
+        }
+    }
+
+    if !flows.is_empty() {
+        let flows: Vec<String> = flows
+            .into_iter()
+            .map(|(kind, count)| match count {
+                1 => kind.to_string(),
+                n => format!("{}×{}", kind, n),
+            })
+            .collect();
+        lines.push(format!("FLOWS: {}", flows.join(", ")));
+    }
+
+    // Shortcuts line (only when the page declares any)
+    let shortcuts: Vec<String> = sdom
+        .shortcuts()
+        .into_iter()
+        .flat_map(|n| {
+            n.key_shortcuts
+                .iter()
+                .cloned()
+                .chain(n.access_key.iter().map(|k| format!("accesskey:{}", k)))
+                .map(move |key| format!("{}={}", key, n.label))
+        })
+        .take(10)
+        .collect();
+
+    if !shortcuts.is_empty() {
+        lines.push(format!("SHORTCUTS: {}", shortcuts.join(", ")));
+    }
+
+    // Contact line: emails and phones, postal addresses only counted
+    let contact = sdom.contact_info();
+    if !contact.is_empty() {
+        let mut items: Vec<String> = contact
+            .emails
+            .iter()
+            .chain(&contact.phones)
+            .take(5)
+            .cloned()
+            .collect();
+        match contact.postal.len() {
+            0 => {}
+            1 => items.push("1 address".to_string()),
+            n => items.push(format!("{} addresses", n)),
+        }
+        lines.push(format!("CONTACT: {}", items.join(", ")));
+    }
+
+    // Links line: where the page's links lead
+    let mut links: Vec<(&'static str, usize)> = Vec::new();
+    for kind in sdom.index.values().filter_map(|n| n.link_kind) {
+        match links.iter_mut().find(|(k, _)| *k == kind.as_str()) {
+            Some((_, count)) => *count += 1,
+            None => links.push((kind.as_str(), 1)),
+        }
+    }
+
+    if !links.is_empty() {
+        links.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let links: Vec<String> = links
+            .into_iter()
+            .map(|(kind, count)| format!("{} {}", count, kind))
+            .collect();
+        lines.push(format!("LINKS: {}", links.join(", ")));
+    }
+
     // State graph summary
     if !sdom.state_graph.states.is_empty() {
         let states: Vec<&str> = sdom
@@ -88,16 +338,42 @@ pub fn to_agent_summary(sdom: &SemanticDOM) -> String {
     }
 
     // Stats line
+    let stats = sdom.stats();
     lines.push(format!(
         "STATS: {}L {}A {}H",
-        sdom.landmarks.len(),
-        sdom.interactables.len(),
-        sdom.headings.len()
+        stats.landmark_count, stats.interactable_count, stats.heading_count
     ));
 
+    let lines: Vec<String> =
+        lines.iter().map(|line| truncate(line, MAX_SUMMARY_LINE_CHARS)).collect();
     lines.join("\n")
 }
 
+/// Summarize one landmark region and the actions inside it
+///
+/// Returns `None` if `id` is not a landmark.
+///
+/// # Format
+/// ```text
+/// nav(nav#primary): [nav]Home, [nav]Pricing
+/// ```
+pub fn to_landmark_summary(sdom: &SemanticDOM, id: &str) -> Option<String> {
+    let landmark = sdom.index.get(id).filter(|n| n.role.is_landmark())?;
+    let actions: Vec<String> = sdom
+        .descendants(id)
+        .into_iter()
+        .filter(|n| n.role.is_interactable())
+        .map(action_entry)
+        .take(10)
+        .collect();
+    Some(format!(
+        "{}({}): {}",
+        role_abbrev(&landmark.role),
+        selector_short(&landmark.selector),
+        actions.join(", ")
+    ))
+}
+
 /// Generate a one-line summary (~20 tokens)
 ///
 /// # Format
@@ -115,17 +391,13 @@ pub fn to_one_liner(sdom: &SemanticDOM) -> String {
         .take(3)
         .collect();
 
-    let actions: Vec<String> = sdom
-        .interactables
-        .iter()
-        .filter_map(|id| sdom.index.get(id))
+    let mut nodes: Vec<&SemanticNode> =
+        sdom.interactables.iter().filter_map(|id| sdom.index.get(id)).collect();
+    nodes.sort_by_key(|n| is_utility(n));
+    let actions: Vec<String> = nodes
+        .into_iter()
         .map(|n| {
-            let label = if n.label.len() > 10 {
-                format!("{}...", &n.label[..7])
-            } else {
-                n.label.clone()
-            };
-            format!("{}:{}", role_short(&n.role), label)
+            format!("{}:{}", role_short(&n.role), truncate(&n.label, 10))
         })
         .take(3)
         .collect();
@@ -176,6 +448,54 @@ pub fn to_nav_summary(sdom: &SemanticDOM) -> String {
     lines.join("\n")
 }
 
+/// Summarize the lists of a page, for pages where a list is the content
+///
+/// Nested lists are indented under their parent. Items are named by their
+/// first control, such as a result's link, else by their text; at most 10
+/// are shown per list.
+///
+/// # Format
+/// ```text
+/// LISTS:
+///   ol#results 12 items: Rust book; Rust by example; … +2 more
+///     ul 2 items: Ownership; Traits
+/// ```
+pub fn to_list_summary(sdom: &SemanticDOM) -> String {
+    let mut lines = vec!["LISTS:".to_string()];
+    for list in sdom.lists() {
+        let Some(node) = sdom.index.get(&list.id) else {
+            continue;
+        };
+        let names: Vec<String> = list
+            .items
+            .iter()
+            .filter_map(|id| sdom.index.get(id))
+            .map(|item| {
+                let control = item
+                    .children
+                    .iter()
+                    .filter_map(|id| sdom.index.get(id))
+                    .find(|n| n.role.is_interactable());
+                let label = control.unwrap_or(item).label.split_whitespace();
+                truncate(&label.collect::<Vec<_>>().join(" "), 40)
+            })
+            .take(10)
+            .collect();
+        let mut line = format!(
+            "{}{} {} items: {}",
+            "  ".repeat(list.nesting + 1),
+            selector_short(&node.selector),
+            list.item_count(),
+            names.join("; ")
+        );
+        if list.item_count() > names.len() {
+            line.push_str(&format!("; … +{} more", list.item_count() - names.len()));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
 /// Generate an audio/screen-reader friendly summary
 pub fn to_audio_summary(sdom: &SemanticDOM) -> String {
     let mut parts = Vec::new();
@@ -272,6 +592,26 @@ pub struct TokenComparison {
 
 // Helper functions
 
+fn action_entry(n: &SemanticNode) -> String {
+    let intent = n
+        .intent
+        .as_ref()
+        .map(intent_abbrev)
+        .unwrap_or_else(|| "act".to_string());
+    // Typed inputs name their type, required fields end in `*`
+    let kind = match n.input_type.as_deref().and_then(crate::inputs::type_abbrev) {
+        Some(kind) if kind != intent => format!("{}:{}", intent, kind),
+        _ => intent,
+    };
+    let required = n.constraints.as_ref().is_some_and(|c| c.required);
+    let label = format!("{}{}", truncate(&n.label, 20), if required { "*" } else { "" });
+    // Sliders and spin buttons show their current value
+    match n.range.as_ref().and_then(|r| r.display()) {
+        Some(value) => format!("[{}]{}={}", kind, label, value),
+        None => format!("[{}]{}", kind, label),
+    }
+}
+
 fn role_abbrev(role: &SemanticRole) -> &'static str {
     match role {
         SemanticRole::Navigation => "nav",
@@ -282,6 +622,7 @@ fn role_abbrev(role: &SemanticRole) -> &'static str {
         SemanticRole::Article => "article",
         SemanticRole::Section => "section",
         SemanticRole::Search => "search",
+        SemanticRole::Region => "region",
         SemanticRole::Form => "form",
         SemanticRole::Button => "btn",
         SemanticRole::Link => "link",
@@ -301,6 +642,11 @@ fn role_abbrev(role: &SemanticRole) -> &'static str {
         SemanticRole::Menu => "menu",
         SemanticRole::Tab => "tab",
         SemanticRole::TabPanel => "tabpanel",
+        SemanticRole::Slider => "slider",
+        SemanticRole::SpinButton => "spin",
+        SemanticRole::ProgressBar => "progress",
+        SemanticRole::Tree => "tree",
+        SemanticRole::TreeItem => "treeitem",
         SemanticRole::Interactive => "int",
         SemanticRole::Container => "div",
         SemanticRole::Unknown => "?",
@@ -320,6 +666,11 @@ fn role_short(role: &SemanticRole) -> &'static str {
     }
 }
 
+/// Whether a node is a print, share, copy or bookmark control
+fn is_utility(node: &SemanticNode) -> bool {
+    node.intent.as_ref().is_some_and(SemanticIntent::is_utility)
+}
+
 fn intent_abbrev(intent: &SemanticIntent) -> String {
     match intent {
         SemanticIntent::Navigate => "nav",
@@ -336,34 +687,45 @@ fn intent_abbrev(intent: &SemanticIntent) -> String {
         SemanticIntent::Expand => "exp",
         SemanticIntent::Collapse => "col",
         SemanticIntent::Download => "dl",
+        SemanticIntent::Upload => "up",
         SemanticIntent::Delete => "del",
         SemanticIntent::Edit => "edit",
         SemanticIntent::Create => "new",
+        SemanticIntent::Save => "save",
+        SemanticIntent::Cancel => "cncl",
+        SemanticIntent::Email => "mail",
+        SemanticIntent::Phone => "tel",
+        SemanticIntent::Print => "print",
+        SemanticIntent::Share => "share",
+        SemanticIntent::Copy => "copy",
+        SemanticIntent::Bookmark => "bkmk",
         SemanticIntent::Unknown => "?",
     }
     .to_string()
 }
 
 fn selector_short(selector: &str) -> String {
-    if selector.len() <= 20 {
-        selector.to_string()
-    } else {
-        format!("{}...", &selector[..17])
-    }
+    truncate(selector, 20)
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len - 3])
+/// Shorten text to at most `max_len` characters, ending in `...` when cut
+///
+/// Counts characters rather than bytes, so multibyte text is never split.
+pub(crate) fn truncate(s: &str, max_len: usize) -> String {
+    match s.char_indices().nth(max_len) {
+        None => s.to_string(),
+        Some(_) => {
+            let keep = max_len.saturating_sub(3);
+            let end = s.char_indices().nth(keep).map_or(s.len(), |(i, _)| i);
+            format!("{}...", &s[..end])
+        }
     }
 }
 
-fn estimate_tokens(text: &str) -> usize {
+pub(crate) fn estimate_tokens(text: &str) -> usize {
     // Rough estimation: 1 token ≈ 4 characters for English
     // This is a simplification; actual tokenization varies by model
-    (text.len() + 3) / 4
+    text.len().div_ceil(4)
 }
 
 #[cfg(test)]
@@ -389,6 +751,67 @@ mod tests {
         assert!(summary.contains("PAGE:"));
         assert!(summary.contains("LANDMARKS:"));
         assert!(summary.contains("STATS:"));
+        assert!(!summary.contains("SHORTCUTS:"));
+    }
+
+    #[test]
+    fn test_agent_summary_overlays() {
+        let html = r#"<div class="cookie-notice">Cookies! <button>Accept</button></div><main></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let summary = to_agent_summary(&sdom);
+        assert!(summary.starts_with("OVERLAYS: consent(accept:Accept)\n"), "{}", summary);
+    }
+
+    #[test]
+    fn test_agent_summary_intents_and_errors() {
+        let html = r#"<nav><a href="/">Home</a></nav><main><button>Submit</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        assert!(!to_agent_summary(&sdom).contains("INTENTS:"));
+
+        let links: String =
+            (0..12).map(|i| format!(r#"<a href="/p{}">Page {}</a>"#, i, i)).collect();
+        let html = format!(
+            r#"<nav>{}</nav><form><input aria-label="Email" aria-invalid="true"
+            aria-errormessage="m"><p id="m">{}</p></form>"#,
+            links,
+            "Enter a valid email address, like name@example.com, and try again"
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let summary = to_agent_summary(&sdom);
+        assert!(summary.contains("INTENTS: 12 navigate"), "{}", summary);
+        assert!(
+            summary.contains("ERRORS: Email=Enter a valid email address, like nam...\n"),
+            "{}",
+            summary
+        );
+    }
+
+    #[test]
+    fn test_agent_summary_shortcuts() {
+        let html = r#"
+            <nav><a href="/" accesskey="h">Home</a></nav>
+            <main><input type="search" aria-label="Search" aria-keyshortcuts="Control+K /"></main>
+        "#;
+
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let summary = to_agent_summary(&sdom);
+
+        assert!(summary.contains("SHORTCUTS: accesskey:h=Home, Control+K=Search, /=Search"));
+        assert_eq!(sdom.shortcuts().len(), 2);
+    }
+
+    #[test]
+    fn test_multibyte_labels_are_not_split() {
+        let label = "日本語のボタンです。クリックしてください。";
+        let html = format!(
+            "<main><h1>{0}{0}{0}</h1><button>{0}</button><a href=\"/\">ü{0}</a></main>",
+            label
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        assert!(sdom.to_agent_summary().contains("[act]日本語のボタンです。クリックしてく..."));
+        assert!(!sdom.to_one_liner().is_empty());
+        assert_eq!(truncate("åäö", 3), "åäö");
+        assert_eq!(truncate("åäöü", 3), "...");
     }
 
     #[test]
@@ -411,6 +834,26 @@ mod tests {
         assert!(one_liner.contains("Test"));
     }
 
+    #[test]
+    fn test_list_summary() {
+        let items: String = (1..=12)
+            .map(|i| format!(r#"<li><a href="/r/{i}">Result {i}</a> snippet</li>"#))
+            .collect();
+        let html = format!(
+            r#"<main><ol id="results">{}</ol><ul><li>Plain
+            <ul><li>Nested</li></ul></li></ul></main>"#,
+            items
+        );
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let summary = to_list_summary(&sdom);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", summary);
+        assert_eq!(lines[2], "  ul 1 items: Plain Nested");
+        assert!(lines[1].starts_with("  ol#results 12 items: Result 1; Result 2;"));
+        assert!(lines[1].ends_with("; Result 10; … +2 more"));
+        assert_eq!(lines[3], "    ul 1 items: Nested");
+    }
+
     #[test]
     fn test_token_comparison() {
         let html = r#"
@@ -0,0 +1,231 @@
+//! Caching summaries across repeated parses
+//!
+//! Services that summarize the same page again and again (polling,
+//! retries, many agents on one URL) can keep a [`SummaryCache`]. Whole
+//! document summaries are keyed by [`SemanticDOM::structure_hash`], so an
+//! identical re-parse is a cache hit. Per-landmark summaries are keyed by
+//! landmark ID, which [`SemanticDOM::update`] keeps stable, and are dropped
+//! by [`SummaryCache::invalidate`] only for the landmarks a
+//! [`DocumentChanges`] touches.
+
+use crate::diff::DocumentChanges;
+use crate::eval::SummaryFormat;
+use crate::parser::SemanticDOM;
+use ahash::AHashSet;
+use indexmap::IndexMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Write as _;
+use std::hash::Hasher;
+
+/// Feeds formatted text into a hasher without allocating
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl std::fmt::Write for HashWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl SemanticDOM {
+    /// Hash of everything the summaries are built from
+    ///
+    /// Equal for two parses of the same markup, including node IDs, so it
+    /// can key caches of derived output.
+    pub fn structure_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let mut w = HashWriter(&mut hasher);
+        // Writing into a hasher cannot fail
+        let _ = write!(w, "{:?}{:?}{:?}", self.title, self.lang, self.landmarks);
+        for node in self.reading_order() {
+            let _ = write!(w, "{:?}", node);
+        }
+        let _ = write!(
+            w,
+            "{:?}{:?}{:?}{:?}{:?}{:?}{:?}{:?}{:?}",
+            self.state_graph,
+            self.notifications,
+            self.loading,
+            self.contact,
+            self.js_signals,
+            self.products,
+            self.clusters,
+            self.facets,
+            self.noise
+        );
+        hasher.finish()
+    }
+}
+
+/// A cached landmark summary and the nodes it was built from
+#[derive(Debug, Clone)]
+struct LandmarkEntry {
+    summary: String,
+    members: AHashSet<String>,
+}
+
+/// Least-recently-used cache of rendered summaries
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Config, SemanticDOM, SummaryCache, SummaryFormat};
+///
+/// let html = r#"<nav id="top"><a href="/">Home</a></nav>
+///     <main id="body"><button>Buy</button></main>"#;
+/// let mut sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let mut cache = SummaryCache::new(64);
+///
+/// let summary = cache.summary(&sdom, SummaryFormat::Agent);
+/// assert_eq!(cache.summary(&sdom, SummaryFormat::Agent), summary);
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+///
+/// cache.landmark_summary(&sdom, "sdom_top");
+/// cache.landmark_summary(&sdom, "sdom_body");
+/// let changes = sdom.update(&html.replace("Buy", "Buy now")).unwrap();
+/// cache.invalidate(&changes, &sdom);
+/// // The navigation did not change, so only the main region is rendered again
+/// assert_eq!(cache.landmark_summary(&sdom, "sdom_top").unwrap(), "nav(nav#top): [nav]Home");
+/// assert!(cache.landmark_summary(&sdom, "sdom_body").unwrap().ends_with("Buy now"));
+/// assert_eq!(cache.misses(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SummaryCache {
+    capacity: usize,
+    /// Least recently used first
+    summaries: IndexMap<(u64, SummaryFormat), String>,
+    landmarks: IndexMap<String, LandmarkEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+impl SummaryCache {
+    /// Create a cache holding up to `capacity` document summaries and as
+    /// many landmark summaries
+    pub fn new(capacity: usize) -> Self {
+        SummaryCache {
+            capacity: capacity.max(1),
+            summaries: IndexMap::new(),
+            landmarks: IndexMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Get a document summary, rendering it on a miss
+    pub fn summary(&mut self, sdom: &SemanticDOM, format: SummaryFormat) -> String {
+        let key = (sdom.structure_hash(), format);
+        if let Some(summary) = self.summaries.shift_remove(&key) {
+            self.hits += 1;
+            self.summaries.insert(key, summary.clone());
+            return summary;
+        }
+        self.misses += 1;
+        let summary = format.render(sdom);
+        if self.summaries.len() >= self.capacity {
+            self.summaries.shift_remove_index(0);
+        }
+        self.summaries.insert(key, summary.clone());
+        summary
+    }
+
+    /// Get the summary of one landmark region, rendering it on a miss
+    ///
+    /// Returns `None` if `id` is not a landmark of `sdom`.
+    pub fn landmark_summary(&mut self, sdom: &SemanticDOM, id: &str) -> Option<String> {
+        if let Some(entry) = self.landmarks.shift_remove(id) {
+            self.hits += 1;
+            let summary = entry.summary.clone();
+            self.landmarks.insert(id.to_string(), entry);
+            return Some(summary);
+        }
+        let summary = crate::summary::to_landmark_summary(sdom, id)?;
+        self.misses += 1;
+        let members = std::iter::once(id.to_string())
+            .chain(sdom.descendants(id).into_iter().map(|n| n.id.clone()))
+            .collect();
+        if self.landmarks.len() >= self.capacity {
+            self.landmarks.shift_remove_index(0);
+        }
+        let entry = LandmarkEntry { summary: summary.clone(), members };
+        self.landmarks.insert(id.to_string(), entry);
+        Some(summary)
+    }
+
+    /// Drop the landmark summaries a change set affects
+    ///
+    /// `sdom` is the updated document: added nodes are traced to the
+    /// landmarks now containing them, removed and modified ones are looked
+    /// up among the nodes each cached summary was built from.
+    pub fn invalidate(&mut self, changes: &DocumentChanges, sdom: &SemanticDOM) {
+        let mut stale: AHashSet<&str> = AHashSet::new();
+        for id in &changes.added {
+            let mut parent = sdom.get(id).and_then(|n| n.parent.as_deref());
+            while let Some(ancestor) = parent {
+                stale.insert(ancestor);
+                parent = sdom.get(ancestor).and_then(|n| n.parent.as_deref());
+            }
+        }
+        let changed: AHashSet<&str> = changes
+            .removed
+            .iter()
+            .chain(&changes.modified)
+            .map(String::as_str)
+            .collect();
+        self.landmarks.retain(|id, entry| {
+            !stale.contains(id.as_str())
+                && !entry.members.iter().any(|m| changed.contains(m.as_str()))
+        });
+    }
+
+    /// Remove every cached summary
+    pub fn clear(&mut self) {
+        self.summaries.clear();
+        self.landmarks.clear();
+    }
+
+    /// Number of cached summaries, document and landmark
+    pub fn len(&self) -> usize {
+        self.summaries.len() + self.landmarks.len()
+    }
+
+    /// Check if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to render
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_structure_hash_and_eviction() {
+        let page = |label: &str| {
+            let html = format!("<main><h1>Shop</h1><button>{}</button></main>", label);
+            SemanticDOM::parse(&html, Config::default()).unwrap()
+        };
+        let (a, b) = (page("Buy"), page("Sell"));
+        assert_eq!(a.structure_hash(), page("Buy").structure_hash());
+        assert_ne!(a.structure_hash(), b.structure_hash());
+
+        let mut cache = SummaryCache::new(1);
+        cache.summary(&a, SummaryFormat::OneLiner);
+        cache.summary(&b, SummaryFormat::OneLiner);
+        // Capacity one: the first summary was evicted
+        assert_eq!(cache.summary(&a, SummaryFormat::OneLiner), a.to_one_liner());
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 3, 1));
+        assert_eq!(cache.landmark_summary(&a, "missing"), None);
+        assert_eq!(cache.misses(), 3);
+    }
+}
@@ -0,0 +1,312 @@
+//! Full-text search over the page
+//!
+//! Labels are shortened to a few words, so "find where it says 'free
+//! shipping'" needs more than a label scan. [`SearchIndex`] is a small
+//! inverted index over each node's label and accessible name, which for
+//! nodes without an explicit name is their full text content; hits are
+//! ranked by where the words occur and come with the matching snippet.
+
+use crate::parser::SemanticDOM;
+use ahash::{AHashMap, AHashSet};
+use serde::{Deserialize, Serialize};
+
+/// Characters shown on each side of a match in a snippet
+const SNIPPET_CONTEXT: usize = 30;
+
+/// The part of a node a search matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchField {
+    /// The node's label
+    Label,
+    /// The accessible name or text content, when it differs from the label
+    AccessibleName,
+}
+
+impl MatchField {
+    fn weight(self) -> f32 {
+        match self {
+            MatchField::Label => 2.0,
+            MatchField::AccessibleName => 1.0,
+        }
+    }
+}
+
+/// A node matching a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// ID of the matching node
+    pub id: String,
+    /// Where the best match is
+    pub field: MatchField,
+    /// Higher is better; the whole query in the label scores highest
+    pub score: f32,
+    /// The matched text with some context
+    pub snippet: String,
+}
+
+/// One searchable field of a node
+#[derive(Debug, Clone)]
+struct Entry {
+    node: usize,
+    field: MatchField,
+    text: String,
+    lower: String,
+    /// Character position in `text` of each character of `lower`, which
+    /// can be longer: "İ" lowercases to two characters
+    origin: Vec<usize>,
+}
+
+/// Inverted index over the text of a document's nodes
+///
+/// Build it once with [`SearchIndex::new`] to run many queries; it is a
+/// snapshot and does not follow later edits.
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    /// Node IDs in document order
+    ids: Vec<String>,
+    /// Position of each node's parent in `ids`
+    parents: Vec<Option<usize>>,
+    entries: Vec<Entry>,
+    /// Ascending entry positions per lowercased word
+    postings: AHashMap<String, Vec<usize>>,
+}
+
+/// Lowercased words of a text
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Collapse whitespace runs into single spaces
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl SearchIndex {
+    /// Index the labels and accessible names of every node
+    pub fn new(sdom: &SemanticDOM) -> Self {
+        let nodes = sdom.reading_order();
+        let positions: AHashMap<&str, usize> =
+            nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+        let mut index = SearchIndex {
+            ids: Vec::with_capacity(nodes.len()),
+            parents: Vec::with_capacity(nodes.len()),
+            entries: Vec::new(),
+            postings: AHashMap::new(),
+        };
+
+        for (position, node) in nodes.iter().enumerate() {
+            index.ids.push(node.id.clone());
+            index.parents.push(node.parent.as_deref().and_then(|p| positions.get(p).copied()));
+            let name = node.accessible_name.as_deref().filter(|n| *n != node.label);
+            let label = Some(node.label.as_str());
+            for (field, text) in [(MatchField::Label, label), (MatchField::AccessibleName, name)] {
+                let Some(text) = text.filter(|t| !t.is_empty()) else {
+                    continue;
+                };
+                let text = normalize_text(text);
+                let entry = index.entries.len();
+                for word in words(&text) {
+                    let list = index.postings.entry(word).or_default();
+                    if list.last() != Some(&entry) {
+                        list.push(entry);
+                    }
+                }
+                let lower = text.to_lowercase();
+                let origin = text
+                    .chars()
+                    .enumerate()
+                    .flat_map(|(i, c)| std::iter::repeat(i).take(c.to_lowercase().count()))
+                    .collect();
+                index.entries.push(Entry { node: position, field, text, lower, origin });
+            }
+        }
+        index
+    }
+
+    /// Find the nodes whose text contains every word of `query`
+    ///
+    /// A node scores the weight of its best matching field (label 2,
+    /// accessible name 1), doubled when that field contains the whole query
+    /// as a phrase. Containers take their label and name from the text
+    /// inside them, so a node is left out when a node inside it also
+    /// matches and hits point at the innermost one. Ties keep document
+    /// order.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let mut terms: Vec<String> = words(query).collect();
+        terms.sort();
+        terms.dedup();
+        let Some(lists) = terms.iter().map(|t| self.postings.get(t)).collect::<Option<Vec<_>>>()
+        else {
+            return Vec::new();
+        };
+        let Some(rarest) = lists.iter().min_by_key(|l| l.len()) else {
+            return Vec::new();
+        };
+        let phrase = normalize_text(&query.to_lowercase());
+
+        // Best entry, its score and the longest matching entry per node;
+        // labels are truncated, so the snippet comes from the longest
+        let mut hits: AHashMap<usize, (usize, f32, usize)> = AHashMap::new();
+        for &e in rarest.iter() {
+            if !lists.iter().all(|l| l.binary_search(&e).is_ok()) {
+                continue;
+            }
+            let entry = &self.entries[e];
+            let phrase_bonus = if entry.lower.contains(&phrase) { 2.0 } else { 1.0 };
+            let score = entry.field.weight() * phrase_bonus;
+            let hit = hits.entry(entry.node).or_insert((e, score, e));
+            if score > hit.1 {
+                (hit.0, hit.1) = (e, score);
+            }
+            if entry.text.len() > self.entries[hit.2].text.len() {
+                hit.2 = e;
+            }
+        }
+
+        // Ancestors of a hit only repeat its text
+        let mut outer: AHashSet<usize> = AHashSet::new();
+        for &node in hits.keys() {
+            let mut parent = self.parents[node];
+            while let Some(p) = parent {
+                if !outer.insert(p) {
+                    break;
+                }
+                parent = self.parents[p];
+            }
+        }
+
+        let mut ranked: Vec<(usize, (usize, f32, usize))> =
+            hits.into_iter().filter(|(node, _)| !outer.contains(node)).collect();
+        ranked.sort_by(|a, b| b.1 .1.total_cmp(&a.1 .1).then(a.0.cmp(&b.0)));
+        ranked
+            .into_iter()
+            .map(|(node, (best, score, longest))| SearchHit {
+                id: self.ids[node].clone(),
+                field: self.entries[best].field,
+                score,
+                snippet: snippet(&self.entries[longest], &phrase, &terms),
+            })
+            .collect()
+    }
+}
+
+/// The text around the phrase, or else the first query word
+fn snippet(entry: &Entry, phrase: &str, terms: &[String]) -> String {
+    let found = entry
+        .lower
+        .find(phrase)
+        .map(|at| (at, phrase))
+        .or_else(|| {
+            terms.iter().find_map(|t| entry.lower.find(t.as_str()).map(|at| (at, t.as_str())))
+        });
+    let Some((at, matched)) = found else {
+        return crate::summary::truncate(&entry.text, SNIPPET_CONTEXT * 2);
+    };
+    // Lowercasing can change lengths, so map back through `origin`
+    let total = entry.text.chars().count();
+    let start_lower = entry.lower[..at].chars().count();
+    let end_lower = start_lower + matched.chars().count();
+    let start_char = entry.origin.get(start_lower).copied().unwrap_or(total);
+    let end_char = end_lower
+        .checked_sub(1)
+        .and_then(|last| entry.origin.get(last))
+        .map_or(start_char, |i| i + 1)
+        .max(start_char);
+    let from = start_char.saturating_sub(SNIPPET_CONTEXT);
+    let to = (end_char + SNIPPET_CONTEXT).min(total);
+    let body: String = entry.text.chars().skip(from).take(to - from).collect();
+    // Drop words cut in half by the window
+    let mut body = body.as_str();
+    if from > 0 {
+        body = body.split_once(' ').map_or(body, |(_, rest)| rest);
+    }
+    if to < total {
+        body = body.rsplit_once(' ').map_or(body, |(rest, _)| rest);
+    }
+    format!(
+        "{}{}{}",
+        if from > 0 { "..." } else { "" },
+        body.trim(),
+        if to < total { "..." } else { "" }
+    )
+}
+
+impl SemanticDOM {
+    /// Search the page for text, ranking the nodes that contain it
+    ///
+    /// Builds a [`SearchIndex`] for one query; keep the index to run
+    /// several.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, MatchField, SemanticDOM};
+    ///
+    /// let html = r#"<main>
+    ///     <section id="perks"><h2>Perks</h2>
+    ///         <p>All orders over $50 qualify for free shipping to the mainland.</p>
+    ///     </section>
+    ///     <button>Free shipping details</button>
+    /// </main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let hits = sdom.search("free shipping");
+    /// assert_eq!(hits[0].field, MatchField::Label);
+    /// assert_eq!(hits[0].snippet, "Free shipping details");
+    /// // The section matches through its text; <main> around it is left out
+    /// assert_eq!(hits[1].id, "sdom_perks");
+    /// let snippet = "...orders over $50 qualify for free shipping to the mainland.";
+    /// assert_eq!(hits[1].snippet, snippet);
+    /// assert_eq!(hits.len(), 2);
+    /// ```
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        SearchIndex::new(self).search(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_ranking_and_snippets() {
+        let html = r#"<main><a href="/ship" aria-label="Shipping costs">Costs</a>
+            <article id="faq"><h2>FAQ</h2> <p>Returns are free. Shipping takes two days,
+            and express shipping is available in most regions for a small fee.</p></article>
+            <button aria-label="Close">Close</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let index = SearchIndex::new(&sdom);
+
+        let hits = index.search("SHIPPING");
+        let found: Vec<(&str, MatchField, f32)> =
+            hits.iter().map(|h| (h.id.as_str(), h.field, h.score)).collect();
+        assert_eq!(
+            found,
+            [
+                ("sdom_a_2", MatchField::Label, 4.0),
+                ("sdom_faq", MatchField::Label, 4.0),
+            ]
+        );
+        let snippet = "FAQ Returns are free. Shipping takes two days, and express...";
+        assert_eq!(hits[1].snippet, snippet);
+
+        // Words spread over several nodes match their common container
+        assert_eq!(index.search("free fee")[0].id, "sdom_faq");
+        assert_eq!(index.search("close costs")[0].id, "sdom_main_1");
+        assert!(index.search("overnight shipping").is_empty());
+        assert!(index.search("   ").is_empty());
+    }
+
+    #[test]
+    fn test_snippet_after_lengthening_lowercase() {
+        // "İ" lowercases to two characters, shifting positions in the text
+        let label = format!("{} shipping", "İ".repeat(80));
+        let html = format!(r#"<main><button aria-label="{}">Go</button></main>"#, label);
+        let sdom = SemanticDOM::parse(&html, Config::default()).unwrap();
+        let hits = SearchIndex::new(&sdom).search("shipping");
+        assert_eq!(hits[0].snippet, "...shipping");
+    }
+}
@@ -4,6 +4,7 @@
 //! including roles, intents, and node structures.
 
 use ahash::AHashMap;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 /// Semantic role for an element based on ARIA and HTML5 semantics
@@ -26,6 +27,9 @@ pub enum SemanticRole {
     Section,
     /// Search region
     Search,
+    /// Named region landmark: a `section` with an accessible name, or
+    /// `role=region`
+    Region,
     /// Form element
     Form,
     /// Button element
@@ -64,6 +68,16 @@ pub enum SemanticRole {
     Tab,
     /// Tab panel
     TabPanel,
+    /// Range input (`input[type=range]`, `role=slider`)
+    Slider,
+    /// Numeric input with step controls (`input[type=number]`, `role=spinbutton`)
+    SpinButton,
+    /// Progress or gauge display (`<progress>`, `<meter>`, `role=progressbar`)
+    ProgressBar,
+    /// Hierarchical list such as a file browser (`role=tree`)
+    Tree,
+    /// Item of a tree (`role=treeitem`)
+    TreeItem,
     /// Generic interactive element
     Interactive,
     /// Generic container
@@ -83,6 +97,7 @@ impl SemanticRole {
                 | SemanticRole::Footer
                 | SemanticRole::Aside
                 | SemanticRole::Search
+                | SemanticRole::Region
         )
     }
 
@@ -96,13 +111,133 @@ impl SemanticRole {
                 | SemanticRole::Checkbox
                 | SemanticRole::Radio
                 | SemanticRole::Select
+                | SemanticRole::Slider
+                | SemanticRole::SpinButton
+                | SemanticRole::TreeItem
                 | SemanticRole::Interactive
         )
     }
+
+    /// Canonical role name, identical to the serialized form
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SemanticRole::Navigation => "navigation",
+            SemanticRole::Main => "main",
+            SemanticRole::Header => "header",
+            SemanticRole::Footer => "footer",
+            SemanticRole::Aside => "aside",
+            SemanticRole::Article => "article",
+            SemanticRole::Section => "section",
+            SemanticRole::Search => "search",
+            SemanticRole::Region => "region",
+            SemanticRole::Form => "form",
+            SemanticRole::Button => "button",
+            SemanticRole::Link => "link",
+            SemanticRole::TextInput => "textinput",
+            SemanticRole::Checkbox => "checkbox",
+            SemanticRole::Radio => "radio",
+            SemanticRole::Select => "select",
+            SemanticRole::Heading => "heading",
+            SemanticRole::List => "list",
+            SemanticRole::ListItem => "listitem",
+            SemanticRole::Table => "table",
+            SemanticRole::Image => "image",
+            SemanticRole::Video => "video",
+            SemanticRole::Audio => "audio",
+            SemanticRole::Dialog => "dialog",
+            SemanticRole::Alert => "alert",
+            SemanticRole::Menu => "menu",
+            SemanticRole::Tab => "tab",
+            SemanticRole::TabPanel => "tabpanel",
+            SemanticRole::Slider => "slider",
+            SemanticRole::SpinButton => "spinbutton",
+            SemanticRole::ProgressBar => "progressbar",
+            SemanticRole::Tree => "tree",
+            SemanticRole::TreeItem => "treeitem",
+            SemanticRole::Interactive => "interactive",
+            SemanticRole::Container => "container",
+            SemanticRole::Unknown => "unknown",
+        }
+    }
+
+    /// Equivalent WAI-ARIA role, if there is one
+    pub fn aria_role(&self) -> Option<&'static str> {
+        match self {
+            SemanticRole::Header => Some("banner"),
+            SemanticRole::Footer => Some("contentinfo"),
+            SemanticRole::Aside => Some("complementary"),
+            SemanticRole::TextInput => Some("textbox"),
+            SemanticRole::Select => Some("combobox"),
+            SemanticRole::Image => Some("img"),
+            // Sections without a name are generic
+            SemanticRole::Section
+            | SemanticRole::Video
+            | SemanticRole::Audio
+            | SemanticRole::Interactive
+            | SemanticRole::Container
+            | SemanticRole::Unknown => None,
+            other => Some(other.as_str()),
+        }
+    }
+}
+
+impl std::fmt::Display for SemanticRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SemanticRole {
+    type Err = crate::Error;
+
+    /// Parse a canonical role name or a WAI-ARIA role string
+    /// (case-insensitive), e.g. `"header"` or `"banner"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let role = match s.trim().to_ascii_lowercase().as_str() {
+            "navigation" => SemanticRole::Navigation,
+            "main" => SemanticRole::Main,
+            "header" | "banner" => SemanticRole::Header,
+            "footer" | "contentinfo" => SemanticRole::Footer,
+            "aside" | "complementary" => SemanticRole::Aside,
+            "article" => SemanticRole::Article,
+            "section" => SemanticRole::Section,
+            "search" => SemanticRole::Search,
+            "region" => SemanticRole::Region,
+            "form" => SemanticRole::Form,
+            "button" => SemanticRole::Button,
+            "link" => SemanticRole::Link,
+            "textinput" | "textbox" | "searchbox" => SemanticRole::TextInput,
+            "checkbox" | "switch" => SemanticRole::Checkbox,
+            "radio" => SemanticRole::Radio,
+            "select" | "combobox" | "listbox" => SemanticRole::Select,
+            "heading" => SemanticRole::Heading,
+            "list" => SemanticRole::List,
+            "listitem" => SemanticRole::ListItem,
+            "table" | "grid" | "treegrid" => SemanticRole::Table,
+            "image" | "img" => SemanticRole::Image,
+            "video" => SemanticRole::Video,
+            "audio" => SemanticRole::Audio,
+            "dialog" | "alertdialog" => SemanticRole::Dialog,
+            "alert" => SemanticRole::Alert,
+            "menu" | "menubar" => SemanticRole::Menu,
+            "tab" => SemanticRole::Tab,
+            "tabpanel" => SemanticRole::TabPanel,
+            "slider" => SemanticRole::Slider,
+            "spinbutton" => SemanticRole::SpinButton,
+            "progressbar" | "meter" => SemanticRole::ProgressBar,
+            "tree" => SemanticRole::Tree,
+            "treeitem" => SemanticRole::TreeItem,
+            "interactive" => SemanticRole::Interactive,
+            "container" => SemanticRole::Container,
+            "unknown" => SemanticRole::Unknown,
+            _ => return Err(crate::Error::UnknownRole(s.to_string())),
+        };
+        Ok(role)
+    }
 }
 
 /// User intent classification for an element
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SemanticIntent {
     /// Navigation action
@@ -133,16 +268,320 @@ pub enum SemanticIntent {
     Collapse,
     /// Download resource
     Download,
+    /// Upload a file
+    Upload,
     /// Delete/remove item
     Delete,
     /// Edit/modify item
     Edit,
     /// Create new item
     Create,
+    /// Save changes without leaving the page
+    Save,
+    /// Cancel/abandon the current operation
+    Cancel,
+    /// Compose an email or enter an email address
+    Email,
+    /// Place a call or enter a phone number
+    Phone,
+    /// Print the page
+    Print,
+    /// Share the page or an item
+    Share,
+    /// Copy a link, code or text to the clipboard
+    Copy,
+    /// Bookmark or favorite an item
+    Bookmark,
     /// Unknown intent
     Unknown,
 }
 
+impl SemanticIntent {
+    /// Canonical intent name, identical to the serialized form
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SemanticIntent::Navigate => "navigate",
+            SemanticIntent::Submit => "submit",
+            SemanticIntent::Action => "action",
+            SemanticIntent::Toggle => "toggle",
+            SemanticIntent::Select => "select",
+            SemanticIntent::Input => "input",
+            SemanticIntent::Search => "search",
+            SemanticIntent::Play => "play",
+            SemanticIntent::Pause => "pause",
+            SemanticIntent::Open => "open",
+            SemanticIntent::Close => "close",
+            SemanticIntent::Expand => "expand",
+            SemanticIntent::Collapse => "collapse",
+            SemanticIntent::Download => "download",
+            SemanticIntent::Upload => "upload",
+            SemanticIntent::Delete => "delete",
+            SemanticIntent::Edit => "edit",
+            SemanticIntent::Create => "create",
+            SemanticIntent::Save => "save",
+            SemanticIntent::Cancel => "cancel",
+            SemanticIntent::Email => "email",
+            SemanticIntent::Phone => "phone",
+            SemanticIntent::Print => "print",
+            SemanticIntent::Share => "share",
+            SemanticIntent::Copy => "copy",
+            SemanticIntent::Bookmark => "bookmark",
+            SemanticIntent::Unknown => "unknown",
+        }
+    }
+
+    /// Check whether this is a utility action found on most pages (print,
+    /// share, copy, bookmark), which summaries list after the rest
+    pub fn is_utility(&self) -> bool {
+        matches!(
+            self,
+            SemanticIntent::Print
+                | SemanticIntent::Share
+                | SemanticIntent::Copy
+                | SemanticIntent::Bookmark
+        )
+    }
+}
+
+impl std::fmt::Display for SemanticIntent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SemanticIntent {
+    type Err = crate::Error;
+
+    /// Parse an intent name as used in `data-intent` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let intent = match s.trim().to_ascii_lowercase().as_str() {
+            "navigate" => SemanticIntent::Navigate,
+            "submit" => SemanticIntent::Submit,
+            "action" => SemanticIntent::Action,
+            "toggle" => SemanticIntent::Toggle,
+            "select" => SemanticIntent::Select,
+            "input" => SemanticIntent::Input,
+            "search" => SemanticIntent::Search,
+            "play" => SemanticIntent::Play,
+            "pause" => SemanticIntent::Pause,
+            "open" => SemanticIntent::Open,
+            "close" => SemanticIntent::Close,
+            "expand" => SemanticIntent::Expand,
+            "collapse" => SemanticIntent::Collapse,
+            "download" => SemanticIntent::Download,
+            "upload" => SemanticIntent::Upload,
+            "delete" => SemanticIntent::Delete,
+            "edit" => SemanticIntent::Edit,
+            "create" => SemanticIntent::Create,
+            "save" => SemanticIntent::Save,
+            "cancel" => SemanticIntent::Cancel,
+            "email" => SemanticIntent::Email,
+            "phone" | "tel" => SemanticIntent::Phone,
+            "print" => SemanticIntent::Print,
+            "share" => SemanticIntent::Share,
+            "copy" => SemanticIntent::Copy,
+            "bookmark" => SemanticIntent::Bookmark,
+            "unknown" => SemanticIntent::Unknown,
+            _ => return Err(crate::Error::UnknownIntent(s.to_string())),
+        };
+        Ok(intent)
+    }
+}
+
+/// Where a link leads relative to the current page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// Another page on the same site
+    Internal,
+    /// A page on a different host
+    External,
+    /// A fragment on the current page
+    Anchor,
+    /// `mailto:` link
+    Mailto,
+    /// `tel:` link
+    Tel,
+}
+
+impl LinkKind {
+    /// Canonical lowercase name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::Internal => "internal",
+            LinkKind::External => "external",
+            LinkKind::Anchor => "anchor",
+            LinkKind::Mailto => "mailto",
+            LinkKind::Tel => "tel",
+        }
+    }
+}
+
+/// Which item of a set represents the current state (`aria-current`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AriaCurrent {
+    /// The current page, e.g. in site navigation or breadcrumbs
+    Page,
+    /// The current step of a multi-step process
+    Step,
+    /// The current location, e.g. on a map or flow chart
+    Location,
+    /// The current date in a calendar
+    Date,
+    /// The current time in a timetable
+    Time,
+    /// Current, without saying of what kind
+    True,
+}
+
+impl AriaCurrent {
+    /// Parse an `aria-current` value; `false` and empty values mean none,
+    /// and unknown tokens count as `true`
+    pub fn from_attr(value: &str) -> Option<Self> {
+        let current = match value.trim().to_ascii_lowercase().as_str() {
+            "" | "false" => return None,
+            "page" => AriaCurrent::Page,
+            "step" => AriaCurrent::Step,
+            "location" => AriaCurrent::Location,
+            "date" => AriaCurrent::Date,
+            "time" => AriaCurrent::Time,
+            _ => AriaCurrent::True,
+        };
+        Some(current)
+    }
+}
+
+/// Text direction from the `dir` attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    /// Left to right
+    Ltr,
+    /// Right to left
+    Rtl,
+    /// Determined from the content by the user agent
+    Auto,
+}
+
+impl TextDirection {
+    /// Parse a `dir` attribute value, ignoring invalid values
+    pub fn from_attr(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ltr" => Some(TextDirection::Ltr),
+            "rtl" => Some(TextDirection::Rtl),
+            "auto" => Some(TextDirection::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// Location of an element's start tag in the source HTML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceSpan {
+    /// Byte offset of the `<`
+    pub start: usize,
+    /// Byte offset just past the closing `>`
+    pub end: usize,
+    /// Line of the `<`, starting at 1
+    pub line: usize,
+    /// Column of the `<` in characters, starting at 1
+    pub column: usize,
+}
+
+/// On-screen box of a rendered element, in CSS pixels from the top left of
+/// the page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    /// Left edge
+    pub x: f64,
+    /// Top edge
+    pub y: f64,
+    /// Width
+    pub width: f64,
+    /// Height
+    pub height: f64,
+}
+
+impl Bounds {
+    /// Check if the box covers no area, as for hidden elements
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+}
+
+/// Current value and bounds of a slider, spin button or progress bar
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RangeValue {
+    /// Minimum value
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min: Option<f64>,
+    /// Maximum value
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max: Option<f64>,
+    /// Current value; `None` for an indeterminate progress bar
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub now: Option<f64>,
+    /// Step between allowed values
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub step: Option<f64>,
+    /// Human-readable value from `aria-valuetext` (e.g. "3 of 5 stars")
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text: Option<String>,
+}
+
+impl RangeValue {
+    /// Position of the current value between min and max, from 0.0 to 1.0
+    pub fn fraction(&self) -> Option<f64> {
+        let (min, max, now) = (self.min?, self.max?, self.now?);
+        (max > min).then(|| ((now - min) / (max - min)).clamp(0.0, 1.0))
+    }
+
+    /// Value as shown to agents: the value text, else the number
+    pub fn display(&self) -> Option<String> {
+        self.text.clone().or_else(|| self.now.map(|now| now.to_string()))
+    }
+}
+
+/// Validation constraints of a form field
+///
+/// Bounds are kept as written, since dates and times are bounded too;
+/// sliders and spin buttons have theirs as numbers in [`RangeValue`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputConstraints {
+    /// Whether a value is required (`required`)
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub required: bool,
+    /// Format the input type expects values in, e.g. `YYYY-MM-DD`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub format: Option<String>,
+    /// Least value (`min`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min: Option<String>,
+    /// Greatest value (`max`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max: Option<String>,
+    /// Granularity of values (`step`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub step: Option<String>,
+    /// Fewest characters (`minlength`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min_length: Option<u32>,
+    /// Most characters (`maxlength`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_length: Option<u32>,
+    /// Regular expression the whole value must match (`pattern`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pattern: Option<String>,
+    /// File types a file input takes (`accept`): extensions such as
+    /// `.pdf` and MIME types such as `image/*`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub accept: Vec<String>,
+    /// Whether several values may be chosen (`multiple`)
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub multiple: bool,
+}
+
 /// A semantic node in the DOM tree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SemanticNode {
@@ -155,6 +594,12 @@ pub struct SemanticNode {
     /// User intent (for interactables)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub intent: Option<SemanticIntent>,
+    /// Confidence in the assigned role (1.0 = explicit markup)
+    #[serde(default = "full_confidence")]
+    pub role_confidence: f32,
+    /// Confidence in the inferred intent (1.0 = explicit attribute)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intent_confidence: Option<f32>,
     /// CSS selector path to this element
     pub selector: String,
     /// Accessible name from ARIA or content
@@ -169,13 +614,95 @@ pub struct SemanticNode {
     /// Parent node ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
-    /// Additional metadata
+    /// Lowercased `type` attribute for `<input>` elements
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub input_type: Option<String>,
+    /// Whether a form field is marked invalid (`aria-invalid` or an error message)
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub invalid: bool,
+    /// Validation error message associated with a form field
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error_message: Option<String>,
+    /// Keyboard access key from the `accesskey` attribute
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub access_key: Option<String>,
+    /// Keyboard shortcuts from `aria-keyshortcuts` (e.g. `Control+K`)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub key_shortcuts: Vec<String>,
+    /// Heading level (1-6) for heading nodes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub level: Option<u8>,
+    /// Where a link leads, for link nodes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub link_kind: Option<LinkKind>,
+    /// Lowercased `rel` tokens of a link (e.g. `nofollow`, `next`)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub rel: Vec<String>,
+    /// Language of the link target (`hreflang`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hreflang: Option<String>,
+    /// Interactive node built from a generic element rather than a native
+    /// control (e.g. `<div onclick>` or `<span role="button">`)
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub nonstandard: bool,
+    /// Landmark inferred from the layout of a plain container rather than
+    /// declared in markup, with `Config::infer_landmarks`
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub inferred: bool,
+    /// Role declared by a `role` or `data-agent-role` attribute rather than
+    /// implied by the tag
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub explicit_role: bool,
+    /// Events with inline handlers, e.g. `click` for `onclick`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub event_handlers: Vec<String>,
+    /// Effective language, when it differs from the document language
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub lang: Option<String>,
+    /// Effective text direction, when it differs from the document direction
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub dir: Option<TextDirection>,
+    /// Start tag location in the source, with `Config::source_spans`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source: Option<SourceSpan>,
+    /// Marks the node as the current item of its set, from `aria-current`
+    /// or an `active` class on a navigation link
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub current: Option<AriaCurrent>,
+    /// Expansion state from `aria-expanded`; `None` when not expandable
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expanded: Option<bool>,
+    /// Whether the node is selected (`aria-selected`)
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub selected: bool,
+    /// Value and bounds for sliders, spin buttons and progress bars
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub range: Option<RangeValue>,
+    /// Validation constraints of a form field
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub constraints: Option<InputConstraints>,
+    /// Text of the paragraphs, list items and table cells in the node, cut
+    /// to `Config::text_excerpts` characters; unset by default
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub text_excerpt: Option<String>,
+    /// Rendered geometry, when set from a browser capture; parsing HTML
+    /// alone leaves it empty
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub bounds: Option<Bounds>,
+    /// Raw attributes kept by `Config::preserve_attributes`, sorted by name
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    pub attributes: IndexMap<String, String>,
+    /// Additional metadata, in insertion order
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<AHashMap<String, String>>,
+    pub metadata: Option<IndexMap<String, String>>,
     /// Depth in the tree (0 = root)
     pub depth: usize,
 }
 
+fn full_confidence() -> f32 {
+    1.0
+}
+
 impl SemanticNode {
     /// Create a new semantic node
     pub fn new(id: String, label: String, role: SemanticRole, selector: String) -> Self {
@@ -184,15 +711,46 @@ impl SemanticNode {
             label,
             role,
             intent: None,
+            role_confidence: 1.0,
+            intent_confidence: None,
             selector,
             accessible_name: None,
             href: None,
             children: Vec::new(),
             parent: None,
+            input_type: None,
+            invalid: false,
+            error_message: None,
+            access_key: None,
+            key_shortcuts: Vec::new(),
+            level: None,
+            link_kind: None,
+            rel: Vec::new(),
+            hreflang: None,
+            nonstandard: false,
+            inferred: false,
+            explicit_role: false,
+            event_handlers: Vec::new(),
+            lang: None,
+            dir: None,
+            source: None,
+            current: None,
+            expanded: None,
+            selected: false,
+            range: None,
+            constraints: None,
+            text_excerpt: None,
+            bounds: None,
+            attributes: IndexMap::new(),
             metadata: None,
             depth: 0,
         }
     }
+
+    /// Get a raw attribute kept by `Config::preserve_attributes`
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
 }
 
 /// A state in the Semantic State Graph
@@ -214,6 +772,9 @@ pub struct State {
     /// Whether this is a terminal state
     #[serde(default)]
     pub is_terminal: bool,
+    /// Node scrolled into view, for in-page anchor states
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub anchor: Option<String>,
 }
 
 /// A transition between states in the SSG
@@ -231,11 +792,59 @@ pub struct Transition {
     /// Guard condition
     #[serde(skip_serializing_if = "Option::is_none")]
     pub guard: Option<String>,
+    /// Request sent by a form submit transition
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request: Option<FormRequest>,
+    /// Route parameters of the target URL, for transitions into a templated
+    /// state such as `/product/{id}`; for sort and filter transitions, the
+    /// query parameter the chosen option sets
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    pub params: IndexMap<String, String>,
+}
+
+/// The HTTP request a form submission will make
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormRequest {
+    /// Uppercased HTTP method, `GET` or `POST`
+    pub method: String,
+    /// Target URL, resolved against the page URL when it is known; `None`
+    /// submits to the current page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Body encoding for POST requests (`enctype`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enctype: Option<String>,
+    /// Named fields submitted with the form, in document order
+    pub fields: Vec<FormField>,
+}
+
+/// A named field submitted with a form
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormField {
+    /// Submitted name
+    pub name: String,
+    /// Input type, or `select`/`textarea`
+    pub field_type: String,
+    /// Whether the field must be filled in
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub required: bool,
+    /// Preset value of a hidden field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// ID of the field's node, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
 }
 
 /// The Semantic State Graph
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Serialized graphs follow a versioned schema; loading one written by an
+/// older version of the crate migrates it (see
+/// [`STATE_GRAPH_SCHEMA_VERSION`](crate::STATE_GRAPH_SCHEMA_VERSION)).
+#[derive(Debug, Clone, Serialize)]
 pub struct StateGraph {
+    /// Schema version; the current one for new and loaded graphs
+    pub schema_version: u32,
     /// All states in the graph
     pub states: Vec<State>,
     /// All transitions between states
@@ -247,7 +856,8 @@ pub struct StateGraph {
 
 impl Default for StateGraph {
     fn default() -> Self {
-        Self {
+        StateGraph {
+            schema_version: crate::graph_schema::STATE_GRAPH_SCHEMA_VERSION,
             states: Vec::new(),
             transitions: Vec::new(),
             initial_state: None,
@@ -322,6 +932,23 @@ mod tests {
         assert!(!SemanticRole::Navigation.is_interactable());
     }
 
+    #[test]
+    fn test_role_string_round_trip() {
+        assert_eq!("banner".parse::<SemanticRole>().unwrap(), SemanticRole::Header);
+        assert_eq!("Contentinfo".parse::<SemanticRole>().unwrap(), SemanticRole::Footer);
+        assert_eq!("textbox".parse::<SemanticRole>().unwrap(), SemanticRole::TextInput);
+        assert_eq!("region".parse::<SemanticRole>().unwrap(), SemanticRole::Region);
+        assert!("presentation".parse::<SemanticRole>().is_err());
+
+        let role = SemanticRole::TabPanel;
+        assert_eq!(role.to_string().parse::<SemanticRole>().unwrap(), role);
+        let json = serde_json::to_string(&role).unwrap();
+        assert_eq!(json, format!("\"{}\"", role));
+        assert_eq!(SemanticRole::Header.aria_role(), Some("banner"));
+        assert_eq!(SemanticRole::Container.aria_role(), None);
+        assert_eq!(SemanticRole::Section.aria_role(), None);
+    }
+
     #[test]
     fn test_state_graph_deterministic() {
         let mut graph = StateGraph::new();
@@ -332,6 +959,7 @@ mod tests {
             url_pattern: Some("/".to_string()),
             is_initial: true,
             is_terminal: false,
+            anchor: None,
         });
         graph.transitions.push(Transition {
             from: "home".to_string(),
@@ -339,6 +967,8 @@ mod tests {
             trigger: "about-link".to_string(),
             action: None,
             guard: None,
+            request: None,
+            params: IndexMap::new(),
         });
         graph.initial_state = Some("home".to_string());
 
@@ -351,6 +981,8 @@ mod tests {
             trigger: "about-link".to_string(), // Same trigger!
             action: None,
             guard: None,
+            request: None,
+            params: IndexMap::new(),
         });
 
         assert!(!graph.is_deterministic());
@@ -0,0 +1,155 @@
+//! File uploads
+//!
+//! Submitting a document takes two steps an agent has to plan: choosing a
+//! file that the input accepts, then sending the form that carries it.
+//! Each file input adds a "file selected" state to the state graph,
+//! guarded by its `accept` filter, and an "uploaded" state reached by the
+//! form's submit control, or by the input itself when no form sends it
+//! (scripts usually upload on change).
+
+use crate::parser::SemanticDOM;
+use crate::types::{SemanticIntent, SemanticNode, State, Transition};
+use indexmap::IndexMap;
+
+impl SemanticDOM {
+    /// Add "file selected" and "uploaded" states for each file input
+    pub(crate) fn add_upload_states(&mut self) {
+        let initial = self.initial_state_id();
+        let mut states = Vec::new();
+        let mut transitions = Vec::new();
+        for input in self.file_inputs() {
+            let constraints = input.constraints.clone().unwrap_or_default();
+            let guard = (!constraints.accept.is_empty())
+                .then(|| format!("accepts {}", constraints.accept.join(", ")));
+            let selected = format!("state_file_selected_{}", input.id);
+            states.push(State {
+                id: selected.clone(),
+                name: format!("{} (file selected)", input.label),
+                description: constraints.multiple.then(|| "Several files".to_string()),
+                url_pattern: None,
+                is_initial: false,
+                is_terminal: false,
+                anchor: None,
+            });
+            transitions.push(Transition {
+                from: initial.clone(),
+                to: selected.clone(),
+                trigger: input.id.clone(),
+                action: Some("select-file".to_string()),
+                guard,
+                request: None,
+                params: IndexMap::new(),
+            });
+
+            let submit = self.form_submits.iter().find(|s| {
+                s.request.fields.iter().any(|f| f.node.as_deref() == Some(&input.id))
+            });
+            let uploaded = format!("state_uploaded_{}", input.id);
+            states.push(State {
+                id: uploaded.clone(),
+                name: format!("{} (uploaded)", input.label),
+                description: None,
+                url_pattern: submit.and_then(|s| s.request.action.clone()),
+                is_initial: false,
+                is_terminal: false,
+                anchor: None,
+            });
+            transitions.push(Transition {
+                from: selected,
+                to: uploaded,
+                trigger: submit.map_or_else(|| input.id.clone(), |s| s.trigger.clone()),
+                action: Some("upload".to_string()),
+                guard: None,
+                request: submit.map(|s| s.request.clone()),
+                params: IndexMap::new(),
+            });
+        }
+        self.state_graph.states.extend(states);
+        self.state_graph.transitions.extend(transitions);
+    }
+
+    /// Get the file inputs, in document order
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, SemanticIntent};
+    ///
+    /// let html = r#"<form action="/claims" method="post" enctype="multipart/form-data">
+    ///     <input type="file" id="receipt" name="receipt" accept=".pdf, image/*">
+    ///     <button>Send claim</button>
+    /// </form>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    ///
+    /// let receipt = sdom.file_inputs()[0];
+    /// assert_eq!(receipt.intent, Some(SemanticIntent::Upload));
+    /// assert_eq!(receipt.constraints.as_ref().unwrap().accept, [".pdf", "image/*"]);
+    ///
+    /// let graph = &sdom.state_graph;
+    /// let upload = graph.transitions.iter().find(|t| t.to == "state_uploaded_sdom_receipt");
+    /// assert_eq!(upload.unwrap().from, "state_file_selected_sdom_receipt");
+    /// ```
+    pub fn file_inputs(&self) -> Vec<&SemanticNode> {
+        self.interactables
+            .iter()
+            .filter_map(|id| self.index.get(id))
+            .filter(|n| n.input_type.as_deref() == Some("file"))
+            .collect()
+    }
+
+    /// Get the controls that upload files, file inputs first
+    pub fn upload_controls(&self) -> Vec<&SemanticNode> {
+        let mut controls = self.file_inputs();
+        controls.extend(
+            self.get_interactables()
+                .into_iter()
+                .filter(|n| n.intent == Some(SemanticIntent::Upload))
+                .filter(|n| n.input_type.as_deref() != Some("file")),
+        );
+        controls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, SemanticDOM};
+
+    #[test]
+    fn test_upload_states() {
+        let html = r#"<main><form action="/docs" method="post">
+            <input type="file" id="scans" name="scans" aria-label="Scans"
+              accept="image/png,, .JPG" multiple>
+            <button>Send</button></form>
+            <input type="file" id="avatar" aria-label="Avatar">
+            <button type="button">Attach from Drive</button></main>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+
+        let scans = sdom.get("sdom_scans").unwrap().constraints.clone().unwrap();
+        assert_eq!(scans.accept, ["image/png", ".jpg"]);
+        assert!(scans.multiple);
+
+        let flows: Vec<(&str, &str, &str, Option<&str>)> = sdom
+            .state_graph
+            .transitions
+            .iter()
+            .filter(|t| matches!(t.action.as_deref(), Some("select-file" | "upload")))
+            .map(|t| {
+                let to = t.to.trim_start_matches("state_").trim_end_matches("_sdom_scans");
+                (t.from.as_str(), to, t.trigger.as_str(), t.guard.as_deref())
+            })
+            .collect();
+        assert_eq!(
+            flows,
+            [
+                ("initial", "file_selected", "sdom_scans", Some("accepts image/png, .jpg")),
+                ("state_file_selected_sdom_scans", "uploaded", "sdom_button_3", None),
+                ("initial", "file_selected_sdom_avatar", "sdom_avatar", None),
+                ("state_file_selected_sdom_avatar", "uploaded_sdom_avatar", "sdom_avatar", None),
+            ]
+        );
+        let upload = sdom.state_graph.transitions.iter().find(|t| t.trigger == "sdom_button_3");
+        assert_eq!(upload.unwrap().request.as_ref().unwrap().action.as_deref(), Some("/docs"));
+
+        let labels: Vec<&str> = sdom.upload_controls().iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, ["Scans", "Avatar", "Attach from Drive"]);
+    }
+}
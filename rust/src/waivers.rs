@@ -0,0 +1,143 @@
+//! Suppressing certification checks
+//!
+//! Some failures are known and accepted for a page, such as a legacy
+//! widget that cannot be relabeled yet. A check can be waived for every
+//! page parsed with a configuration ([`Config::ignore_checks`]) or by the
+//! page itself with a comment:
+//!
+//! ```html
+//! <!-- sdom-ignore A11Y-002 reason="legacy icon buttons" -->
+//! ```
+//!
+//! A comment may name several checks. Waived checks still run: their
+//! result stays in the report with the [`Waiver`] attached, and only the
+//! score treats them as passed.
+//!
+//! [`Config::ignore_checks`]: crate::Config::ignore_checks
+
+use crate::parser::SemanticDOM;
+use scraper::{Html, Node};
+use serde::{Deserialize, Serialize};
+
+/// Keyword that starts a waiver comment
+const DIRECTIVE: &str = "sdom-ignore";
+
+/// Where a waiver was declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaiverSource {
+    /// [`Config::ignore_checks`](crate::Config::ignore_checks)
+    Config,
+    /// An `sdom-ignore` comment in the page
+    Comment,
+}
+
+/// A check whose failure is accepted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Waiver {
+    /// ID of the waived check, e.g. `A11Y-002`
+    pub check: String,
+    /// Why the failure is accepted
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+    /// Where the waiver was declared
+    pub source: WaiverSource,
+}
+
+/// Waivers declared in `sdom-ignore` comments, in document order
+pub(crate) fn comment_waivers(document: &Html) -> Vec<Waiver> {
+    document
+        .tree
+        .values()
+        .filter_map(|node| match node {
+            Node::Comment(comment) => Some(parse_comment(comment)),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Parse `sdom-ignore ID [ID...] [reason="..."]`
+fn parse_comment(comment: &str) -> Vec<Waiver> {
+    let Some(rest) = comment.trim().strip_prefix(DIRECTIVE) else {
+        return Vec::new();
+    };
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return Vec::new();
+    }
+    let (ids, reason) = match rest.find("reason=") {
+        Some(at) => {
+            let value = rest[at + "reason=".len()..].trim();
+            let reason = match value.chars().next() {
+                Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or_default(),
+                _ => value.split_whitespace().next().unwrap_or_default(),
+            };
+            (&rest[..at], Some(reason.to_string()).filter(|r| !r.is_empty()))
+        }
+        None => (rest, None),
+    };
+    ids.split_whitespace()
+        .map(|id| Waiver {
+            check: id.to_ascii_uppercase(),
+            reason: reason.clone(),
+            source: WaiverSource::Comment,
+        })
+        .collect()
+}
+
+impl SemanticDOM {
+    /// Waivers applying to this document: those from the configuration,
+    /// then those from comments in the page
+    pub fn waivers(&self) -> Vec<Waiver> {
+        self.config
+            .ignore_checks
+            .iter()
+            .map(|id| Waiver {
+                check: id.to_ascii_uppercase(),
+                reason: None,
+                source: WaiverSource::Config,
+            })
+            .chain(self.comment_waivers.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_parse_comment() {
+        let waivers = parse_comment(r#" sdom-ignore a11y-002 STRUCT-001 reason="legacy nav" "#);
+        let checks: Vec<&str> = waivers.iter().map(|w| w.check.as_str()).collect();
+        assert_eq!(checks, ["A11Y-002", "STRUCT-001"]);
+        assert_eq!(waivers[1].reason.as_deref(), Some("legacy nav"));
+        let unquoted = parse_comment("sdom-ignore NAV-001 reason=wip");
+        assert_eq!(unquoted[0].reason.as_deref(), Some("wip"));
+        assert!(parse_comment("sdom-ignored A11Y-002").is_empty());
+        assert!(parse_comment("todo: sdom-ignore A11Y-002").is_empty());
+    }
+
+    #[test]
+    fn test_waived_checks_are_reported_and_scored_as_passed() {
+        let page = r#"<main><h1>Shop</h1><button></button><button></button></main>"#;
+        let plain = SemanticDOM::parse(page, Config::default()).unwrap().certify();
+        let failing = plain.checks.iter().find(|c| c.id == "A11Y-003").unwrap();
+        assert!(failing.is_failure());
+
+        let html = format!("<!-- sdom-ignore A11Y-003 reason=\"icon font\" -->{}", page);
+        let config = Config::builder().ignore_check("nav-001").build().unwrap();
+        let sdom = SemanticDOM::parse(&html, config).unwrap();
+        let cert = sdom.certification.clone().unwrap();
+        let check = cert.checks.iter().find(|c| c.id == "A11Y-003").unwrap();
+        assert!(!check.passed && !check.is_failure());
+        let waiver = check.waiver.as_ref().unwrap();
+        assert_eq!(waiver.source, WaiverSource::Comment);
+        assert_eq!(waiver.reason.as_deref(), Some("icon font"));
+        let nav = cert.checks.iter().find(|c| c.id == "NAV-001").unwrap();
+        assert_eq!(nav.waiver.as_ref().map(|w| w.source), Some(WaiverSource::Config));
+        assert_eq!(cert.stats.waived_checks, 2);
+        assert!(cert.score > plain.score);
+    }
+}
@@ -0,0 +1,257 @@
+//! Visitor API for traversing the semantic tree
+//!
+//! Lets downstream crates implement custom extraction passes over the
+//! parsed model without depending on how nodes are stored internally.
+
+use crate::parser::SemanticDOM;
+use crate::types::SemanticNode;
+
+/// Controls how a walk proceeds after entering a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalkControl {
+    /// Continue into the node's children
+    #[default]
+    Continue,
+    /// Skip the node's children but continue with its siblings
+    SkipChildren,
+    /// Stop the walk immediately
+    Stop,
+}
+
+/// Callbacks invoked while walking the semantic tree
+///
+/// Both methods have default implementations, so visitors only need to
+/// override the callbacks they care about.
+///
+/// # Examples
+/// ```
+/// use semantic_dom_ssg::{Config, SemanticDOM, SemanticNode, SemanticRole, Visitor, WalkControl};
+///
+/// struct LinkCollector(Vec<String>);
+///
+/// impl Visitor for LinkCollector {
+///     fn enter(&mut self, node: &SemanticNode) -> WalkControl {
+///         if node.role == SemanticRole::Link {
+///             self.0.push(node.label.clone());
+///         }
+///         WalkControl::Continue
+///     }
+/// }
+///
+/// let html = r#"<nav><a href="/">Home</a><a href="/about">About</a></nav>"#;
+/// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+/// let mut links = LinkCollector(Vec::new());
+/// sdom.walk(&mut links);
+/// assert_eq!(links.0, vec!["Home", "About"]);
+/// ```
+pub trait Visitor {
+    /// Called before a node's children are visited
+    fn enter(&mut self, _node: &SemanticNode) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called after a node's children have been visited (or skipped)
+    fn leave(&mut self, _node: &SemanticNode) {}
+}
+
+impl SemanticDOM {
+    /// Get all nodes linearized in document (reading) order
+    ///
+    /// The index is a hash map and has no meaningful iteration order; use
+    /// this when output should follow the page as a reader would see it.
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM};
+    ///
+    /// let html = r#"<main><h1>Title</h1><a href="/a">A</a><button>B</button></main>"#;
+    /// let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+    /// let labels: Vec<_> = sdom.reading_order().iter().map(|n| n.label.as_str()).collect();
+    /// assert_eq!(labels[1..], ["Title", "A", "B"]);
+    /// ```
+    pub fn reading_order(&self) -> Vec<&SemanticNode> {
+        self.order.iter().filter_map(|id| self.index.get(id)).collect()
+    }
+
+    /// Get all descendants of a node in document order (excluding the node)
+    ///
+    /// Returns an empty list if no node has the given ID.
+    pub fn descendants(&self, id: &str) -> Vec<&SemanticNode> {
+        let Some(start) = self.order.iter().position(|o| o == id) else {
+            return Vec::new();
+        };
+        let depth = self.index.get(id).map_or(0, |n| n.depth);
+        // Document order is a pre-order traversal, so a subtree is the run
+        // of deeper nodes directly after its root
+        self.order[start + 1..]
+            .iter()
+            .filter_map(|o| self.index.get(o))
+            .take_while(|n| n.depth > depth)
+            .collect()
+    }
+
+    /// Get the top-level nodes (those without a parent) in document order
+    pub fn roots(&self) -> Vec<&SemanticNode> {
+        self.reading_order()
+            .into_iter()
+            .filter(|n| n.parent.is_none())
+            .collect()
+    }
+
+    /// Find the nearest ancestor of `node` matching a predicate
+    pub(crate) fn ancestor_with(
+        &self,
+        node: &SemanticNode,
+        predicate: impl Fn(&SemanticNode) -> bool,
+    ) -> Option<&SemanticNode> {
+        let mut current = node.parent.as_deref().and_then(|p| self.index.get(p));
+        while let Some(ancestor) = current {
+            if predicate(ancestor) {
+                return Some(ancestor);
+            }
+            current = ancestor.parent.as_deref().and_then(|p| self.index.get(p));
+        }
+        None
+    }
+
+    /// Walk the semantic tree depth-first in document order
+    ///
+    /// Every `enter` is paired with a `leave`, except when the visitor
+    /// returns [`WalkControl::Stop`], which ends the walk without further
+    /// callbacks.
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        // Iterative to stay safe on pathologically deep documents
+        let mut stack: Vec<(&SemanticNode, usize)> = Vec::new();
+
+        for root in self.roots() {
+            match visitor.enter(root) {
+                WalkControl::Stop => return,
+                WalkControl::SkipChildren => {
+                    visitor.leave(root);
+                    continue;
+                }
+                WalkControl::Continue => stack.push((root, 0)),
+            }
+
+            while let Some(top) = stack.last_mut() {
+                let (node, next_child) = *top;
+                if next_child >= node.children.len() {
+                    stack.pop();
+                    visitor.leave(node);
+                    continue;
+                }
+                top.1 += 1;
+
+                if let Some(child) = self.index.get(&node.children[next_child]) {
+                    match visitor.enter(child) {
+                        WalkControl::Stop => return,
+                        WalkControl::SkipChildren => visitor.leave(child),
+                        WalkControl::Continue => stack.push((child, 0)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SemanticRole;
+    use crate::Config;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skip: Option<SemanticRole>,
+        stop_at: Option<String>,
+    }
+
+    impl Visitor for Recorder {
+        fn enter(&mut self, node: &SemanticNode) -> WalkControl {
+            self.events.push(format!("+{}", node.label));
+            if self.stop_at.as_deref() == Some(node.label.as_str()) {
+                return WalkControl::Stop;
+            }
+            if self.skip.as_ref() == Some(&node.role) {
+                return WalkControl::SkipChildren;
+            }
+            WalkControl::Continue
+        }
+
+        fn leave(&mut self, node: &SemanticNode) {
+            self.events.push(format!("-{}", node.label));
+        }
+    }
+
+    const HTML: &str = r#"
+        <nav aria-label="Nav"><a href="/">Home</a></nav>
+        <main aria-label="Main"><h1>Title</h1><form aria-label="Form"><button>Go</button></form></main>
+    "#;
+
+    #[test]
+    fn test_walk_enter_leave_order() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let mut recorder = Recorder::default();
+        sdom.walk(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            vec![
+                "+Nav", "+Home", "-Home", "-Nav", "+Main", "+Title", "-Title", "+Form", "+Go",
+                "-Go", "-Form", "-Main"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_pruning_and_stop() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+
+        let mut recorder = Recorder {
+            skip: Some(SemanticRole::Form),
+            ..Default::default()
+        };
+        sdom.walk(&mut recorder);
+        assert!(!recorder.events.contains(&"+Go".to_string()));
+        assert!(recorder.events.contains(&"-Form".to_string()));
+
+        let mut recorder = Recorder {
+            stop_at: Some("Title".to_string()),
+            ..Default::default()
+        };
+        sdom.walk(&mut recorder);
+        assert_eq!(recorder.events.last().unwrap(), "+Title");
+    }
+
+    #[test]
+    fn test_reading_order_matches_walk() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let mut recorder = Recorder::default();
+        sdom.walk(&mut recorder);
+
+        let entered: Vec<String> = recorder
+            .events
+            .iter()
+            .filter_map(|e| e.strip_prefix('+'))
+            .map(str::to_string)
+            .collect();
+        let linear: Vec<String> = sdom.reading_order().iter().map(|n| n.label.clone()).collect();
+        assert_eq!(linear, entered);
+    }
+
+    #[test]
+    fn test_tree_links() {
+        let sdom = SemanticDOM::parse(HTML, Config::default()).unwrap();
+        let button = sdom
+            .index
+            .values()
+            .find(|n| n.role == SemanticRole::Button)
+            .unwrap();
+
+        assert_eq!(button.depth, 2);
+        let form = sdom.get(button.parent.as_deref().unwrap()).unwrap();
+        assert_eq!(form.role, SemanticRole::Form);
+        assert_eq!(form.children, vec![button.id.clone()]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Well-known descriptor advertising agent readiness
+//!
+//! A site hosts a small JSON document at [`WELL_KNOWN_PATH`] so agents can
+//! tell, before crawling, which standard the site follows, where its
+//! landmarks are, how well it certifies and where to fetch its state
+//! graph. [`SemanticDOM::to_well_known_descriptor`] writes the payload for
+//! a page; [`WellKnownDescriptor::from_json`] reads and validates one.
+//!
+//! ```json
+//! {
+//!   "standard": "ISO/IEC-SDOM-SSG-DRAFT-2024",
+//!   "version": 1,
+//!   "generator": "semantic-dom-ssg 0.1.0",
+//!   "url": "https://shop.example/",
+//!   "landmarks": {
+//!     "navigation": [{ "id": "sdom_primary", "selector": "nav#primary", "label": "Primary" }],
+//!     "main": [{ "id": "sdom_main_2", "selector": "main", "label": "Gadgets" }]
+//!   },
+//!   "certification": { "level": "AA", "score": 84 },
+//!   "state_graph_url": "https://shop.example/.well-known/semantic-dom-graph.json",
+//!   "state_graph_schema_version": 2
+//! }
+//! ```
+
+use crate::certification::CertificationLevel;
+use crate::graph_schema::STATE_GRAPH_SCHEMA_VERSION;
+use crate::parser::SemanticDOM;
+use crate::security::validate_url;
+use crate::types::SemanticRole;
+use crate::{Error, Result, STANDARD, VERSION};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Where sites host the descriptor
+pub const WELL_KNOWN_PATH: &str = "/.well-known/semantic-dom.json";
+
+/// Where the descriptor points for the state graph by default
+pub const STATE_GRAPH_PATH: &str = "/.well-known/semantic-dom-graph.json";
+
+/// Version of the descriptor format written by this crate
+pub const DESCRIPTOR_VERSION: u32 = 1;
+
+/// A landmark as listed in the descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LandmarkRef {
+    /// Semantic ID
+    pub id: String,
+    /// CSS selector
+    pub selector: String,
+    /// Human-readable label, if it says more than the role
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub label: Option<String>,
+}
+
+/// Certification result as advertised
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertifiedLevel {
+    /// Certification level
+    pub level: CertificationLevel,
+    /// Numeric score (0-100)
+    pub score: u32,
+}
+
+/// Contents of `/.well-known/semantic-dom.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WellKnownDescriptor {
+    /// Standard the site follows, [`STANDARD`]
+    pub standard: String,
+    /// Descriptor format version
+    pub version: u32,
+    /// Tool that wrote the descriptor
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub generator: Option<String>,
+    /// The described page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub url: Option<String>,
+    /// Landmarks by canonical role name, in reading order
+    #[serde(default)]
+    pub landmarks: IndexMap<String, Vec<LandmarkRef>>,
+    /// Certification of the page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub certification: Option<CertifiedLevel>,
+    /// Where the state graph is published
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state_graph_url: Option<String>,
+    /// Schema version of the published state graph
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state_graph_schema_version: Option<u32>,
+}
+
+impl WellKnownDescriptor {
+    /// Serialize the descriptor to JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    /// Read a descriptor published by a site and validate it
+    ///
+    /// # Errors
+    /// - [`Error::SerializationError`] if `json` is not a descriptor
+    /// - [`Error::InvalidDescriptor`] for an unknown standard, a newer format
+    ///   or state graph version, an unknown landmark role, a score over 100
+    ///   or a level the score does not reach
+    /// - the [`validate_url`] errors for an unsafe URL
+    pub fn from_json(json: &str) -> Result<Self> {
+        let descriptor: Self =
+            serde_json::from_str(json).map_err(|e| Error::SerializationError(e.to_string()))?;
+        descriptor.validate()?;
+        Ok(descriptor)
+    }
+
+    /// Check the descriptor against the format, as [`Self::from_json`] does
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(Error::InvalidDescriptor(message));
+        // Drafts of the standard share the prefix
+        let family = STANDARD.rsplit_once('-').map_or(STANDARD, |(family, _)| family);
+        if !self.standard.starts_with(family) {
+            return invalid(format!("unsupported standard {:?}", self.standard));
+        }
+        if self.version == 0 || self.version > DESCRIPTOR_VERSION {
+            return invalid(format!(
+                "version {} is not supported (max {})",
+                self.version, DESCRIPTOR_VERSION
+            ));
+        }
+        for role in self.landmarks.keys() {
+            let is_landmark = role.parse::<SemanticRole>().is_ok_and(|r| r.is_landmark());
+            if !is_landmark {
+                return invalid(format!("{:?} is not a landmark role", role));
+            }
+        }
+        if let Some(cert) = self.certification {
+            if cert.score > 100 {
+                return invalid(format!("score {} is over 100", cert.score));
+            }
+            if cert.level > CertificationLevel::for_score(cert.score) {
+                return invalid(format!("score {} does not reach {:?}", cert.score, cert.level));
+            }
+        }
+        if let Some(version) = self.state_graph_schema_version {
+            if version > STATE_GRAPH_SCHEMA_VERSION {
+                return invalid(format!("state graph schema version {} is not supported", version));
+            }
+        }
+        for url in self.url.iter().chain(&self.state_graph_url) {
+            validate_url(url)?;
+        }
+        Ok(())
+    }
+}
+
+impl SemanticDOM {
+    /// Describe the page for [`WELL_KNOWN_PATH`]
+    ///
+    /// The certification is the one computed at parse time, if any, else a
+    /// fresh one. With a page URL, the state graph URL is
+    /// [`STATE_GRAPH_PATH`] on the same origin; set
+    /// [`WellKnownDescriptor::state_graph_url`] when it is published
+    /// elsewhere.
+    pub fn well_known_descriptor(&self) -> WellKnownDescriptor {
+        let mut landmarks: IndexMap<String, Vec<LandmarkRef>> = IndexMap::new();
+        for node in self.landmarks.iter().filter_map(|id| self.index.get(id)) {
+            let label = Some(node.label.clone())
+                .filter(|l| !l.is_empty() && l.as_str() != node.role.as_str());
+            landmarks.entry(node.role.as_str().to_string()).or_default().push(LandmarkRef {
+                id: node.id.clone(),
+                selector: node.selector.clone(),
+                label,
+            });
+        }
+        let cert = self.certification.clone().unwrap_or_else(|| self.certify());
+        let base = self.base_url.as_deref().and_then(|u| Url::parse(u).ok());
+        let state_graph_url = base.and_then(|b| b.join(STATE_GRAPH_PATH).ok());
+        WellKnownDescriptor {
+            standard: STANDARD.to_string(),
+            version: DESCRIPTOR_VERSION,
+            generator: Some(format!("semantic-dom-ssg {}", VERSION)),
+            url: self.base_url.clone(),
+            landmarks,
+            certification: Some(CertifiedLevel { level: cert.level, score: cert.score }),
+            state_graph_url: state_graph_url.map(String::from),
+            state_graph_schema_version: Some(STATE_GRAPH_SCHEMA_VERSION),
+        }
+    }
+
+    /// The [`Self::well_known_descriptor`] as JSON
+    ///
+    /// # Examples
+    /// ```
+    /// use semantic_dom_ssg::{Config, SemanticDOM, WellKnownDescriptor};
+    ///
+    /// let config = Config::builder().base_url("https://docs.example/guide").build().unwrap();
+    /// let html = r#"<nav><a href="/">Home</a></nav><main><h1>Guide</h1></main>"#;
+    /// let sdom = SemanticDOM::parse(html, config).unwrap();
+    ///
+    /// let json = sdom.to_well_known_descriptor().unwrap();
+    /// let descriptor = WellKnownDescriptor::from_json(&json).unwrap();
+    /// assert_eq!(descriptor.landmarks["main"][0].label.as_deref(), Some("Guide"));
+    /// assert_eq!(
+    ///     descriptor.state_graph_url.as_deref(),
+    ///     Some("https://docs.example/.well-known/semantic-dom-graph.json")
+    /// );
+    /// ```
+    pub fn to_well_known_descriptor(&self) -> Result<String> {
+        self.well_known_descriptor().to_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_descriptor_validation() {
+        let html = r#"<header>Shop</header><nav id="primary"><a href="/">Home</a></nav>
+            <main><h1>Gadgets</h1></main><nav aria-label="Legal"><a href="/t">Terms</a></nav>"#;
+        let sdom = SemanticDOM::parse(html, Config::default()).unwrap();
+        let descriptor = sdom.well_known_descriptor();
+        let roles: Vec<(&str, usize)> =
+            descriptor.landmarks.iter().map(|(r, l)| (r.as_str(), l.len())).collect();
+        assert_eq!(roles, [("header", 1), ("navigation", 2), ("main", 1)]);
+        assert_eq!(descriptor.landmarks["navigation"][0].selector, "nav#primary");
+        assert_eq!(descriptor.state_graph_url, None);
+        let json = descriptor.to_json().unwrap();
+        assert_eq!(WellKnownDescriptor::from_json(&json).unwrap(), descriptor);
+
+        let rejected = |edit: fn(&mut WellKnownDescriptor)| {
+            let mut bad = descriptor.clone();
+            edit(&mut bad);
+            WellKnownDescriptor::from_json(&bad.to_json().unwrap()).unwrap_err().to_string()
+        };
+        assert!(rejected(|d| d.standard = "schema.org".into()).contains("unsupported standard"));
+        assert!(rejected(|d| d.version = 2).contains("version 2"));
+        assert!(rejected(|d| {
+            d.landmarks.insert("button".into(), Vec::new());
+        })
+        .contains("not a landmark"));
+        assert!(rejected(|d| {
+            d.certification = Some(CertifiedLevel { level: CertificationLevel::AAA, score: 75 })
+        })
+        .contains("does not reach"));
+        assert!(rejected(|d| d.state_graph_url = Some("javascript:alert(1)".into()))
+            .contains("protocol"));
+        assert!(WellKnownDescriptor::from_json(r#"{"version": 1}"#).is_err());
+    }
+}